@@ -0,0 +1,44 @@
+-- Migration 043: Cancellation policies and refunds
+-- Each club can configure how much of a paid share is refunded when a reservation
+-- is cancelled. Every refund issued through Stripe (or released from an uncaptured
+-- authorization) is recorded against the originating payment.
+
+CREATE TABLE IF NOT EXISTS club_cancellation_policies (
+    club_id UUID PRIMARY KEY REFERENCES clubs(id) ON DELETE CASCADE,
+    -- Full refund when cancelled at least this many hours before the event starts
+    full_refund_hours INTEGER NOT NULL DEFAULT 48 CHECK (full_refund_hours >= 0),
+    -- Percentage refunded when cancelled after the full-refund window but before the event
+    partial_refund_percent DECIMAL(5, 2) NOT NULL DEFAULT 50
+        CHECK (partial_refund_percent >= 0 AND partial_refund_percent <= 100),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS refunded_amount DECIMAL(10, 2) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS refunds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    payment_id UUID NOT NULL REFERENCES payments(id) ON DELETE CASCADE,
+    reservation_id UUID REFERENCES table_reservations(id) ON DELETE SET NULL,
+    payment_share_id UUID REFERENCES reservation_payment_shares(id) ON DELETE SET NULL,
+    stripe_refund_id VARCHAR(255) UNIQUE,
+    amount DECIMAL(10, 2) NOT NULL,
+    -- pending, succeeded, failed, released (uncaptured authorization cancelled or reduced)
+    status VARCHAR(32) NOT NULL,
+    -- user_cancelled, owner_cancelled, stripe_dashboard
+    reason VARCHAR(50) NOT NULL,
+    refund_percent DECIMAL(5, 2),
+    application_fee_refunded BOOLEAN NOT NULL DEFAULT FALSE,
+    failure_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_refunds_payment_id ON refunds(payment_id);
+CREATE INDEX IF NOT EXISTS idx_refunds_reservation_id ON refunds(reservation_id);
+
+-- A share can only be refunded once per cancellation; retries reuse the existing row.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_refunds_unique_share_cancellation
+    ON refunds(payment_share_id)
+    WHERE payment_share_id IS NOT NULL AND status IN ('pending', 'succeeded', 'released');
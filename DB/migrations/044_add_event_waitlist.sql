@@ -0,0 +1,40 @@
+-- Migration 044: Event waitlist
+-- Users can queue for a sold-out event, optionally restricted to an area or a
+-- minimum table size. When a table or a guest slot frees up, the first matching
+-- entry receives a time-boxed hold on it.
+
+CREATE TABLE IF NOT EXISTS event_waitlist_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    area_id UUID REFERENCES areas(id) ON DELETE SET NULL,
+    min_capacity INTEGER CHECK (min_capacity IS NULL OR min_capacity > 0),
+    -- Queue order within the event; owners can rewrite it
+    position INTEGER NOT NULL,
+    -- waiting, offered, fulfilled, expired, cancelled
+    status VARCHAR(20) NOT NULL DEFAULT 'waiting',
+    -- Set while offered: a whole table, or a guest slot on an existing reservation
+    held_table_id UUID REFERENCES tables(id) ON DELETE SET NULL,
+    held_reservation_id UUID REFERENCES table_reservations(id) ON DELETE SET NULL,
+    hold_expires_at TIMESTAMPTZ,
+    offered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_waitlist_entries_queue
+    ON event_waitlist_entries(event_id, status, position);
+
+-- One active entry per user and event
+CREATE UNIQUE INDEX IF NOT EXISTS idx_event_waitlist_entries_active_user
+    ON event_waitlist_entries(event_id, user_id)
+    WHERE status IN ('waiting', 'offered');
+
+-- A table can only be held for one user at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_event_waitlist_entries_active_table_hold
+    ON event_waitlist_entries(held_table_id)
+    WHERE status = 'offered' AND held_table_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_event_waitlist_entries_hold_expiry
+    ON event_waitlist_entries(hold_expires_at)
+    WHERE status = 'offered';
@@ -0,0 +1,58 @@
+-- Migration 045: Promo codes
+-- Owner-managed discount codes that can be applied to the organizer's share when
+-- booking a table and to a guest's share on a payment link. Every use is recorded
+-- as a redemption; the counter on the code is bumped in the same transaction so
+-- usage caps hold under concurrent checkouts.
+
+CREATE TABLE IF NOT EXISTS promo_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    -- Stored upper-case; matched case-insensitively
+    code VARCHAR(50) NOT NULL,
+    description TEXT,
+    -- percentage, fixed
+    discount_type VARCHAR(20) NOT NULL CHECK (discount_type IN ('percentage', 'fixed')),
+    discount_value DECIMAL(10, 2) NOT NULL CHECK (discount_value > 0),
+    -- Optional scope: when set, the code only applies to that event / area / table
+    event_id UUID REFERENCES events(id) ON DELETE CASCADE,
+    area_id UUID REFERENCES areas(id) ON DELETE CASCADE,
+    table_id UUID REFERENCES tables(id) ON DELETE CASCADE,
+    max_redemptions INTEGER CHECK (max_redemptions IS NULL OR max_redemptions > 0),
+    redemption_count INTEGER NOT NULL DEFAULT 0,
+    valid_from TIMESTAMPTZ,
+    valid_until TIMESTAMPTZ,
+    single_use_per_phone BOOLEAN NOT NULL DEFAULT TRUE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT promo_codes_percentage_range
+        CHECK (discount_type <> 'percentage' OR discount_value <= 100),
+    CONSTRAINT promo_codes_redemption_cap
+        CHECK (max_redemptions IS NULL OR redemption_count <= max_redemptions)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_promo_codes_club_code ON promo_codes(club_id, code);
+
+CREATE TABLE IF NOT EXISTS promo_code_redemptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    promo_code_id UUID NOT NULL REFERENCES promo_codes(id) ON DELETE CASCADE,
+    reservation_id UUID NOT NULL REFERENCES table_reservations(id) ON DELETE CASCADE,
+    payment_share_id UUID REFERENCES reservation_payment_shares(id) ON DELETE SET NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    phone_number VARCHAR(50),
+    original_amount DECIMAL(10, 2) NOT NULL,
+    discount_amount DECIMAL(10, 2) NOT NULL,
+    final_amount DECIMAL(10, 2) NOT NULL,
+    -- pending (guest checkout in flight), redeemed, released (checkout never paid)
+    status VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_promo_code_redemptions_code
+    ON promo_code_redemptions(promo_code_id, status);
+CREATE INDEX IF NOT EXISTS idx_promo_code_redemptions_phone
+    ON promo_code_redemptions(promo_code_id, phone_number);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_promo_code_redemptions_share
+    ON promo_code_redemptions(payment_share_id)
+    WHERE payment_share_id IS NOT NULL;
@@ -0,0 +1,59 @@
+-- Migration 046: Club-scoped check-in
+-- Reservations can be checked in a few people at a time; checked_in_count tracks how
+-- many of num_people have arrived and the reservation becomes 'completed' once everyone
+-- is in. Every scan and check-in attempt is written to an append-only audit log.
+
+ALTER TABLE table_reservations
+    ADD COLUMN IF NOT EXISTS checked_in_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS checked_in_at TIMESTAMPTZ;
+
+-- Reservations checked in before partial check-in existed arrived in full
+UPDATE table_reservations
+SET checked_in_count = num_people
+WHERE status = 'completed'
+  AND checked_in_count = 0;
+
+ALTER TABLE table_reservations
+    DROP CONSTRAINT IF EXISTS table_reservations_checked_in_count_range;
+ALTER TABLE table_reservations
+    ADD CONSTRAINT table_reservations_checked_in_count_range
+    CHECK (checked_in_count >= 0 AND checked_in_count <= num_people);
+
+-- No foreign keys: audit rows must outlive the tickets, reservations and events they
+-- describe, and cascades would need to UPDATE/DELETE them.
+CREATE TABLE IF NOT EXISTS checkin_audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    club_id UUID NOT NULL,
+    event_id UUID,
+    -- Account that performed the scan
+    scanned_by UUID NOT NULL,
+    code VARCHAR(100) NOT NULL,
+    -- scan (read-only lookup), checkin
+    action VARCHAR(20) NOT NULL,
+    -- ticket, reservation, unknown
+    scan_type VARCHAR(20) NOT NULL,
+    ticket_id UUID,
+    reservation_id UUID,
+    -- People admitted by this check-in (0 for scans and rejected attempts)
+    people_count INTEGER NOT NULL DEFAULT 0,
+    -- success, partial, already_used, wrong_date, cancelled, exceeds_remaining, not_found
+    outcome VARCHAR(30) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_checkin_audit_log_club
+    ON checkin_audit_log(club_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_checkin_audit_log_event
+    ON checkin_audit_log(event_id, created_at DESC);
+
+CREATE OR REPLACE FUNCTION checkin_audit_log_append_only()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'checkin_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_checkin_audit_log_append_only ON checkin_audit_log;
+CREATE TRIGGER trg_checkin_audit_log_append_only
+    BEFORE UPDATE OR DELETE ON checkin_audit_log
+    FOR EACH ROW EXECUTE FUNCTION checkin_audit_log_append_only();
@@ -0,0 +1,36 @@
+-- Migration 047: Club staff accounts
+-- Owners invite door staff, hosts, managers and finance people who log in with their
+-- own credentials. Tokens carry the staff role; revoking a member (or changing their
+-- role) takes effect on the next request because every staff token is checked
+-- against this table.
+
+CREATE TABLE IF NOT EXISTS club_staff (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    invited_by UUID NOT NULL REFERENCES club_owners(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    phone_number VARCHAR(50),
+    -- door, host, manager, finance
+    role VARCHAR(20) NOT NULL CHECK (role IN ('door', 'host', 'manager', 'finance')),
+    -- Set when the invitation is accepted
+    password_hash VARCHAR(255),
+    -- invited, active, revoked
+    status VARCHAR(20) NOT NULL DEFAULT 'invited',
+    -- SHA-256 of the one-time invitation token
+    invite_token_hash VARCHAR(64),
+    invite_expires_at TIMESTAMPTZ,
+    accepted_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Staff log in by email, so a live account's email must be unique across clubs
+CREATE UNIQUE INDEX IF NOT EXISTS idx_club_staff_live_email
+    ON club_staff(LOWER(email))
+    WHERE status <> 'revoked';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_club_staff_invite_token
+    ON club_staff(invite_token_hash)
+    WHERE invite_token_hash IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_club_staff_club ON club_staff(club_id, status);
@@ -0,0 +1,33 @@
+-- Migration 048: Rotating refresh-token sessions
+-- Reshapes user_sessions (migration 004, never written by the API) into one row per
+-- issued refresh token. Tokens are stored as SHA-256 hashes; rows of one login share a
+-- family_id. Refreshing marks the presented row rotated and inserts its successor;
+-- presenting a rotated or revoked token again revokes the whole family. Access tokens
+-- carry the family id and stop working as soon as the family is revoked.
+
+-- The old rows were never created by the application and carry no usable token
+DELETE FROM user_sessions;
+
+ALTER TABLE user_sessions DROP COLUMN IF EXISTS refresh_token;
+DROP INDEX IF EXISTS idx_user_sessions_token;
+
+ALTER TABLE user_sessions ALTER COLUMN user_id DROP NOT NULL;
+
+ALTER TABLE user_sessions
+    ADD COLUMN IF NOT EXISTS club_owner_id UUID REFERENCES club_owners(id) ON DELETE CASCADE,
+    ADD COLUMN IF NOT EXISTS family_id UUID NOT NULL DEFAULT gen_random_uuid(),
+    ADD COLUMN IF NOT EXISTS refresh_token_hash VARCHAR(64) NOT NULL,
+    ADD COLUMN IF NOT EXISTS rotated_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMPTZ,
+    -- logout, password_changed, reuse_detected, account_inactive
+    ADD COLUMN IF NOT EXISTS revoked_reason VARCHAR(30);
+
+ALTER TABLE user_sessions DROP CONSTRAINT IF EXISTS user_sessions_one_subject;
+ALTER TABLE user_sessions
+    ADD CONSTRAINT user_sessions_one_subject
+    CHECK ((user_id IS NULL) <> (club_owner_id IS NULL));
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_sessions_refresh_token_hash
+    ON user_sessions(refresh_token_hash);
+CREATE INDEX IF NOT EXISTS idx_user_sessions_family ON user_sessions(family_id);
+CREATE INDEX IF NOT EXISTS idx_user_sessions_club_owner_id ON user_sessions(club_owner_id);
@@ -0,0 +1,31 @@
+-- Migration 049: Password reset codes
+-- A forgot-password request issues a six-digit code delivered by email or SMS. Only the
+-- SHA-256 hash is stored. A code expires after PASSWORD_RESET_CODE_TTL_MINUTES, works
+-- once, and is discarded after too many wrong guesses; issuing a new code invalidates the
+-- previous ones. requested_ip feeds the per-IP rate limit.
+
+CREATE TABLE IF NOT EXISTS password_reset_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    club_owner_id UUID REFERENCES club_owners(id) ON DELETE CASCADE,
+    -- email, sms
+    channel VARCHAR(10) NOT NULL,
+    code_hash VARCHAR(64) NOT NULL,
+    requested_ip VARCHAR(64),
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    invalidated_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT password_reset_codes_one_subject
+        CHECK ((user_id IS NULL) <> (club_owner_id IS NULL)),
+    CONSTRAINT password_reset_codes_channel
+        CHECK (channel IN ('email', 'sms'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_reset_codes_user
+    ON password_reset_codes(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_password_reset_codes_club_owner
+    ON password_reset_codes(club_owner_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_password_reset_codes_ip
+    ON password_reset_codes(requested_ip, created_at DESC);
@@ -0,0 +1,16 @@
+-- Migration 050: Email language
+-- Transactional emails are written in Italian or English. Users get the language their
+-- app sent at registration; guests paying through a payment link get their browser's.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS preferred_locale VARCHAR(5) NOT NULL DEFAULT 'it';
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_preferred_locale_check;
+ALTER TABLE users
+    ADD CONSTRAINT users_preferred_locale_check CHECK (preferred_locale IN ('it', 'en'));
+
+ALTER TABLE reservation_payment_shares
+    ADD COLUMN IF NOT EXISTS guest_locale VARCHAR(5);
+ALTER TABLE reservation_payment_shares DROP CONSTRAINT IF EXISTS reservation_payment_shares_guest_locale_check;
+ALTER TABLE reservation_payment_shares
+    ADD CONSTRAINT reservation_payment_shares_guest_locale_check
+    CHECK (guest_locale IS NULL OR guest_locale IN ('it', 'en'));
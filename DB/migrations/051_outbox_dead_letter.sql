@@ -0,0 +1,14 @@
+-- Migration 051: Outbox dead letters
+-- Events that fail permanently, or run out of attempts for their type, stop being retried
+-- and move to status 'dead_lettered' until an admin replays them (back to 'pending') or
+-- discards them ('discarded').
+
+ALTER TABLE outbox_events
+    ADD COLUMN IF NOT EXISTS dead_lettered_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS discarded_at TIMESTAMPTZ,
+    -- How many times an admin sent the event back to the queue
+    ADD COLUMN IF NOT EXISTS replay_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_outbox_events_dead_lettered
+    ON outbox_events(dead_lettered_at DESC)
+    WHERE status = 'dead_lettered';
@@ -0,0 +1,47 @@
+-- Migration 052: Push devices and delivery receipts
+-- A user can receive push notifications on several devices. users.expo_push_token keeps
+-- the most recently registered token for older readers and is cleared when that token is
+-- unregistered.
+-- Every push sent to Expo yields a ticket; the receipts job later resolves the ticket to
+-- delivered / failed and removes devices Expo reports as no longer registered.
+
+CREATE TABLE IF NOT EXISTS push_devices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- A token belongs to one device, hence to one user at a time
+    expo_push_token TEXT NOT NULL UNIQUE,
+    -- ios, android, web (as reported by the app)
+    platform VARCHAR(20),
+    last_registered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_push_devices_user ON push_devices(user_id);
+
+INSERT INTO push_devices (user_id, expo_push_token)
+SELECT id, expo_push_token
+FROM users
+WHERE expo_push_token IS NOT NULL
+  AND expo_push_token <> ''
+  AND deleted_at IS NULL
+ON CONFLICT (expo_push_token) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS push_tickets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    outbox_event_id UUID,
+    expo_push_token TEXT NOT NULL,
+    -- Expo ticket id, present when the ticket status was ok
+    ticket_id VARCHAR(100),
+    -- pending_receipt, delivered, failed, receipt_expired
+    status VARCHAR(20) NOT NULL,
+    -- Expo error code (e.g. DeviceNotRegistered, MessageRateExceeded) and message
+    error_code VARCHAR(50),
+    error_message TEXT,
+    receipt_checked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_push_tickets_pending
+    ON push_tickets(created_at)
+    WHERE status = 'pending_receipt';
+CREATE INDEX IF NOT EXISTS idx_push_tickets_created_at ON push_tickets(created_at DESC);
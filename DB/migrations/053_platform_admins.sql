@@ -0,0 +1,34 @@
+-- Migration 053: Platform admins
+-- Platform staff who run the back office (/admin/*): clubs, commissions, genres, owners and
+-- refunds. Admins are separate from users and club owners and log in at /auth/admin/login.
+-- Every admin write is recorded in admin_audit_log.
+
+CREATE TABLE IF NOT EXISTS platform_admins (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email VARCHAR(255) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    password_hash VARCHAR(255) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    last_login_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS admin_audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- NULL when the action was taken with the ADMIN_API_KEY ops key
+    admin_id UUID REFERENCES platform_admins(id) ON DELETE SET NULL,
+    -- e.g. club_updated, club_commission_updated, owner_suspended, reservation_cancelled
+    action VARCHAR(50) NOT NULL,
+    target_type VARCHAR(30) NOT NULL,
+    target_id UUID,
+    details JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_target
+    ON admin_audit_log(target_type, target_id, created_at DESC);
+
+-- A suspended owner cannot log in or refresh; their sessions are revoked on suspension
+ALTER TABLE club_owners
+    ADD COLUMN IF NOT EXISTS suspended_at TIMESTAMPTZ;
@@ -0,0 +1,66 @@
+-- Migration 054: General-admission ticket tiers and orders
+-- Owners sell entry tickets per event in tiers (early bird, standard, door...). Buying opens a
+-- Stripe Checkout session for a ticket_orders row; the order holds inventory until the session
+-- completes (tickets are issued) or expires (the hold is released).
+-- Inventory per tier: quantity_sold + quantity_held <= quantity_total. Holds are taken with a
+-- conditional UPDATE so concurrent checkouts cannot oversell; the CHECK is the backstop.
+
+CREATE TABLE IF NOT EXISTS ticket_tiers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    price DECIMAL(10, 2) NOT NULL CHECK (price > 0),
+    quantity_total INTEGER NOT NULL CHECK (quantity_total >= 0),
+    quantity_sold INTEGER NOT NULL DEFAULT 0,
+    -- Tickets in open checkout sessions
+    quantity_held INTEGER NOT NULL DEFAULT 0,
+    -- NULL: on sale as soon as the tier is active / until the event
+    sales_start_at TIMESTAMPTZ,
+    sales_end_at TIMESTAMPTZ,
+    -- NULL: no per-user limit
+    max_per_user INTEGER CHECK (max_per_user IS NULL OR max_per_user > 0),
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT ticket_tiers_inventory CHECK (
+        quantity_sold >= 0
+        AND quantity_held >= 0
+        AND quantity_sold + quantity_held <= quantity_total
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_tiers_event ON ticket_tiers(event_id, sort_order);
+
+CREATE TABLE IF NOT EXISTS ticket_orders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tier_id UUID NOT NULL REFERENCES ticket_tiers(id) ON DELETE RESTRICT,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    unit_price DECIMAL(10, 2) NOT NULL,
+    total_amount DECIMAL(10, 2) NOT NULL,
+    -- Platform fee routed with the charge when the club has a Connect account
+    application_fee_amount DECIMAL(10, 2),
+    -- pending, paid, expired
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    stripe_checkout_session_id VARCHAR(255) UNIQUE,
+    stripe_payment_intent_id VARCHAR(255),
+    payment_id UUID REFERENCES payments(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    paid_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_orders_user_tier ON ticket_orders(user_id, tier_id);
+CREATE INDEX IF NOT EXISTS idx_ticket_orders_pending
+    ON ticket_orders(expires_at)
+    WHERE status = 'pending';
+
+ALTER TABLE tickets
+    ADD COLUMN IF NOT EXISTS tier_id UUID REFERENCES ticket_tiers(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS order_id UUID REFERENCES ticket_orders(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_tickets_order ON tickets(order_id) WHERE order_id IS NOT NULL;
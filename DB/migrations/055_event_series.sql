@@ -0,0 +1,42 @@
+-- Migration 055: Recurring event series
+-- A series describes a club night that repeats (every Friday, every other Saturday, the first
+-- Friday of each month...). A background job materializes ordinary `events` rows for the
+-- upcoming occurrences; each occurrence can then get tables and reservations like any event.
+-- Deleting an occurrence adds its date to excluded_dates so it is not materialized again.
+
+CREATE TABLE IF NOT EXISTS event_series (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    -- Copied onto every occurrence
+    title VARCHAR(255) NOT NULL,
+    venue VARCHAR(512) NOT NULL,
+    image VARCHAR(512) NOT NULL,
+    time VARCHAR(20),
+    end_time VARCHAR(20),
+    age_limit VARCHAR(10),
+    price VARCHAR(20),
+    description TEXT,
+    genre_ids UUID[] NOT NULL DEFAULT '{}',
+    -- weekly, biweekly or monthly (same weekday of the same week of the month as start_date)
+    frequency VARCHAR(20) NOT NULL CHECK (frequency IN ('weekly', 'biweekly', 'monthly')),
+    start_date DATE NOT NULL,
+    -- NULL: repeats until the series is deactivated
+    end_date DATE,
+    excluded_dates DATE[] NOT NULL DEFAULT '{}',
+    -- Tables of this event are copied onto each new occurrence
+    template_event_id UUID REFERENCES events(id) ON DELETE SET NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT event_series_dates CHECK (end_date IS NULL OR end_date >= start_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_series_club ON event_series(club_id);
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS series_id UUID REFERENCES event_series(id) ON DELETE SET NULL;
+
+-- One occurrence per series and date; makes materialization idempotent
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_series_date
+    ON events(series_id, event_date)
+    WHERE series_id IS NOT NULL;
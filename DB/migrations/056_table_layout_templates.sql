@@ -0,0 +1,50 @@
+-- Migration 056: Reusable table layout templates
+-- A template is a club-level snapshot of an event's tables (with their area, virtual tour hotspot
+-- and images) plus the event's Marzipano scenes. Owners save one from an existing event, edit it,
+-- and apply it to a new event in a single transaction instead of recreating every table.
+
+CREATE TABLE IF NOT EXISTS table_layout_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    -- Copied onto the event when it has no tour configured yet
+    tour_provider VARCHAR(50),
+    marzipano_config JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT table_layout_templates_club_name UNIQUE (club_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS table_layout_template_tables (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_id UUID NOT NULL REFERENCES table_layout_templates(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    zone VARCHAR(100),
+    capacity INT NOT NULL CHECK (capacity > 0),
+    min_spend DECIMAL(10, 2) NOT NULL CHECK (min_spend >= 0),
+    available BOOLEAN NOT NULL DEFAULT TRUE,
+    location_description TEXT,
+    features TEXT[],
+    marzipano_position JSONB,
+    -- NULL falls back to the club's default area when applied
+    area_id UUID REFERENCES areas(id) ON DELETE SET NULL,
+    sort_order INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_table_layout_template_tables_template
+    ON table_layout_template_tables(template_id);
+
+CREATE TABLE IF NOT EXISTS table_layout_template_images (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_table_id UUID NOT NULL REFERENCES table_layout_template_tables(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    display_order INT NOT NULL DEFAULT 0,
+    alt_text TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_table_layout_template_images_table
+    ON table_layout_template_images(template_table_id);
@@ -0,0 +1,29 @@
+-- Migration 057: Event discovery search
+-- events.price and events.age_limit are free text ("20€", "18+"), so numeric copies are kept
+-- as generated columns for range filters, together with a weighted full-text vector over the
+-- title, venue and description. Clubs get optional coordinates for distance sorting.
+
+ALTER TABLE clubs
+    ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION
+        CHECK (latitude BETWEEN -90 AND 90),
+    ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION
+        CHECK (longitude BETWEEN -180 AND 180);
+
+ALTER TABLE events
+    -- First amount in the price text: "da 15,50 €" -> 15.50
+    ADD COLUMN IF NOT EXISTS price_amount NUMERIC GENERATED ALWAYS AS (
+        replace(substring(price FROM '[0-9]{1,7}(?:[.,][0-9]{1,2})?'), ',', '.')::numeric
+    ) STORED,
+    -- "18+" -> 18
+    ADD COLUMN IF NOT EXISTS min_age INT GENERATED ALWAYS AS (
+        substring(age_limit FROM '[0-9]{1,3}')::int
+    ) STORED,
+    ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('italian', coalesce(title, '')), 'A')
+        || setweight(to_tsvector('italian', coalesce(venue, '')), 'B')
+        || setweight(to_tsvector('italian', coalesce(description, '')), 'C')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_events_search_vector ON events USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_events_event_date_id ON events(event_date, id);
+CREATE INDEX IF NOT EXISTS idx_event_genres_genre ON event_genres(genre_id, event_id);
@@ -0,0 +1,22 @@
+-- Migration 058: Club geolocation
+-- latitude/longitude (added in 057) are either geocoded from the address by a background job or
+-- pinned manually by the owner. geocoded_address remembers which address the job last looked up,
+-- so an address change triggers a new lookup and an address the geocoder cannot resolve is not
+-- retried until it changes.
+
+ALTER TABLE clubs
+    ADD COLUMN IF NOT EXISTS coordinates_source VARCHAR(20)
+        CHECK (coordinates_source IN ('geocoder', 'manual')),
+    ADD COLUMN IF NOT EXISTS geocoded_address TEXT,
+    ADD COLUMN IF NOT EXISTS geocoded_at TIMESTAMPTZ;
+
+ALTER TABLE clubs
+    DROP CONSTRAINT IF EXISTS clubs_coordinates_pair;
+ALTER TABLE clubs
+    ADD CONSTRAINT clubs_coordinates_pair
+        CHECK ((latitude IS NULL) = (longitude IS NULL));
+
+-- Bounding-box prefilter of the nearby search
+CREATE INDEX IF NOT EXISTS idx_clubs_coordinates
+    ON clubs(latitude, longitude)
+    WHERE latitude IS NOT NULL;
@@ -0,0 +1,69 @@
+-- Migration 059: Stripe Connect reconciliation
+-- Local mirror of what Stripe moved for each connected club: transfers from the platform
+-- (one per destination charge, with the charge amount and the application fee the platform
+-- kept), payouts to the club's bank and the connected account's balance transactions.
+-- Rows are upserted by Stripe id by the reconciliation job; statements and mismatch flags
+-- are computed from them against `payments`.
+
+CREATE TABLE IF NOT EXISTS connect_transfers (
+    id VARCHAR(255) PRIMARY KEY,                 -- tr_...
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    connected_account_id VARCHAR(255) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,              -- sent to the club
+    amount_reversed DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    currency VARCHAR(3) NOT NULL,
+    charge_id VARCHAR(255),                      -- ch_... the transfer was created from
+    payment_intent_id VARCHAR(255),
+    charge_amount DECIMAL(12, 2),                -- what the guest paid
+    application_fee_amount DECIMAL(12, 2),       -- what the platform kept
+    stripe_created_at TIMESTAMPTZ NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_connect_transfers_club_created
+    ON connect_transfers(club_id, stripe_created_at);
+CREATE INDEX IF NOT EXISTS idx_connect_transfers_payment_intent
+    ON connect_transfers(payment_intent_id);
+
+CREATE TABLE IF NOT EXISTS connect_payouts (
+    id VARCHAR(255) PRIMARY KEY,                 -- po_...
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    connected_account_id VARCHAR(255) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    status VARCHAR(32) NOT NULL,                 -- paid, pending, in_transit, canceled, failed
+    arrival_date DATE NOT NULL,
+    failure_message TEXT,
+    stripe_created_at TIMESTAMPTZ NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_connect_payouts_club_arrival
+    ON connect_payouts(club_id, arrival_date);
+
+CREATE TABLE IF NOT EXISTS connect_balance_transactions (
+    id VARCHAR(255) PRIMARY KEY,                 -- txn_...
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    connected_account_id VARCHAR(255) NOT NULL,
+    type VARCHAR(64) NOT NULL,                   -- payment, payment_refund, payout, adjustment, ...
+    amount DECIMAL(12, 2) NOT NULL,
+    fee DECIMAL(12, 2) NOT NULL DEFAULT 0,
+    net DECIMAL(12, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    source_id VARCHAR(255),
+    available_on DATE,
+    stripe_created_at TIMESTAMPTZ NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_connect_balance_transactions_club_created
+    ON connect_balance_transactions(club_id, stripe_created_at);
+
+-- Where the next sync of each club starts; synced_until stays NULL until the first
+-- successful sync
+CREATE TABLE IF NOT EXISTS connect_sync_state (
+    club_id UUID PRIMARY KEY REFERENCES clubs(id) ON DELETE CASCADE,
+    synced_until TIMESTAMPTZ,
+    last_error TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
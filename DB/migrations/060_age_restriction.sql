@@ -0,0 +1,8 @@
+-- Migration 060: Age restriction enforcement
+-- events.min_age (generated from age_limit in 057) is the structured minimum age checked
+-- against the holder's date of birth on reservations, ticket checkouts and payment links.
+-- Guests paying through a payment link have no account, so the birth date they give is
+-- kept on their share.
+
+ALTER TABLE reservation_payment_shares
+    ADD COLUMN IF NOT EXISTS guest_date_of_birth DATE;
@@ -0,0 +1,34 @@
+-- Migration 061: Durable Stripe webhook inbox
+-- Webhooks are stored verbatim on receipt and acknowledged right away; a background worker
+-- processes them with retries and exponential backoff. An event that keeps failing moves to
+-- 'dead_lettered' until an admin re-runs it. The unique stripe_event_id deduplicates
+-- Stripe's own retries.
+--
+-- processed_stripe_events (migration 034) is no longer written. It is still read on receipt,
+-- so events handled before this migration are not processed twice if Stripe resends them.
+
+CREATE TABLE IF NOT EXISTS stripe_webhook_events (
+    id UUID PRIMARY KEY,
+    stripe_event_id TEXT NOT NULL UNIQUE,
+    event_type TEXT NOT NULL,
+    -- Request body exactly as Stripe sent it
+    payload TEXT NOT NULL,
+    status VARCHAR(32) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'processing', 'failed', 'processed', 'dead_lettered')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    available_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_at TIMESTAMPTZ,
+    dead_lettered_at TIMESTAMPTZ,
+    -- How many times an admin sent the event back to the queue
+    replay_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_stripe_webhook_events_due
+    ON stripe_webhook_events(available_at, received_at)
+    WHERE status IN ('pending', 'failed', 'processing');
+
+CREATE INDEX IF NOT EXISTS idx_stripe_webhook_events_status_received
+    ON stripe_webhook_events(status, received_at DESC);
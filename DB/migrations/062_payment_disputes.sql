@@ -0,0 +1,36 @@
+-- Migration 062: Stripe disputes
+-- charge.dispute.created / charge.dispute.closed are recorded per dispute, linked to the
+-- payment, reservation share or ticket order they were opened against and to its club.
+-- A disputed share is frozen: cancellation refunds skip it until the dispute is won or
+-- closed with a warning. A lost dispute leaves it frozen.
+
+CREATE TABLE IF NOT EXISTS payment_disputes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    stripe_dispute_id VARCHAR(255) NOT NULL UNIQUE,
+    stripe_charge_id VARCHAR(255),
+    stripe_payment_intent_id VARCHAR(255),
+    payment_id UUID REFERENCES payments(id) ON DELETE SET NULL,
+    payment_share_id UUID REFERENCES reservation_payment_shares(id) ON DELETE SET NULL,
+    reservation_id UUID REFERENCES table_reservations(id) ON DELETE SET NULL,
+    ticket_order_id UUID REFERENCES ticket_orders(id) ON DELETE SET NULL,
+    club_id UUID REFERENCES clubs(id) ON DELETE SET NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    reason VARCHAR(64),
+    -- Stripe dispute status: warning_needs_response, needs_response, under_review, won, lost, ...
+    status VARCHAR(32) NOT NULL,
+    evidence_due_by TIMESTAMPTZ,
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_disputes_club_opened
+    ON payment_disputes(club_id, opened_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_payment_disputes_open_share
+    ON payment_disputes(payment_share_id)
+    WHERE closed_at IS NULL;
+
+ALTER TABLE reservation_payment_shares
+    ADD COLUMN IF NOT EXISTS frozen_at TIMESTAMPTZ;
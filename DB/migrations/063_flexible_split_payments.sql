@@ -0,0 +1,28 @@
+-- Migration 063: Uneven and flexible split payments
+-- The organizer of a shared reservation chooses how the table is split:
+--   equal    - every slot costs the same, guests buy open slots on the shared link
+--              (several at once to pay for friends); the organizer may cover N slots
+--   custom   - fixed amount per named guest
+--   weighted - amounts in proportion to each person's weight
+-- Custom and weighted splits create one 'reserved' share per named guest at booking.
+-- Anyone with the shared link can pay it; an unpaid checkout puts it back to 'reserved'.
+
+ALTER TABLE table_reservations
+    ADD COLUMN IF NOT EXISTS split_mode VARCHAR(16) NOT NULL DEFAULT 'equal'
+        CHECK (split_mode IN ('equal', 'custom', 'weighted'));
+
+-- slots:          people the share pays for (the organizer's covered slots on the owner share)
+-- planned_amount: amount set by the organizer for a named allocation, NULL for open-slot shares
+-- weight:         the allocation's weight in a weighted split
+-- paid_by_phone:  who paid a named allocation, when it is not the named guest
+-- checkout_started_at: start of the current checkout, for share expiry
+ALTER TABLE reservation_payment_shares
+    ADD COLUMN IF NOT EXISTS slots INTEGER NOT NULL DEFAULT 1 CHECK (slots > 0),
+    ADD COLUMN IF NOT EXISTS planned_amount DECIMAL(10, 2),
+    ADD COLUMN IF NOT EXISTS weight DECIMAL(8, 2),
+    ADD COLUMN IF NOT EXISTS paid_by_phone VARCHAR(50),
+    ADD COLUMN IF NOT EXISTS checkout_started_at TIMESTAMPTZ;
+
+UPDATE reservation_payment_shares
+SET checkout_started_at = created_at
+WHERE status = 'checkout_pending' AND checkout_started_at IS NULL;
@@ -0,0 +1,87 @@
+-- Migration 064: Ticket and reservation transfers
+-- A holder hands a ticket or reservation over to someone identified by phone number or
+-- email. Once the recipient accepts in-app, ownership moves to them and a new code (and
+-- so a new QR) is issued; the old code stops scanning. Owners can turn transfers off per
+-- event, and every step is written to an append-only audit log.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS transfers_enabled BOOLEAN NOT NULL DEFAULT TRUE;
+
+CREATE TABLE IF NOT EXISTS ownership_transfers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- ticket, reservation
+    item_type VARCHAR(20) NOT NULL CHECK (item_type IN ('ticket', 'reservation')),
+    ticket_id UUID REFERENCES tickets(id) ON DELETE CASCADE,
+    reservation_id UUID REFERENCES table_reservations(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    from_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Set when the recipient accepts
+    to_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    recipient_phone VARCHAR(50),
+    recipient_email VARCHAR(255),
+    -- pending, accepted, declined, cancelled, expired
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'accepted', 'declined', 'cancelled', 'expired')),
+    expires_at TIMESTAMPTZ NOT NULL,
+    responded_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (recipient_phone IS NOT NULL OR recipient_email IS NOT NULL),
+    CHECK (
+        (item_type = 'ticket' AND ticket_id IS NOT NULL AND reservation_id IS NULL)
+        OR (item_type = 'reservation' AND reservation_id IS NOT NULL AND ticket_id IS NULL)
+    )
+);
+
+-- At most one open transfer per ticket or reservation
+CREATE UNIQUE INDEX IF NOT EXISTS idx_ownership_transfers_pending_ticket
+    ON ownership_transfers(ticket_id) WHERE status = 'pending';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_ownership_transfers_pending_reservation
+    ON ownership_transfers(reservation_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_ownership_transfers_recipient_phone
+    ON ownership_transfers(recipient_phone) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_ownership_transfers_recipient_email
+    ON ownership_transfers(LOWER(recipient_email)) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_ownership_transfers_expiry
+    ON ownership_transfers(expires_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_ownership_transfers_from_user
+    ON ownership_transfers(from_user_id, created_at DESC);
+
+-- No foreign keys, like checkin_audit_log: rows outlive what they describe.
+CREATE TABLE IF NOT EXISTS transfer_audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    transfer_id UUID NOT NULL,
+    event_id UUID NOT NULL,
+    item_type VARCHAR(20) NOT NULL,
+    ticket_id UUID,
+    reservation_id UUID,
+    -- User who performed the action; NULL when the system expired the transfer
+    actor_user_id UUID,
+    -- initiated, accepted, declined, cancelled, expired
+    action VARCHAR(20) NOT NULL,
+    from_user_id UUID NOT NULL,
+    to_user_id UUID,
+    recipient_phone VARCHAR(50),
+    recipient_email VARCHAR(255),
+    -- Codes replaced and issued on acceptance
+    old_code VARCHAR(100),
+    new_code VARCHAR(100),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_transfer_audit_log_event
+    ON transfer_audit_log(event_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_transfer_audit_log_transfer
+    ON transfer_audit_log(transfer_id, created_at);
+
+CREATE OR REPLACE FUNCTION transfer_audit_log_append_only()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'transfer_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_transfer_audit_log_append_only ON transfer_audit_log;
+CREATE TRIGGER trg_transfer_audit_log_append_only
+    BEFORE UPDATE OR DELETE ON transfer_audit_log
+    FOR EACH ROW EXECUTE FUNCTION transfer_audit_log_append_only();
@@ -0,0 +1,70 @@
+-- Migration 065: Minimum-spend tabs
+-- Hosts open a tab on a checked-in reservation and record what the table consumes during
+-- the night. The tab's minimum is the table's total minimum spend (min_spend x capacity)
+-- at opening. At close the table pays max(consumed, minimum), less what was prepaid for
+-- the reservation, by Stripe Checkout or as cash entered by staff. The final spend feeds
+-- owner analytics.
+
+CREATE TABLE IF NOT EXISTS table_tabs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reservation_id UUID NOT NULL UNIQUE REFERENCES table_reservations(id) ON DELETE CASCADE,
+    table_id UUID NOT NULL REFERENCES tables(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    club_id UUID NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+    -- open, settling (a Stripe payment is in progress), closed
+    status VARCHAR(20) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'settling', 'closed')),
+    minimum_spend DECIMAL(10, 2) NOT NULL CHECK (minimum_spend >= 0),
+    -- Owner or staff account
+    opened_by UUID NOT NULL,
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_by UUID,
+    closed_at TIMESTAMPTZ,
+    -- max(consumed, minimum_spend), set at close
+    final_spend DECIMAL(10, 2),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_table_tabs_event ON table_tabs(event_id, status);
+CREATE INDEX IF NOT EXISTS idx_table_tabs_club ON table_tabs(club_id, closed_at DESC);
+
+-- Voided lines are kept for the record and left out of the totals
+CREATE TABLE IF NOT EXISTS table_tab_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tab_id UUID NOT NULL REFERENCES table_tabs(id) ON DELETE CASCADE,
+    -- bottle, drink, service, other
+    category VARCHAR(20) NOT NULL CHECK (category IN ('bottle', 'drink', 'service', 'other')),
+    description VARCHAR(255) NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    unit_price DECIMAL(10, 2) NOT NULL CHECK (unit_price >= 0),
+    amount DECIMAL(10, 2) NOT NULL,
+    added_by UUID NOT NULL,
+    voided_at TIMESTAMPTZ,
+    voided_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_table_tab_lines_tab ON table_tab_lines(tab_id, created_at);
+
+CREATE TABLE IF NOT EXISTS table_tab_settlements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tab_id UUID NOT NULL REFERENCES table_tabs(id) ON DELETE CASCADE,
+    -- cash, stripe
+    method VARCHAR(20) NOT NULL CHECK (method IN ('cash', 'stripe')),
+    amount DECIMAL(10, 2) NOT NULL CHECK (amount > 0),
+    -- pending (Stripe checkout open), paid, expired
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'paid', 'expired')),
+    stripe_checkout_session_id VARCHAR(255),
+    stripe_payment_intent_id VARCHAR(255),
+    payment_id UUID REFERENCES payments(id) ON DELETE SET NULL,
+    recorded_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    paid_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_table_tab_settlements_tab ON table_tab_settlements(tab_id);
+CREATE INDEX IF NOT EXISTS idx_table_tab_settlements_session
+    ON table_tab_settlements(stripe_checkout_session_id)
+    WHERE stripe_checkout_session_id IS NOT NULL;
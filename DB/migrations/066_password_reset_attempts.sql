@@ -0,0 +1,16 @@
+-- Migration 066: Failed password reset attempts
+-- Every rejected reset code is logged with the caller's IP address, including guesses
+-- against unknown emails, so one address cannot brute-force codes across accounts. The
+-- per-code counter on password_reset_codes still locks a single code after too many
+-- wrong guesses.
+
+CREATE TABLE IF NOT EXISTS password_reset_failures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    requested_ip VARCHAR(64) NOT NULL,
+    -- NULL when no redeemable code exists for the email
+    code_id UUID REFERENCES password_reset_codes(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_reset_failures_ip
+    ON password_reset_failures(requested_ip, created_at DESC);
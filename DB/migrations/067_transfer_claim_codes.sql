@@ -0,0 +1,9 @@
+-- Migration 067: Claim codes for transfers sent by email
+-- Account emails are not verified, so a transfer addressed to an email carries a six-digit
+-- code sent to that address; the recipient enters it to accept. Only the SHA-256 hash is
+-- stored, and the code stops working after too many wrong guesses. Transfers to a phone
+-- number are accepted through the recipient's verified phone and need no code.
+
+ALTER TABLE ownership_transfers
+    ADD COLUMN IF NOT EXISTS claim_code_hash VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS failed_claim_attempts INTEGER NOT NULL DEFAULT 0;
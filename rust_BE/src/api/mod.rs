@@ -58,6 +58,9 @@ pub fn build_router(app_state: Arc<AppState>) -> Router {
         .merge(crate::api::routers::payments::router())
         .merge(crate::api::routers::areas::router())
         .merge(crate::api::routers::webhooks::router())
+        .merge(crate::api::routers::waitlist::router())
+        .merge(crate::api::routers::transfers::router())
+        .merge(crate::api::routers::admin::router())
         .with_state(app_state)
         .layer(from_fn(crate::middleware::request_id::trace_request))
         .layer(set_request_id)
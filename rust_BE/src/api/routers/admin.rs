@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{get, patch, post, put},
+    Router,
+};
+
+use crate::bootstrap::state::AppState;
+use crate::controllers::club_controller::{create_club, delete_club, update_club};
+use crate::controllers::connect_reconciliation_controller::get_platform_finance_report;
+use crate::controllers::genre_controller::{create_genre, delete_genre, update_genre};
+use crate::controllers::outbox_admin_controller::{
+    discard_dead_letter, get_outbox_event, get_push_delivery_metrics, list_dead_letters,
+    replay_dead_letter,
+};
+use crate::controllers::platform_admin_controller::{
+    cancel_reservation, create_admin, list_admins, list_clubs, list_owners, list_refunds,
+    update_admin_status, update_club_commission, update_owner_status,
+};
+use crate::controllers::stripe_webhook_admin_controller::{
+    get_webhook_event, list_webhook_events, replay_webhook_event,
+};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/admin/admins", get(list_admins).post(create_admin))
+        .route("/admin/admins/:id", patch(update_admin_status))
+        .route("/admin/clubs", get(list_clubs).post(create_club))
+        .route("/admin/clubs/:id", put(update_club).delete(delete_club))
+        .route("/admin/clubs/:id/commission", put(update_club_commission))
+        .route("/admin/finance/report", get(get_platform_finance_report))
+        .route("/admin/genres", post(create_genre))
+        .route("/admin/genres/:id", put(update_genre).delete(delete_genre))
+        .route("/admin/owners", get(list_owners))
+        .route("/admin/owners/:id", patch(update_owner_status))
+        .route("/admin/refunds", get(list_refunds))
+        .route("/admin/reservations/:id/cancel", post(cancel_reservation))
+        .route("/admin/outbox/dead-letters", get(list_dead_letters))
+        .route("/admin/outbox/events/:id", get(get_outbox_event))
+        .route(
+            "/admin/outbox/dead-letters/:id/replay",
+            post(replay_dead_letter),
+        )
+        .route(
+            "/admin/outbox/dead-letters/:id/discard",
+            post(discard_dead_letter),
+        )
+        .route("/admin/push/metrics", get(get_push_delivery_metrics))
+        .route("/admin/stripe/webhook-events", get(list_webhook_events))
+        .route("/admin/stripe/webhook-events/:id", get(get_webhook_event))
+        .route(
+            "/admin/stripe/webhook-events/:id/replay",
+            post(replay_webhook_event),
+        )
+}
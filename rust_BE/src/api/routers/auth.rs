@@ -10,12 +10,17 @@ use tower_governor::{
 
 use crate::bootstrap::state::AppState;
 use crate::controllers::auth_controller::{
-    change_password, delete_account, login, register, register_push_token, send_sms_verification,
-    verify_sms_code,
+    change_password, delete_account, login, logout, refresh, register, register_push_token,
+    send_sms_verification, unregister_push_token, verify_sms_code,
 };
 use crate::controllers::club_owner_controller::{
     change_club_owner_password, login_club_owner, register_club_owner,
 };
+use crate::controllers::password_reset_controller::{
+    forgot_club_owner_password, forgot_password, reset_club_owner_password, reset_password,
+};
+use crate::controllers::platform_admin_controller::login_admin;
+use crate::controllers::staff_controller::{accept_staff_invite, login_staff};
 
 pub fn router() -> Router<Arc<AppState>> {
     let auth_governor_conf = Arc::new(
@@ -30,17 +35,35 @@ pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/auth/register", post(register))
         .route("/auth/login", post(login))
+        .route("/auth/refresh", post(refresh))
+        .route("/auth/logout", post(logout))
         .route("/auth/change-password", post(change_password))
+        .route("/auth/forgot-password", post(forgot_password))
+        .route("/auth/reset-password", post(reset_password))
         .route("/auth/account", delete(delete_account))
         .route("/auth/send-sms-verification", post(send_sms_verification))
         .route("/auth/verify-sms-code", post(verify_sms_code))
-        .route("/auth/push-token", post(register_push_token))
+        .route(
+            "/auth/push-token",
+            post(register_push_token).delete(unregister_push_token),
+        )
         .route("/auth/club-owner/register", post(register_club_owner))
         .route("/auth/club-owner/login", post(login_club_owner))
         .route(
             "/auth/club-owner/change-password",
             post(change_club_owner_password),
         )
+        .route(
+            "/auth/club-owner/forgot-password",
+            post(forgot_club_owner_password),
+        )
+        .route(
+            "/auth/club-owner/reset-password",
+            post(reset_club_owner_password),
+        )
+        .route("/auth/staff/login", post(login_staff))
+        .route("/auth/staff/accept-invite", post(accept_staff_invite))
+        .route("/auth/admin/login", post(login_admin))
         .layer(GovernorLayer {
             config: auth_governor_conf,
         })
@@ -4,11 +4,12 @@ use axum::{routing::get, Router};
 
 use crate::bootstrap::state::AppState;
 use crate::controllers::club_controller::{
-    create_club, delete_club, get_all_clubs, get_club, update_club,
+    create_club, delete_club, get_all_clubs, get_club, get_nearby, update_club,
 };
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/clubs", get(get_all_clubs).post(create_club))
+        .route("/clubs/nearby", get(get_nearby))
         .route(
             "/clubs/:id",
             get(get_club).put(update_club).delete(delete_club),
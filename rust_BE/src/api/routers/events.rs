@@ -4,12 +4,13 @@ use axum::{routing::get, Router};
 
 use crate::bootstrap::state::AppState;
 use crate::controllers::event_controller::{
-    create_event, delete_event, get_all_events, get_event, update_event,
+    create_event, delete_event, get_all_events, get_event, search_events, update_event,
 };
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/events", get(get_all_events).post(create_event))
+        .route("/events/search", get(search_events))
         .route(
             "/events/:id",
             get(get_event).put(update_event).delete(delete_event),
@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod areas;
 pub mod auth;
 pub mod clubs;
@@ -7,4 +8,6 @@ pub mod owner;
 pub mod payments;
 pub mod reservations;
 pub mod tickets;
+pub mod transfers;
+pub mod waitlist;
 pub mod webhooks;
@@ -6,13 +6,38 @@ use crate::bootstrap::state::AppState;
 use crate::controllers::club_owner_controller::{
     add_my_club_image, add_table_image_handler, checkin_handler, create_club_event,
     create_club_table, create_manual_reservation_handler, create_my_club_stripe_onboarding_link,
-    delete_club_event, delete_my_club_image, delete_table_image_handler,
-    get_event_reservations_handler, get_my_club, get_my_club_events, get_my_club_images,
-    get_my_club_stripe_status, get_my_club_tables, get_owner_stats_handler,
-    get_table_images_handler, scan_code_handler, update_club_event, update_my_club,
+    create_my_promo_code, delete_club_event, delete_my_club_image, delete_table_image_handler,
+    get_checkin_audit_handler, get_event_reservations_handler, get_event_waitlist_handler,
+    get_my_cancellation_policy, get_my_club, get_my_club_events, get_my_club_images,
+    get_my_club_stripe_status, get_my_club_tables, get_my_promo_codes, get_owner_stats_handler,
+    get_table_images_handler, reorder_event_waitlist_handler, scan_code_handler, update_club_event,
+    update_my_cancellation_policy, update_my_club, update_my_promo_code,
     update_reservation_status_handler,
 };
+use crate::controllers::connect_reconciliation_controller::get_my_finance_statement;
 use crate::controllers::event_image_controller::upload_event_image;
+use crate::controllers::event_series_controller::{
+    create_my_event_series, get_my_event_series, get_my_event_series_events, update_my_event_series,
+};
+use crate::controllers::guest_export_controller::{
+    export_event_guests, export_event_reservations, export_event_tickets,
+};
+use crate::controllers::owner_analytics_controller::get_my_analytics;
+use crate::controllers::staff_controller::{
+    invite_staff_member, list_my_staff, revoke_staff_member, update_staff_member,
+};
+use crate::controllers::table_layout_controller::{
+    apply_table_layout_to_event, create_my_table_layout, delete_my_table_layout,
+    get_my_table_layout, get_my_table_layouts, update_my_table_layout,
+};
+use crate::controllers::table_tab_controller::{
+    add_tab_line_handler, cancel_tab_settlement_handler, get_event_tabs_handler,
+    get_reservation_tab_handler, open_tab_handler, settle_tab_handler, void_tab_line_handler,
+};
+use crate::controllers::ticket_tier_controller::{
+    create_my_ticket_tier, delete_my_ticket_tier, get_my_event_ticket_tiers, update_my_ticket_tier,
+};
+use crate::controllers::transfer_controller::get_event_transfers_handler;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -22,6 +47,18 @@ pub fn router() -> Router<Arc<AppState>> {
             "/owner/club/stripe/connect",
             axum::routing::post(create_my_club_stripe_onboarding_link),
         )
+        .route(
+            "/owner/club/cancellation-policy",
+            get(get_my_cancellation_policy).put(update_my_cancellation_policy),
+        )
+        .route(
+            "/owner/promo-codes",
+            get(get_my_promo_codes).post(create_my_promo_code),
+        )
+        .route(
+            "/owner/promo-codes/:id",
+            axum::routing::patch(update_my_promo_code),
+        )
         .route(
             "/owner/club/images",
             get(get_my_club_images).post(add_my_club_image),
@@ -42,22 +79,97 @@ pub fn router() -> Router<Arc<AppState>> {
             "/owner/events/:event_id",
             axum::routing::put(update_club_event).delete(delete_club_event),
         )
+        .route(
+            "/owner/event-series",
+            get(get_my_event_series).post(create_my_event_series),
+        )
+        .route(
+            "/owner/event-series/:id",
+            axum::routing::patch(update_my_event_series),
+        )
+        .route(
+            "/owner/event-series/:id/events",
+            get(get_my_event_series_events),
+        )
         .route(
             "/owner/events/:event_id/tables",
             get(get_my_club_tables).post(create_club_table),
         )
+        .route(
+            "/owner/events/:event_id/table-layout",
+            axum::routing::post(apply_table_layout_to_event),
+        )
+        .route(
+            "/owner/table-layouts",
+            get(get_my_table_layouts).post(create_my_table_layout),
+        )
+        .route(
+            "/owner/table-layouts/:id",
+            get(get_my_table_layout)
+                .patch(update_my_table_layout)
+                .delete(delete_my_table_layout),
+        )
+        .route(
+            "/owner/events/:event_id/ticket-tiers",
+            get(get_my_event_ticket_tiers).post(create_my_ticket_tier),
+        )
+        .route(
+            "/owner/ticket-tiers/:id",
+            axum::routing::patch(update_my_ticket_tier).delete(delete_my_ticket_tier),
+        )
         .route(
             "/owner/events/:event_id/reservations",
             get(get_event_reservations_handler),
         )
+        .route(
+            "/owner/events/:event_id/export/reservations",
+            get(export_event_reservations),
+        )
+        .route(
+            "/owner/events/:event_id/export/guests",
+            get(export_event_guests),
+        )
+        .route(
+            "/owner/events/:event_id/export/tickets",
+            get(export_event_tickets),
+        )
         .route(
             "/owner/events/:event_id/reservations/manual",
             axum::routing::post(create_manual_reservation_handler),
         )
+        .route(
+            "/owner/events/:event_id/waitlist",
+            get(get_event_waitlist_handler).put(reorder_event_waitlist_handler),
+        )
+        .route(
+            "/owner/events/:event_id/transfers",
+            get(get_event_transfers_handler),
+        )
+        .route("/owner/events/:event_id/tabs", get(get_event_tabs_handler))
         .route(
             "/owner/reservations/:id/status",
             axum::routing::patch(update_reservation_status_handler),
         )
+        .route(
+            "/owner/reservations/:id/tab",
+            get(get_reservation_tab_handler).post(open_tab_handler),
+        )
+        .route(
+            "/owner/tabs/:id/lines",
+            axum::routing::post(add_tab_line_handler),
+        )
+        .route(
+            "/owner/tabs/:id/lines/:line_id",
+            axum::routing::delete(void_tab_line_handler),
+        )
+        .route(
+            "/owner/tabs/:id/settle",
+            axum::routing::post(settle_tab_handler),
+        )
+        .route(
+            "/owner/tabs/:id/settle/cancel",
+            axum::routing::post(cancel_tab_settlement_handler),
+        )
         .route(
             "/owner/tables/:id/images",
             get(get_table_images_handler).post(add_table_image_handler),
@@ -68,5 +180,13 @@ pub fn router() -> Router<Arc<AppState>> {
         )
         .route("/owner/scan/:code", get(scan_code_handler))
         .route("/owner/checkin/:code", axum::routing::post(checkin_handler))
+        .route("/owner/checkins", get(get_checkin_audit_handler))
         .route("/owner/stats", get(get_owner_stats_handler))
+        .route("/owner/analytics", get(get_my_analytics))
+        .route("/owner/finance/statement", get(get_my_finance_statement))
+        .route("/owner/staff", get(list_my_staff).post(invite_staff_member))
+        .route(
+            "/owner/staff/:id",
+            axum::routing::patch(update_staff_member).delete(revoke_staff_member),
+        )
 }
@@ -4,14 +4,14 @@ use axum::{routing::get, Router};
 
 use crate::bootstrap::state::AppState;
 use crate::controllers::table_controller::{
-    add_payment_to_reservation, create_payment_intent, create_payment_link_checkout,
-    create_reservation, create_reservation_with_payment, create_table, delete_reservation,
-    delete_table, get_all_reservations, get_all_tables, get_available_tables_by_event,
-    get_payment_link_preview, get_reservation, get_reservation_by_code,
-    get_reservation_payment_status, get_reservations_by_table, get_table, get_tables_by_event,
-    get_tickets_for_reservation, get_user_reservations_with_details, guest_payment_page,
-    link_ticket_to_reservation, payment_cancel_page, payment_success_page, update_reservation,
-    update_table,
+    add_payment_to_reservation, cancel_reservation, create_payment_intent,
+    create_payment_link_checkout, create_reservation, create_reservation_with_payment,
+    create_table, delete_reservation, delete_table, get_all_reservations, get_all_tables,
+    get_available_tables_by_event, get_payment_link_preview, get_reservation,
+    get_reservation_by_code, get_reservation_payment_status, get_reservations_by_table, get_table,
+    get_tables_by_event, get_tickets_for_reservation, get_user_reservations_with_details,
+    guest_payment_page, link_ticket_to_reservation, payment_cancel_page, payment_success_page,
+    update_reservation, update_table,
 };
 
 pub fn router() -> Router<Arc<AppState>> {
@@ -33,6 +33,10 @@ pub fn router() -> Router<Arc<AppState>> {
                 .put(update_reservation)
                 .delete(delete_reservation),
         )
+        .route(
+            "/reservations/:id/cancel",
+            axum::routing::post(cancel_reservation),
+        )
         .route("/reservations/code/:code", get(get_reservation_by_code))
         .route(
             "/reservations/user/:user_id",
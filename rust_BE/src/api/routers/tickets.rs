@@ -7,6 +7,7 @@ use crate::controllers::ticket_controller::{
     create_ticket, delete_ticket, get_all_tickets, get_ticket, get_ticket_by_code,
     get_user_tickets_with_events, update_ticket,
 };
+use crate::controllers::ticket_tier_controller::{create_ticket_checkout, list_event_ticket_tiers};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -17,4 +18,9 @@ pub fn router() -> Router<Arc<AppState>> {
         )
         .route("/tickets/code/:code", get(get_ticket_by_code))
         .route("/tickets/user/:user_id", get(get_user_tickets_with_events))
+        .route("/events/:id/ticket-tiers", get(list_event_ticket_tiers))
+        .route(
+            "/ticket-tiers/:id/checkout",
+            axum::routing::post(create_ticket_checkout),
+        )
 }
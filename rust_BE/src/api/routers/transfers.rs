@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::bootstrap::state::AppState;
+use crate::controllers::transfer_controller::{
+    accept_transfer, cancel_transfer, decline_transfer, list_my_transfers, transfer_reservation,
+    transfer_ticket,
+};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/tickets/:id/transfer", post(transfer_ticket))
+        .route("/reservations/:id/transfer", post(transfer_reservation))
+        .route("/transfers", get(list_my_transfers))
+        .route("/transfers/:id/accept", post(accept_transfer))
+        .route("/transfers/:id/decline", post(decline_transfer))
+        .route("/transfers/:id/cancel", post(cancel_transfer))
+}
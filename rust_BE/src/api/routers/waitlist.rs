@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Router};
+
+use crate::bootstrap::state::AppState;
+use crate::controllers::waitlist_controller::{
+    get_my_waitlist_entry, join_waitlist, leave_waitlist,
+};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/events/:id/waitlist",
+        get(get_my_waitlist_entry)
+            .post(join_waitlist)
+            .delete(leave_waitlist),
+    )
+}
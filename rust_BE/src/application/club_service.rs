@@ -1 +1,56 @@
 pub use crate::infrastructure::repositories::club_repository::*;
+
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::application::event_service;
+use crate::infrastructure::repositories::club_repository;
+use crate::middleware::auth::ClubManager;
+use crate::models::Event;
+
+/// Club a legacy CRUD caller acts for: `None` for platform admins (every club),
+/// the owned club for club owners (404 when they have none yet).
+pub async fn managed_club_id(
+    pool: &PgPool,
+    manager: &ClubManager,
+) -> Result<Option<Uuid>, StatusCode> {
+    match manager {
+        ClubManager::Admin(_) => Ok(None),
+        ClubManager::Owner(owner_id) => {
+            let club = club_repository::get_club_by_owner_id(pool, *owner_id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            Ok(Some(club.id))
+        }
+    }
+}
+
+/// 403 unless the caller may manage `club_id`. Data not attached to any club is
+/// admin-only.
+pub async fn ensure_manages_club(
+    pool: &PgPool,
+    manager: &ClubManager,
+    club_id: Option<Uuid>,
+) -> Result<(), StatusCode> {
+    match managed_club_id(pool, manager).await? {
+        None => Ok(()),
+        Some(own_club_id) if club_id == Some(own_club_id) => Ok(()),
+        Some(_) => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+/// Load an event the caller may manage (404 when it does not exist).
+pub async fn ensure_manages_event(
+    pool: &PgPool,
+    manager: &ClubManager,
+    event_id: Uuid,
+) -> Result<Event, StatusCode> {
+    let event = event_service::get_event_by_id(pool, event_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    ensure_manages_club(pool, manager, event.club_id).await?;
+    Ok(event)
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::infrastructure::repositories::connect_reconciliation_repository;
+use crate::models::connect_reconciliation::{
+    ClubFinanceSummary, ConnectStatementResponse, ConnectTotals, FinancePeriod,
+    PlatformFinanceReportResponse, ReconciliationIssue, ReconciliationPairRow,
+};
+
+pub use crate::infrastructure::repositories::connect_reconciliation_repository::*;
+
+fn classify_pairs(
+    pairs: Vec<ReconciliationPairRow>,
+    now: DateTime<Utc>,
+) -> Vec<ReconciliationIssue> {
+    pairs
+        .into_iter()
+        .filter_map(|pair| {
+            pair.classify(now)
+                .map(|kind| ReconciliationIssue::from_pair(kind, pair))
+        })
+        .collect()
+}
+
+/// Statement of one club for the period: Stripe totals next to the local
+/// ledger, the payouts and every mismatch found.
+pub async fn build_club_statement(
+    pool: &PgPool,
+    club_id: Uuid,
+    period: FinancePeriod,
+    now: DateTime<Utc>,
+) -> sqlx::Result<ConnectStatementResponse> {
+    let (totals, payouts, pairs) = tokio::try_join!(
+        connect_reconciliation_repository::get_club_totals(
+            pool,
+            Some(club_id),
+            period.from,
+            period.to
+        ),
+        connect_reconciliation_repository::get_payouts(
+            pool,
+            Some(club_id),
+            period.from,
+            period.to,
+            false
+        ),
+        connect_reconciliation_repository::get_reconciliation_pairs(
+            pool,
+            Some(club_id),
+            period.from,
+            period.to
+        ),
+    )?;
+
+    let mut issues = classify_pairs(pairs, now);
+    issues.extend(
+        payouts
+            .iter()
+            .filter(|payout| payout.status == "failed")
+            .map(ReconciliationIssue::failed_payout),
+    );
+
+    let row = totals.into_iter().next();
+    Ok(ConnectStatementResponse {
+        period,
+        connected_account_id: row
+            .as_ref()
+            .and_then(|row| row.connected_account_id.clone()),
+        synced_until: row.as_ref().and_then(|row| row.synced_until),
+        last_sync_error: row.as_ref().and_then(|row| row.last_sync_error.clone()),
+        totals: row.map(|row| row.totals).unwrap_or_default(),
+        payouts,
+        issues,
+    })
+}
+
+/// Platform-wide report for the period: one summary per connected club,
+/// their sum and every mismatch found.
+pub async fn build_platform_report(
+    pool: &PgPool,
+    period: FinancePeriod,
+    now: DateTime<Utc>,
+) -> sqlx::Result<PlatformFinanceReportResponse> {
+    let (rows, failed_payouts, pairs) = tokio::try_join!(
+        connect_reconciliation_repository::get_club_totals(pool, None, period.from, period.to),
+        connect_reconciliation_repository::get_payouts(pool, None, period.from, period.to, true),
+        connect_reconciliation_repository::get_reconciliation_pairs(
+            pool,
+            None,
+            period.from,
+            period.to
+        ),
+    )?;
+
+    let mut issues = classify_pairs(pairs, now);
+    issues.extend(
+        failed_payouts
+            .iter()
+            .map(ReconciliationIssue::failed_payout),
+    );
+    let mut issues_per_club: HashMap<Uuid, usize> = HashMap::new();
+    for issue in &issues {
+        *issues_per_club.entry(issue.club_id).or_default() += 1;
+    }
+
+    let mut totals = ConnectTotals::default();
+    let clubs = rows
+        .into_iter()
+        .map(|row| {
+            totals.add(&row.totals);
+            ClubFinanceSummary {
+                issues: issues_per_club.get(&row.club_id).copied().unwrap_or(0),
+                club_id: row.club_id,
+                club_name: row.club_name,
+                connected_account_id: row.connected_account_id,
+                synced_until: row.synced_until,
+                last_sync_error: row.last_sync_error,
+                totals: row.totals,
+            }
+        })
+        .collect();
+
+    Ok(PlatformFinanceReportResponse {
+        period,
+        totals,
+        clubs,
+        issues,
+    })
+}
@@ -0,0 +1,215 @@
+use axum::http::StatusCode;
+use tracing::{error, info, warn};
+
+use crate::application::outbox_service;
+use crate::infrastructure::repositories::dispute_repository;
+use crate::models::dispute::{self, DisputeContext, PaymentDispute, StripeDispute};
+use crate::models::AppState;
+
+/// Handles `charge.dispute.created` and `charge.dispute.closed`: records the
+/// dispute, freezes the share it was opened against while it is open, and
+/// tells the club owner when it opens and when it closes. Safe to run again.
+pub async fn handle_dispute_event(state: &AppState, object: &serde_json::Value) -> StatusCode {
+    let Some(stripe_dispute) = StripeDispute::from_object(object) else {
+        warn!("Stripe dispute event without id or status, skipping");
+        return StatusCode::OK;
+    };
+
+    let context = match stripe_dispute.payment_intent_id.as_deref() {
+        Some(intent_id) => {
+            match dispute_repository::find_dispute_context(&state.db_pool, intent_id).await {
+                Ok(context) => context,
+                Err(e) => {
+                    error!(error = %e, dispute_id = %stripe_dispute.id, "Failed to resolve disputed payment");
+                    return StatusCode::INTERNAL_SERVER_ERROR;
+                }
+            }
+        }
+        None => DisputeContext::default(),
+    };
+
+    let recorded = match dispute_repository::record_dispute(
+        &state.db_pool,
+        &stripe_dispute,
+        dispute::evidence_due_by(object),
+        &context,
+    )
+    .await
+    {
+        Ok(recorded) => recorded,
+        Err(e) => {
+            error!(error = %e, dispute_id = %stripe_dispute.id, "Failed to record Stripe dispute");
+            let _ = outbox_service::enqueue_analytics_error(
+                &state.db_pool,
+                &state.config,
+                "stripe_dispute_record_failed",
+                None,
+                Some("payment"),
+                context.payment_id,
+                &e.to_string(),
+                serde_json::json!({ "dispute_id": stripe_dispute.id }),
+            )
+            .await;
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+    let dispute = &recorded.dispute;
+
+    if let Some(share_id) = dispute.payment_share_id {
+        let updated = if dispute.keeps_share_frozen() {
+            dispute_repository::freeze_share(&state.db_pool, share_id).await
+        } else {
+            dispute_repository::release_share(&state.db_pool, share_id).await
+        };
+        if let Err(e) = updated {
+            error!(error = %e, share_id = %share_id, "Failed to update disputed share");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    }
+
+    let opened = recorded.newly_opened();
+    let closed = recorded.newly_closed();
+    info!(
+        dispute_id = %dispute.stripe_dispute_id,
+        status = %dispute.status,
+        amount = %dispute.amount,
+        opened,
+        closed,
+        "Stripe dispute recorded"
+    );
+    if opened || closed {
+        let _ = outbox_service::enqueue_analytics_event(
+            &state.db_pool,
+            &state.config,
+            if closed {
+                "stripe_dispute_closed"
+            } else {
+                "stripe_dispute_created"
+            },
+            None,
+            Some("payment"),
+            dispute.payment_id,
+            serde_json::json!({
+                "dispute_id": dispute.stripe_dispute_id,
+                "club_id": dispute.club_id,
+                "reservation_id": dispute.reservation_id,
+                "ticket_order_id": dispute.ticket_order_id,
+                "amount": dispute.amount,
+                "reason": dispute.reason,
+                "status": dispute.status,
+            }),
+        )
+        .await;
+        notify_dispute(state, dispute, closed).await;
+    }
+
+    StatusCode::OK
+}
+
+fn dispute_subject(dispute: &PaymentDispute) -> String {
+    match (dispute.reservation_id, dispute.ticket_order_id) {
+        (Some(reservation_id), _) => format!("la prenotazione {reservation_id}"),
+        (None, Some(order_id)) => format!("l'ordine biglietti {order_id}"),
+        (None, None) => "un pagamento".to_string(),
+    }
+}
+
+/// Alerts ops and emails (and texts) the club owner. Notification failures are
+/// logged only: the dispute itself is already recorded.
+async fn notify_dispute(state: &AppState, dispute: &PaymentDispute, closed: bool) {
+    let subject = dispute_subject(dispute);
+    let reason = dispute.reason.as_deref().unwrap_or("non indicato");
+
+    let alert = if closed {
+        format!(
+            "Stripe dispute {} closed as {} ({:.2} {}, {subject})",
+            dispute.stripe_dispute_id,
+            dispute.status,
+            dispute.amount,
+            dispute.currency.to_uppercase(),
+        )
+    } else {
+        format!(
+            "Stripe dispute {} opened: {:.2} {} ({reason}) on {subject}, evidence due {}",
+            dispute.stripe_dispute_id,
+            dispute.amount,
+            dispute.currency.to_uppercase(),
+            dispute
+                .evidence_due_by
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_else(|| "-".to_string()),
+        )
+    };
+    if state.alert_webhook_url.is_some() {
+        if let Err(e) =
+            outbox_service::enqueue_alert_webhook(&state.db_pool, &alert, "stripe_disputes").await
+        {
+            error!(error = %e, "Failed to enqueue dispute alert webhook");
+        }
+    }
+
+    let Some(club_id) = dispute.club_id else {
+        return;
+    };
+    let (email, phone, club_name) =
+        match dispute_repository::get_club_owner_contact(&state.db_pool, club_id).await {
+            Ok(Some(contact)) => contact,
+            Ok(None) => return,
+            Err(e) => {
+                error!(error = %e, club_id = %club_id, "Failed to load club owner for dispute");
+                return;
+            }
+        };
+
+    let (title, text) = if closed {
+        let outcome = match dispute.status.as_str() {
+            "won" => "è stata risolta a tuo favore: l'importo resta a te",
+            "lost" => "si è chiusa a favore del cliente: l'importo è stato restituito",
+            _ => "è stata chiusa",
+        };
+        (
+            "Contestazione di pagamento chiusa".to_string(),
+            format!(
+                "La contestazione di €{:.2} per {subject} ({club_name}) {outcome}.",
+                dispute.amount
+            ),
+        )
+    } else {
+        (
+            "Contestazione di pagamento aperta".to_string(),
+            format!(
+                "Un cliente ha contestato un pagamento di €{:.2} per {subject} ({club_name}), \
+                 motivo: {reason}. Il pagamento è bloccato e non verrà rimborsato finché la \
+                 contestazione non si chiude.",
+                dispute.amount
+            ),
+        )
+    };
+
+    if let Err(e) = outbox_service::enqueue_email_notification(
+        &state.db_pool,
+        &email,
+        &title,
+        &text,
+        None,
+        Some("payment_dispute"),
+        Some(dispute.id),
+    )
+    .await
+    {
+        error!(error = %e, dispute_id = %dispute.stripe_dispute_id, "Failed to enqueue dispute email");
+    }
+    if let Some(phone) = phone.filter(|phone| !phone.is_empty()) {
+        if let Err(e) = outbox_service::enqueue_sms_notification(
+            &state.db_pool,
+            &phone,
+            &text,
+            Some("payment_dispute"),
+            Some(dispute.id),
+        )
+        .await
+        {
+            error!(error = %e, dispute_id = %dispute.stripe_dispute_id, "Failed to enqueue dispute SMS");
+        }
+    }
+}
@@ -0,0 +1,72 @@
+pub use crate::infrastructure::repositories::event_series_repository::*;
+
+use chrono::{Duration, NaiveDate};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::infrastructure::repositories::event_series_repository;
+use crate::models::{EventSeries, SeriesPropagation, UpdateEventSeriesRequest};
+
+/// Create the events for every occurrence from `today` to `horizon_days`
+/// ahead that does not exist yet. Returns how many were created.
+pub async fn materialize_series(
+    pool: &PgPool,
+    series: &EventSeries,
+    today: NaiveDate,
+    horizon_days: i64,
+) -> Result<u64, sqlx::Error> {
+    if !series.is_active {
+        return Ok(0);
+    }
+    let mut created = 0;
+    for date in series.occurrences_between(today, today + Duration::days(horizon_days)) {
+        if event_series_repository::materialize_occurrence(pool, series, date)
+            .await?
+            .is_some()
+        {
+            created += 1;
+        }
+    }
+    Ok(created)
+}
+
+/// Bring the future occurrences in line with an edited series: copy the new
+/// details, drop dates the rule no longer covers and create the new ones.
+/// Occurrences that already have bookings are left as they are.
+pub async fn apply_series_update(
+    pool: &PgPool,
+    series: &EventSeries,
+    request: &UpdateEventSeriesRequest,
+    today: NaiveDate,
+    horizon_days: i64,
+) -> Result<SeriesPropagation, sqlx::Error> {
+    let mut propagation = SeriesPropagation::default();
+
+    if request.changes_schedule() {
+        let unscheduled: Vec<Uuid> =
+            event_series_repository::get_series_events(pool, series.id, today)
+                .await?
+                .into_iter()
+                .filter(|event| {
+                    !series.is_active || !event.event_date.is_some_and(|date| series.runs_on(date))
+                })
+                .map(|event| event.id)
+                .collect();
+        if !unscheduled.is_empty() {
+            propagation.removed_occurrences =
+                event_series_repository::remove_occurrences(pool, &unscheduled).await?;
+            propagation.skipped_occurrences =
+                unscheduled.len() as u64 - propagation.removed_occurrences;
+        }
+    }
+
+    if request.changes_details() && series.is_active {
+        let (updated, skipped) =
+            event_series_repository::propagate_series_details(pool, series, today).await?;
+        propagation.updated_occurrences = updated;
+        propagation.skipped_occurrences += skipped;
+    }
+
+    propagation.created_occurrences = materialize_series(pool, series, today, horizon_days).await?;
+    Ok(propagation)
+}
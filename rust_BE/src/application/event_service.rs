@@ -1 +1,61 @@
+use axum::http::StatusCode;
+use chrono::{NaiveDate, Utc};
+use sqlx::PgPool;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::infrastructure::repositories::{event_repository, user_repository};
+use crate::models::age_restriction::{check_min_age, AgeRejection};
+
 pub use crate::infrastructure::repositories::event_repository::*;
+
+pub fn age_rejection_error(rejection: AgeRejection) -> (StatusCode, String) {
+    let status = match rejection {
+        AgeRejection::BirthDateRequired { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        AgeRejection::UnderAge { .. } => StatusCode::FORBIDDEN,
+    };
+    (status, rejection.message())
+}
+
+/// Checks that a user is old enough for an event on its date (today when it
+/// has no machine-readable one). A birth date sent with the request is saved
+/// when the profile has none; one already on file is never replaced.
+pub async fn ensure_user_meets_min_age(
+    pool: &PgPool,
+    event_id: Uuid,
+    user_id: Uuid,
+    supplied_date_of_birth: Option<NaiveDate>,
+) -> Result<(), (StatusCode, String)> {
+    let db_error = |e: sqlx::Error| {
+        error!(error = %e, event_id = %event_id, user_id = %user_id, "Failed to check event minimum age");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Errore del database".to_string(),
+        )
+    };
+
+    let Some((min_age, event_date)) = event_repository::get_event_min_age(pool, event_id)
+        .await
+        .map_err(db_error)?
+    else {
+        return Ok(());
+    };
+    if min_age.unwrap_or(0) <= 0 {
+        return Ok(());
+    }
+
+    let date_of_birth = match supplied_date_of_birth {
+        Some(date_of_birth) => {
+            user_repository::set_date_of_birth_if_missing(pool, user_id, date_of_birth)
+                .await
+                .map_err(db_error)?
+        }
+        None => user_repository::find_user_by_id(pool, user_id)
+            .await
+            .map_err(db_error)?
+            .and_then(|user| user.date_of_birth),
+    };
+
+    let event_day = event_date.unwrap_or_else(|| Utc::now().date_naive());
+    check_min_age(min_age, date_of_birth, event_day).map_err(age_rejection_error)
+}
@@ -0,0 +1,46 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use crate::models::guest_export::{ExportSheet, ExportValue};
+
+pub use crate::infrastructure::repositories::guest_export_repository::*;
+
+/// One-sheet workbook with a bold, frozen header row and fitted columns.
+pub fn render_xlsx(sheet: &ExportSheet) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let amount_format = Format::new().set_num_format("0.00");
+
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet.name)?;
+    for (col, header) in sheet.headers.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
+    for (index, row) in sheet.rows.iter().enumerate() {
+        let row_num = index as u32 + 1;
+        for (col, value) in row.iter().enumerate() {
+            let col = col as u16;
+            match value {
+                ExportValue::Text(text) => {
+                    worksheet.write_string(row_num, col, text.as_str())?;
+                }
+                ExportValue::Integer(number) => {
+                    worksheet.write_number(row_num, col, *number as f64)?;
+                }
+                ExportValue::Amount(amount) => {
+                    worksheet.write_number_with_format(
+                        row_num,
+                        col,
+                        amount.to_f64().unwrap_or_default(),
+                        &amount_format,
+                    )?;
+                }
+                ExportValue::Empty => {}
+            }
+        }
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofit();
+
+    workbook.save_to_buffer()
+}
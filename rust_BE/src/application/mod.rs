@@ -3,9 +3,27 @@ pub mod area_service;
 pub mod auth_service;
 pub mod club_owner_service;
 pub mod club_service;
+pub mod connect_reconciliation_service;
+pub mod dispute_service;
 pub mod event_service;
+pub mod event_series_service;
 pub mod genre_service;
+pub mod guest_export_service;
 pub mod outbox_service;
+pub mod owner_analytics_service;
+pub mod password_reset_service;
 pub mod payment_service;
+pub mod platform_admin_service;
+pub mod promo_code_service;
+pub mod push_service;
+pub mod refund_service;
 pub mod reservation_service;
+pub mod session_service;
+pub mod staff_service;
+pub mod stripe_webhook_service;
+pub mod table_layout_service;
+pub mod table_tab_service;
 pub mod ticket_service;
+pub mod ticket_tier_service;
+pub mod transfer_service;
+pub mod waitlist_service;
@@ -1,9 +1,12 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde_json::json;
 use uuid::Uuid;
 
-use crate::application::analytics_service;
+use crate::application::{analytics_service, push_service};
 use crate::bootstrap::config::AppConfig;
 use crate::infrastructure::outbox;
+use crate::services::email_templates::{self, EmailLocale, RenderedEmail, ReservationEmail};
 
 pub async fn enqueue_alert_webhook(
     pool: &sqlx::PgPool,
@@ -45,6 +48,7 @@ pub async fn enqueue_push_notification(
     .await
 }
 
+/// Queues the notification once per registered device of the user
 pub async fn enqueue_push_notification_for_user(
     pool: &sqlx::PgPool,
     user_id: Uuid,
@@ -52,24 +56,17 @@ pub async fn enqueue_push_notification_for_user(
     body: &str,
     aggregate_type: Option<&str>,
     aggregate_id: Option<Uuid>,
-) -> Result<Option<Uuid>, sqlx::Error> {
-    let push_token = sqlx::query_scalar::<_, Option<String>>(
-        "SELECT expo_push_token FROM users WHERE id = $1 AND deleted_at IS NULL",
-    )
-    .bind(user_id)
-    .fetch_optional(pool)
-    .await?
-    .flatten()
-    .filter(|token| !token.is_empty());
-
-    match push_token {
-        Some(token) => {
-            enqueue_push_notification(pool, &token, title, body, aggregate_type, aggregate_id)
-                .await
-                .map(Some)
-        }
-        None => Ok(None),
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let push_tokens = push_service::get_user_push_tokens(pool, user_id).await?;
+
+    let mut event_ids = Vec::with_capacity(push_tokens.len());
+    for token in push_tokens.iter().filter(|token| !token.is_empty()) {
+        event_ids.push(
+            enqueue_push_notification(pool, token, title, body, aggregate_type, aggregate_id)
+                .await?,
+        );
     }
+    Ok(event_ids)
 }
 
 pub async fn enqueue_sms_notification(
@@ -92,6 +89,263 @@ pub async fn enqueue_sms_notification(
     .await
 }
 
+pub async fn enqueue_email_notification(
+    pool: &sqlx::PgPool,
+    to: &str,
+    subject: &str,
+    text: &str,
+    html: Option<&str>,
+    aggregate_type: Option<&str>,
+    aggregate_id: Option<Uuid>,
+) -> Result<Uuid, sqlx::Error> {
+    outbox::enqueue_event(
+        pool,
+        "notification.email",
+        aggregate_type,
+        aggregate_id,
+        json!({
+            "to": to,
+            "subject": subject,
+            "text": text,
+            "html": html,
+        }),
+    )
+    .await
+}
+
+pub async fn enqueue_templated_email(
+    pool: &sqlx::PgPool,
+    to: &str,
+    email: &RenderedEmail,
+    aggregate_type: Option<&str>,
+    aggregate_id: Option<Uuid>,
+) -> Result<Uuid, sqlx::Error> {
+    enqueue_email_notification(
+        pool,
+        to,
+        &email.subject,
+        &email.text,
+        Some(&email.html),
+        aggregate_type,
+        aggregate_id,
+    )
+    .await
+}
+
+#[derive(sqlx::FromRow)]
+struct ReservationEmailRow {
+    reservation_code: String,
+    num_people: i32,
+    event_title: String,
+    event_date: Option<NaiveDate>,
+    table_name: String,
+    contact_name: String,
+    contact_email: String,
+    booker_locale: Option<String>,
+}
+
+impl ReservationEmailRow {
+    fn for_recipient(&self, recipient_name: &str) -> ReservationEmail {
+        ReservationEmail {
+            recipient_name: recipient_name.to_string(),
+            event_title: self.event_title.clone(),
+            event_date: self.event_date,
+            table_name: self.table_name.clone(),
+            reservation_code: self.reservation_code.clone(),
+            num_people: self.num_people,
+        }
+    }
+}
+
+async fn load_reservation_email(
+    pool: &sqlx::PgPool,
+    reservation_id: Uuid,
+) -> Result<Option<ReservationEmailRow>, sqlx::Error> {
+    sqlx::query_as::<_, ReservationEmailRow>(
+        r#"
+        SELECT
+            tr.reservation_code,
+            tr.num_people,
+            e.title AS event_title,
+            e.event_date,
+            t.name AS table_name,
+            tr.contact_name,
+            tr.contact_email,
+            u.preferred_locale AS booker_locale
+        FROM table_reservations tr
+        JOIN events e ON e.id = tr.event_id
+        JOIN tables t ON t.id = tr.table_id
+        LEFT JOIN users u ON u.id = tr.user_id AND u.deleted_at IS NULL
+        WHERE tr.id = $1
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Email the booking contact that the reservation is confirmed
+pub async fn enqueue_reservation_confirmed_email(
+    pool: &sqlx::PgPool,
+    reservation_id: Uuid,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let Some(row) = load_reservation_email(pool, reservation_id)
+        .await?
+        .filter(|row| !row.contact_email.is_empty())
+    else {
+        return Ok(None);
+    };
+
+    let email = email_templates::reservation_confirmed(
+        EmailLocale::from_stored(row.booker_locale.as_deref()),
+        &row.for_recipient(&row.contact_name),
+    );
+    enqueue_templated_email(
+        pool,
+        &row.contact_email,
+        &email,
+        Some("reservation"),
+        Some(reservation_id),
+    )
+    .await
+    .map(Some)
+}
+
+/// Email a payment-link guest the receipt for their share
+pub async fn enqueue_share_receipt_email(
+    pool: &sqlx::PgPool,
+    share_id: Uuid,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let share = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Decimal,
+        ),
+    >(
+        r#"
+        SELECT reservation_id, guest_email, guest_name, guest_locale, amount
+        FROM reservation_payment_shares
+        WHERE id = $1
+        "#,
+    )
+    .bind(share_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((reservation_id, Some(guest_email), guest_name, guest_locale, amount)) = share else {
+        return Ok(None);
+    };
+    let Some(row) = load_reservation_email(pool, reservation_id).await? else {
+        return Ok(None);
+    };
+
+    let email = email_templates::share_payment_receipt(
+        EmailLocale::from_stored(guest_locale.as_deref()),
+        &row.for_recipient(guest_name.as_deref().unwrap_or_default()),
+        amount,
+    );
+    enqueue_templated_email(
+        pool,
+        &guest_email,
+        &email,
+        Some("payment_share"),
+        Some(share_id),
+    )
+    .await
+    .map(Some)
+}
+
+/// Email the booking contact and every guest who paid by payment link that the
+/// reservation is cancelled, with what each of them gets back. Call after the
+/// cancellation refunds were issued.
+pub async fn enqueue_reservation_cancelled_emails(
+    pool: &sqlx::PgPool,
+    reservation_id: Uuid,
+) -> Result<usize, sqlx::Error> {
+    let Some(row) = load_reservation_email(pool, reservation_id).await? else {
+        return Ok(0);
+    };
+
+    // Refunds not tied to a guest share went back to the booker
+    let booker_refund: Decimal = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(r.amount), 0)
+        FROM refunds r
+        LEFT JOIN reservation_payment_shares s ON s.id = r.payment_share_id
+        WHERE r.reservation_id = $1
+          AND r.status != 'failed'
+          AND (s.id IS NULL OR s.is_owner)
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_one(pool)
+    .await?;
+
+    let guests = sqlx::query_as::<_, (Uuid, String, Option<String>, Option<String>, Decimal)>(
+        r#"
+        SELECT
+            s.id,
+            s.guest_email,
+            s.guest_name,
+            s.guest_locale,
+            COALESCE((
+                SELECT SUM(r.amount)
+                FROM refunds r
+                WHERE r.payment_share_id = s.id
+                  AND r.status != 'failed'
+            ), 0)
+        FROM reservation_payment_shares s
+        WHERE s.reservation_id = $1
+          AND NOT s.is_owner
+          AND s.guest_email IS NOT NULL
+          AND s.status IN ('paid', 'refunded')
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut queued = 0;
+    if !row.contact_email.is_empty() {
+        let email = email_templates::reservation_cancelled(
+            EmailLocale::from_stored(row.booker_locale.as_deref()),
+            &row.for_recipient(&row.contact_name),
+            Some(booker_refund),
+        );
+        enqueue_templated_email(
+            pool,
+            &row.contact_email,
+            &email,
+            Some("reservation"),
+            Some(reservation_id),
+        )
+        .await?;
+        queued += 1;
+    }
+
+    for (share_id, guest_email, guest_name, guest_locale, refunded) in guests {
+        let email = email_templates::reservation_cancelled(
+            EmailLocale::from_stored(guest_locale.as_deref()),
+            &row.for_recipient(guest_name.as_deref().unwrap_or_default()),
+            Some(refunded),
+        );
+        enqueue_templated_email(
+            pool,
+            &guest_email,
+            &email,
+            Some("payment_share"),
+            Some(share_id),
+        )
+        .await?;
+        queued += 1;
+    }
+
+    Ok(queued)
+}
+
 pub async fn enqueue_analytics_event(
     pool: &sqlx::PgPool,
     config: &AppConfig,
@@ -0,0 +1 @@
+pub use crate::infrastructure::repositories::owner_analytics_repository::*;
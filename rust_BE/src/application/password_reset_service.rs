@@ -0,0 +1,220 @@
+pub use crate::infrastructure::repositories::password_reset_repository::*;
+
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::application::{outbox_service, session_service};
+use crate::bootstrap::state::AppState;
+use crate::models::{PasswordResetChannel, ResetCodeCheck, SessionSubject};
+
+/// Codes an account can be sent per hour
+const MAX_CODES_PER_ACCOUNT_PER_HOUR: i64 = 3;
+/// Codes one IP address can request per hour, across accounts
+const MAX_CODES_PER_IP_PER_HOUR: i64 = 10;
+/// Wrong guesses before a code stops working
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+/// Rejected codes one IP address can submit per hour, across accounts
+const MAX_FAILURES_PER_IP_PER_HOUR: i64 = 20;
+
+/// What an IP address did with reset codes in the last hour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpResetActivity {
+    pub codes_requested: i64,
+    pub codes_rejected: i64,
+}
+
+impl IpResetActivity {
+    /// An address that keeps guessing codes cannot request more of them either.
+    pub fn may_request_code(&self) -> bool {
+        self.codes_requested < MAX_CODES_PER_IP_PER_HOUR
+            && self.codes_rejected < MAX_FAILURES_PER_IP_PER_HOUR
+    }
+
+    pub fn may_redeem_code(&self) -> bool {
+        self.codes_rejected < MAX_FAILURES_PER_IP_PER_HOUR
+    }
+}
+
+/// A fresh six-digit code and the hash stored for it.
+pub fn new_reset_code() -> (String, String) {
+    let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+    let code_hash = hash_reset_code(&code);
+    (code, code_hash)
+}
+
+pub fn hash_reset_code(code: &str) -> String {
+    hex::encode(Sha256::digest(code.trim().as_bytes()))
+}
+
+/// SMS is used only when asked for and the account has a phone number.
+pub fn delivery_channel(
+    requested: PasswordResetChannel,
+    phone_number: Option<&str>,
+) -> PasswordResetChannel {
+    match (requested, phone_number) {
+        (PasswordResetChannel::Sms, Some(_)) => PasswordResetChannel::Sms,
+        _ => PasswordResetChannel::Email,
+    }
+}
+
+pub async fn ip_reset_activity(
+    pool: &sqlx::PgPool,
+    ip: &str,
+) -> Result<IpResetActivity, sqlx::Error> {
+    let since = Utc::now() - Duration::hours(1);
+    Ok(IpResetActivity {
+        codes_requested: count_reset_codes_for_ip(pool, ip, since).await?,
+        codes_rejected: count_reset_failures_for_ip(pool, ip, since).await?,
+    })
+}
+
+/// Issue a reset code and queue its delivery. Returns `None` when the account
+/// already received its hourly quota of codes.
+pub async fn issue_reset_code(
+    state: &AppState,
+    subject: SessionSubject,
+    email: &str,
+    phone_number: Option<&str>,
+    channel: PasswordResetChannel,
+    requested_ip: &str,
+) -> Result<Option<PasswordResetChannel>, sqlx::Error> {
+    let recent =
+        count_reset_codes_for_subject(&state.db_pool, subject, Utc::now() - Duration::hours(1))
+            .await?;
+    if recent >= MAX_CODES_PER_ACCOUNT_PER_HOUR {
+        return Ok(None);
+    }
+
+    let channel = delivery_channel(channel, phone_number);
+    let ttl_minutes = state.config.password_reset_code_ttl_minutes;
+    let (code, code_hash) = new_reset_code();
+    let code_id = create_reset_code(
+        &state.db_pool,
+        subject,
+        channel,
+        &code_hash,
+        Some(requested_ip),
+        Utc::now() + Duration::minutes(ttl_minutes),
+    )
+    .await?;
+
+    let text = format!(
+        "Il tuo codice per reimpostare la password è {code}. Scade tra {ttl_minutes} minuti. \
+         Se non hai richiesto tu il codice, ignora questo messaggio."
+    );
+    match (channel, phone_number) {
+        (PasswordResetChannel::Sms, Some(phone_number)) => {
+            outbox_service::enqueue_sms_notification(
+                &state.db_pool,
+                phone_number,
+                &text,
+                Some("password_reset"),
+                Some(code_id),
+            )
+            .await?;
+        }
+        _ => {
+            outbox_service::enqueue_email_notification(
+                &state.db_pool,
+                email,
+                "Codice per reimpostare la password",
+                &text,
+                None,
+                Some("password_reset"),
+                Some(code_id),
+            )
+            .await?;
+        }
+    }
+
+    Ok(Some(channel))
+}
+
+/// Redeem a reset code and set the new password. Every session of the account
+/// is revoked. A rejected code is logged against `ip` and, when wrong, counted
+/// against the code, which locks after `MAX_FAILED_ATTEMPTS` wrong guesses.
+pub async fn redeem_reset_code(
+    state: &AppState,
+    subject: SessionSubject,
+    code: &str,
+    new_password_hash: &str,
+    ip: &str,
+) -> Result<ResetCodeCheck, sqlx::Error> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let Some(reset_code) = find_latest_reset_code_for_update(&mut tx, subject).await? else {
+        record_reset_failure(&mut *tx, ip, None).await?;
+        tx.commit().await?;
+        return Ok(ResetCodeCheck::Invalidated);
+    };
+
+    let check = reset_code.check(&hash_reset_code(code), Utc::now(), MAX_FAILED_ATTEMPTS);
+    match check {
+        ResetCodeCheck::Accepted => {
+            mark_reset_code_used(&mut *tx, reset_code.id).await?;
+            update_password_hash(&mut *tx, subject, new_password_hash).await?;
+        }
+        ResetCodeCheck::WrongCode { locks } => {
+            record_failed_reset_attempt(&mut *tx, reset_code.id, locks).await?;
+            record_reset_failure(&mut *tx, ip, Some(reset_code.id)).await?;
+        }
+        ResetCodeCheck::Expired | ResetCodeCheck::Used | ResetCodeCheck::Invalidated => {
+            record_reset_failure(&mut *tx, ip, Some(reset_code.id)).await?;
+        }
+    }
+    tx.commit().await?;
+
+    if check == ResetCodeCheck::Accepted {
+        session_service::revoke_all_sessions(&state.db_pool, subject, "password_reset").await?;
+    }
+    Ok(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_code_has_six_digits_and_matching_hash() {
+        for _ in 0..50 {
+            let (code, code_hash) = new_reset_code();
+            assert_eq!(code.len(), 6);
+            assert!(code.chars().all(|c| c.is_ascii_digit()));
+            assert_eq!(code_hash, hash_reset_code(&code));
+            assert_eq!(code_hash, hash_reset_code(&format!(" {code} ")));
+        }
+    }
+
+    #[test]
+    fn sms_needs_a_phone_number() {
+        assert_eq!(
+            delivery_channel(PasswordResetChannel::Sms, Some("+393331234567")),
+            PasswordResetChannel::Sms
+        );
+        assert_eq!(
+            delivery_channel(PasswordResetChannel::Sms, None),
+            PasswordResetChannel::Email
+        );
+        assert_eq!(
+            delivery_channel(PasswordResetChannel::Email, Some("+393331234567")),
+            PasswordResetChannel::Email
+        );
+    }
+
+    #[test]
+    fn ip_is_limited_on_requests_and_rejected_codes() {
+        let activity = |codes_requested, codes_rejected| IpResetActivity {
+            codes_requested,
+            codes_rejected,
+        };
+        assert!(activity(MAX_CODES_PER_IP_PER_HOUR - 1, 0).may_request_code());
+        assert!(!activity(MAX_CODES_PER_IP_PER_HOUR, 0).may_request_code());
+        assert!(activity(MAX_CODES_PER_IP_PER_HOUR, 0).may_redeem_code());
+
+        let guessing = activity(0, MAX_FAILURES_PER_IP_PER_HOUR);
+        assert!(!guessing.may_redeem_code());
+        assert!(!guessing.may_request_code());
+        assert!(activity(0, MAX_FAILURES_PER_IP_PER_HOUR - 1).may_redeem_code());
+    }
+}
@@ -1 +1,20 @@
 pub use crate::infrastructure::repositories::payment_repository::*;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+/// Platform fee in cents for a charge of `total_amount`: the club's commission
+/// percentage plus its fixed fee, never more than the charge itself.
+pub fn compute_application_fee_cents(
+    total_amount: Decimal,
+    commission_percent: Option<Decimal>,
+    commission_fixed_fee: Option<Decimal>,
+) -> i64 {
+    let percent = commission_percent.unwrap_or(Decimal::ZERO);
+    let fixed_fee = commission_fixed_fee.unwrap_or(Decimal::ZERO);
+    let percent_fee = (total_amount * percent / Decimal::new(100, 0)).round_dp(2);
+    let fee = (percent_fee + fixed_fee)
+        .max(Decimal::ZERO)
+        .min(total_amount);
+    ((fee.to_f64().unwrap_or(0.0) * 100.0).round()) as i64
+}
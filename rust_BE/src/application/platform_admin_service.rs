@@ -0,0 +1,31 @@
+pub use crate::infrastructure::repositories::platform_admin_repository::*;
+
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::middleware::auth::AdminActor;
+
+/// Record a back-office write in the audit log. Failures are logged and do not
+/// fail the request: the change itself has already been applied.
+pub async fn audit(
+    pool: &PgPool,
+    actor: AdminActor,
+    action: &str,
+    target_type: &str,
+    target_id: Option<Uuid>,
+    details: serde_json::Value,
+) {
+    if let Err(e) = record_admin_action(
+        pool,
+        actor.admin_id(),
+        action,
+        target_type,
+        target_id,
+        details,
+    )
+    .await
+    {
+        warn!(error = %e, action, target_type, "Failed to record admin action");
+    }
+}
@@ -0,0 +1,51 @@
+pub use crate::infrastructure::repositories::promo_code_repository::*;
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{PromoCode, PromoCodeRejection};
+
+/// A promo code priced against one share, before it is redeemed.
+#[derive(Clone, Debug)]
+pub struct PromoQuote {
+    pub promo: PromoCode,
+    pub discount: Decimal,
+    pub final_amount: Decimal,
+}
+
+/// Resolve `code` for a table of `event_id` and price it against `amount`.
+///
+/// This is a read-only check used to size the Stripe charge; the use is only
+/// consumed by `redeem_promo_code` inside the booking transaction.
+pub async fn quote_promo_code(
+    pool: &PgPool,
+    code: &str,
+    event_id: Uuid,
+    area_id: Option<Uuid>,
+    table_id: Uuid,
+    phone_number: Option<&str>,
+    amount: Decimal,
+) -> Result<Result<PromoQuote, PromoCodeRejection>, sqlx::Error> {
+    let Some(promo) = find_promo_code_for_event(pool, event_id, code).await? else {
+        return Ok(Err(PromoCodeRejection::NotFound));
+    };
+
+    if let Err(rejection) = promo.check_applicable(event_id, area_id, table_id, Utc::now()) {
+        return Ok(Err(rejection));
+    }
+
+    if let Some(phone) = phone_number {
+        if promo.single_use_per_phone && phone_has_redeemed(pool, promo.id, phone).await? {
+            return Ok(Err(PromoCodeRejection::AlreadyUsed));
+        }
+    }
+
+    let discount = promo.discount_for(amount);
+    Ok(Ok(PromoQuote {
+        final_amount: amount - discount,
+        discount,
+        promo,
+    }))
+}
@@ -0,0 +1,127 @@
+pub use crate::infrastructure::repositories::push_repository::*;
+
+use chrono::{Duration, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::bootstrap::state::AppState;
+use crate::services::notification_service::{self, PushStatus, PushTicket};
+
+/// Expo asks to wait before fetching receipts and keeps them for a day
+const RECEIPT_MIN_AGE_MINUTES: i64 = 15;
+const RECEIPT_RETENTION_HOURS: i64 = 24;
+/// getReceipts accepts at most 1000 ids per request
+const RECEIPT_BATCH_SIZE: i64 = 1000;
+
+/// Receipt outcomes of one polling run
+#[derive(Debug, Default)]
+pub struct ReceiptCheckSummary {
+    pub checked: usize,
+    pub delivered: usize,
+    pub failed: usize,
+    pub expired: usize,
+    pub devices_removed: usize,
+}
+
+/// Stores the ticket Expo returned for a push and forgets the device when
+/// Expo already knows it is unregistered.
+pub async fn record_push_ticket(
+    state: &AppState,
+    outbox_event_id: Uuid,
+    token: &str,
+    ticket: &PushTicket,
+) -> Result<(), sqlx::Error> {
+    match &ticket.status {
+        PushStatus::Ok => {
+            insert_push_ticket(
+                &state.db_pool,
+                outbox_event_id,
+                token,
+                ticket.id.as_deref(),
+                "pending_receipt",
+                None,
+                None,
+            )
+            .await
+        }
+        PushStatus::Error { message, code } => {
+            insert_push_ticket(
+                &state.db_pool,
+                outbox_event_id,
+                token,
+                None,
+                "failed",
+                code.as_deref(),
+                Some(message),
+            )
+            .await?;
+            if ticket.status.is_device_not_registered() {
+                forget_unregistered_device(state, token).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Resolves pending tickets against Expo's push receipts.
+pub async fn check_push_receipts(
+    state: &AppState,
+) -> Result<ReceiptCheckSummary, Box<dyn std::error::Error + Send + Sync>> {
+    let now = Utc::now();
+    let tickets = get_tickets_awaiting_receipt(
+        &state.db_pool,
+        now - Duration::minutes(RECEIPT_MIN_AGE_MINUTES),
+        RECEIPT_BATCH_SIZE,
+    )
+    .await?;
+
+    let mut summary = ReceiptCheckSummary::default();
+    if tickets.is_empty() {
+        return Ok(summary);
+    }
+
+    let ticket_ids: Vec<String> = tickets.iter().map(|(_, id, _, _)| id.clone()).collect();
+    let receipts = notification_service::fetch_push_receipts(&ticket_ids).await?;
+
+    for (row_id, ticket_id, token, created_at) in tickets {
+        summary.checked += 1;
+        match receipts.get(&ticket_id) {
+            Some(PushStatus::Ok) => {
+                resolve_push_ticket(&state.db_pool, row_id, "delivered", None, None).await?;
+                summary.delivered += 1;
+            }
+            Some(status @ PushStatus::Error { message, code }) => {
+                resolve_push_ticket(
+                    &state.db_pool,
+                    row_id,
+                    "failed",
+                    code.as_deref(),
+                    Some(message),
+                )
+                .await?;
+                summary.failed += 1;
+                if status.is_device_not_registered()
+                    && forget_unregistered_device(state, &token).await?
+                {
+                    summary.devices_removed += 1;
+                }
+            }
+            // Not ready yet; Expo drops receipts after a day
+            None if created_at <= now - Duration::hours(RECEIPT_RETENTION_HOURS) => {
+                resolve_push_ticket(&state.db_pool, row_id, "receipt_expired", None, None).await?;
+                summary.expired += 1;
+            }
+            None => {}
+        }
+    }
+
+    Ok(summary)
+}
+
+async fn forget_unregistered_device(state: &AppState, token: &str) -> Result<bool, sqlx::Error> {
+    let removed = remove_push_device(&state.db_pool, token, None).await?;
+    if removed {
+        info!("Removed push device Expo reports as not registered");
+    }
+    Ok(removed)
+}
@@ -0,0 +1,562 @@
+use axum::http::StatusCode;
+use chrono::{NaiveDateTime, NaiveTime};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use stripe::{
+    CancelPaymentIntent, CapturePaymentIntent, CreateRefund, PaymentIntent, PaymentIntentId,
+    RequestStrategy,
+};
+use tracing::{error, info, warn};
+
+use crate::application::{event_service, outbox_service, payment_service};
+use crate::infrastructure::repositories::refund_repository::{self, RefundableShare};
+use crate::models::{
+    AppState, CancellationActor, CancellationPolicy, Event, Refund, TableReservation,
+};
+
+pub use crate::infrastructure::repositories::refund_repository::*;
+
+/// Machine-readable start of an event: `event_date` plus the `HH:MM` `time`
+/// when present, otherwise midnight. Times are treated as UTC like the scheduler.
+pub fn event_start(event: &Event) -> Option<NaiveDateTime> {
+    let date = event.event_date?;
+    let time = event
+        .time
+        .as_deref()
+        .and_then(|t| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok())
+        .unwrap_or(NaiveTime::MIN);
+    Some(date.and_time(time))
+}
+
+/// Percentage (0-100) of each paid share returned on cancellation.
+///
+/// - A platform admin cancelling always refunds in full (disputes, closed venues).
+/// - Once the event has started nothing is refunded (no-show) otherwise.
+/// - A club cancelling before the event always refunds in full.
+/// - A guest cancelling gets a full refund until `full_refund_hours` before the
+///   start, then `partial_refund_percent`.
+/// - Events without a machine-readable date are refunded in full.
+pub fn refund_percent(
+    policy: &CancellationPolicy,
+    actor: CancellationActor,
+    event_start: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> Decimal {
+    let full = Decimal::new(100, 0);
+    if actor == CancellationActor::Admin {
+        return full;
+    }
+    let Some(start) = event_start else {
+        return full;
+    };
+    if now >= start {
+        return Decimal::ZERO;
+    }
+    if actor == CancellationActor::Owner {
+        return full;
+    }
+    if start - now >= chrono::Duration::hours(policy.full_refund_hours as i64) {
+        full
+    } else {
+        policy.partial_refund_percent.clamp(Decimal::ZERO, full)
+    }
+}
+
+fn to_cents(amount: Decimal) -> i64 {
+    (amount * Decimal::new(100, 0))
+        .round()
+        .to_i64()
+        .unwrap_or(0)
+}
+
+/// Issue refunds for every paid share of an already-cancelled reservation
+/// according to the club's cancellation policy.
+///
+/// Each share is handled independently: a Stripe failure is recorded as a
+/// `failed` refund row and alerted, and does not block the remaining shares.
+pub async fn refund_cancelled_reservation(
+    state: &AppState,
+    reservation: &TableReservation,
+    actor: CancellationActor,
+) -> Result<(Decimal, Vec<Refund>), StatusCode> {
+    let event = event_service::get_event_by_id(&state.db_pool, reservation.event_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let policy = match event.club_id {
+        Some(club_id) => refund_repository::get_cancellation_policy(&state.db_pool, club_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, club_id = %club_id, "Failed to load cancellation policy");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .unwrap_or_else(|| CancellationPolicy::default_for_club(club_id)),
+        None => CancellationPolicy::default_for_club(uuid::Uuid::nil()),
+    };
+
+    let percent = refund_percent(
+        &policy,
+        actor,
+        event_start(&event),
+        chrono::Utc::now().naive_utc(),
+    );
+
+    info!(
+        reservation_id = %reservation.id,
+        actor = ?actor,
+        refund_percent = %percent,
+        "Evaluating cancellation refunds"
+    );
+
+    if percent <= Decimal::ZERO {
+        return Ok((percent, Vec::new()));
+    }
+
+    let shares = refund_repository::get_refundable_shares(&state.db_pool, reservation.id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, reservation_id = %reservation.id, "Failed to load refundable shares");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut refunds = Vec::with_capacity(shares.len());
+    for share in shares {
+        let remaining = share.payment_amount - share.refunded_amount;
+        let amount = (share.share_amount * percent / Decimal::new(100, 0))
+            .round_dp(2)
+            .min(remaining);
+        if amount <= Decimal::ZERO {
+            continue;
+        }
+
+        match refund_share(state, &share, amount, percent, actor).await {
+            Ok(refund) => refunds.push(refund),
+            Err(e) => {
+                error!(error = %e, share_id = %share.share_id, "Failed to record refund for share");
+            }
+        }
+    }
+
+    Ok((percent, refunds))
+}
+
+/// Record that the refunds of a cancelled reservation could not be processed
+/// at all (policy or shares failed to load): alert, and leave a `failed`
+/// refund for each paid share so the back office sees what is still owed.
+pub async fn record_reservation_refund_failure(
+    state: &AppState,
+    reservation: &TableReservation,
+    actor: CancellationActor,
+    failure: &str,
+) {
+    if state.alert_webhook_url.is_some() {
+        let message = format!(
+            "Refunds FAILED for cancelled reservation {}: {}",
+            reservation.id, failure
+        );
+        if let Err(e) =
+            outbox_service::enqueue_alert_webhook(&state.db_pool, &message, "refunds").await
+        {
+            error!(error = %e, "Failed to enqueue refund alert");
+        }
+    }
+
+    let shares = match refund_repository::get_refundable_shares(&state.db_pool, reservation.id)
+        .await
+    {
+        Ok(shares) => shares,
+        Err(e) => {
+            error!(error = %e, reservation_id = %reservation.id, "Failed to load shares for failed refunds");
+            return;
+        }
+    };
+    for share in shares {
+        if let Err(e) = refund_repository::insert_refund(
+            &state.db_pool,
+            share.payment_id,
+            Some(share.reservation_id),
+            Some(share.share_id),
+            None,
+            share.payment_amount - share.refunded_amount,
+            "failed",
+            actor.refund_reason(),
+            None,
+            false,
+            Some(failure),
+        )
+        .await
+        {
+            error!(error = %e, share_id = %share.share_id, "Failed to record failed refund");
+        }
+    }
+}
+
+/// Tell the booker their refund did not go through and is being followed up.
+pub async fn notify_refund_delayed(state: &AppState, reservation: &TableReservation) {
+    if let Err(error) = outbox_service::enqueue_push_notification_for_user(
+        &state.db_pool,
+        reservation.user_id,
+        "Rimborso in ritardo",
+        "Il rimborso della tua prenotazione annullata non è andato a buon fine. Il nostro team lo sta verificando e ti contatterà.",
+        Some("reservation"),
+        Some(reservation.id),
+    )
+    .await
+    {
+        warn!(error = %error, reservation_id = %reservation.id, "Failed to enqueue refund delay notification");
+    }
+}
+
+/// Refund a PaymentIntent in full, outside any reservation: a checkout that was
+/// paid after its hold was released. `idempotency_key` keeps retried webhooks
+/// from refunding twice. Failures are sent to the alert webhook for a manual refund.
+pub async fn refund_payment_intent_in_full(
+    state: &AppState,
+    payment_intent_id: &str,
+    idempotency_key: String,
+    context: &str,
+) -> Result<(), String> {
+    let result = async {
+        let pi_id = payment_intent_id
+            .parse::<PaymentIntentId>()
+            .map_err(|_| "invalid_payment_intent".to_string())?;
+        let intent = PaymentIntent::retrieve(&state.stripe_client, &pi_id, &[])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut params = CreateRefund::new();
+        params.payment_intent = Some(pi_id);
+        params.reason = Some(stripe::RefundReasonFilter::RequestedByCustomer);
+        if intent.application_fee_amount.is_some() {
+            params.refund_application_fee = Some(true);
+        }
+        if intent.transfer_data.is_some() {
+            params.reverse_transfer = Some(true);
+        }
+        let client = state
+            .stripe_client
+            .clone()
+            .with_strategy(RequestStrategy::Idempotent(idempotency_key));
+        stripe::Refund::create(&client, params)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+    .await;
+
+    match &result {
+        Ok(()) => {
+            info!(payment_intent_id = %payment_intent_id, %context, "PaymentIntent refunded in full")
+        }
+        Err(failure) => {
+            error!(payment_intent_id = %payment_intent_id, %context, failure = %failure, "Full refund failed");
+            if state.alert_webhook_url.is_some() {
+                let message = format!(
+                    "Refund FAILED for {context} (payment intent {payment_intent_id}): {failure}. Refund it manually."
+                );
+                if let Err(e) =
+                    outbox_service::enqueue_alert_webhook(&state.db_pool, &message, "refunds").await
+                {
+                    error!(error = %e, "Failed to enqueue refund alert");
+                }
+            }
+        }
+    }
+    result
+}
+
+async fn refund_share(
+    state: &AppState,
+    share: &RefundableShare,
+    amount: Decimal,
+    percent: Decimal,
+    actor: CancellationActor,
+) -> Result<Refund, sqlx::Error> {
+    let reason = actor.refund_reason();
+    let Some(pi) = share
+        .stripe_payment_intent_id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+    else {
+        return record_failure(
+            state,
+            share,
+            amount,
+            percent,
+            reason,
+            "missing_payment_intent",
+        )
+        .await;
+    };
+    let Ok(pi_id) = pi.parse::<PaymentIntentId>() else {
+        return record_failure(
+            state,
+            share,
+            amount,
+            percent,
+            reason,
+            "invalid_payment_intent",
+        )
+        .await;
+    };
+
+    // Uncaptured authorization: release the hold instead of refunding a charge.
+    if share.capture_method.as_deref() == Some("manual")
+        && share.authorization_status.as_deref() == Some("authorized")
+    {
+        return release_authorization(state, share, &pi_id, amount, percent, reason).await;
+    }
+
+    let intent = match PaymentIntent::retrieve(&state.stripe_client, &pi_id, &[]).await {
+        Ok(intent) => intent,
+        Err(e) => {
+            error!(error = ?e, payment_intent_id = %pi, "Failed to retrieve PaymentIntent for refund");
+            return record_failure(state, share, amount, percent, reason, &e.to_string()).await;
+        }
+    };
+    let refund_fee = intent.application_fee_amount.is_some();
+    let reverse_transfer = intent.transfer_data.is_some();
+
+    let mut params = CreateRefund::new();
+    params.payment_intent = Some(pi_id);
+    params.amount = Some(to_cents(amount));
+    params.reason = Some(stripe::RefundReasonFilter::RequestedByCustomer);
+    if refund_fee {
+        params.refund_application_fee = Some(true);
+    }
+    if reverse_transfer {
+        params.reverse_transfer = Some(true);
+    }
+
+    // Same share + amount always maps to the same Stripe request, so a retried
+    // cancellation cannot refund twice.
+    let client = state
+        .stripe_client
+        .clone()
+        .with_strategy(RequestStrategy::Idempotent(format!(
+            "refund-{}-{}",
+            share.share_id,
+            to_cents(amount)
+        )));
+
+    let stripe_refund = match stripe::Refund::create(&client, params).await {
+        Ok(refund) => refund,
+        Err(e) => {
+            error!(error = ?e, share_id = %share.share_id, payment_intent_id = %pi, "Stripe refund failed");
+            return record_failure(state, share, amount, percent, reason, &e.to_string()).await;
+        }
+    };
+
+    let status = match stripe_refund.status.as_deref() {
+        Some("succeeded") => "succeeded",
+        Some("failed") | Some("canceled") => "failed",
+        _ => "pending",
+    };
+
+    let refund = refund_repository::insert_refund(
+        &state.db_pool,
+        share.payment_id,
+        Some(share.reservation_id),
+        Some(share.share_id),
+        Some(stripe_refund.id.as_str()),
+        amount,
+        status,
+        reason,
+        Some(percent),
+        refund_fee,
+        None,
+    )
+    .await?;
+
+    if status != "failed" {
+        refund_repository::apply_payment_refund_total(
+            &state.db_pool,
+            share.payment_id,
+            share.refunded_amount + amount,
+        )
+        .await?;
+    }
+
+    info!(
+        share_id = %share.share_id,
+        stripe_refund_id = %stripe_refund.id,
+        amount = %amount,
+        "Refund issued for payment share"
+    );
+    Ok(refund)
+}
+
+/// Cancel (full refund) or partially capture (partial refund) a manual-capture
+/// PaymentIntent that is still only authorized.
+async fn release_authorization(
+    state: &AppState,
+    share: &RefundableShare,
+    pi_id: &PaymentIntentId,
+    amount: Decimal,
+    percent: Decimal,
+    reason: &str,
+) -> Result<Refund, sqlx::Error> {
+    let retained = share.payment_amount - share.refunded_amount - amount;
+
+    let result = if retained <= Decimal::ZERO {
+        PaymentIntent::cancel(&state.stripe_client, pi_id, CancelPaymentIntent::default())
+            .await
+            .map(|_| ())
+    } else {
+        let mut params = CapturePaymentIntent {
+            amount_to_capture: Some(to_cents(retained) as u64),
+            ..Default::default()
+        };
+        // Scale the platform fee down with the captured amount.
+        if let Ok(intent) = PaymentIntent::retrieve(&state.stripe_client, pi_id, &[]).await {
+            if let Some(fee) = intent.application_fee_amount {
+                let authorized = to_cents(share.payment_amount).max(1);
+                params.application_fee_amount =
+                    Some(((fee as i128 * to_cents(retained) as i128) / authorized as i128) as u64);
+            }
+        }
+        PaymentIntent::capture(&state.stripe_client, pi_id, params)
+            .await
+            .map(|_| ())
+    };
+
+    if let Err(e) = result {
+        error!(error = ?e, share_id = %share.share_id, "Failed to release payment authorization");
+        return record_failure(state, share, amount, percent, reason, &e.to_string()).await;
+    }
+
+    if retained <= Decimal::ZERO {
+        payment_service::mark_payment_authorization_released(&state.db_pool, share.payment_id)
+            .await?;
+    } else {
+        refund_repository::mark_payment_partially_captured(
+            &state.db_pool,
+            share.payment_id,
+            retained,
+        )
+        .await?;
+    }
+
+    let refund = refund_repository::insert_refund(
+        &state.db_pool,
+        share.payment_id,
+        Some(share.reservation_id),
+        Some(share.share_id),
+        None,
+        amount,
+        "released",
+        reason,
+        Some(percent),
+        false,
+        None,
+    )
+    .await?;
+
+    refund_repository::apply_payment_refund_total(
+        &state.db_pool,
+        share.payment_id,
+        share.refunded_amount + amount,
+    )
+    .await?;
+
+    info!(share_id = %share.share_id, amount = %amount, "Authorization released for payment share");
+    Ok(refund)
+}
+
+async fn record_failure(
+    state: &AppState,
+    share: &RefundableShare,
+    amount: Decimal,
+    percent: Decimal,
+    reason: &str,
+    failure: &str,
+) -> Result<Refund, sqlx::Error> {
+    warn!(share_id = %share.share_id, failure = %failure, "Recording failed refund");
+    if state.alert_webhook_url.is_some() {
+        let message = format!(
+            "Refund FAILED for share {} (payment {}), amount {:.2} €: {}",
+            share.share_id, share.payment_id, amount, failure
+        );
+        if let Err(e) =
+            outbox_service::enqueue_alert_webhook(&state.db_pool, &message, "refunds").await
+        {
+            error!(error = %e, "Failed to enqueue refund alert");
+        }
+    }
+
+    refund_repository::insert_refund(
+        &state.db_pool,
+        share.payment_id,
+        Some(share.reservation_id),
+        Some(share.share_id),
+        None,
+        amount,
+        "failed",
+        reason,
+        Some(percent),
+        false,
+        Some(failure),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn policy() -> CancellationPolicy {
+        CancellationPolicy {
+            club_id: uuid::Uuid::nil(),
+            full_refund_hours: 48,
+            partial_refund_percent: Decimal::new(50, 0),
+        }
+    }
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 6, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn user_refund_depends_on_notice() {
+        let start = Some(at(10, 23));
+        assert_eq!(
+            refund_percent(&policy(), CancellationActor::User, start, at(7, 12)),
+            Decimal::new(100, 0)
+        );
+        assert_eq!(
+            refund_percent(&policy(), CancellationActor::User, start, at(10, 12)),
+            Decimal::new(50, 0)
+        );
+        assert_eq!(
+            refund_percent(&policy(), CancellationActor::User, start, at(11, 1)),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn owner_cancellation_refunds_in_full_until_no_show() {
+        let start = Some(at(10, 23));
+        assert_eq!(
+            refund_percent(&policy(), CancellationActor::Owner, start, at(10, 22)),
+            Decimal::new(100, 0)
+        );
+        assert_eq!(
+            refund_percent(&policy(), CancellationActor::Owner, start, at(11, 2)),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn admin_cancellation_always_refunds_in_full() {
+        let start = Some(at(10, 23));
+        assert_eq!(
+            refund_percent(&policy(), CancellationActor::Admin, start, at(11, 2)),
+            Decimal::new(100, 0)
+        );
+    }
+}
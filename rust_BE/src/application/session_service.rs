@@ -0,0 +1,196 @@
+pub use crate::infrastructure::repositories::session_repository::*;
+
+use chrono::{Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::application::{auth_service, club_owner_service};
+use crate::bootstrap::state::AppState;
+use crate::models::SessionSubject;
+use crate::utils::jwt;
+
+/// Access and refresh token handed to a client.
+#[derive(Debug)]
+pub struct IssuedSession {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    /// Unknown, expired, or belonging to an account that can no longer log in
+    Invalid,
+    /// A rotated or revoked token was presented again; its family is now revoked
+    Reused,
+    Database(sqlx::Error),
+    Token(jsonwebtoken::errors::Error),
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "invalid refresh token"),
+            Self::Reused => write!(f, "refresh token reused"),
+            Self::Database(e) => write!(f, "database error: {e}"),
+            Self::Token(e) => write!(f, "token error: {e}"),
+        }
+    }
+}
+
+impl From<sqlx::Error> for SessionError {
+    fn from(error: sqlx::Error) -> Self {
+        SessionError::Database(error)
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for SessionError {
+    fn from(error: jsonwebtoken::errors::Error) -> Self {
+        SessionError::Token(error)
+    }
+}
+
+fn new_refresh_token() -> (String, String) {
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect();
+    let token_hash = hash_refresh_token(&token);
+    (token, token_hash)
+}
+
+fn hash_refresh_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
+}
+
+fn access_token(
+    state: &AppState,
+    subject: SessionSubject,
+    email: &str,
+    family_id: Uuid,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    jwt::generate_access_token(
+        subject.id(),
+        email.to_string(),
+        subject.role().to_string(),
+        family_id,
+        state.config.auth.access_token_ttl_minutes,
+        &state.jwt_secret,
+    )
+}
+
+/// Start a new session family at login.
+pub async fn start_session(
+    state: &AppState,
+    subject: SessionSubject,
+    email: &str,
+) -> Result<IssuedSession, SessionError> {
+    let family_id = Uuid::new_v4();
+    let (refresh_token, refresh_token_hash) = new_refresh_token();
+    insert_session(
+        &state.db_pool,
+        subject,
+        family_id,
+        &refresh_token_hash,
+        Utc::now() + Duration::days(state.config.auth.refresh_token_ttl_days),
+    )
+    .await?;
+
+    Ok(IssuedSession {
+        access_token: access_token(state, subject, email, family_id)?,
+        refresh_token,
+        expires_in: state.config.auth.access_token_ttl_minutes * 60,
+    })
+}
+
+/// Exchange a refresh token for a new access token and a new refresh token.
+///
+/// Each refresh token works once. Presenting one that was already rotated (or
+/// revoked) means it leaked, so the whole family is revoked.
+pub async fn refresh_session(
+    state: &AppState,
+    refresh_token: &str,
+) -> Result<IssuedSession, SessionError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let session = find_session_for_update(&mut tx, &hash_refresh_token(refresh_token))
+        .await?
+        .ok_or(SessionError::Invalid)?;
+    let subject = session.subject().ok_or(SessionError::Invalid)?;
+
+    if session.rotated_at.is_some() || session.revoked_at.is_some() {
+        revoke_session_family(&mut *tx, session.family_id, "reuse_detected").await?;
+        tx.commit().await?;
+        warn!(
+            family_id = %session.family_id,
+            subject_id = %subject.id(),
+            "Refresh token reuse detected; session family revoked"
+        );
+        return Err(SessionError::Reused);
+    }
+
+    if session.expires_at <= Utc::now() {
+        return Err(SessionError::Invalid);
+    }
+
+    let email = match subject {
+        SessionSubject::User(user_id) => {
+            if !auth_service::user_is_active(&state.db_pool, user_id).await? {
+                None
+            } else {
+                auth_service::find_user_by_id(&state.db_pool, user_id)
+                    .await?
+                    .map(|user| user.email)
+            }
+        }
+        SessionSubject::ClubOwner(owner_id) => {
+            if !club_owner_service::club_owner_is_active(&state.db_pool, owner_id).await? {
+                None
+            } else {
+                club_owner_service::find_club_owner_by_id(&state.db_pool, owner_id)
+                    .await?
+                    .map(|owner| owner.email)
+            }
+        }
+    };
+    let Some(email) = email else {
+        revoke_session_family(&mut *tx, session.family_id, "account_inactive").await?;
+        tx.commit().await?;
+        return Err(SessionError::Invalid);
+    };
+
+    let (new_refresh_token, new_refresh_token_hash) = new_refresh_token();
+    mark_session_rotated(&mut *tx, session.id).await?;
+    insert_session(
+        &mut *tx,
+        subject,
+        session.family_id,
+        &new_refresh_token_hash,
+        Utc::now() + Duration::days(state.config.auth.refresh_token_ttl_days),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(IssuedSession {
+        access_token: access_token(state, subject, &email, session.family_id)?,
+        refresh_token: new_refresh_token,
+        expires_in: state.config.auth.access_token_ttl_minutes * 60,
+    })
+}
+
+/// Revoke the session family of a refresh token (logout on one device).
+/// Returns false when the token is unknown.
+pub async fn end_session(state: &AppState, refresh_token: &str) -> Result<bool, SessionError> {
+    let mut tx = state.db_pool.begin().await?;
+    let Some(session) =
+        find_session_for_update(&mut tx, &hash_refresh_token(refresh_token)).await?
+    else {
+        return Ok(false);
+    };
+    revoke_session_family(&mut *tx, session.family_id, "logout").await?;
+    tx.commit().await?;
+    Ok(true)
+}
@@ -0,0 +1,27 @@
+pub use crate::infrastructure::repositories::staff_repository::*;
+
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+
+/// A fresh invitation token and the hash stored for it.
+pub fn new_invite_token() -> (String, String) {
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    let token_hash = hash_invite_token(&token);
+    (token, token_hash)
+}
+
+pub fn hash_invite_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
+}
+
+pub fn invite_url(owner_app_base_url: &str, token: &str) -> String {
+    format!(
+        "{}/staff/accept?token={}",
+        owner_app_base_url.trim_end_matches('/'),
+        token
+    )
+}
@@ -0,0 +1,1089 @@
+use axum::http::StatusCode;
+use rust_decimal::Decimal;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::application::club_service as club_persistence;
+use crate::application::dispute_service;
+use crate::application::outbox_service;
+use crate::application::payment_service as payment_persistence;
+use crate::application::promo_code_service as promo_code_persistence;
+use crate::application::refund_service as refund_persistence;
+use crate::application::reservation_service as table_persistence;
+use crate::application::table_tab_service as table_tab_persistence;
+use crate::application::ticket_tier_service as ticket_tier_persistence;
+use crate::models::{AppState, PaymentStatus};
+
+pub use crate::infrastructure::repositories::stripe_webhook_repository::*;
+
+/// Applies a stored Stripe event. Anything but `200 OK` means the event could
+/// not be applied and is retried; every handler is safe to run again.
+pub async fn process_event(state: &AppState, event: &serde_json::Value) -> StatusCode {
+    let event_type = event["type"].as_str().unwrap_or("");
+    let payment_intent_id = event["data"]["object"]["id"].as_str().unwrap_or("");
+
+    match event_type {
+        "payment_intent.succeeded" => {
+            update_payment_status(state, payment_intent_id, PaymentStatus::Completed).await
+        }
+        "payment_intent.payment_failed" => {
+            update_payment_status(state, payment_intent_id, PaymentStatus::Failed).await
+        }
+        "checkout.session.completed" => {
+            let session_id = event["data"]["object"]["id"].as_str().unwrap_or("");
+            match (ticket_order_id(event), tab_settlement_id(event)) {
+                (Some(order_id), _) => handle_ticket_order_completed(state, order_id, event).await,
+                (None, Some(settlement_id)) => {
+                    handle_tab_settlement_completed(state, settlement_id, event).await
+                }
+                (None, None) => handle_checkout_session_completed(state, session_id, event).await,
+            }
+        }
+        // Ticket checkouts and guest shares hold inventory until their session
+        // ends unpaid; release it now rather than at the share TTL. A tab
+        // waiting on its Stripe payment goes back to open.
+        "checkout.session.expired" => match (ticket_order_id(event), tab_settlement_id(event)) {
+            (Some(order_id), _) => handle_ticket_order_expired(state, order_id).await,
+            (None, Some(settlement_id)) => {
+                handle_tab_settlement_expired(state, settlement_id).await
+            }
+            (None, None) => {
+                let session_id = event["data"]["object"]["id"].as_str().unwrap_or("");
+                handle_share_checkout_expired(state, session_id).await
+            }
+        },
+        "payment_intent.canceled" => handle_payment_intent_canceled(state, payment_intent_id).await,
+        // Fired when a manual-capture PaymentIntent is authorized (requires_capture).
+        // We store authorized_at and the payment_method_id here so the scheduler
+        // can re-authorize off-session if the 7-day hold is about to expire.
+        "payment_intent.amount_capturable_updated" => {
+            let payment_method_id = event["data"]["object"]["payment_method"]
+                .as_str()
+                .unwrap_or("")
+                .to_string();
+            store_authorization(state, payment_intent_id, &payment_method_id).await
+        }
+        // Fired for every refund on a charge, including ones we issued on
+        // cancellation and ones issued from the Stripe dashboard.
+        "charge.refunded" => handle_charge_refunded(state, &event["data"]["object"]).await,
+        "charge.dispute.created" | "charge.dispute.closed" => {
+            dispute_service::handle_dispute_event(state, &event["data"]["object"]).await
+        }
+        // Connect account capabilities changed (onboarding finished, requirements due)
+        "account.updated" => handle_account_updated(state, &event["data"]["object"]).await,
+        _ => {
+            info!(event_type = %event_type, "Unhandled Stripe webhook event type");
+            StatusCode::OK
+        }
+    }
+}
+
+async fn store_authorization(
+    state: &AppState,
+    payment_intent_id: &str,
+    payment_method_id: &str,
+) -> StatusCode {
+    match sqlx::query(
+        "UPDATE payments
+         SET authorization_status = 'authorized',
+             authorized_at = NOW(),
+             stripe_payment_method_id = COALESCE(NULLIF($1, ''), stripe_payment_method_id),
+             update_date = NOW()
+         WHERE stripe_payment_intent_id = $2",
+    )
+    .bind(payment_method_id)
+    .bind(payment_intent_id)
+    .execute(&state.db_pool)
+    .await
+    {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                info!(payment_intent_id = %payment_intent_id, "Payment authorization stored");
+                let _ = outbox_service::enqueue_analytics_event(
+                    &state.db_pool,
+                    &state.config,
+                    "stripe_payment_authorization_stored",
+                    None,
+                    Some("payment"),
+                    None,
+                    serde_json::json!({
+                        "payment_intent_id": payment_intent_id,
+                        "outcome": "success",
+                    }),
+                )
+                .await;
+            }
+            StatusCode::OK
+        }
+        Err(e) => {
+            error!(error = %e, payment_intent_id = %payment_intent_id, "Failed to store payment authorization");
+            let _ = outbox_service::enqueue_analytics_error(
+                &state.db_pool,
+                &state.config,
+                "stripe_payment_authorization_store_failed",
+                None,
+                Some("payment"),
+                None,
+                &e.to_string(),
+                serde_json::json!({
+                    "payment_intent_id": payment_intent_id,
+                }),
+            )
+            .await;
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn update_payment_status(
+    state: &AppState,
+    payment_intent_id: &str,
+    status: PaymentStatus,
+) -> StatusCode {
+    match sqlx::query(
+        "UPDATE payments SET status = $1, update_date = NOW() WHERE stripe_payment_intent_id = $2",
+    )
+    .bind(&status)
+    .bind(payment_intent_id)
+    .execute(&state.db_pool)
+    .await
+    {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                info!(payment_intent_id = %payment_intent_id, status = ?status, "Payment status updated");
+                let _ = outbox_service::enqueue_analytics_event(
+                    &state.db_pool,
+                    &state.config,
+                    "stripe_payment_status_updated",
+                    None,
+                    Some("payment"),
+                    None,
+                    serde_json::json!({
+                        "payment_intent_id": payment_intent_id,
+                        "status": format!("{:?}", status),
+                        "outcome": "success",
+                    }),
+                )
+                .await;
+            } else {
+                info!(payment_intent_id = %payment_intent_id, "No payment found for this PaymentIntent (may be external)");
+            }
+            StatusCode::OK
+        }
+        Err(e) => {
+            error!(error = %e, payment_intent_id = %payment_intent_id, "Failed to update payment status");
+            let _ = outbox_service::enqueue_analytics_error(
+                &state.db_pool,
+                &state.config,
+                "stripe_payment_status_update_failed",
+                None,
+                Some("payment"),
+                None,
+                &e.to_string(),
+                serde_json::json!({
+                    "payment_intent_id": payment_intent_id,
+                    "status": format!("{:?}", status),
+                }),
+            )
+            .await;
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Handle charge.refunded: record any refunds we did not issue ourselves and
+/// bring `payments.refunded_amount` / reservation `amount_paid` in line with
+/// the cumulative `amount_refunded` reported by Stripe.
+async fn handle_charge_refunded(state: &AppState, charge: &serde_json::Value) -> StatusCode {
+    let payment_intent_id = charge["payment_intent"].as_str().unwrap_or("");
+    if payment_intent_id.is_empty() {
+        info!("charge.refunded without payment_intent, skipping");
+        return StatusCode::OK;
+    }
+
+    let (payment_id, reservation_id, released) = match refund_persistence::find_payment_for_intent(
+        &state.db_pool,
+        payment_intent_id,
+    )
+    .await
+    {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            info!(payment_intent_id = %payment_intent_id, "No payment found for refunded charge (may be external)");
+            return StatusCode::OK;
+        }
+        Err(e) => {
+            error!(error = %e, payment_intent_id = %payment_intent_id, "Failed to look up payment for refunded charge");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    // Refund objects are only embedded on older API versions; when present,
+    // keep the refunds table complete.
+    if let Some(refunds) = charge["refunds"]["data"].as_array() {
+        for refund in refunds {
+            let Some(stripe_refund_id) = refund["id"].as_str() else {
+                continue;
+            };
+            let amount = Decimal::new(refund["amount"].as_i64().unwrap_or(0), 2);
+            let status = refund["status"].as_str().unwrap_or("pending");
+            if let Err(e) = refund_persistence::upsert_stripe_refund(
+                &state.db_pool,
+                payment_id,
+                reservation_id,
+                stripe_refund_id,
+                amount,
+                status,
+            )
+            .await
+            {
+                error!(error = %e, stripe_refund_id = %stripe_refund_id, "Failed to record Stripe refund");
+                return StatusCode::INTERNAL_SERVER_ERROR;
+            }
+        }
+    }
+
+    let amount_refunded = Decimal::new(charge["amount_refunded"].as_i64().unwrap_or(0), 2);
+    match refund_persistence::apply_payment_refund_total(
+        &state.db_pool,
+        payment_id,
+        released + amount_refunded,
+    )
+    .await
+    {
+        Ok(applied) => {
+            info!(payment_id = %payment_id, amount_refunded = %amount_refunded, applied = %applied, "Charge refund synced");
+            let _ = outbox_service::enqueue_analytics_event(
+                &state.db_pool,
+                &state.config,
+                "stripe_charge_refunded",
+                None,
+                Some("payment"),
+                Some(payment_id),
+                serde_json::json!({
+                    "payment_intent_id": payment_intent_id,
+                    "reservation_id": reservation_id,
+                    "amount_refunded": amount_refunded,
+                    "newly_applied": applied,
+                    "outcome": "success",
+                }),
+            )
+            .await;
+            StatusCode::OK
+        }
+        Err(e) => {
+            error!(error = %e, payment_id = %payment_id, "Failed to sync refunded amount");
+            let _ = outbox_service::enqueue_analytics_error(
+                &state.db_pool,
+                &state.config,
+                "stripe_charge_refund_sync_failed",
+                None,
+                Some("payment"),
+                Some(payment_id),
+                &e.to_string(),
+                serde_json::json!({
+                    "payment_intent_id": payment_intent_id,
+                }),
+            )
+            .await;
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Handle checkout.session.expired for a guest split payment: the share's
+/// `checkout_pending` slot is released and any promo code use it held is
+/// given back.
+async fn handle_share_checkout_expired(state: &AppState, session_id: &str) -> StatusCode {
+    let share = match table_persistence::expire_checkout_pending_share(&state.db_pool, session_id)
+        .await
+    {
+        Ok(Some(share)) => share,
+        Ok(None) => {
+            info!(session_id = %session_id, "No pending payment share for expired checkout session");
+            return StatusCode::OK;
+        }
+        Err(e) => {
+            error!(error = %e, session_id = %session_id, "Failed to expire payment share");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    // The share is already expired, so a retry would not get back here:
+    // failing to release the promo code is only logged.
+    if let Err(e) = promo_code_persistence::release_share_redemption(&state.db_pool, share.id).await
+    {
+        error!(error = %e, share_id = %share.id, "Failed to release promo code redemption for expired share");
+    }
+
+    info!(share_id = %share.id, reservation_id = %share.reservation_id, "Payment share released after checkout expiry");
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "split_payment_checkout_expired",
+        share.user_id.map(|id| id.to_string()).as_deref(),
+        Some("reservation"),
+        Some(share.reservation_id),
+        serde_json::json!({
+            "share_id": share.id,
+            "session_id": session_id,
+            "amount": share.amount,
+        }),
+    )
+    .await;
+    StatusCode::OK
+}
+
+/// Handle payment_intent.canceled. Cancellations we make ourselves have
+/// already updated the payment; anything else (an authorization Stripe let
+/// expire, a cancel from the dashboard) is recorded and alerted on when it
+/// drops a hold.
+async fn handle_payment_intent_canceled(state: &AppState, payment_intent_id: &str) -> StatusCode {
+    let (payment_id, was_authorized) = match payment_persistence::mark_payment_intent_cancelled(
+        &state.db_pool,
+        payment_intent_id,
+    )
+    .await
+    {
+        Ok(Some(cancelled)) => cancelled,
+        Ok(None) => {
+            info!(payment_intent_id = %payment_intent_id, "No open payment for cancelled PaymentIntent");
+            return StatusCode::OK;
+        }
+        Err(e) => {
+            error!(error = %e, payment_intent_id = %payment_intent_id, "Failed to record cancelled PaymentIntent");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    warn!(payment_id = %payment_id, payment_intent_id = %payment_intent_id, was_authorized, "PaymentIntent cancelled outside the app");
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "stripe_payment_intent_canceled",
+        None,
+        Some("payment"),
+        Some(payment_id),
+        serde_json::json!({
+            "payment_intent_id": payment_intent_id,
+            "was_authorized": was_authorized,
+        }),
+    )
+    .await;
+    if was_authorized && state.alert_webhook_url.is_some() {
+        let message = format!(
+            "Authorized payment {payment_id} ({payment_intent_id}) was cancelled on Stripe: the hold is gone and will not be captured"
+        );
+        if let Err(e) =
+            outbox_service::enqueue_alert_webhook(&state.db_pool, &message, "stripe_webhook").await
+        {
+            error!(error = %e, "Failed to enqueue cancelled authorization alert");
+        }
+    }
+    StatusCode::OK
+}
+
+/// Handle account.updated: keep the club's Connect flags current so owners
+/// do not have to open the Stripe status page to refresh them.
+async fn handle_account_updated(state: &AppState, account: &serde_json::Value) -> StatusCode {
+    let Some(account_id) = account["id"].as_str() else {
+        return StatusCode::OK;
+    };
+    let charges_enabled = account["charges_enabled"].as_bool().unwrap_or(false);
+    let payouts_enabled = account["payouts_enabled"].as_bool().unwrap_or(false);
+
+    match club_persistence::sync_stripe_account_status(
+        &state.db_pool,
+        account_id,
+        account["details_submitted"].as_bool().unwrap_or(false),
+        charges_enabled,
+        payouts_enabled,
+    )
+    .await
+    {
+        Ok(Some((club_id, charges_were_enabled))) => {
+            info!(club_id = %club_id, account_id = %account_id, charges_enabled, payouts_enabled, "Connect account status synced");
+            if charges_were_enabled && !charges_enabled {
+                warn!(club_id = %club_id, account_id = %account_id, "Connect account can no longer take charges");
+                let _ = outbox_service::enqueue_analytics_event(
+                    &state.db_pool,
+                    &state.config,
+                    "stripe_connect_charges_disabled",
+                    None,
+                    Some("club"),
+                    Some(club_id),
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "requirements_due": account["requirements"]["currently_due"],
+                    }),
+                )
+                .await;
+            }
+            StatusCode::OK
+        }
+        Ok(None) => {
+            info!(account_id = %account_id, "account.updated for an account no club uses");
+            StatusCode::OK
+        }
+        Err(e) => {
+            error!(error = %e, account_id = %account_id, "Failed to sync Connect account status");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Handle checkout.session.completed webhook for guest split payments.
+/// All DB writes run inside a single transaction — if any step fails the whole
+/// batch is rolled back, preventing partial state (e.g. payment created but
+/// reservation amount_paid not incremented).
+async fn handle_checkout_session_completed(
+    state: &AppState,
+    session_id: &str,
+    event: &serde_json::Value,
+) -> StatusCode {
+    info!(session_id = %session_id, "Processing checkout.session.completed");
+
+    // Look up the payment share by checkout session ID
+    let share = match table_persistence::get_payment_share_by_checkout_session(
+        &state.db_pool,
+        session_id,
+    )
+    .await
+    {
+        Ok(share) => share,
+        Err(_) => {
+            info!(session_id = %session_id, "No payment share found for checkout session (may be external)");
+            return StatusCode::OK;
+        }
+    };
+
+    if share.status == "paid" {
+        info!(share_id = %share.id, "Payment share already marked as paid, skipping");
+        let _ = outbox_service::enqueue_analytics_event(
+            &state.db_pool,
+            &state.config,
+            "split_payment_checkout_duplicate",
+            None,
+            Some("reservation"),
+            Some(share.reservation_id),
+            serde_json::json!({
+                "share_id": share.id,
+                "session_id": session_id,
+                "outcome": "duplicate",
+            }),
+        )
+        .await;
+        return StatusCode::OK;
+    }
+
+    // Extract Stripe PaymentIntent ID from the checkout session
+    let stripe_pi_id = event["data"]["object"]["payment_intent"]
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+
+    let now = chrono::Utc::now().naive_utc();
+    let payment_id = Uuid::new_v4();
+    let sender_id = share.user_id.unwrap_or_else(Uuid::nil);
+
+    // Fetch the reservation before starting the transaction (read-only)
+    let reservation = match table_persistence::get_reservation_by_id(
+        &state.db_pool,
+        share.reservation_id,
+    )
+    .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            error!(error = %e, "Failed to get reservation for ticket creation");
+            let _ = outbox_service::enqueue_analytics_error(
+                &state.db_pool,
+                &state.config,
+                "split_payment_checkout_failed",
+                None,
+                Some("reservation"),
+                Some(share.reservation_id),
+                &e.to_string(),
+                serde_json::json!({
+                    "share_id": share.id,
+                    "session_id": session_id,
+                    "stage": "reservation_lookup",
+                }),
+            )
+            .await;
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    // Begin transaction — all writes are atomic
+    let mut tx = match state.db_pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(error = %e, "Failed to begin transaction for checkout completion");
+            let _ = outbox_service::enqueue_analytics_error(
+                &state.db_pool,
+                &state.config,
+                "split_payment_checkout_failed",
+                None,
+                Some("reservation"),
+                Some(share.reservation_id),
+                &e.to_string(),
+                serde_json::json!({
+                    "share_id": share.id,
+                    "session_id": session_id,
+                    "stage": "begin_transaction",
+                }),
+            )
+            .await;
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+
+    // 1. Create payment record
+    if let Err(e) = sqlx::query(
+        r#"
+        INSERT INTO payments (id, sender_id, receiver_id, amount, status, insert_date, update_date, stripe_payment_intent_id, user_ids)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#
+    )
+    .bind(payment_id)
+    .bind(sender_id)
+    .bind(sender_id)
+    .bind(share.amount)
+    .bind(PaymentStatus::Completed)
+    .bind(now)
+    .bind(now)
+    .bind(&stripe_pi_id)
+    .bind(&vec![sender_id])
+    .execute(&mut *tx)
+    .await
+    {
+        error!(error = %e, "Failed to create payment record for guest");
+        let _ = tx.rollback().await;
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    info!(payment_id = %payment_id, "Created payment record for guest");
+
+    // 2. Mark payment share as paid
+    let stripe_pi_opt = if stripe_pi_id.is_empty() {
+        None
+    } else {
+        Some(stripe_pi_id.clone())
+    };
+    if let Err(e) = sqlx::query(
+        r#"UPDATE reservation_payment_shares
+           SET status = 'paid',
+               payment_id = $1,
+               stripe_payment_intent_id = COALESCE($2, stripe_payment_intent_id),
+               updated_at = NOW()
+           WHERE id = $3"#,
+    )
+    .bind(payment_id)
+    .bind(&stripe_pi_opt)
+    .bind(share.id)
+    .execute(&mut *tx)
+    .await
+    {
+        error!(error = %e, share_id = %share.id, "Failed to update payment share");
+        let _ = tx.rollback().await;
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    info!(share_id = %share.id, "Payment share marked as paid");
+
+    if let Err(e) = promo_code_persistence::confirm_share_redemption(&mut *tx, share.id).await {
+        error!(error = %e, share_id = %share.id, "Failed to confirm promo code redemption");
+        let _ = tx.rollback().await;
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    // 3. Increment reservation amount_paid and num_people (the share may pay for friends too)
+    if let Err(e) = sqlx::query(
+        "UPDATE table_reservations SET amount_paid = amount_paid + $1, num_people = num_people + $2, updated_at = NOW() WHERE id = $3"
+    )
+    .bind(share.amount)
+    .bind(share.slots)
+    .bind(share.reservation_id)
+    .execute(&mut *tx)
+    .await
+    {
+        error!(error = %e, "Failed to update reservation amount_paid and num_people");
+        let _ = tx.rollback().await;
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    info!(reservation_id = %share.reservation_id, amount = %share.amount, "Reservation amount_paid and num_people updated");
+
+    // 4. Add payment ID to reservation's payment_ids array
+    let _ = sqlx::query(
+        "UPDATE table_reservations SET payment_ids = array_append(payment_ids, $1) WHERE id = $2",
+    )
+    .bind(payment_id)
+    .bind(share.reservation_id)
+    .execute(&mut *tx)
+    .await;
+
+    // 5. Create ticket for the guest
+    let ticket_code = generate_ticket_code();
+    let ticket_id = Uuid::new_v4();
+    match sqlx::query(
+        r#"
+        INSERT INTO tickets (id, event_id, user_id, ticket_code, ticket_type, price, status, purchase_date, qr_code, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NULL, NOW(), NOW())
+        "#
+    )
+    .bind(ticket_id)
+    .bind(reservation.event_id)
+    .bind(share.user_id)
+    .bind(&ticket_code)
+    .bind("table")
+    .bind(share.amount)
+    .bind("active")
+    .execute(&mut *tx)
+    .await
+    {
+        Ok(_) => {
+            info!(ticket_id = %ticket_id, "Created ticket for guest");
+            let _ = sqlx::query(
+                "UPDATE table_reservations SET ticket_ids = array_append(ticket_ids, $1) WHERE id = $2"
+            )
+            .bind(ticket_id)
+            .bind(share.reservation_id)
+            .execute(&mut *tx)
+            .await;
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to create ticket for guest");
+        }
+    }
+
+    // 6. Auto-confirm the reservation once amount_paid >= total_amount
+    let became_confirmed = match sqlx::query(
+        r#"UPDATE table_reservations SET status = 'confirmed', updated_at = NOW()
+           WHERE id = $1
+             AND status != 'confirmed'
+             AND amount_paid >= total_amount"#,
+    )
+    .bind(share.reservation_id)
+    .execute(&mut *tx)
+    .await
+    {
+        Ok(result) => result.rows_affected() > 0,
+        Err(e) => {
+            error!(error = %e, "Failed to check reservation confirmation");
+            false
+        }
+    };
+
+    // Commit everything atomically
+    if let Err(e) = tx.commit().await {
+        error!(error = %e, "Failed to commit checkout completion transaction");
+        let _ = outbox_service::enqueue_analytics_error(
+            &state.db_pool,
+            &state.config,
+            "split_payment_checkout_failed",
+            None,
+            Some("reservation"),
+            Some(share.reservation_id),
+            &e.to_string(),
+            serde_json::json!({
+                "share_id": share.id,
+                "session_id": session_id,
+                "stage": "commit_transaction",
+            }),
+        )
+        .await;
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    info!(reservation_id = %share.reservation_id, "Checkout completion transaction committed");
+    let distinct_id = share.user_id.map(|id| id.to_string());
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "split_payment_checkout_completed",
+        distinct_id.as_deref(),
+        Some("reservation"),
+        Some(share.reservation_id),
+        serde_json::json!({
+            "share_id": share.id,
+            "session_id": session_id,
+            "payment_id": payment_id,
+            "ticket_id": ticket_id,
+            "reservation_id": share.reservation_id,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    // Check final reservation status (for push notification — outside transaction)
+    let reservation_status: Option<String> =
+        sqlx::query_scalar("SELECT status FROM table_reservations WHERE id = $1")
+            .bind(share.reservation_id)
+            .fetch_optional(&state.db_pool)
+            .await
+            .ok()
+            .flatten();
+
+    let guest_name = share.guest_name.as_deref().unwrap_or("Un ospite");
+    let all_confirmed = reservation_status.as_deref() == Some("confirmed");
+    let (title, body) = if all_confirmed {
+        (
+            "Prenotazione confermata!".to_string(),
+            "Tutti gli ospiti hanno pagato. La tua prenotazione e' confermata.".to_string(),
+        )
+    } else {
+        (
+            "Pagamento ricevuto".to_string(),
+            format!("{} ha pagato la sua parte.", guest_name),
+        )
+    };
+    let _ = outbox_service::enqueue_push_notification_for_user(
+        &state.db_pool,
+        reservation.user_id,
+        &title,
+        &body,
+        Some("reservation"),
+        Some(share.reservation_id),
+    )
+    .await;
+
+    if let Err(e) = outbox_service::enqueue_share_receipt_email(&state.db_pool, share.id).await {
+        warn!(error = %e, share_id = %share.id, "Failed to enqueue share receipt email");
+    }
+    if became_confirmed {
+        if let Err(e) = outbox_service::enqueue_reservation_confirmed_email(
+            &state.db_pool,
+            share.reservation_id,
+        )
+        .await
+        {
+            warn!(error = %e, reservation_id = %share.reservation_id, "Failed to enqueue reservation confirmation email");
+        }
+    }
+
+    StatusCode::OK
+}
+
+/// Ticket order a Checkout session was opened for, from its metadata.
+fn ticket_order_id(event: &serde_json::Value) -> Option<Uuid> {
+    event["data"]["object"]["metadata"]["ticket_order_id"]
+        .as_str()
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+/// Handle checkout.session.completed for a general-admission ticket order:
+/// record the payment and issue one ticket per seat.
+async fn handle_ticket_order_completed(
+    state: &AppState,
+    order_id: Uuid,
+    event: &serde_json::Value,
+) -> StatusCode {
+    let session_id = event["data"]["object"]["id"].as_str().unwrap_or("");
+    info!(order_id = %order_id, session_id = %session_id, "Processing ticket order checkout completion");
+
+    let order =
+        match ticket_tier_persistence::get_ticket_order_by_id(&state.db_pool, order_id).await {
+            Ok(Some(order)) => order,
+            Ok(None) => {
+                warn!(order_id = %order_id, "Ticket order from checkout metadata not found");
+                return StatusCode::OK;
+            }
+            Err(e) => {
+                error!(error = %e, order_id = %order_id, "Failed to load ticket order");
+                return StatusCode::INTERNAL_SERVER_ERROR;
+            }
+        };
+
+    match order.status.as_str() {
+        "paid" => {
+            info!(order_id = %order.id, "Ticket order already paid, skipping");
+            return StatusCode::OK;
+        }
+        // The hold was released before Stripe reported the payment: the buyer
+        // paid for seats that may have been resold, so the payment is refunded.
+        "expired" => {
+            error!(order_id = %order.id, session_id = %session_id, "Ticket order paid after its hold was released");
+            let outcome = match event["data"]["object"]["payment_intent"]
+                .as_str()
+                .filter(|id| !id.is_empty())
+            {
+                Some(pi) => match refund_persistence::refund_payment_intent_in_full(
+                    state,
+                    pi,
+                    format!("ticket-order-refund-{}", order.id),
+                    &format!("expired ticket order {}", order.id),
+                )
+                .await
+                {
+                    Ok(()) => "refunded",
+                    Err(_) => "refund_failed",
+                },
+                None => {
+                    if state.alert_webhook_url.is_some() {
+                        let message = format!(
+                            "Ticket order {} was paid after its hold was released (session {}) and has no payment intent. Refund it manually.",
+                            order.id, session_id
+                        );
+                        if let Err(e) = outbox_service::enqueue_alert_webhook(
+                            &state.db_pool,
+                            &message,
+                            "refunds",
+                        )
+                        .await
+                        {
+                            error!(error = %e, "Failed to enqueue refund alert");
+                        }
+                    }
+                    "refund_failed"
+                }
+            };
+            let _ = outbox_service::enqueue_analytics_event(
+                &state.db_pool,
+                &state.config,
+                "ticket_checkout_paid_after_expiry",
+                Some(&order.user_id.to_string()),
+                Some("ticket_order"),
+                Some(order.id),
+                serde_json::json!({
+                    "order_id": order.id,
+                    "session_id": session_id,
+                    "outcome": outcome,
+                }),
+            )
+            .await;
+            if outcome == "refunded" {
+                let _ = outbox_service::enqueue_push_notification_for_user(
+                    &state.db_pool,
+                    order.user_id,
+                    "Pagamento rimborsato",
+                    "Il pagamento è arrivato dopo la scadenza della prenotazione dei biglietti ed è stato rimborsato.",
+                    Some("event"),
+                    Some(order.event_id),
+                )
+                .await;
+            }
+            return StatusCode::OK;
+        }
+        _ => {}
+    }
+
+    let stripe_pi_id = event["data"]["object"]["payment_intent"]
+        .as_str()
+        .filter(|id| !id.is_empty());
+
+    let (order, ticket_ids) = match ticket_tier_persistence::complete_ticket_order(
+        &state.db_pool,
+        order.id,
+        stripe_pi_id,
+    )
+    .await
+    {
+        Ok(Some(completed)) => completed,
+        Ok(None) => {
+            info!(order_id = %order.id, "Ticket order no longer pending, skipping");
+            return StatusCode::OK;
+        }
+        Err(e) => {
+            error!(error = %e, order_id = %order.id, "Failed to complete ticket order");
+            let _ = outbox_service::enqueue_analytics_error(
+                &state.db_pool,
+                &state.config,
+                "ticket_checkout_failed",
+                Some(&order.user_id.to_string()),
+                Some("ticket_order"),
+                Some(order.id),
+                &e.to_string(),
+                serde_json::json!({
+                    "order_id": order.id,
+                    "session_id": session_id,
+                    "stage": "complete_order",
+                }),
+            )
+            .await;
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+    info!(order_id = %order.id, tickets = ticket_ids.len(), "Ticket order paid and tickets issued");
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "ticket_checkout_completed",
+        Some(&order.user_id.to_string()),
+        Some("ticket_order"),
+        Some(order.id),
+        serde_json::json!({
+            "order_id": order.id,
+            "tier_id": order.tier_id,
+            "event_id": order.event_id,
+            "payment_id": order.payment_id,
+            "ticket_ids": ticket_ids,
+            "quantity": order.quantity,
+            "total_amount": order.total_amount,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    let body = if order.quantity == 1 {
+        "Il tuo biglietto è pronto.".to_string()
+    } else {
+        format!("I tuoi {} biglietti sono pronti.", order.quantity)
+    };
+    let _ = outbox_service::enqueue_push_notification_for_user(
+        &state.db_pool,
+        order.user_id,
+        "Pagamento ricevuto",
+        &body,
+        Some("event"),
+        Some(order.event_id),
+    )
+    .await;
+
+    StatusCode::OK
+}
+
+/// Handle checkout.session.expired for a ticket order: give the held tickets back.
+async fn handle_ticket_order_expired(state: &AppState, order_id: Uuid) -> StatusCode {
+    match ticket_tier_persistence::release_ticket_order(&state.db_pool, order_id).await {
+        Ok(released) => {
+            info!(order_id = %order_id, released, "Ticket checkout session expired");
+            StatusCode::OK
+        }
+        Err(e) => {
+            error!(error = %e, order_id = %order_id, "Failed to release ticket hold");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Table tab settlement a Checkout session was opened for, from its metadata.
+fn tab_settlement_id(event: &serde_json::Value) -> Option<Uuid> {
+    event["data"]["object"]["metadata"]["table_tab_settlement_id"]
+        .as_str()
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+/// Handle checkout.session.completed for a table tab settlement: record the
+/// payment and close the tab when it covers the balance.
+async fn handle_tab_settlement_completed(
+    state: &AppState,
+    settlement_id: Uuid,
+    event: &serde_json::Value,
+) -> StatusCode {
+    let session_id = event["data"]["object"]["id"].as_str().unwrap_or("");
+    info!(settlement_id = %settlement_id, session_id = %session_id, "Processing tab settlement checkout completion");
+
+    let stripe_pi_id = event["data"]["object"]["payment_intent"]
+        .as_str()
+        .filter(|id| !id.is_empty());
+
+    let (settlement, closed) = match table_tab_persistence::complete_stripe_settlement(
+        &state.db_pool,
+        settlement_id,
+        stripe_pi_id,
+    )
+    .await
+    {
+        Ok(Some(completed)) => completed,
+        Ok(None) => {
+            // Already paid (a redelivery) or cancelled by staff before the
+            // guest paid: the latter needs a manual refund.
+            match table_tab_persistence::get_settlement(&state.db_pool, settlement_id).await {
+                Ok(Some(settlement)) if settlement.status == "expired" => {
+                    error!(settlement_id = %settlement_id, session_id = %session_id, "Tab settlement paid after it was released");
+                    if state.alert_webhook_url.is_some() {
+                        let message = format!(
+                            "Tab settlement {} (tab {}) was paid after it was released: {:.2} € via session {}. Refund it manually.",
+                            settlement_id, settlement.tab_id, settlement.amount, session_id
+                        );
+                        if let Err(e) = outbox_service::enqueue_alert_webhook(
+                            &state.db_pool,
+                            &message,
+                            "stripe_webhook",
+                        )
+                        .await
+                        {
+                            error!(error = %e, "Failed to enqueue tab settlement alert");
+                        }
+                    }
+                    let _ = outbox_service::enqueue_analytics_event(
+                        &state.db_pool,
+                        &state.config,
+                        "table_tab_paid_after_release",
+                        None,
+                        Some("table_tab"),
+                        Some(settlement.tab_id),
+                        serde_json::json!({
+                            "settlement_id": settlement_id,
+                            "session_id": session_id,
+                            "amount": settlement.amount,
+                            "outcome": "needs_refund",
+                        }),
+                    )
+                    .await;
+                }
+                Ok(_) => {
+                    info!(settlement_id = %settlement_id, "Tab settlement no longer pending, skipping")
+                }
+                Err(e) => {
+                    error!(error = %e, settlement_id = %settlement_id, "Failed to load tab settlement");
+                    return StatusCode::INTERNAL_SERVER_ERROR;
+                }
+            }
+            return StatusCode::OK;
+        }
+        Err(e) => {
+            error!(error = %e, settlement_id = %settlement_id, "Failed to complete tab settlement");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
+    info!(settlement_id = %settlement.id, tab_id = %settlement.tab_id, closed, "Tab settlement paid");
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "table_tab_settled",
+        None,
+        Some("table_tab"),
+        Some(settlement.tab_id),
+        serde_json::json!({
+            "settlement_id": settlement.id,
+            "payment_id": settlement.payment_id,
+            "method": settlement.method,
+            "amount": settlement.amount,
+            "tab_closed": closed,
+        }),
+    )
+    .await;
+
+    StatusCode::OK
+}
+
+/// Handle checkout.session.expired for a table tab settlement: reopen the tab.
+async fn handle_tab_settlement_expired(state: &AppState, settlement_id: Uuid) -> StatusCode {
+    match table_tab_persistence::release_stripe_settlement(&state.db_pool, settlement_id).await {
+        Ok(released) => {
+            info!(settlement_id = %settlement_id, released, "Tab settlement checkout session expired");
+            StatusCode::OK
+        }
+        Err(e) => {
+            error!(error = %e, settlement_id = %settlement_id, "Failed to release tab settlement");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+fn generate_ticket_code() -> String {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    let random_part: String = (0..8)
+        .map(|_| {
+            let idx = rng.gen_range(0..36);
+            if idx < 26 {
+                (b'A' + idx) as char
+            } else {
+                (b'0' + (idx - 26)) as char
+            }
+        })
+        .collect();
+    format!("TKT-{}", random_part)
+}
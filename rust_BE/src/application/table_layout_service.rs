@@ -0,0 +1 @@
+pub use crate::infrastructure::repositories::table_layout_repository::*;
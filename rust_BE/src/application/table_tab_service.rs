@@ -0,0 +1,20 @@
+pub use crate::infrastructure::repositories::table_tab_repository::*;
+
+use rust_decimal::Decimal;
+
+use crate::models::table_tab::{AddTabLineRequest, TAB_LINE_CATEGORIES};
+
+/// Reservation statuses a tab can be opened on
+pub const TAB_RESERVATION_STATUSES: [&str; 2] = ["confirmed", "completed"];
+
+/// Check a consumption line and return its quantity (1 when omitted).
+pub fn validate_tab_line(request: &AddTabLineRequest) -> Option<i32> {
+    let quantity = request.quantity.unwrap_or(1);
+    let description = request.description.trim();
+    let valid = TAB_LINE_CATEGORIES.contains(&request.category.as_str())
+        && !description.is_empty()
+        && description.len() <= 255
+        && quantity > 0
+        && request.unit_price >= Decimal::ZERO;
+    valid.then_some(quantity)
+}
@@ -0,0 +1 @@
+pub use crate::infrastructure::repositories::ticket_tier_repository::*;
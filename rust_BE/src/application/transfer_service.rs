@@ -0,0 +1,139 @@
+pub use crate::infrastructure::repositories::transfer_repository::*;
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::application::outbox_service;
+use crate::infrastructure::repositories::user_repository;
+use crate::models::{AppState, OwnershipTransfer};
+
+/// How long a recipient has to accept before the transfer expires
+pub const TRANSFER_EXPIRY_HOURS: i64 = 48;
+
+/// A six-digit claim code for a transfer sent by email, and its stored hash.
+pub fn new_claim_code() -> (String, String) {
+    let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+    let code_hash = hash_claim_code(&code);
+    (code, code_hash)
+}
+
+pub fn hash_claim_code(code: &str) -> String {
+    hex::encode(Sha256::digest(code.trim().as_bytes()))
+}
+
+fn item_label(transfer: &OwnershipTransfer) -> &'static str {
+    if transfer.item_type == "ticket" {
+        "un biglietto"
+    } else {
+        "una prenotazione"
+    }
+}
+
+/// Tell the recipient a transfer is waiting for them: in-app when they already
+/// have an account, and by SMS or email at the address the sender gave. The
+/// email carries the claim code needed to accept.
+pub async fn notify_recipient(
+    state: &AppState,
+    transfer: &OwnershipTransfer,
+    claim_code: Option<&str>,
+) {
+    let sender = transfer.from_user_name.as_deref().unwrap_or("Un utente");
+    let event_title = transfer.event_title.as_deref().unwrap_or("un evento");
+    let body = format!(
+        "{} ti ha trasferito {} per {}. Accettalo dall'app entro {} ore.",
+        sender,
+        item_label(transfer),
+        event_title,
+        TRANSFER_EXPIRY_HOURS
+    );
+
+    let recipient = match (&transfer.recipient_phone, &transfer.recipient_email) {
+        (Some(phone), _) => user_repository::find_user_by_phone(&state.db_pool, phone).await,
+        (None, Some(email)) => user_repository::find_user_by_email(&state.db_pool, email).await,
+        (None, None) => Ok(None),
+    };
+    match recipient {
+        Ok(Some(user)) => {
+            if let Err(error) = outbox_service::enqueue_push_notification_for_user(
+                &state.db_pool,
+                user.id,
+                "Trasferimento in arrivo",
+                &body,
+                Some("ownership_transfer"),
+                Some(transfer.id),
+            )
+            .await
+            {
+                warn!(transfer_id = %transfer.id, error = %error, "Transfer: failed to enqueue push notification");
+            }
+        }
+        Ok(None) => {}
+        Err(error) => {
+            warn!(transfer_id = %transfer.id, error = %error, "Transfer: failed to look up recipient")
+        }
+    }
+
+    let queued = if let Some(phone) = transfer.recipient_phone.as_deref() {
+        outbox_service::enqueue_sms_notification(
+            &state.db_pool,
+            phone,
+            &format!("{} Scarica l'app: {}", body, state.config.app_base_url),
+            Some("ownership_transfer"),
+            Some(transfer.id),
+        )
+        .await
+    } else if let Some(email) = transfer.recipient_email.as_deref() {
+        outbox_service::enqueue_email_notification(
+            &state.db_pool,
+            email,
+            "Hai ricevuto un trasferimento",
+            &format!(
+                "{}\n\nPer accettarlo inserisci il codice {}.\n\n{}",
+                body,
+                claim_code.unwrap_or_default(),
+                state.config.app_base_url
+            ),
+            None,
+            Some("ownership_transfer"),
+            Some(transfer.id),
+        )
+        .await
+    } else {
+        return;
+    };
+    if let Err(error) = queued {
+        warn!(transfer_id = %transfer.id, error = %error, "Transfer: failed to enqueue recipient notification");
+    }
+}
+
+/// Let the sender know the recipient accepted or declined.
+pub async fn notify_sender(state: &AppState, transfer: &OwnershipTransfer) {
+    let event_title = transfer.event_title.as_deref().unwrap_or("l'evento");
+    let body = match transfer.status.as_str() {
+        "accepted" => format!(
+            "Il trasferimento di {} per {} è stato accettato. Il tuo codice non è più valido.",
+            item_label(transfer),
+            event_title
+        ),
+        "declined" => format!(
+            "Il trasferimento di {} per {} è stato rifiutato. È ancora tuo.",
+            item_label(transfer),
+            event_title
+        ),
+        _ => return,
+    };
+
+    if let Err(error) = outbox_service::enqueue_push_notification_for_user(
+        &state.db_pool,
+        transfer.from_user_id,
+        "Trasferimento aggiornato",
+        &body,
+        Some("ownership_transfer"),
+        Some(transfer.id),
+    )
+    .await
+    {
+        warn!(transfer_id = %transfer.id, error = %error, "Transfer: failed to enqueue push notification");
+    }
+}
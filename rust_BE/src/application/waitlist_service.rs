@@ -0,0 +1,106 @@
+pub use crate::infrastructure::repositories::waitlist_repository::*;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::outbox_service;
+use crate::models::waitlist::{hold_notification, WaitlistHold};
+use crate::models::{AppState, WaitlistEntry};
+
+/// Hold a freed table for the next matching waitlisted user and notify them.
+pub async fn offer_table(
+    state: &AppState,
+    table_id: Uuid,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    let hold_expires_at = Utc::now() + Duration::minutes(state.config.waitlist_hold_minutes);
+    let entry = claim_next_entry_for_table(&state.db_pool, table_id, hold_expires_at).await?;
+    if let Some(entry) = &entry {
+        info!(entry_id = %entry.id, table_id = %table_id, user_id = %entry.user_id, "Waitlist: table offered");
+        notify_hold(state, entry).await;
+    }
+    Ok(entry)
+}
+
+/// Hold a freed guest slot of a split reservation for the next waitlisted user
+/// who asked for a single spot, and notify them.
+pub async fn offer_reservation_slot(
+    state: &AppState,
+    reservation_id: Uuid,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    let hold_expires_at = Utc::now() + Duration::minutes(state.config.waitlist_hold_minutes);
+    let entry = claim_next_entry_for_slot(&state.db_pool, reservation_id, hold_expires_at).await?;
+    if let Some(entry) = &entry {
+        info!(entry_id = %entry.id, reservation_id = %reservation_id, user_id = %entry.user_id, "Waitlist: guest slot offered");
+        notify_hold(state, entry).await;
+    }
+    Ok(entry)
+}
+
+/// Pass whatever an expired or abandoned hold was reserving on to the next in line.
+pub async fn reoffer_released_hold(
+    state: &AppState,
+    released: &WaitlistEntry,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    match released.hold() {
+        Some(WaitlistHold::Table(table_id)) => offer_table(state, table_id).await,
+        Some(WaitlistHold::Slot(reservation_id)) => {
+            offer_reservation_slot(state, reservation_id).await
+        }
+        None => Ok(None),
+    }
+}
+
+async fn notify_hold(state: &AppState, entry: &WaitlistEntry) {
+    let Some(hold) = entry.hold() else {
+        return;
+    };
+    let context = match get_hold_context(&state.db_pool, entry.id).await {
+        Ok(context) => context,
+        Err(error) => {
+            warn!(entry_id = %entry.id, error = %error, "Waitlist: failed to load hold context");
+            return;
+        }
+    };
+
+    let (title, body) = hold_notification(
+        hold,
+        context.table_name.as_deref().unwrap_or("un tavolo"),
+        &context.event_title,
+        state.config.waitlist_hold_minutes,
+    );
+
+    if let Err(error) = outbox_service::enqueue_push_notification_for_user(
+        &state.db_pool,
+        entry.user_id,
+        &title,
+        &body,
+        Some("waitlist_entry"),
+        Some(entry.id),
+    )
+    .await
+    {
+        warn!(entry_id = %entry.id, error = %error, "Waitlist: failed to enqueue push notification");
+    }
+
+    if let Some(phone) = context.user_phone.as_deref() {
+        let sms = match context.payment_link_token.as_deref() {
+            Some(token) if matches!(hold, WaitlistHold::Slot(_)) => format!(
+                "{} Paga la tua quota qui: {}/pay/{}",
+                body, state.config.app_base_url, token
+            ),
+            _ => format!("{} Prenota dall'app.", body),
+        };
+        if let Err(error) = outbox_service::enqueue_sms_notification(
+            &state.db_pool,
+            phone,
+            &sms,
+            Some("waitlist_entry"),
+            Some(entry.id),
+        )
+        .await
+        {
+            warn!(entry_id = %entry.id, error = %error, "Waitlist: failed to enqueue SMS notification");
+        }
+    }
+}
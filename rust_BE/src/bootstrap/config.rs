@@ -10,6 +10,10 @@ pub struct DatabaseConfig {
 #[derive(Clone, Debug)]
 pub struct AuthConfig {
     pub jwt_secret: String,
+    pub access_token_ttl_minutes: i64,
+    pub refresh_token_ttl_days: i64,
+    /// Shared key for the `/admin/*` operations endpoints; unset disables them
+    pub admin_api_key: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -17,6 +21,9 @@ pub struct StripeConfig {
     pub api_key: String,
     pub publishable_key: String,
     pub webhook_secret: String,
+    /// Where Connect transfers and payouts are read from for reconciliation:
+    /// "stripe" (platform API), "fake" (mirrors local payments) or "none"
+    pub reconciliation_provider: String,
 }
 
 #[derive(Clone, Debug)]
@@ -31,6 +38,30 @@ pub struct NotificationsConfig {
     pub app_review_bypass_phone_numbers: Vec<String>,
 }
 
+#[derive(Clone, Debug)]
+pub struct EmailConfig {
+    /// "http" (JSON email API), "smtp" or "log" (print instead of sending)
+    pub provider: String,
+    pub from: Option<String>,
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    /// "starttls", "tls" or "none" (local SMTP sinks)
+    pub smtp_tls: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct GeocodingConfig {
+    /// "nominatim" (Nominatim-compatible search API), "stub" (fake local
+    /// coordinates) or "none" (only manual coordinates)
+    pub provider: String,
+    pub api_url: String,
+    pub api_key: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct AnalyticsConfig {
     pub outbox_poll_interval_seconds: u64,
@@ -51,6 +82,14 @@ pub struct FeatureFlagsConfig {
 pub struct JobsConfig {
     pub payment_frequent_interval_seconds: u64,
     pub idempotency_cleanup_interval_seconds: u64,
+    pub waitlist_hold_check_interval_seconds: u64,
+    pub push_receipt_check_interval_seconds: u64,
+    pub ticket_hold_check_interval_seconds: u64,
+    pub event_series_interval_seconds: u64,
+    pub event_series_horizon_days: i64,
+    pub geocoding_interval_seconds: u64,
+    pub connect_reconciliation_interval_seconds: u64,
+    pub stripe_webhook_poll_interval_seconds: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -66,6 +105,8 @@ pub struct AppConfig {
     pub auth: AuthConfig,
     pub stripe: StripeConfig,
     pub notifications: NotificationsConfig,
+    pub email: EmailConfig,
+    pub geocoding: GeocodingConfig,
     pub analytics: AnalyticsConfig,
     pub feature_flags: FeatureFlagsConfig,
     pub jobs: JobsConfig,
@@ -74,6 +115,9 @@ pub struct AppConfig {
     pub owner_app_base_url: String,
     pub auto_run_db_migrations: bool,
     pub payment_share_ttl_hours: i64,
+    pub waitlist_hold_minutes: i64,
+    pub checkin_grace_hours: i64,
+    pub password_reset_code_ttl_minutes: i64,
     pub port: u16,
 }
 
@@ -99,12 +143,23 @@ impl AppConfig {
         if jwt_secret.len() < 32 {
             panic!("JWT_SECRET must be at least 32 characters long");
         }
+        let access_token_ttl_minutes = env::var("ACCESS_TOKEN_TTL_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(15);
+        let refresh_token_ttl_days = env::var("REFRESH_TOKEN_TTL_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+        let admin_api_key = env::var("ADMIN_API_KEY").ok().filter(|s| !s.is_empty());
 
         let stripe_webhook_secret = env::var("STRIPE_WEBHOOK_SECRET")
             .expect("STRIPE_WEBHOOK_SECRET env var must be set — webhook signature verification cannot be disabled");
         if stripe_webhook_secret.is_empty() {
             panic!("STRIPE_WEBHOOK_SECRET must not be empty");
         }
+        let stripe_reconciliation_provider =
+            env::var("STRIPE_RECONCILIATION_PROVIDER").unwrap_or_else(|_| "none".to_string());
 
         let app_base_url = env::var("APP_BASE_URL")
             .expect("APP_BASE_URL env var must be set (used for Stripe Checkout redirect URLs)");
@@ -131,6 +186,25 @@ impl AppConfig {
         let twilio_phone_number = env::var("TWILIO_PHONE_NUMBER")
             .ok()
             .filter(|s| !s.is_empty());
+        let email_provider = env::var("EMAIL_PROVIDER").unwrap_or_else(|_| "http".to_string());
+        let email_from = env::var("EMAIL_FROM").ok().filter(|s| !s.is_empty());
+        let email_api_url = env::var("EMAIL_API_URL").ok().filter(|s| !s.is_empty());
+        let email_api_key = env::var("EMAIL_API_KEY").ok().filter(|s| !s.is_empty());
+        let smtp_host = env::var("SMTP_HOST").ok().filter(|s| !s.is_empty());
+        let smtp_port = env::var("SMTP_PORT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(587);
+        let smtp_username = env::var("SMTP_USERNAME").ok().filter(|s| !s.is_empty());
+        let smtp_password = env::var("SMTP_PASSWORD").ok().filter(|s| !s.is_empty());
+        let smtp_tls = env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string());
+        let geocoder_provider =
+            env::var("GEOCODER_PROVIDER").unwrap_or_else(|_| "none".to_string());
+        let geocoder_api_url = env::var("GEOCODER_API_URL")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "https://nominatim.openstreetmap.org".to_string());
+        let geocoder_api_key = env::var("GEOCODER_API_KEY").ok().filter(|s| !s.is_empty());
         let app_review_bypass_enabled = env::var("APP_REVIEW_BYPASS_ENABLED")
             .ok()
             .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "YES"))
@@ -153,6 +227,18 @@ impl AppConfig {
             .ok()
             .and_then(|v| v.parse().ok())
             .unwrap_or(48);
+        let waitlist_hold_minutes = env::var("WAITLIST_HOLD_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+        let checkin_grace_hours = env::var("CHECKIN_GRACE_HOURS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(8);
+        let password_reset_code_ttl_minutes = env::var("PASSWORD_RESET_CODE_TTL_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(15);
         let outbox_poll_interval_seconds = env::var("OUTBOX_POLL_INTERVAL_SECONDS")
             .ok()
             .and_then(|v| v.parse().ok())
@@ -183,6 +269,39 @@ impl AppConfig {
             .ok()
             .and_then(|v| v.parse().ok())
             .unwrap_or(3600);
+        let waitlist_hold_check_interval_seconds = env::var("WAITLIST_HOLD_CHECK_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(60);
+        let push_receipt_check_interval_seconds = env::var("PUSH_RECEIPT_CHECK_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(900);
+        let ticket_hold_check_interval_seconds = env::var("TICKET_HOLD_CHECK_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+        let event_series_interval_seconds = env::var("EVENT_SERIES_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3600);
+        let event_series_horizon_days = env::var("EVENT_SERIES_HORIZON_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(56);
+        let geocoding_interval_seconds = env::var("GEOCODING_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(600);
+        let connect_reconciliation_interval_seconds =
+            env::var("CONNECT_RECONCILIATION_INTERVAL_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3600);
+        let stripe_webhook_poll_interval_seconds = env::var("STRIPE_WEBHOOK_POLL_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(5);
         let port = env::var("PORT")
             .ok()
             .and_then(|v| v.parse().ok())
@@ -201,11 +320,17 @@ impl AppConfig {
                 read_url: read_database_url,
                 public_cache_ttl_seconds,
             },
-            auth: AuthConfig { jwt_secret },
+            auth: AuthConfig {
+                jwt_secret,
+                access_token_ttl_minutes,
+                refresh_token_ttl_days,
+                admin_api_key,
+            },
             stripe: StripeConfig {
                 api_key: stripe_api_key,
                 publishable_key: stripe_publishable_key,
                 webhook_secret: stripe_webhook_secret,
+                reconciliation_provider: stripe_reconciliation_provider,
             },
             notifications: NotificationsConfig {
                 alert_webhook_url,
@@ -217,6 +342,22 @@ impl AppConfig {
                 app_review_bypass_code,
                 app_review_bypass_phone_numbers,
             },
+            email: EmailConfig {
+                provider: email_provider,
+                from: email_from,
+                api_url: email_api_url,
+                api_key: email_api_key,
+                smtp_host,
+                smtp_port,
+                smtp_username,
+                smtp_password,
+                smtp_tls,
+            },
+            geocoding: GeocodingConfig {
+                provider: geocoder_provider,
+                api_url: geocoder_api_url,
+                api_key: geocoder_api_key,
+            },
             analytics: AnalyticsConfig {
                 outbox_poll_interval_seconds,
                 outbox_batch_size,
@@ -232,6 +373,14 @@ impl AppConfig {
             jobs: JobsConfig {
                 payment_frequent_interval_seconds,
                 idempotency_cleanup_interval_seconds,
+                waitlist_hold_check_interval_seconds,
+                push_receipt_check_interval_seconds,
+                ticket_hold_check_interval_seconds,
+                event_series_interval_seconds,
+                event_series_horizon_days,
+                geocoding_interval_seconds,
+                connect_reconciliation_interval_seconds,
+                stripe_webhook_poll_interval_seconds,
             },
             storage: StorageConfig {
                 supabase_url,
@@ -242,6 +391,9 @@ impl AppConfig {
             owner_app_base_url,
             auto_run_db_migrations,
             payment_share_ttl_hours,
+            waitlist_hold_minutes,
+            checkin_grace_hours,
+            password_reset_code_ttl_minutes,
             port,
         }
     }
@@ -1,12 +1,21 @@
 use crate::application::auth_service as user_persistence;
 use crate::application::outbox_service;
+use crate::application::push_service;
+use crate::application::session_service::{self, SessionError};
 use crate::middleware::auth::{AuthUser, SmsVerificationUser};
 use crate::models::{
-    ApiError, AppState, AuthResponse, LoginRequest, RegisterRequest, User, UserResponse,
+    ApiError, AppState, AuthResponse, LoginRequest, RefreshTokenRequest, RegisterRequest,
+    SessionSubject, TokenPairResponse, User, UserResponse,
 };
+use crate::services::email_templates::EmailLocale;
 use crate::services::sms_service;
 use crate::utils::jwt;
-use axum::{body::Bytes, extract::State, http::StatusCode, Json};
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
 use bcrypt::{hash, verify, DEFAULT_COST};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -14,7 +23,7 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 
 /// Validate an email address — must contain @ and a dot after it
-fn is_valid_email(email: &str) -> bool {
+pub(crate) fn is_valid_email(email: &str) -> bool {
     let parts: Vec<&str> = email.splitn(2, '@').collect();
     if parts.len() != 2 {
         return false;
@@ -23,7 +32,7 @@ fn is_valid_email(email: &str) -> bool {
     !parts[0].is_empty() && domain.contains('.') && domain.len() > 2
 }
 
-fn normalize_phone_number(phone: &str) -> Option<String> {
+pub(crate) fn normalize_phone_number(phone: &str) -> Option<String> {
     let compact: String = phone
         .trim()
         .chars()
@@ -67,10 +76,24 @@ fn is_unique_violation(error: &sqlx::Error) -> bool {
 /// Register a new user
 pub async fn register(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(payload): Json<RegisterRequest>,
 ) -> Result<(StatusCode, Json<AuthResponse>), (StatusCode, axum::Json<crate::models::ApiError>)> {
     info!(email = %payload.email, "Registration attempt");
 
+    // Emails go out in the app's language, else the device's
+    let preferred_locale = payload
+        .locale
+        .as_deref()
+        .and_then(EmailLocale::parse)
+        .unwrap_or_else(|| {
+            EmailLocale::from_accept_language(
+                headers
+                    .get(header::ACCEPT_LANGUAGE)
+                    .and_then(|value| value.to_str().ok()),
+            )
+        });
+
     if !is_valid_email(&payload.email) {
         warn!(email = %payload.email, "Registration rejected: invalid email format");
         return Err(crate::models::ApiError::new(
@@ -151,6 +174,7 @@ pub async fn register(
         payload.name,
         normalized_phone_number.clone(),
         payload.date_of_birth,
+        preferred_locale.as_str(),
     )
     .await
     {
@@ -187,6 +211,8 @@ pub async fn register(
     let response = AuthResponse {
         user: UserResponse::from(user),
         token,
+        // Issued at login, once the phone number is verified
+        refresh_token: None,
     };
 
     let _ = outbox_service::enqueue_analytics_event(
@@ -262,21 +288,19 @@ pub async fn login(
 
     let _ = user_persistence::update_last_login(&state.db_pool, user.id).await;
 
-    let token = match jwt::generate_token(
-        user.id,
-        user.email.clone(),
-        "user".to_string(),
-        &state.jwt_secret,
-    ) {
-        Ok(token) => token,
-        Err(e) => {
-            error!(error = %e, user_id = %user.id, "Failed to generate JWT");
-            return Err(ApiError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Errore durante la generazione del token",
-            ));
-        }
-    };
+    let session =
+        match session_service::start_session(&state, SessionSubject::User(user.id), &user.email)
+            .await
+        {
+            Ok(session) => session,
+            Err(e) => {
+                error!(error = %e, user_id = %user.id, "Failed to start session");
+                return Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Errore durante la generazione del token",
+                ));
+            }
+        };
 
     info!(user_id = %user.id, email = %user.email, "Login successful");
 
@@ -297,10 +321,64 @@ pub async fn login(
 
     Ok(Json(AuthResponse {
         user: UserResponse::from(user),
-        token,
+        token: session.access_token,
+        refresh_token: Some(session.refresh_token),
     }))
 }
 
+/// Exchange a refresh token for a new token pair. Works for users and club
+/// owners; the presented refresh token cannot be used again.
+pub async fn refresh(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RefreshTokenRequest>,
+) -> Result<Json<TokenPairResponse>, (StatusCode, axum::Json<crate::models::ApiError>)> {
+    if payload.refresh_token.trim().is_empty() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Refresh token mancante",
+        ));
+    }
+
+    match session_service::refresh_session(&state, &payload.refresh_token).await {
+        Ok(session) => Ok(Json(TokenPairResponse {
+            token: session.access_token,
+            refresh_token: session.refresh_token,
+            expires_in: session.expires_in,
+        })),
+        Err(SessionError::Invalid) => Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Sessione scaduta, effettua di nuovo l'accesso",
+        )),
+        Err(SessionError::Reused) => Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Sessione revocata per motivi di sicurezza, effettua di nuovo l'accesso",
+        )),
+        Err(e) => {
+            error!(error = %e, "Failed to refresh session");
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore interno",
+            ))
+        }
+    }
+}
+
+/// Revoke the session of a refresh token. Unknown tokens are ignored so
+/// logging out twice is harmless.
+pub async fn logout(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RefreshTokenRequest>,
+) -> Result<StatusCode, (StatusCode, axum::Json<crate::models::ApiError>)> {
+    session_service::end_session(&state, &payload.refresh_token)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to end session");
+            ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SendSmsRequest {
     phone_number: String,
@@ -324,12 +402,15 @@ pub struct VerifySmsResponse {
     user: Option<UserResponse>,
 }
 
-/// Register or update the Expo push token for the authenticated user.
+/// Register or update the Expo push token of one of the authenticated user's devices.
 ///
 /// Called by the frontend after login and whenever the token refreshes.
 #[derive(Debug, Deserialize)]
 pub struct RegisterPushTokenRequest {
     pub push_token: String,
+    /// "ios", "android" or "web"
+    #[serde(default)]
+    pub platform: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -341,6 +422,9 @@ pub struct ChangePasswordRequest {
 #[derive(Debug, Serialize)]
 pub struct ChangePasswordResponse {
     pub message: String,
+    /// Every other session is revoked; these replace the caller's tokens
+    pub token: String,
+    pub refresh_token: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -368,10 +452,12 @@ pub async fn register_push_token(
         Err(status) => return status,
     };
 
-    match sqlx::query("UPDATE users SET expo_push_token = $1, updated_at = NOW() WHERE id = $2")
-        .bind(&payload.push_token)
-        .bind(user_id)
-        .execute(&state.db_pool)
+    let platform = payload
+        .platform
+        .as_deref()
+        .filter(|platform| matches!(*platform, "ios" | "android" | "web"));
+
+    match push_service::upsert_push_device(&state.db_pool, user_id, &payload.push_token, platform)
         .await
     {
         Ok(_) => {
@@ -385,6 +471,7 @@ pub async fn register_push_token(
                 serde_json::json!({
                     "user_id": user_id,
                     "push_token_length": payload.push_token.len(),
+                    "platform": platform,
                     "outcome": "success",
                 }),
             )
@@ -398,6 +485,32 @@ pub async fn register_push_token(
     }
 }
 
+/// Forget one of the user's devices, e.g. on logout, so it stops receiving pushes.
+pub async fn unregister_push_token(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    body: Bytes,
+) -> StatusCode {
+    let user_id = match Uuid::parse_str(&claims.sub) {
+        Ok(id) => id,
+        Err(_) => return StatusCode::UNAUTHORIZED,
+    };
+
+    let payload = match parse_push_token_request(&body) {
+        Ok(payload) => payload,
+        Err(status) => return status,
+    };
+
+    match push_service::remove_push_device(&state.db_pool, &payload.push_token, Some(user_id)).await
+    {
+        Ok(_) => StatusCode::NO_CONTENT,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to remove push token");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
 fn parse_push_token_request(body: &Bytes) -> Result<RegisterPushTokenRequest, StatusCode> {
     if body.is_empty() {
         return Err(StatusCode::BAD_REQUEST);
@@ -419,6 +532,7 @@ fn parse_push_token_request(body: &Bytes) -> Result<RegisterPushTokenRequest, St
     if raw_body.starts_with("ExponentPushToken[") || raw_body.starts_with("ExpoPushToken[") {
         return Ok(RegisterPushTokenRequest {
             push_token: raw_body.to_string(),
+            platform: None,
         });
     }
 
@@ -496,6 +610,24 @@ pub async fn change_password(
             ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
         })?;
 
+    session_service::revoke_all_sessions(
+        &state.db_pool,
+        SessionSubject::User(user_id),
+        "password_changed",
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, %user_id, "Failed to revoke sessions after password change");
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
+    })?;
+    let session =
+        session_service::start_session(&state, SessionSubject::User(user_id), &user.email)
+            .await
+            .map_err(|e| {
+                error!(error = %e, %user_id, "Failed to start session after password change");
+                ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
+            })?;
+
     let _ = outbox_service::enqueue_analytics_event(
         &state.db_pool,
         &state.config,
@@ -514,6 +646,8 @@ pub async fn change_password(
 
     Ok(Json(ChangePasswordResponse {
         message: "Password aggiornata correttamente.".to_string(),
+        token: session.access_token,
+        refresh_token: session.refresh_token,
     }))
 }
 
@@ -574,6 +708,8 @@ pub async fn delete_account(
         crate::models::ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
     })?;
 
+    // Also deletes every refresh-token session, which invalidates the
+    // account's access tokens.
     user_persistence::anonymize_and_delete_user_account(
         &state.db_pool,
         user_id,
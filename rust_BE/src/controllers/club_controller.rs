@@ -1,12 +1,20 @@
-use crate::application::club_service as club_persistence;
-use crate::middleware::auth::ClubOwnerUser;
-use crate::models::{AppState, ClubResponse, CreateClubRequest, UpdateClubRequest};
+use crate::application::{
+    club_service as club_persistence, event_service as event_persistence, platform_admin_service,
+};
+use crate::middleware::auth::{AdminUser, ClubManager};
+use crate::models::event_search::EventSearchSort;
+use crate::models::{
+    AppState, ClubResponse, CreateClubRequest, EventResponse, EventSearchParams, EventSearchResult,
+    NearbyClubResponse, NearbyParams, NearbyResponse, UpdateClubRequest,
+};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use chrono::Utc;
 use std::sync::Arc;
+use tracing::warn;
 use uuid::Uuid;
 
 /// Get all clubs
@@ -22,6 +30,67 @@ pub async fn get_all_clubs(
     }
 }
 
+/// Clubs around a point, closest first, with their upcoming events
+pub async fn get_nearby(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<NearbyParams>,
+) -> Result<Json<NearbyResponse>, StatusCode> {
+    let (lat, lng, radius_km, limit) = params.validate().ok_or(StatusCode::BAD_REQUEST)?;
+
+    let clubs = club_persistence::get_nearby_clubs(&state.read_db_pool, lat, lng, radius_km, limit)
+        .await
+        .map_err(|error| {
+            warn!(error = %error, "Nearby clubs lookup failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let filters = EventSearchParams {
+        sort: Some(EventSearchSort::Distance),
+        lat: Some(lat),
+        lng: Some(lng),
+        radius_km: Some(radius_km),
+        limit: Some(limit),
+        ..Default::default()
+    }
+    .into_filters(Utc::now().date_naive())
+    .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let mut rows = event_persistence::search_events(&state.read_db_pool, &filters)
+        .await
+        .map_err(|error| {
+            warn!(error = %error, "Nearby events lookup failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    rows.truncate(filters.limit as usize);
+
+    let event_ids: Vec<Uuid> = rows.iter().map(|row| row.event.id).collect();
+    let genres_map = event_persistence::get_genres_for_events(&state.read_db_pool, &event_ids)
+        .await
+        .unwrap_or_default();
+
+    Ok(Json(NearbyResponse {
+        clubs: clubs
+            .into_iter()
+            .map(|row| NearbyClubResponse {
+                club: row.club.into(),
+                distance_km: row.distance_km,
+            })
+            .collect(),
+        events: rows
+            .into_iter()
+            .map(|row| {
+                let id = row.event.id;
+                let mut event = EventResponse::from(row.event);
+                event.genres = genres_map.get(&id).cloned().unwrap_or_default();
+                EventSearchResult {
+                    event,
+                    popularity: row.popularity,
+                    distance_km: row.distance_km,
+                }
+            })
+            .collect(),
+    }))
+}
+
 /// Get a single club by ID
 pub async fn get_club(
     State(state): State<Arc<AppState>>,
@@ -36,44 +105,103 @@ pub async fn get_club(
     }
 }
 
-/// Create a new club (requires club_owner JWT)
+/// Create a new club (platform admin)
 pub async fn create_club(
-    _: ClubOwnerUser,
+    AdminUser(admin): AdminUser,
     State(state): State<Arc<AppState>>,
     Json(payload): Json<CreateClubRequest>,
 ) -> Result<(StatusCode, Json<ClubResponse>), StatusCode> {
     match club_persistence::create_club(&state.db_pool, payload).await {
-        Ok(club) => Ok((StatusCode::CREATED, Json(club.into()))),
+        Ok(club) => {
+            platform_admin_service::audit(
+                &state.db_pool,
+                admin,
+                "club_created",
+                "club",
+                Some(club.id),
+                serde_json::json!({ "name": club.name, "owner_id": club.owner_id }),
+            )
+            .await;
+            Ok((StatusCode::CREATED, Json(club.into())))
+        }
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
 
-/// Update a club (requires club_owner JWT)
+/// Update a club. Admins may change every field; an owner only the profile of
+/// their own club (owner, Stripe and commission fields are rejected with 403).
 pub async fn update_club(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateClubRequest>,
 ) -> Result<Json<ClubResponse>, StatusCode> {
     let club_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
+    club_persistence::ensure_manages_club(&state.db_pool, &manager, Some(club_id)).await?;
+    if matches!(manager, ClubManager::Owner(_)) && touches_admin_fields(&payload) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let details = serde_json::json!({
+        "owner_id": payload.owner_id,
+        "platform_commission_percent": payload.platform_commission_percent,
+        "platform_commission_fixed_fee": payload.platform_commission_fixed_fee,
+    });
+
     match club_persistence::update_club(&state.db_pool, club_id, payload).await {
-        Ok(Some(club)) => Ok(Json(club.into())),
+        Ok(Some(club)) => {
+            if let ClubManager::Admin(admin) = manager {
+                platform_admin_service::audit(
+                    &state.db_pool,
+                    admin,
+                    "club_updated",
+                    "club",
+                    Some(club.id),
+                    details,
+                )
+                .await;
+            }
+            Ok(Json(club.into()))
+        }
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
 
-/// Delete a club (requires club_owner JWT)
+/// Fields only the platform may set: who owns the club, its Stripe account state
+/// and the commission charged on its payments.
+fn touches_admin_fields(payload: &UpdateClubRequest) -> bool {
+    payload.owner_id.is_some()
+        || payload.stripe_connected_account_id.is_some()
+        || payload.stripe_onboarding_complete.is_some()
+        || payload.stripe_charges_enabled.is_some()
+        || payload.stripe_payouts_enabled.is_some()
+        || payload.platform_commission_percent.is_some()
+        || payload.platform_commission_fixed_fee.is_some()
+}
+
+/// Delete a club (platform admin)
 pub async fn delete_club(
-    _: ClubOwnerUser,
+    AdminUser(admin): AdminUser,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
     let club_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     match club_persistence::delete_club(&state.db_pool, club_id).await {
-        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(true) => {
+            platform_admin_service::audit(
+                &state.db_pool,
+                admin,
+                "club_deleted",
+                "club",
+                Some(club_id),
+                serde_json::json!({}),
+            )
+            .await;
+            Ok(StatusCode::NO_CONTENT)
+        }
         Ok(false) => Err(StatusCode::NOT_FOUND),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
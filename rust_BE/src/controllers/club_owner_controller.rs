@@ -1,26 +1,35 @@
 use crate::application::{
-    club_owner_service as club_owner_persistence, club_service as club_persistence,
-    event_service as event_persistence, outbox_service, reservation_service as table_persistence,
+    area_service as area_persistence, club_owner_service as club_owner_persistence,
+    club_service as club_persistence, event_service as event_persistence, outbox_service,
+    promo_code_service as promo_code_persistence, refund_service as refund_persistence,
+    reservation_service as table_persistence, session_service,
+    waitlist_service as waitlist_persistence,
+};
+use crate::middleware::auth::{
+    AnyStaff, ClubOwnerUser, ClubStaffUser, ManageClub, ManageReservations, ScanCodes, ViewFinance,
 };
-use crate::middleware::auth::ClubOwnerUser;
 use crate::models::club_owner::{
-    AddImageRequest, ClubImageRow, ClubOwnerAuthResponse, ClubOwnerLoginRequest,
+    checkin_event_dates, AddImageRequest, CheckinAuditEntryResponse, CheckinAuditParams,
+    CheckinRequest, ClubImageRow, ClubOwnerAuthResponse, ClubOwnerLoginRequest,
     ClubOwnerRegisterRequest, ClubOwnerResponse, CreateManualReservationRequest, OwnerStats,
     OwnerUpdateClubRequest, ScanResult, StripeConnectStatusResponse, StripeOnboardingLinkResponse,
     TableImageRow, UpdateReservationStatusRequest,
 };
 use crate::models::table::TableReservationResponse;
 use crate::models::{
-    ApiError, AppState, ClubResponse, CreateClubRequest, CreateEventRequest, CreateTableRequest,
-    EventResponse, TableResponse, TablesResponse, UpdateClubRequest, UpdateEventRequest,
+    ApiError, AppState, CancellationActor, CancellationPolicy, CancellationPolicyResponse,
+    ClubResponse, CreateClubRequest, CreateEventRequest, CreatePromoCodeRequest,
+    CreateTableRequest, EventResponse, PromoCodeResponse, ReorderWaitlistRequest, SessionSubject,
+    TableResponse, TablesResponse, UpdateCancellationPolicyRequest, UpdateClubRequest,
+    UpdateEventRequest, UpdatePromoCodeRequest, WaitlistResponse,
 };
-use crate::utils::jwt;
+use crate::services::geocoding_service;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
-use chrono::NaiveDate;
+use chrono::{NaiveDate, Utc};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -95,19 +104,16 @@ pub async fn register_club_owner(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // Generate JWT token with club_owner role
-    let token = jwt::generate_token(
-        owner.id,
-        owner.email.clone(),
-        "club_owner".to_string(),
-        &state.jwt_secret,
-    )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let session =
+        session_service::start_session(&state, SessionSubject::ClubOwner(owner.id), &owner.email)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let response = ClubOwnerAuthResponse {
         owner: ClubOwnerResponse::from(owner),
         club: Some(ClubResponse::from(club)),
-        token,
+        token: session.access_token,
+        refresh_token: session.refresh_token,
     };
 
     Ok((StatusCode::CREATED, Json(response)))
@@ -143,26 +149,34 @@ pub async fn login_club_owner(
         return Err(StatusCode::UNAUTHORIZED);
     }
 
+    // Suspended from the back office
+    let is_active = club_owner_persistence::club_owner_is_active(&state.db_pool, owner.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !is_active {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
     // Look up the owner's club
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner.id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // Generate JWT token
-    let token = jwt::generate_token(
-        owner.id,
-        owner.email.clone(),
-        "club_owner".to_string(),
-        &state.jwt_secret,
-    )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let session =
+        session_service::start_session(&state, SessionSubject::ClubOwner(owner.id), &owner.email)
+            .await
+            .map_err(|e| {
+                tracing::error!("login_club_owner: session error: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
 
     let owner_id = owner.id;
     let club_id = club.as_ref().map(|club| club.id);
     let response = ClubOwnerAuthResponse {
         owner: ClubOwnerResponse::from(owner),
         club: club.map(ClubResponse::from),
-        token,
+        token: session.access_token,
+        refresh_token: session.refresh_token,
     };
 
     let _ = outbox_service::enqueue_analytics_event(
@@ -192,6 +206,9 @@ pub struct ChangeClubOwnerPasswordRequest {
 #[derive(Debug, serde::Serialize)]
 pub struct ChangeClubOwnerPasswordResponse {
     pub message: String,
+    /// Every other session is revoked; these replace the caller's tokens
+    pub token: String,
+    pub refresh_token: String,
 }
 
 /// Change the authenticated club owner's password.
@@ -243,6 +260,27 @@ pub async fn change_club_owner_password(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    session_service::revoke_all_sessions(
+        &state.db_pool,
+        SessionSubject::ClubOwner(owner_id),
+        "password_changed",
+    )
+    .await
+    .map_err(|error| {
+        error!(%error, %owner_id, "Failed to revoke club owner sessions after password change");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let session = session_service::start_session(
+        &state,
+        SessionSubject::ClubOwner(owner_id),
+        &owner.email,
+    )
+    .await
+    .map_err(|error| {
+        error!(%error, %owner_id, "Failed to start club owner session after password change");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
     let _ = outbox_service::enqueue_analytics_event(
         &state.db_pool,
         &state.config,
@@ -259,15 +297,17 @@ pub async fn change_club_owner_password(
 
     Ok(Json(ChangeClubOwnerPasswordResponse {
         message: "Password aggiornata correttamente.".to_string(),
+        token: session.access_token,
+        refresh_token: session.refresh_token,
     }))
 }
 
 /// Get the authenticated club owner's club
 pub async fn get_my_club(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<AnyStaff>,
 ) -> Result<Json<ClubResponse>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -281,10 +321,10 @@ pub async fn get_my_club(
 /// Accepts optional `?from_date=YYYY-MM-DD` to filter server-side.
 pub async fn get_my_club_events(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<AnyStaff>,
     Query(params): Query<EventFilterParams>,
 ) -> Result<Json<Vec<EventResponse>>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -316,13 +356,16 @@ pub async fn get_my_club_events(
 /// Create an event for the authenticated club owner's club
 pub async fn create_club_event(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Json(mut payload): Json<CreateEventRequest>,
 ) -> Result<(StatusCode, Json<EventResponse>), StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     if !crate::models::is_valid_event_image_url(&payload.image) {
         return Err(StatusCode::BAD_REQUEST);
     }
+    payload
+        .normalize_age_limit()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -355,7 +398,7 @@ pub async fn create_club_event(
         Some("event"),
         Some(event.id),
         serde_json::json!({
-            "owner_id": claims.sub,
+            "owner_id": owner_id,
             "club_id": club.id,
             "event_id": event.id,
             "outcome": "success",
@@ -371,10 +414,10 @@ pub async fn create_club_event(
 /// Get tables for a specific event owned by the club owner
 pub async fn get_my_club_tables(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
     Path(event_id): Path<String>,
 ) -> Result<Json<TablesResponse>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     // Verify the event belongs to the owner's club
@@ -405,11 +448,11 @@ pub async fn get_my_club_tables(
 /// Create a table for an event owned by the club owner
 pub async fn create_club_table(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Path(event_id): Path<String>,
     Json(req): Json<CreateTableRequest>,
 ) -> Result<(StatusCode, Json<TableResponse>), StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     // Verify the event belongs to the owner's club
@@ -451,7 +494,7 @@ pub async fn create_club_table(
         Some("table"),
         Some(table.id),
         serde_json::json!({
-            "owner_id": claims.sub,
+            "owner_id": owner_id,
             "event_id": event_uuid,
             "table_id": table.id,
             "capacity": table.capacity,
@@ -460,22 +503,40 @@ pub async fn create_club_table(
     )
     .await;
 
+    if let Err(error) = waitlist_persistence::offer_table(&state, table.id).await {
+        warn!(error = %error, table_id = %table.id, "Failed to offer new table to waitlist");
+    }
+
     Ok((StatusCode::CREATED, Json(TableResponse::from(table))))
 }
 
 /// Update the authenticated club owner's club settings
 pub async fn update_my_club(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Json(payload): Json<OwnerUpdateClubRequest>,
 ) -> Result<Json<ClubResponse>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
+    let pin = match (payload.latitude, payload.longitude) {
+        (Some(latitude), Some(longitude))
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+        {
+            Some((latitude, longitude))
+        }
+        (None, None) => None,
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+    let address_changed = payload
+        .address
+        .as_deref()
+        .is_some_and(|address| club.address.as_deref() != Some(address));
+
     let update_req = UpdateClubRequest {
         name: payload.name,
         subtitle: payload.subtitle,
@@ -492,11 +553,26 @@ pub async fn update_my_club(
         platform_commission_fixed_fee: None,
     };
 
-    let updated = club_persistence::update_club(&state.db_pool, club.id, update_req)
+    let mut updated = club_persistence::update_club(&state.db_pool, club.id, update_req)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
+    if let Some((latitude, longitude)) = pin {
+        updated = club_persistence::set_manual_coordinates(
+            &state.db_pool,
+            updated.id,
+            latitude,
+            longitude,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    } else if address_changed && geocoding_service::geocoding_enabled(&state.config.geocoding) {
+        // Locate the new address right away; the geocoding job retries on failure
+        spawn_club_geocoding(Arc::clone(&state), updated.id, updated.address.clone());
+    }
+
     let _ = outbox_service::enqueue_analytics_event(
         &state.db_pool,
         &state.config,
@@ -505,7 +581,7 @@ pub async fn update_my_club(
         Some("club"),
         Some(updated.id),
         serde_json::json!({
-            "owner_id": claims.sub,
+            "owner_id": owner_id,
             "club_id": updated.id,
             "outcome": "success",
         }),
@@ -515,12 +591,41 @@ pub async fn update_my_club(
     Ok(Json(ClubResponse::from(updated)))
 }
 
+fn spawn_club_geocoding(state: Arc<AppState>, club_id: Uuid, address: Option<String>) {
+    let Some(address) = address.filter(|address| !address.trim().is_empty()) else {
+        return;
+    };
+    tokio::spawn(async move {
+        match geocoding_service::geocode_address(
+            &state.config.geocoding,
+            &state.config.analytics.service_name,
+            &address,
+        )
+        .await
+        {
+            Ok(coordinates) => {
+                if let Err(e) = club_persistence::record_geocoding_result(
+                    &state.db_pool,
+                    club_id,
+                    &address,
+                    coordinates,
+                )
+                .await
+                {
+                    warn!(club_id = %club_id, error = %e, "Geocoding: failed to save result");
+                }
+            }
+            Err(e) => warn!(club_id = %club_id, error = %e, "Geocoding: lookup failed"),
+        }
+    });
+}
+
 /// Get the authenticated club owner's current Stripe Connect status.
 pub async fn get_my_club_stripe_status(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ViewFinance>,
 ) -> Result<Json<StripeConnectStatusResponse>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -771,9 +876,9 @@ pub async fn create_my_club_stripe_onboarding_link(
 /// Get images for the authenticated club owner's club
 pub async fn get_my_club_images(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
 ) -> Result<Json<Vec<ClubImageRow>>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -790,10 +895,10 @@ pub async fn get_my_club_images(
 /// Add an image to the authenticated club owner's club
 pub async fn add_my_club_image(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Json(payload): Json<AddImageRequest>,
 ) -> Result<(StatusCode, Json<ClubImageRow>), StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -816,10 +921,10 @@ pub async fn add_my_club_image(
 /// Delete a club image (ownership checked)
 pub async fn delete_my_club_image(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Path(image_id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let image_uuid = Uuid::parse_str(&image_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
@@ -841,10 +946,10 @@ pub async fn delete_my_club_image(
 /// Get images for a specific table (verifies table belongs to owner's club)
 pub async fn get_table_images_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Path(table_id): Path<String>,
 ) -> Result<Json<Vec<TableImageRow>>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let table_uuid = Uuid::parse_str(&table_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
@@ -876,11 +981,11 @@ pub async fn get_table_images_handler(
 /// Add an image to a table (verifies ownership)
 pub async fn add_table_image_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Path(table_id): Path<String>,
     Json(payload): Json<AddImageRequest>,
 ) -> Result<(StatusCode, Json<TableImageRow>), StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let table_uuid = Uuid::parse_str(&table_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
@@ -917,10 +1022,10 @@ pub async fn add_table_image_handler(
 /// Delete a table image (ownership check: image must belong to owner's club)
 pub async fn delete_table_image_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Path(image_id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let image_uuid = Uuid::parse_str(&image_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
@@ -943,10 +1048,10 @@ pub async fn delete_table_image_handler(
 /// Get all reservations for a specific event owned by the club owner
 pub async fn get_event_reservations_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
     Path(event_id): Path<String>,
 ) -> Result<Json<Vec<TableReservationResponse>>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
@@ -971,14 +1076,111 @@ pub async fn get_event_reservations_handler(
     Ok(Json(reservations))
 }
 
+/// Get the active waitlist of an event owned by the club owner, in queue order
+pub async fn get_event_waitlist_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(event_id): Path<String>,
+) -> Result<Json<WaitlistResponse>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let event = event_persistence::get_event_by_id(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if event.club_id != Some(club.id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let entries = waitlist_persistence::get_event_waitlist(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(WaitlistResponse {
+        total: entries.len(),
+        entries: entries.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Reorder the waitlist of an event owned by the club owner.
+/// The body must list every active entry exactly once, first in line first.
+pub async fn reorder_event_waitlist_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(event_id): Path<String>,
+    Json(payload): Json<ReorderWaitlistRequest>,
+) -> Result<Json<WaitlistResponse>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let entry_ids = payload
+        .entry_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let event = event_persistence::get_event_by_id(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if event.club_id != Some(club.id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let reordered = waitlist_persistence::reorder_entries(&state.db_pool, event_uuid, &entry_ids)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !reordered {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_waitlist_reordered",
+        Some(&claims.sub),
+        Some("event"),
+        Some(event_uuid),
+        serde_json::json!({
+            "owner_id": owner_id,
+            "event_id": event_uuid,
+            "entry_count": entry_ids.len(),
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    let entries = waitlist_persistence::get_event_waitlist(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(WaitlistResponse {
+        total: entries.len(),
+        entries: entries.into_iter().map(Into::into).collect(),
+    }))
+}
+
 /// Create a manual reservation (no Stripe, no user account needed)
 pub async fn create_manual_reservation_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
     Path(event_id): Path<String>,
     Json(payload): Json<CreateManualReservationRequest>,
 ) -> Result<(StatusCode, Json<TableReservationResponse>), StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
     let table_uuid = Uuid::parse_str(&payload.table_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
@@ -1019,7 +1221,7 @@ pub async fn create_manual_reservation_handler(
         serde_json::json!({
             "event_id": event_uuid,
             "table_id": table_uuid,
-            "owner_id": claims.sub,
+            "owner_id": owner_id,
             "reservation_id": reservation.id,
             "outcome": "success",
         }),
@@ -1038,17 +1240,31 @@ pub async fn create_manual_reservation_handler(
 /// Update the status of a reservation
 pub async fn update_reservation_status_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
     Path(reservation_id): Path<String>,
     Json(payload): Json<UpdateReservationStatusRequest>,
 ) -> Result<Json<TableReservationResponse>, StatusCode> {
-    let _owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| StatusCode::BAD_REQUEST)?;
     let previous_reservation =
         table_persistence::get_reservation_by_id(&state.db_pool, reservation_uuid)
             .await
             .map_err(|_| StatusCode::NOT_FOUND)?;
 
+    // Cancelling releases money, so the reservation must belong to this owner's club.
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let reservation_event =
+        event_persistence::get_event_by_id(&state.db_pool, previous_reservation.event_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+    if reservation_event.club_id != Some(club.id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
     let reservation = club_owner_persistence::update_reservation_status(
         &state.db_pool,
         reservation_uuid,
@@ -1068,7 +1284,7 @@ pub async fn update_reservation_status_handler(
         serde_json::json!({
             "reservation_id": reservation.id,
             "status": reservation.status,
-            "owner_id": claims.sub,
+            "owner_id": owner_id,
             "outcome": "success",
         }),
     )
@@ -1077,6 +1293,39 @@ pub async fn update_reservation_status_handler(
         warn!(error = %error, reservation_id = %reservation.id, "Failed to enqueue reservation status analytics event");
     }
 
+    if reservation.status == "cancelled" && previous_reservation.status != "cancelled" {
+        match refund_persistence::refund_cancelled_reservation(
+            &state,
+            &reservation,
+            CancellationActor::Owner,
+        )
+        .await
+        {
+            Ok((percent, refunds)) => {
+                tracing::info!(reservation_id = %reservation.id, refund_percent = %percent, refunds = refunds.len(), "Owner cancellation refunds processed");
+                // Failed shares are already recorded and alerted one by one
+                if refunds.iter().any(|refund| refund.status == "failed") {
+                    refund_persistence::notify_refund_delayed(&state, &reservation).await;
+                }
+            }
+            Err(status) => {
+                error!(reservation_id = %reservation.id, status = %status, "Owner cancellation refunds failed");
+                refund_persistence::record_reservation_refund_failure(
+                    &state,
+                    &reservation,
+                    CancellationActor::Owner,
+                    &format!("refund processing failed ({status})"),
+                )
+                .await;
+                refund_persistence::notify_refund_delayed(&state, &reservation).await;
+            }
+        }
+
+        if let Err(error) = waitlist_persistence::offer_table(&state, reservation.table_id).await {
+            warn!(error = %error, reservation_id = %reservation.id, "Failed to offer freed table to waitlist");
+        }
+    }
+
     if previous_reservation.status != reservation.status {
         let table = table_persistence::get_table_by_id(&state.db_pool, reservation.table_id)
             .await
@@ -1100,11 +1349,95 @@ pub async fn update_reservation_status_handler(
         {
             warn!(error = %error, reservation_id = %reservation.id, "Failed to enqueue reservation status push notification");
         }
+
+        let emailed = match reservation.status.as_str() {
+            "confirmed" => {
+                outbox_service::enqueue_reservation_confirmed_email(&state.db_pool, reservation.id)
+                    .await
+                    .map(|_| ())
+            }
+            "cancelled" => {
+                outbox_service::enqueue_reservation_cancelled_emails(&state.db_pool, reservation.id)
+                    .await
+                    .map(|_| ())
+            }
+            _ => Ok(()),
+        };
+        if let Err(error) = emailed {
+            warn!(error = %error, reservation_id = %reservation.id, "Failed to enqueue reservation status email");
+        }
     }
 
     Ok(Json(TableReservationResponse::from(reservation)))
 }
 
+/// Get the authenticated owner's club cancellation policy (defaults if never set)
+pub async fn get_my_cancellation_policy(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+) -> Result<Json<CancellationPolicyResponse>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let policy = refund_persistence::get_cancellation_policy(&state.db_pool, club.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .unwrap_or_else(|| CancellationPolicy::default_for_club(club.id));
+
+    Ok(Json(CancellationPolicyResponse::from(policy)))
+}
+
+/// Update the authenticated owner's club cancellation policy
+pub async fn update_my_cancellation_policy(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Json(payload): Json<UpdateCancellationPolicyRequest>,
+) -> Result<Json<CancellationPolicyResponse>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if payload.full_refund_hours < 0
+        || payload.partial_refund_percent < Decimal::ZERO
+        || payload.partial_refund_percent > Decimal::new(100, 0)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let policy = refund_persistence::upsert_cancellation_policy(
+        &state.db_pool,
+        club.id,
+        payload.full_refund_hours,
+        payload.partial_refund_percent,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_cancellation_policy_updated",
+        Some(&claims.sub),
+        Some("club"),
+        Some(club.id),
+        serde_json::json!({
+            "club_id": club.id,
+            "full_refund_hours": policy.full_refund_hours,
+            "partial_refund_percent": policy.partial_refund_percent,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok(Json(CancellationPolicyResponse::from(policy)))
+}
+
 fn build_reservation_status_notification(
     status: &str,
     event_title: &str,
@@ -1149,17 +1482,25 @@ fn build_reservation_status_notification(
     }
 }
 
-/// Scan a QR code (ticket or reservation) — read-only lookup
+/// Scan a QR code (ticket or reservation) of the owner's club — read-only lookup
 pub async fn scan_code_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ScanCodes>,
     Path(code): Path<String>,
 ) -> Result<Json<ScanResult>, StatusCode> {
-    let _owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
-
-    let result = club_owner_persistence::scan_code(&state.db_pool, &code)
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    // Owner or staff member doing the scan, for the audit log
+    let actor_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let valid_dates = checkin_event_dates(Utc::now(), state.config.checkin_grace_hours);
+
+    let result =
+        club_owner_persistence::scan_code(&state.db_pool, club.id, actor_id, &code, &valid_dates)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     match result {
         Some(scan) => {
@@ -1171,12 +1512,15 @@ pub async fn scan_code_handler(
                 Some("checkin"),
                 None,
                 serde_json::json!({
-                    "owner_id": claims.sub,
+                    "owner_id": owner_id,
+                    "club_id": club.id,
                     "code": code,
                     "scan_type": scan.scan_type,
                     "event_title": scan.event_title,
                     "valid": scan.valid,
                     "already_used": scan.already_used,
+                    "reason": scan.reason,
+                    "under_age": scan.under_age,
                     "outcome": "success",
                 }),
             )
@@ -1192,7 +1536,8 @@ pub async fn scan_code_handler(
                 Some("checkin"),
                 None,
                 serde_json::json!({
-                    "owner_id": claims.sub,
+                    "owner_id": owner_id,
+                    "club_id": club.id,
                     "code": code,
                     "scan_type": "unknown",
                     "valid": false,
@@ -1202,47 +1547,73 @@ pub async fn scan_code_handler(
             )
             .await;
 
-            Ok(Json(ScanResult {
-                valid: false,
-                already_used: false,
-                scan_type: "unknown".to_string(),
-                guest_name: None,
-                num_people: None,
-                event_title: None,
-                table_name: None,
-                code,
-            }))
+            Ok(Json(ScanResult::not_found(code)))
         }
     }
 }
 
-/// Check in a ticket or reservation by code — marks it as used/completed
+/// Check in a ticket or reservation of the owner's club on its event day.
+/// Reservations accept `{ "people": n }` to admit part of the group.
 pub async fn checkin_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ScanCodes>,
     Path(code): Path<String>,
+    payload: Option<Json<CheckinRequest>>,
 ) -> Result<Json<ScanResult>, StatusCode> {
-    let _owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    // Owner or staff member doing the scan, for the audit log
+    let actor_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let people = payload.and_then(|Json(request)| request.people);
+    if people.is_some_and(|people| people < 1) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
-    let result = club_owner_persistence::checkin_by_code(&state.db_pool, &code)
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let valid_dates = checkin_event_dates(Utc::now(), state.config.checkin_grace_hours);
+
+    let result = club_owner_persistence::checkin_by_code(
+        &state.db_pool,
+        club.id,
+        actor_id,
+        &code,
+        people,
+        &valid_dates,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     match result {
         Some(scan) => {
+            let checked_in = scan.reason.is_none() && !scan.already_used;
             let _ = outbox_service::enqueue_analytics_event(
                 &state.db_pool,
                 &state.config,
-                "owner_checkin_completed",
+                if checked_in {
+                    "owner_checkin_completed"
+                } else {
+                    "owner_checkin_failed"
+                },
                 Some(&claims.sub),
                 Some("checkin"),
                 None,
                 serde_json::json!({
-                    "owner_id": claims.sub,
+                    "owner_id": owner_id,
+                    "club_id": club.id,
                     "code": code,
                     "scan_type": scan.scan_type,
                     "event_title": scan.event_title,
-                    "outcome": "success",
+                    "num_people": scan.num_people,
+                    "checked_in_count": scan.checked_in_count,
+                    "outcome": if checked_in {
+                        "success"
+                    } else if scan.already_used {
+                        "already_used"
+                    } else {
+                        scan.reason.as_deref().unwrap_or("rejected")
+                    },
                 }),
             )
             .await;
@@ -1257,41 +1628,69 @@ pub async fn checkin_handler(
                 Some("checkin"),
                 None,
                 serde_json::json!({
-                    "owner_id": claims.sub,
+                    "owner_id": owner_id,
+                    "club_id": club.id,
                     "code": code,
                     "outcome": "not_found",
                 }),
             )
             .await;
 
-            Ok(Json(ScanResult {
-                valid: false,
-                already_used: false,
-                scan_type: "unknown".to_string(),
-                guest_name: None,
-                num_people: None,
-                event_title: None,
-                table_name: None,
-                code,
-            }))
+            Ok(Json(ScanResult::not_found(code)))
         }
     }
 }
 
+/// Check-in audit log of the owner's club, newest first
+pub async fn get_checkin_audit_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Query(params): Query<CheckinAuditParams>,
+) -> Result<Json<Vec<CheckinAuditEntryResponse>>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let event_id = params
+        .event_id
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let entries =
+        club_owner_persistence::get_checkin_audit_log(&state.db_pool, club.id, event_id, limit)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        entries
+            .into_iter()
+            .map(CheckinAuditEntryResponse::from)
+            .collect(),
+    ))
+}
+
 /// Update an event owned by the club owner (with ownership check)
 pub async fn update_club_event(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Path(event_id): Path<String>,
     Json(mut payload): Json<UpdateEventRequest>,
 ) -> Result<Json<EventResponse>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
     if let Some(image) = payload.image.as_deref() {
         if !crate::models::is_valid_event_image_url(image) {
             return Err(StatusCode::BAD_REQUEST);
         }
     }
+    payload
+        .normalize_age_limit()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -1334,10 +1733,10 @@ pub async fn update_club_event(
 /// Delete an event owned by the club owner (with ownership check)
 pub async fn delete_club_event(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     Path(event_id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
@@ -1368,9 +1767,9 @@ pub async fn delete_club_event(
 /// Get aggregated stats for the authenticated club owner
 pub async fn get_owner_stats_handler(
     State(state): State<Arc<AppState>>,
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ViewFinance>,
 ) -> Result<Json<OwnerStats>, StatusCode> {
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
 
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
@@ -1383,3 +1782,194 @@ pub async fn get_owner_stats_handler(
 
     Ok(Json(stats))
 }
+
+/// List the authenticated owner's promo codes with their usage
+pub async fn get_my_promo_codes(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ViewFinance>,
+) -> Result<Json<Vec<PromoCodeResponse>>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let promo_codes = promo_code_persistence::get_promo_codes_by_club(&state.db_pool, club.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        promo_codes
+            .into_iter()
+            .map(PromoCodeResponse::from)
+            .collect(),
+    ))
+}
+
+/// Create a promo code for the authenticated owner's club.
+/// Event, area and table scopes must belong to the club.
+pub async fn create_my_promo_code(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Json(payload): Json<CreatePromoCodeRequest>,
+) -> Result<(StatusCode, Json<PromoCodeResponse>), StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let code = payload.code.trim();
+    let valid_code = !code.is_empty()
+        && code.len() <= 50
+        && code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let valid_discount = match payload.discount_type.as_str() {
+        "percentage" => {
+            payload.discount_value > Decimal::ZERO && payload.discount_value <= Decimal::new(100, 0)
+        }
+        "fixed" => payload.discount_value > Decimal::ZERO,
+        _ => false,
+    };
+    let valid_window = match (payload.valid_from, payload.valid_until) {
+        (Some(from), Some(until)) => from < until,
+        _ => true,
+    };
+    if !valid_code
+        || !valid_discount
+        || !valid_window
+        || payload.max_redemptions.is_some_and(|max| max < 1)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let parse_optional = |value: &Option<String>| -> Result<Option<Uuid>, StatusCode> {
+        value
+            .as_deref()
+            .map(Uuid::parse_str)
+            .transpose()
+            .map_err(|_| StatusCode::BAD_REQUEST)
+    };
+    let event_id = parse_optional(&payload.event_id)?;
+    let area_id = parse_optional(&payload.area_id)?;
+    let table_id = parse_optional(&payload.table_id)?;
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if let Some(event_id) = event_id {
+        let event = event_persistence::get_event_by_id(&state.db_pool, event_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        if event.club_id != Some(club.id) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+    if let Some(area_id) = area_id {
+        let area = area_persistence::get_area_by_id(&state.db_pool, area_id)
+            .await
+            .map_err(|_| StatusCode::NOT_FOUND)?;
+        if area.club_id != club.id {
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+    if let Some(table_id) = table_id {
+        let table = table_persistence::get_table_by_id(&state.db_pool, table_id)
+            .await
+            .map_err(|_| StatusCode::NOT_FOUND)?;
+        let table_event = event_persistence::get_event_by_id(&state.db_pool, table.event_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        if table_event.club_id != Some(club.id) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+
+    let promo = match promo_code_persistence::create_promo_code(
+        &state.db_pool,
+        club.id,
+        code,
+        payload.description,
+        &payload.discount_type,
+        payload.discount_value,
+        event_id,
+        area_id,
+        table_id,
+        payload.max_redemptions,
+        payload.valid_from,
+        payload.valid_until,
+        payload.single_use_per_phone.unwrap_or(true),
+    )
+    .await
+    {
+        Ok(promo) => promo,
+        Err(sqlx::Error::Database(db_error)) if db_error.is_unique_violation() => {
+            return Err(StatusCode::CONFLICT)
+        }
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_promo_code_created",
+        Some(&claims.sub),
+        Some("promo_code"),
+        Some(promo.id),
+        serde_json::json!({
+            "club_id": club.id,
+            "promo_code_id": promo.id,
+            "discount_type": promo.discount_type,
+            "discount_value": promo.discount_value,
+            "max_redemptions": promo.max_redemptions,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(PromoCodeResponse::from(promo))))
+}
+
+/// Update (or deactivate) a promo code of the authenticated owner's club
+pub async fn update_my_promo_code(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(promo_code_id): Path<String>,
+    Json(payload): Json<UpdatePromoCodeRequest>,
+) -> Result<Json<PromoCodeResponse>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let promo_uuid = Uuid::parse_str(&promo_code_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    if payload.max_redemptions.is_some_and(|max| max < 1) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let promo = match promo_code_persistence::update_promo_code(
+        &state.db_pool,
+        promo_uuid,
+        club.id,
+        payload.description,
+        payload.max_redemptions,
+        payload.valid_from,
+        payload.valid_until,
+        payload.active,
+    )
+    .await
+    {
+        Ok(Some(promo)) => promo,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        // A cap below the uses already made violates promo_codes_redemption_cap
+        Err(sqlx::Error::Database(db_error)) if db_error.is_check_violation() => {
+            return Err(StatusCode::CONFLICT)
+        }
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    Ok(Json(PromoCodeResponse::from(promo)))
+}
@@ -0,0 +1,72 @@
+use crate::application::{
+    club_service as club_persistence,
+    connect_reconciliation_service as connect_reconciliation_persistence,
+};
+use crate::middleware::auth::{AdminUser, ClubStaffUser, ViewFinance};
+use crate::models::connect_reconciliation::{
+    ConnectStatementResponse, PlatformFinanceReportResponse,
+};
+use crate::models::{AppState, FinancePeriodParams};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::error;
+
+fn internal_error(error: sqlx::Error) -> (StatusCode, String) {
+    error!(error = %error, "Connect reconciliation query failed");
+    (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+}
+
+/// What Stripe collected, kept and paid out for the caller's club over a
+/// period, next to the local payments, with the mismatches between the two.
+pub async fn get_my_finance_statement(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ViewFinance>,
+    Query(params): Query<FinancePeriodParams>,
+) -> Result<Json<ConnectStatementResponse>, (StatusCode, String)> {
+    let owner_id = claims
+        .acting_owner_id()
+        .ok_or((StatusCode::UNAUTHORIZED, String::new()))?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, String::new()))?;
+
+    let now = Utc::now();
+    let period = params
+        .into_period(now.date_naive())
+        .map_err(|message| (StatusCode::BAD_REQUEST, message.to_string()))?;
+
+    let statement = connect_reconciliation_persistence::build_club_statement(
+        &state.read_db_pool,
+        club.id,
+        period,
+        now,
+    )
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(statement))
+}
+
+/// Connect totals of every club over a period and the platform-wide sum,
+/// with every mismatch found.
+pub async fn get_platform_finance_report(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(params): Query<FinancePeriodParams>,
+) -> Result<Json<PlatformFinanceReportResponse>, (StatusCode, String)> {
+    let now = Utc::now();
+    let period = params
+        .into_period(now.date_naive())
+        .map_err(|message| (StatusCode::BAD_REQUEST, message.to_string()))?;
+
+    let report =
+        connect_reconciliation_persistence::build_platform_report(&state.read_db_pool, period, now)
+            .await
+            .map_err(internal_error)?;
+    Ok(Json(report))
+}
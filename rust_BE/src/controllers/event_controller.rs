@@ -1,15 +1,16 @@
 use crate::application::event_service as event_persistence;
-use crate::application::outbox_service;
-use crate::middleware::auth::ClubOwnerUser;
+use crate::application::{club_service, outbox_service};
+use crate::middleware::auth::ClubManager;
 use crate::models::{
-    is_valid_event_image_url, AppState, CreateEventRequest, EventResponse, PaginationParams,
-    UpdateEventRequest,
+    is_valid_event_image_url, AppState, CreateEventRequest, EventResponse, EventSearchParams,
+    EventSearchResponse, EventSearchResult, PaginationParams, UpdateEventRequest,
 };
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use chrono::Utc;
 use serde::Serialize;
 use std::sync::Arc;
 use tracing::warn;
@@ -90,6 +91,78 @@ pub async fn get_all_events(
     }
 }
 
+/// Discovery search with filters, full-text and cursor pagination
+pub async fn search_events(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<EventSearchParams>,
+) -> Result<Json<EventSearchResponse>, (StatusCode, String)> {
+    let filters = params
+        .into_filters(Utc::now().date_naive())
+        .map_err(|message| (StatusCode::BAD_REQUEST, message.to_string()))?;
+
+    let mut rows = event_persistence::search_events(&state.read_db_pool, &filters)
+        .await
+        .map_err(|error| {
+            warn!(error = %error, "Event search failed");
+            (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+        })?;
+
+    let next_cursor = if rows.len() as i64 > filters.limit {
+        rows.truncate(filters.limit as usize);
+        rows.last()
+            .and_then(|row| row.cursor(filters.sort))
+            .map(|cursor| cursor.encode())
+    } else {
+        None
+    };
+
+    if let Err(error) = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "events_search_performed",
+        None,
+        Some("event"),
+        None,
+        serde_json::json!({
+            "has_text": filters.text.is_some(),
+            "genre_count": filters.genre_ids.len(),
+            "party_size": filters.party_size,
+            "sort": filters.sort,
+            "page": if filters.after.is_some() { "next" } else { "first" },
+            "result_count": rows.len(),
+            "outcome": "success",
+        }),
+    )
+    .await
+    {
+        warn!(error = %error, "Failed to enqueue event search analytics event");
+    }
+
+    let event_ids: Vec<Uuid> = rows.iter().map(|row| row.event.id).collect();
+    let genres_map = event_persistence::get_genres_for_events(&state.read_db_pool, &event_ids)
+        .await
+        .unwrap_or_default();
+
+    let events = rows
+        .into_iter()
+        .map(|row| {
+            let id = row.event.id;
+            let mut event = EventResponse::from(row.event);
+            event.genres = genres_map.get(&id).cloned().unwrap_or_default();
+            EventSearchResult {
+                event,
+                popularity: row.popularity,
+                distance_km: row.distance_km,
+            }
+        })
+        .collect();
+
+    Ok(Json(EventSearchResponse {
+        events,
+        next_cursor,
+    }))
+}
+
 /// Get a single event by ID
 pub async fn get_event(
     State(state): State<Arc<AppState>>,
@@ -144,15 +217,25 @@ pub async fn get_event(
     }
 }
 
-/// Create a new event (requires club_owner JWT)
+/// Create a new event. Owners create events for their own club only; the
+/// club is filled in when omitted.
 pub async fn create_event(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<CreateEventRequest>,
+    Json(mut payload): Json<CreateEventRequest>,
 ) -> Result<(StatusCode, Json<EventResponse>), StatusCode> {
     if !is_valid_event_image_url(&payload.image) {
         return Err(StatusCode::BAD_REQUEST);
     }
+    payload
+        .normalize_age_limit()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if let Some(club_id) = club_service::managed_club_id(&state.db_pool, &manager).await? {
+        if payload.club_id.is_some_and(|id| id != club_id) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        payload.club_id = Some(club_id);
+    }
 
     let genre_ids = payload.genre_ids.clone().unwrap_or_default();
 
@@ -173,14 +256,24 @@ pub async fn create_event(
     }
 }
 
-/// Update an event (requires club_owner JWT)
+/// Update an event of a club the caller manages. Only admins may move an
+/// event to another club.
 pub async fn update_event(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    Json(payload): Json<UpdateEventRequest>,
+    Json(mut payload): Json<UpdateEventRequest>,
 ) -> Result<Json<EventResponse>, StatusCode> {
     let event_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    payload
+        .normalize_age_limit()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let event = club_service::ensure_manages_event(&state.db_pool, &manager, event_id).await?;
+    if matches!(manager, ClubManager::Owner(_))
+        && payload.club_id.is_some_and(|id| Some(id) != event.club_id)
+    {
+        return Err(StatusCode::FORBIDDEN);
+    }
     if let Some(image) = payload.image.as_deref() {
         if !is_valid_event_image_url(image) {
             return Err(StatusCode::BAD_REQUEST);
@@ -205,13 +298,14 @@ pub async fn update_event(
     }
 }
 
-/// Delete an event (requires club_owner JWT)
+/// Delete an event of a club the caller manages
 pub async fn delete_event(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
     let event_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    club_service::ensure_manages_event(&state.db_pool, &manager, event_id).await?;
 
     match event_persistence::delete_event(&state.db_pool, event_id).await {
         Ok(true) => Ok(StatusCode::NO_CONTENT),
@@ -1,5 +1,5 @@
 use crate::application::club_service as club_persistence;
-use crate::middleware::auth::ClubOwnerUser;
+use crate::middleware::auth::{ClubStaffUser, ManageClub};
 use crate::models::AppState;
 use crate::services::storage_service;
 use axum::{
@@ -8,13 +8,12 @@ use axum::{
     Json,
 };
 use std::sync::Arc;
-use uuid::Uuid;
 
 /// Upload a locandina image for an event.
 /// Accepts multipart/form-data with a field named "file".
 /// Returns { "url": "<public_url>" } on success.
 pub async fn upload_event_image(
-    ClubOwnerUser(claims): ClubOwnerUser,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
     State(state): State<Arc<AppState>>,
     mut multipart: Multipart,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
@@ -31,7 +30,7 @@ pub async fn upload_event_image(
     };
 
     // Resolve the club_id for the authenticated owner
-    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
     let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
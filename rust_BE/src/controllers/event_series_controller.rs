@@ -0,0 +1,234 @@
+use crate::application::{
+    club_service as club_persistence, event_series_service as event_series_persistence,
+    event_service as event_persistence, outbox_service,
+};
+use crate::middleware::auth::{ClubStaffUser, ManageClub};
+use crate::models::{
+    is_valid_event_image_url, AppState, Claims, Club, CreateEventSeriesRequest, EventResponse,
+    EventSeries, EventSeriesResponse, SeriesFrequency, UpdateEventSeriesRequest,
+    UpdateEventSeriesResponse,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{NaiveTime, Utc};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+fn valid_time(time: Option<&str>) -> bool {
+    time.is_none_or(|time| NaiveTime::parse_from_str(time.trim(), "%H:%M").is_ok())
+}
+
+async fn my_club(state: &AppState, claims: &Claims) -> Result<Club, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// The template event must be one of the club's own events.
+async fn ensure_template_event(
+    state: &AppState,
+    club: &Club,
+    template_event_id: Option<Uuid>,
+) -> Result<(), StatusCode> {
+    let Some(template_event_id) = template_event_id else {
+        return Ok(());
+    };
+    let event = event_persistence::get_event_by_id(&state.db_pool, template_event_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    if event.club_id != Some(club.id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+async fn owned_series(
+    state: &AppState,
+    club: &Club,
+    series_id: &str,
+) -> Result<EventSeries, StatusCode> {
+    let series_uuid = Uuid::parse_str(series_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let series = event_series_persistence::get_series_by_id(&state.db_pool, series_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if series.club_id != club.id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(series)
+}
+
+/// List the club's recurring event series
+pub async fn get_my_event_series(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+) -> Result<Json<Vec<EventSeriesResponse>>, StatusCode> {
+    let club = my_club(&state, &claims).await?;
+
+    let series = event_series_persistence::get_series_by_club(&state.db_pool, club.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        series.into_iter().map(EventSeriesResponse::from).collect(),
+    ))
+}
+
+/// Create a recurring event series and materialize its upcoming occurrences
+pub async fn create_my_event_series(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Json(mut payload): Json<CreateEventSeriesRequest>,
+) -> Result<(StatusCode, Json<EventSeriesResponse>), StatusCode> {
+    payload
+        .normalize_age_limit()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if payload.title.trim().is_empty()
+        || payload.venue.trim().is_empty()
+        || !is_valid_event_image_url(&payload.image)
+        || SeriesFrequency::parse(&payload.frequency).is_none()
+        || payload.end_date.is_some_and(|end| end < payload.start_date)
+        || !valid_time(payload.time.as_deref())
+        || !valid_time(payload.end_time.as_deref())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let club = my_club(&state, &claims).await?;
+    ensure_template_event(&state, &club, payload.template_event_id).await?;
+
+    let series = event_series_persistence::create_series(&state.db_pool, club.id, payload)
+        .await
+        .map_err(|e| {
+            error!(error = %e, club_id = %club.id, "Failed to create event series");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let created = event_series_persistence::materialize_series(
+        &state.db_pool,
+        &series,
+        Utc::now().date_naive(),
+        state.config.jobs.event_series_horizon_days,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, series_id = %series.id, "Failed to materialize event series");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_event_series_created",
+        Some(&claims.sub),
+        Some("event_series"),
+        Some(series.id),
+        serde_json::json!({
+            "club_id": club.id,
+            "series_id": series.id,
+            "frequency": series.frequency,
+            "created_occurrences": created,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(EventSeriesResponse::from(series))))
+}
+
+/// Edit a series. Detail changes are copied to future occurrences without
+/// bookings; schedule changes add and remove future occurrences accordingly.
+pub async fn update_my_event_series(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(series_id): Path<String>,
+    Json(mut payload): Json<UpdateEventSeriesRequest>,
+) -> Result<Json<UpdateEventSeriesResponse>, StatusCode> {
+    payload
+        .normalize_age_limit()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if payload
+        .title
+        .as_deref()
+        .is_some_and(|title| title.trim().is_empty())
+        || payload
+            .venue
+            .as_deref()
+            .is_some_and(|venue| venue.trim().is_empty())
+        || payload
+            .image
+            .as_deref()
+            .is_some_and(|image| !is_valid_event_image_url(image))
+        || payload
+            .frequency
+            .as_deref()
+            .is_some_and(|frequency| SeriesFrequency::parse(frequency).is_none())
+        || !valid_time(payload.time.as_deref())
+        || !valid_time(payload.end_time.as_deref())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let club = my_club(&state, &claims).await?;
+    let existing = owned_series(&state, &club, &series_id).await?;
+    if payload
+        .end_date
+        .is_some_and(|end| end < existing.start_date)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    ensure_template_event(&state, &club, payload.template_event_id).await?;
+
+    let series = event_series_persistence::update_series(&state.db_pool, existing.id, &payload)
+        .await
+        .map_err(|e| {
+            error!(error = %e, series_id = %existing.id, "Failed to update event series");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let propagation = event_series_persistence::apply_series_update(
+        &state.db_pool,
+        &series,
+        &payload,
+        Utc::now().date_naive(),
+        state.config.jobs.event_series_horizon_days,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, series_id = %series.id, "Failed to propagate event series update");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(UpdateEventSeriesResponse {
+        series: EventSeriesResponse::from(series),
+        propagation,
+    }))
+}
+
+/// Upcoming occurrences of a series
+pub async fn get_my_event_series_events(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(series_id): Path<String>,
+) -> Result<Json<Vec<EventResponse>>, StatusCode> {
+    let club = my_club(&state, &claims).await?;
+    let series = owned_series(&state, &club, &series_id).await?;
+
+    let events = event_series_persistence::get_series_events(
+        &state.db_pool,
+        series.id,
+        Utc::now().date_naive(),
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(events.into_iter().map(EventResponse::from).collect()))
+}
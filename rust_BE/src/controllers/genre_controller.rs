@@ -1,5 +1,5 @@
 use crate::application::genre_service as genre_persistence;
-use crate::middleware::auth::ClubOwnerUser;
+use crate::middleware::auth::AdminUser;
 use crate::models::{AppState, CreateGenreRequest, GenreResponse, UpdateGenreRequest};
 use axum::{
     extract::{Path, State},
@@ -36,9 +36,9 @@ pub async fn get_genre(
     }
 }
 
-/// Create a new genre (requires club_owner JWT)
+/// Create a new genre (platform admin)
 pub async fn create_genre(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(state): State<Arc<AppState>>,
     Json(payload): Json<CreateGenreRequest>,
 ) -> Result<(StatusCode, Json<GenreResponse>), StatusCode> {
@@ -53,9 +53,9 @@ pub async fn create_genre(
     }
 }
 
-/// Update a genre (requires club_owner JWT)
+/// Update a genre (platform admin)
 pub async fn update_genre(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateGenreRequest>,
@@ -76,9 +76,9 @@ pub async fn update_genre(
     }
 }
 
-/// Delete a genre (requires club_owner JWT)
+/// Delete a genre (platform admin)
 pub async fn delete_genre(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
@@ -0,0 +1,192 @@
+use crate::application::{
+    club_service as club_persistence, event_service as event_persistence,
+    guest_export_service as guest_export_persistence, outbox_service,
+};
+use crate::middleware::auth::{ClubStaffUser, ScanCodes};
+use crate::models::guest_export::{
+    ExportFormat, ExportKind, ExportRecord, ExportSheet, ExportSort,
+};
+use crate::models::staff::StaffRole;
+use crate::models::{AppState, Claims, Event, GuestExportParams};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+type ExportError = (StatusCode, String);
+
+fn internal_error(error: impl std::fmt::Display) -> ExportError {
+    error!(error = %error, "Guest list export failed");
+    (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+}
+
+/// The event, if it belongs to the caller's club.
+async fn owned_event(
+    state: &AppState,
+    claims: &Claims,
+    event_id: &str,
+) -> Result<Event, ExportError> {
+    let owner_id = claims
+        .acting_owner_id()
+        .ok_or((StatusCode::UNAUTHORIZED, String::new()))?;
+    let event_uuid =
+        Uuid::parse_str(event_id).map_err(|_| (StatusCode::BAD_REQUEST, String::new()))?;
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, String::new()))?;
+    let event = event_persistence::get_event_by_id(&state.db_pool, event_uuid)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, String::new()))?;
+    if event.club_id != Some(club.id) {
+        return Err((StatusCode::FORBIDDEN, String::new()));
+    }
+    Ok(event)
+}
+
+/// Owners, managers and hosts see guest contacts; door staff get them masked.
+fn shows_contacts(claims: &Claims) -> bool {
+    claims.role == "club_owner"
+        || StaffRole::from_claim_role(&claims.role).is_some_and(|role| role.sees_guest_contacts())
+}
+
+async fn export<R: ExportRecord>(
+    state: &AppState,
+    claims: &Claims,
+    event: &Event,
+    kind: ExportKind,
+    params: &GuestExportParams,
+    records: Vec<R>,
+) -> Result<Response, ExportError> {
+    let columns = kind
+        .select_columns(params.columns.as_deref())
+        .map_err(|message| (StatusCode::BAD_REQUEST, message.to_string()))?;
+    let show_contacts = shows_contacts(claims);
+    let sheet = ExportSheet::build(kind, &columns, &records, show_contacts);
+
+    let body = match params.format {
+        ExportFormat::Csv => sheet.to_csv().into_bytes(),
+        ExportFormat::Xlsx => {
+            guest_export_persistence::render_xlsx(&sheet).map_err(internal_error)?
+        }
+    };
+
+    if let Err(error) = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_guest_list_exported",
+        Some(&claims.sub),
+        Some("event"),
+        Some(event.id),
+        serde_json::json!({
+            "event_id": event.id,
+            "kind": kind.sheet_name(),
+            "format": params.format.extension(),
+            "rows": sheet.rows.len(),
+            "contacts_masked": !show_contacts,
+            "outcome": "success",
+        }),
+    )
+    .await
+    {
+        warn!(error = %error, "Failed to enqueue guest list export analytics event");
+    }
+
+    let filename = format!(
+        "{}-{}.{}",
+        kind.sheet_name(),
+        event
+            .event_date
+            .map(|date| date.to_string())
+            .unwrap_or_else(|| event.id.to_string()),
+        params.format.extension()
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                params.format.content_type().to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Export the event's reservations as CSV or XLSX
+pub async fn export_event_reservations(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ScanCodes>,
+    Path(event_id): Path<String>,
+    Query(params): Query<GuestExportParams>,
+) -> Result<Response, ExportError> {
+    let event = owned_event(&state, &claims, &event_id).await?;
+    let rows = guest_export_persistence::get_reservation_rows(
+        &state.read_db_pool,
+        event.id,
+        params.sort.unwrap_or(ExportSort::Table),
+        params.checked_in,
+    )
+    .await
+    .map_err(internal_error)?;
+    export(
+        &state,
+        &claims,
+        &event,
+        ExportKind::Reservations,
+        &params,
+        rows,
+    )
+    .await
+}
+
+/// Export every paying participant and free guest of the event's reservations
+pub async fn export_event_guests(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ScanCodes>,
+    Path(event_id): Path<String>,
+    Query(params): Query<GuestExportParams>,
+) -> Result<Response, ExportError> {
+    let event = owned_event(&state, &claims, &event_id).await?;
+    let rows = guest_export_persistence::get_guest_rows(
+        &state.read_db_pool,
+        event.id,
+        params.sort.unwrap_or(ExportSort::Table),
+        params.checked_in,
+    )
+    .await
+    .map_err(internal_error)?;
+    export(&state, &claims, &event, ExportKind::Guests, &params, rows).await
+}
+
+/// Export the event's valid tickets, sorted by holder name
+pub async fn export_event_tickets(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ScanCodes>,
+    Path(event_id): Path<String>,
+    Query(params): Query<GuestExportParams>,
+) -> Result<Response, ExportError> {
+    if params.sort.is_some_and(|sort| sort != ExportSort::Name) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "I biglietti si possono ordinare solo per nome".to_string(),
+        ));
+    }
+    let event = owned_event(&state, &claims, &event_id).await?;
+    let rows =
+        guest_export_persistence::get_ticket_rows(&state.read_db_pool, event.id, params.checked_in)
+            .await
+            .map_err(internal_error)?;
+    export(&state, &claims, &event, ExportKind::Tickets, &params, rows).await
+}
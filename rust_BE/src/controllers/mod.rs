@@ -2,10 +2,24 @@ pub mod area_controller;
 pub mod auth_controller;
 pub mod club_controller;
 pub mod club_owner_controller;
+pub mod connect_reconciliation_controller;
 pub mod event_controller;
 pub mod event_image_controller;
+pub mod event_series_controller;
 pub mod genre_controller;
+pub mod guest_export_controller;
+pub mod outbox_admin_controller;
+pub mod owner_analytics_controller;
+pub mod password_reset_controller;
 pub mod payment_controller;
+pub mod platform_admin_controller;
+pub mod staff_controller;
+pub mod stripe_webhook_admin_controller;
 pub mod table_controller;
+pub mod table_layout_controller;
+pub mod table_tab_controller;
 pub mod ticket_controller;
+pub mod ticket_tier_controller;
+pub mod transfer_controller;
+pub mod waitlist_controller;
 pub mod webhook_controller;
@@ -0,0 +1,132 @@
+use crate::application::push_service;
+use crate::infrastructure::logging::log_business_event;
+use crate::infrastructure::outbox;
+use crate::middleware::auth::AdminUser;
+use crate::models::{
+    AppState, DeadLetterParams, OutboxEventResponse, PushDeliveryMetricsResponse, PushMetricsParams,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+/// Dead-lettered outbox events, most recent first
+pub async fn list_dead_letters(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(params): Query<DeadLetterParams>,
+) -> Result<Json<Vec<OutboxEventResponse>>, StatusCode> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let events =
+        outbox::list_dead_lettered(&state.db_pool, params.event_type.as_deref(), limit, offset)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to list dead-lettered outbox events");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+    Ok(Json(
+        events.into_iter().map(OutboxEventResponse::from).collect(),
+    ))
+}
+
+/// One outbox event with its payload and last error, whatever its status
+pub async fn get_outbox_event(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(event_id): Path<String>,
+) -> Result<Json<OutboxEventResponse>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let event = outbox::get_event(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(OutboxEventResponse::from(event)))
+}
+
+/// Send a dead-lettered event back to the dispatcher with a fresh attempt budget
+pub async fn replay_dead_letter(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(event_id): Path<String>,
+) -> Result<Json<OutboxEventResponse>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let event = dead_letter_transition(
+        &state,
+        event_uuid,
+        outbox::replay_dead_lettered(&state.db_pool, event_uuid).await,
+    )
+    .await?;
+    log_business_event("outbox_event_replayed", "outbox_event", &event_id);
+
+    Ok(Json(OutboxEventResponse::from(event)))
+}
+
+/// Give up on a dead-lettered event; it is kept with status "discarded"
+pub async fn discard_dead_letter(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(event_id): Path<String>,
+) -> Result<Json<OutboxEventResponse>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let event = dead_letter_transition(
+        &state,
+        event_uuid,
+        outbox::discard_dead_lettered(&state.db_pool, event_uuid).await,
+    )
+    .await?;
+    log_business_event("outbox_event_discarded", "outbox_event", &event_id);
+
+    Ok(Json(OutboxEventResponse::from(event)))
+}
+
+/// 404 for unknown events, 409 for events that are not dead-lettered
+async fn dead_letter_transition(
+    state: &AppState,
+    event_id: Uuid,
+    result: Result<Option<outbox::OutboxEvent>, sqlx::Error>,
+) -> Result<outbox::OutboxEvent, StatusCode> {
+    match result {
+        Ok(Some(event)) => Ok(event),
+        Ok(None) => match outbox::get_event(&state.db_pool, event_id).await {
+            Ok(Some(_)) => Err(StatusCode::CONFLICT),
+            Ok(None) => Err(StatusCode::NOT_FOUND),
+            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+        Err(e) => {
+            error!(event_id = %event_id, error = %e, "Failed to update dead-lettered outbox event");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Push delivery over the last `days` (default 7): tickets sent, receipts
+/// delivered or failed, unregistered devices and the delivery rate
+pub async fn get_push_delivery_metrics(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(params): Query<PushMetricsParams>,
+) -> Result<Json<PushDeliveryMetricsResponse>, StatusCode> {
+    let days = params.days.unwrap_or(7).clamp(1, 90);
+
+    let metrics =
+        push_service::get_push_delivery_metrics(&state.db_pool, Utc::now() - Duration::days(days))
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to load push delivery metrics");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+    Ok(Json(PushDeliveryMetricsResponse::new(days, metrics)))
+}
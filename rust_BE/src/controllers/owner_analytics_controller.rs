@@ -0,0 +1,82 @@
+use crate::application::{
+    club_service as club_persistence, owner_analytics_service as owner_analytics_persistence,
+};
+use crate::middleware::auth::{ClubStaffUser, ViewFinance};
+use crate::models::owner_analytics::{AnalyticsChange, AnalyticsMetrics};
+use crate::models::{AppState, OwnerAnalyticsParams, OwnerAnalyticsResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::error;
+
+fn internal_error(error: sqlx::Error) -> (StatusCode, String) {
+    error!(error = %error, "Owner analytics query failed");
+    (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+}
+
+/// Revenue, reservations, guests and check-ins of the owner's club over a date
+/// range: totals against the previous period, a time series, breakdowns and
+/// the payment link funnel. Served from the read replica.
+pub async fn get_my_analytics(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ViewFinance>,
+    Query(params): Query<OwnerAnalyticsParams>,
+) -> Result<Json<OwnerAnalyticsResponse>, (StatusCode, String)> {
+    let owner_id = claims
+        .acting_owner_id()
+        .ok_or((StatusCode::UNAUTHORIZED, String::new()))?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or((StatusCode::NOT_FOUND, String::new()))?;
+
+    let range = params
+        .into_range(Utc::now().date_naive())
+        .map_err(|message| (StatusCode::BAD_REQUEST, message.to_string()))?;
+
+    let pool = &state.read_db_pool;
+    let (totals, previous, series, by_event, by_area, by_table, funnel) = tokio::join!(
+        owner_analytics_persistence::get_totals(pool, club.id, range.from, range.to),
+        owner_analytics_persistence::get_totals(
+            pool,
+            club.id,
+            range.previous_from,
+            range.previous_to
+        ),
+        owner_analytics_persistence::get_series(
+            pool,
+            club.id,
+            range.from,
+            range.to,
+            range.granularity
+        ),
+        owner_analytics_persistence::get_breakdown_by_event(pool, club.id, range.from, range.to),
+        owner_analytics_persistence::get_breakdown_by_area(pool, club.id, range.from, range.to),
+        owner_analytics_persistence::get_breakdown_by_table(pool, club.id, range.from, range.to),
+        owner_analytics_persistence::get_payment_funnel(pool, club.id, range.from, range.to),
+    );
+
+    let totals = AnalyticsMetrics::from(totals.map_err(internal_error)?);
+    let previous = AnalyticsMetrics::from(previous.map_err(internal_error)?);
+    let collect = |rows: Vec<_>| rows.into_iter().map(Into::into).collect();
+
+    Ok(Json(OwnerAnalyticsResponse {
+        range,
+        change: AnalyticsChange::between(&totals, &previous),
+        totals,
+        previous,
+        series: series
+            .map_err(internal_error)?
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        by_event: collect(by_event.map_err(internal_error)?),
+        by_area: collect(by_area.map_err(internal_error)?),
+        by_table: collect(by_table.map_err(internal_error)?),
+        funnel: funnel.map_err(internal_error)?.into(),
+    }))
+}
@@ -0,0 +1,320 @@
+use crate::application::{
+    auth_service as user_persistence, club_owner_service as club_owner_persistence, outbox_service,
+    password_reset_service,
+};
+use crate::infrastructure::logging::log_security_event;
+use crate::models::{
+    ApiError, AppState, ForgotPasswordRequest, PasswordResetChannel, PasswordResetResponse,
+    ResetCodeCheck, ResetPasswordRequest, SessionSubject,
+};
+use crate::utils::client_ip::client_ip;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use bcrypt::{hash, DEFAULT_COST};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::error;
+
+/// Account a reset code can be sent to
+struct ResetTarget {
+    subject: SessionSubject,
+    email: String,
+    phone_number: Option<String>,
+}
+
+/// Request a reset code for a user account
+pub async fn forgot_password(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<(StatusCode, Json<PasswordResetResponse>), (StatusCode, Json<ApiError>)> {
+    let target =
+        match user_persistence::find_user_by_email(&state.db_pool, payload.email.trim()).await {
+            Ok(user) => user.map(|user| ResetTarget {
+                subject: SessionSubject::User(user.id),
+                email: user.email,
+                // Codes only go to numbers the user has proven they own
+                phone_number: user.phone_number.filter(|_| user.phone_verified),
+            }),
+            Err(e) => {
+                error!(error = %e, "Failed to load user for password reset");
+                return Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Errore interno",
+                ));
+            }
+        };
+
+    request_reset_code(&state, client_ip(&headers, peer), target, &payload).await
+}
+
+/// Request a reset code for a club owner account
+pub async fn forgot_club_owner_password(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<(StatusCode, Json<PasswordResetResponse>), (StatusCode, Json<ApiError>)> {
+    let target = match club_owner_persistence::find_club_owner_by_email(
+        &state.db_pool,
+        payload.email.trim(),
+    )
+    .await
+    {
+        Ok(owner) => owner.map(|owner| ResetTarget {
+            subject: SessionSubject::ClubOwner(owner.id),
+            email: owner.email,
+            phone_number: owner.phone_number,
+        }),
+        Err(e) => {
+            error!(error = %e, "Failed to load club owner for password reset");
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore interno",
+            ));
+        }
+    };
+
+    request_reset_code(&state, client_ip(&headers, peer), target, &payload).await
+}
+
+/// Redeem a user's reset code and set a new password
+pub async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<Json<PasswordResetResponse>, (StatusCode, Json<ApiError>)> {
+    validate_new_password(&payload)?;
+    let subject =
+        match user_persistence::find_user_by_email(&state.db_pool, payload.email.trim()).await {
+            Ok(user) => user.map(|user| SessionSubject::User(user.id)),
+            Err(e) => {
+                error!(error = %e, "Failed to load user for password reset");
+                return Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Errore interno",
+                ));
+            }
+        };
+
+    redeem_reset_code(&state, client_ip(&headers, peer), subject, &payload).await
+}
+
+/// Redeem a club owner's reset code and set a new password
+pub async fn reset_club_owner_password(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<Json<PasswordResetResponse>, (StatusCode, Json<ApiError>)> {
+    validate_new_password(&payload)?;
+    let subject = match club_owner_persistence::find_club_owner_by_email(
+        &state.db_pool,
+        payload.email.trim(),
+    )
+    .await
+    {
+        Ok(owner) => owner.map(|owner| SessionSubject::ClubOwner(owner.id)),
+        Err(e) => {
+            error!(error = %e, "Failed to load club owner for password reset");
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore interno",
+            ));
+        }
+    };
+
+    redeem_reset_code(&state, client_ip(&headers, peer), subject, &payload).await
+}
+
+/// The response is the same whether or not the email matches an account, so
+/// the endpoint cannot be used to discover registered addresses.
+async fn request_reset_code(
+    state: &AppState,
+    ip: String,
+    target: Option<ResetTarget>,
+    payload: &ForgotPasswordRequest,
+) -> Result<(StatusCode, Json<PasswordResetResponse>), (StatusCode, Json<ApiError>)> {
+    let channel = match payload.channel.as_deref() {
+        None => PasswordResetChannel::Email,
+        Some(channel) => PasswordResetChannel::parse(channel)
+            .ok_or_else(|| ApiError::new(StatusCode::BAD_REQUEST, "Canale non supportato"))?,
+    };
+
+    let activity = ip_reset_activity(state, &ip).await?;
+    if !activity.may_request_code() {
+        log_security_event("password_reset_ip_rate_limited", Some(&ip));
+        return Err(too_many_requests());
+    }
+
+    let accepted = (
+        StatusCode::ACCEPTED,
+        Json(PasswordResetResponse {
+            message: "Se l'account esiste, riceverai un codice per reimpostare la password."
+                .to_string(),
+        }),
+    );
+
+    let Some(target) = target else {
+        log_security_event("password_reset_unknown_account", None);
+        return Ok(accepted);
+    };
+    let actor_id = target.subject.id().to_string();
+
+    match password_reset_service::issue_reset_code(
+        state,
+        target.subject,
+        &target.email,
+        target.phone_number.as_deref(),
+        channel,
+        &ip,
+    )
+    .await
+    {
+        Ok(Some(channel)) => {
+            log_security_event("password_reset_requested", Some(&actor_id));
+            let _ = outbox_service::enqueue_analytics_event(
+                &state.db_pool,
+                &state.config,
+                "password_reset_requested",
+                Some(&actor_id),
+                Some(target.subject.role()),
+                Some(target.subject.id()),
+                serde_json::json!({
+                    "channel": channel.as_str(),
+                    "outcome": "success",
+                }),
+            )
+            .await;
+        }
+        Ok(None) => log_security_event("password_reset_account_rate_limited", Some(&actor_id)),
+        Err(e) => {
+            error!(error = %e, %actor_id, "Failed to issue password reset code");
+            return Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore interno",
+            ));
+        }
+    }
+
+    Ok(accepted)
+}
+
+async fn ip_reset_activity(
+    state: &AppState,
+    ip: &str,
+) -> Result<password_reset_service::IpResetActivity, (StatusCode, Json<ApiError>)> {
+    password_reset_service::ip_reset_activity(&state.db_pool, ip)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to check password reset rate limit");
+            ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
+        })
+}
+
+fn too_many_requests() -> (StatusCode, Json<ApiError>) {
+    ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        "Troppe richieste, riprova più tardi",
+    )
+}
+
+fn validate_new_password(
+    payload: &ResetPasswordRequest,
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    if payload.code.trim().is_empty() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "Inserisci il codice",
+        ));
+    }
+    if payload.new_password.len() < 8 {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "La nuova password deve essere di almeno 8 caratteri",
+        ));
+    }
+    Ok(())
+}
+
+/// Rejected codes are counted per IP address, whether or not the email
+/// matches an account, and the address gets `429` once it hits the limit.
+async fn redeem_reset_code(
+    state: &AppState,
+    ip: String,
+    subject: Option<SessionSubject>,
+    payload: &ResetPasswordRequest,
+) -> Result<Json<PasswordResetResponse>, (StatusCode, Json<ApiError>)> {
+    let invalid_code = || ApiError::new(StatusCode::BAD_REQUEST, "Codice non valido o scaduto");
+
+    let activity = ip_reset_activity(state, &ip).await?;
+    if !activity.may_redeem_code() {
+        log_security_event("password_reset_redeem_ip_rate_limited", Some(&ip));
+        return Err(too_many_requests());
+    }
+
+    // Hash before looking at the account so unknown emails cost the same time
+    let new_password_hash = hash(&payload.new_password, DEFAULT_COST).map_err(|e| {
+        error!(error = %e, "Failed to hash reset password");
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
+    })?;
+
+    let Some(subject) = subject else {
+        if let Err(e) =
+            password_reset_service::record_reset_failure(&state.db_pool, &ip, None).await
+        {
+            error!(error = %e, "Failed to record password reset failure");
+        }
+        log_security_event("password_reset_unknown_account", None);
+        return Err(invalid_code());
+    };
+    let actor_id = subject.id().to_string();
+
+    let check = password_reset_service::redeem_reset_code(
+        state,
+        subject,
+        &payload.code,
+        &new_password_hash,
+        &ip,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, %actor_id, "Failed to redeem password reset code");
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Errore interno")
+    })?;
+
+    match check {
+        ResetCodeCheck::Accepted => {}
+        ResetCodeCheck::WrongCode { locks: true } => {
+            log_security_event("password_reset_code_locked", Some(&actor_id));
+            return Err(invalid_code());
+        }
+        _ => {
+            log_security_event("password_reset_code_rejected", Some(&actor_id));
+            return Err(invalid_code());
+        }
+    }
+
+    log_security_event("password_reset_completed", Some(&actor_id));
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "password_reset_completed",
+        Some(&actor_id),
+        Some(subject.role()),
+        Some(subject.id()),
+        serde_json::json!({
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok(Json(PasswordResetResponse {
+        message: "Password reimpostata. Accedi con la nuova password.".to_string(),
+    }))
+}
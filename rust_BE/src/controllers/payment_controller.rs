@@ -13,14 +13,14 @@ use crate::application::payment_service::{
     create_authorized_payment_service, create_payment_service, erase_payment_service,
     load_all_payments_service, load_payment_service,
 };
-use crate::middleware::auth::ClubOwnerUser;
+use crate::middleware::auth::AdminUser;
 use crate::models::{
     AppState, CancelPaymentRequest, CancelPaymentResponse, CapturePaymentRequest,
     CapturePaymentResponse, PaymentEntity, PaymentFilter, PaymentRequest,
 };
 
 pub async fn get_all_payments(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(app_state): State<Arc<AppState>>,
     Query(filters): Query<PaymentFilter>,
 ) -> Result<Json<Vec<PaymentEntity>>, StatusCode> {
@@ -29,6 +29,7 @@ pub async fn get_all_payments(
 }
 
 pub async fn get_payment(
+    _: AdminUser,
     Path(id): Path<Uuid>,
     State(app_state): State<Arc<AppState>>,
 ) -> Result<Json<PaymentEntity>, StatusCode> {
@@ -37,7 +38,7 @@ pub async fn get_payment(
 }
 
 pub async fn post_payment(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(app_state): State<Arc<AppState>>,
     Json(payload): Json<PaymentRequest>,
 ) -> Result<(StatusCode, Json<PaymentEntity>), StatusCode> {
@@ -87,7 +88,7 @@ pub async fn post_payment(
 }
 
 pub async fn delete_payment(
-    _: ClubOwnerUser,
+    _: AdminUser,
     Path(id): Path<Uuid>,
     State(app_state): State<Arc<AppState>>,
 ) -> StatusCode {
@@ -100,7 +101,7 @@ pub async fn delete_payment(
 
 // Create payment with authorization (manual capture) — requires club_owner JWT
 pub async fn post_authorized_payment(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(app_state): State<Arc<AppState>>,
     Json(payload): Json<PaymentRequest>,
 ) -> Result<(StatusCode, Json<PaymentEntity>), StatusCode> {
@@ -110,7 +111,7 @@ pub async fn post_authorized_payment(
 
 // Capture an authorized payment — requires club_owner JWT
 pub async fn capture_payment(
-    _: ClubOwnerUser,
+    _: AdminUser,
     Path(id): Path<Uuid>,
     State(app_state): State<Arc<AppState>>,
     Json(payload): Json<CapturePaymentRequest>,
@@ -180,7 +181,7 @@ pub async fn capture_payment(
 
 // Cancel an authorized payment — requires club_owner JWT
 pub async fn cancel_payment(
-    _: ClubOwnerUser,
+    _: AdminUser,
     Path(id): Path<Uuid>,
     State(app_state): State<Arc<AppState>>,
     Json(payload): Json<CancelPaymentRequest>,
@@ -0,0 +1,403 @@
+use crate::application::{
+    club_owner_service as club_owner_persistence, club_service as club_persistence, outbox_service,
+    platform_admin_service as admin_persistence, refund_service as refund_persistence,
+    reservation_service as table_persistence, session_service,
+    waitlist_service as waitlist_persistence,
+};
+use crate::infrastructure::logging::log_security_event;
+use crate::middleware::auth::{AdminActor, AdminUser};
+use crate::models::{
+    AdminAuthResponse, AdminLoginRequest, AdminOwnerResponse, AdminRefundParams,
+    AdminRefundResponse, AppState, CancelReservationResponse, CancellationActor, ClubResponse,
+    CreatePlatformAdminRequest, PaginationParams, PlatformAdminResponse, SessionSubject,
+    UpdateCommissionRequest, UpdateOwnerStatusRequest,
+};
+use crate::utils::jwt;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use bcrypt::{hash, verify, DEFAULT_COST};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+const REFUND_STATUSES: [&str; 4] = ["pending", "succeeded", "failed", "released"];
+
+/// Log a platform admin in. Admin tokens carry role `platform_admin` and last
+/// `ADMIN_TOKEN_EXPIRATION_HOURS`; there is no refresh token.
+pub async fn login_admin(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<AdminLoginRequest>,
+) -> Result<Json<AdminAuthResponse>, StatusCode> {
+    let admin =
+        match admin_persistence::find_active_admin_by_email(&state.db_pool, &payload.email).await {
+            Ok(Some(admin)) => admin,
+            Ok(None) => return Err(StatusCode::UNAUTHORIZED),
+            Err(e) => {
+                error!("login_admin: DB error: {:?}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    let is_valid = verify(payload.password, &admin.password_hash).map_err(|e| {
+        error!("login_admin: bcrypt error: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if !is_valid {
+        log_security_event("admin_login_failed", Some(&admin.id.to_string()));
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let token = jwt::generate_admin_token(admin.id, admin.email.clone(), &state.jwt_secret)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Err(e) = admin_persistence::touch_admin_login(&state.db_pool, admin.id).await {
+        warn!(error = %e, admin_id = %admin.id, "Failed to record admin login");
+    }
+    log_security_event("admin_logged_in", Some(&admin.id.to_string()));
+
+    Ok(Json(AdminAuthResponse {
+        admin: PlatformAdminResponse::from(admin),
+        token,
+        expires_in: (jwt::ADMIN_TOKEN_EXPIRATION_HOURS * 3600) as i64,
+    }))
+}
+
+/// Every platform admin, active ones first
+pub async fn list_admins(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<Vec<PlatformAdminResponse>>, StatusCode> {
+    let admins = admin_persistence::list_platform_admins(&state.db_pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(
+        admins
+            .into_iter()
+            .map(PlatformAdminResponse::from)
+            .collect(),
+    ))
+}
+
+/// Create a platform admin. The first one is created with the `X-Admin-Key` ops key.
+pub async fn create_admin(
+    State(state): State<Arc<AppState>>,
+    AdminUser(actor): AdminUser,
+    Json(payload): Json<CreatePlatformAdminRequest>,
+) -> Result<(StatusCode, Json<PlatformAdminResponse>), StatusCode> {
+    if !payload.email.contains('@') || payload.name.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if payload.password.len() < 12 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let password_hash = hash(&payload.password, DEFAULT_COST).map_err(|e| {
+        error!("create_admin: bcrypt error: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let admin = admin_persistence::create_platform_admin(
+        &state.db_pool,
+        &payload.email,
+        payload.name.trim(),
+        &password_hash,
+    )
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => StatusCode::CONFLICT,
+        e => {
+            error!(error = %e, "Failed to create platform admin");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
+
+    admin_persistence::audit(
+        &state.db_pool,
+        actor,
+        "admin_created",
+        "platform_admin",
+        Some(admin.id),
+        serde_json::json!({ "email": admin.email }),
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PlatformAdminResponse::from(admin)),
+    ))
+}
+
+/// Body for PATCH /admin/admins/:id
+#[derive(Debug, Deserialize)]
+pub struct UpdateAdminStatusRequest {
+    pub is_active: bool,
+}
+
+/// Enable or disable a platform admin. Admins cannot disable themselves.
+pub async fn update_admin_status(
+    State(state): State<Arc<AppState>>,
+    AdminUser(actor): AdminUser,
+    Path(admin_id): Path<String>,
+    Json(payload): Json<UpdateAdminStatusRequest>,
+) -> Result<Json<PlatformAdminResponse>, StatusCode> {
+    let admin_uuid = Uuid::parse_str(&admin_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if actor == AdminActor::Admin(admin_uuid) && !payload.is_active {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let admin =
+        admin_persistence::set_platform_admin_active(&state.db_pool, admin_uuid, payload.is_active)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+
+    admin_persistence::audit(
+        &state.db_pool,
+        actor,
+        if admin.is_active {
+            "admin_enabled"
+        } else {
+            "admin_disabled"
+        },
+        "platform_admin",
+        Some(admin.id),
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(Json(PlatformAdminResponse::from(admin)))
+}
+
+/// Every club, with owner, Stripe and commission details
+pub async fn list_clubs(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<Vec<ClubResponse>>, StatusCode> {
+    let clubs = club_persistence::get_all_clubs(&state.db_pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(clubs.into_iter().map(ClubResponse::from).collect()))
+}
+
+/// Set the platform commission charged on a club's payments
+pub async fn update_club_commission(
+    State(state): State<Arc<AppState>>,
+    AdminUser(actor): AdminUser,
+    Path(club_id): Path<String>,
+    Json(payload): Json<UpdateCommissionRequest>,
+) -> Result<Json<ClubResponse>, StatusCode> {
+    let club_uuid = Uuid::parse_str(&club_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let percent = payload.platform_commission_percent;
+    let fixed_fee = payload.platform_commission_fixed_fee;
+    if percent < Decimal::ZERO || percent > Decimal::new(100, 0) || fixed_fee < Decimal::ZERO {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let previous = club_persistence::get_club_by_id(&state.db_pool, club_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let club = club_persistence::set_club_commission(&state.db_pool, club_uuid, percent, fixed_fee)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    admin_persistence::audit(
+        &state.db_pool,
+        actor,
+        "club_commission_updated",
+        "club",
+        Some(club.id),
+        serde_json::json!({
+            "previous_percent": previous.platform_commission_percent,
+            "previous_fixed_fee": previous.platform_commission_fixed_fee,
+            "percent": percent,
+            "fixed_fee": fixed_fee,
+        }),
+    )
+    .await;
+
+    Ok(Json(ClubResponse::from(club)))
+}
+
+/// Club owners with their club, newest first
+pub async fn list_owners(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<Vec<AdminOwnerResponse>>, StatusCode> {
+    let owners = club_owner_persistence::list_owners_with_clubs(
+        &state.db_pool,
+        pagination.limit.clamp(1, 200),
+        pagination.offset.max(0),
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(
+        owners.into_iter().map(AdminOwnerResponse::from).collect(),
+    ))
+}
+
+/// Suspend or reinstate a club owner. Suspending revokes every session, so the
+/// owner is signed out on their next request and cannot log in again.
+pub async fn update_owner_status(
+    State(state): State<Arc<AppState>>,
+    AdminUser(actor): AdminUser,
+    Path(owner_id): Path<String>,
+    Json(payload): Json<UpdateOwnerStatusRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let owner_uuid = Uuid::parse_str(&owner_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let updated = club_owner_persistence::set_club_owner_suspended(
+        &state.db_pool,
+        owner_uuid,
+        payload.suspended,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    if payload.suspended {
+        session_service::revoke_all_sessions(
+            &state.db_pool,
+            SessionSubject::ClubOwner(owner_uuid),
+            "owner_suspended",
+        )
+        .await
+        .map_err(|e| {
+            error!(error = %e, owner_id = %owner_uuid, "Failed to revoke suspended owner sessions");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+
+    admin_persistence::audit(
+        &state.db_pool,
+        actor,
+        if payload.suspended {
+            "owner_suspended"
+        } else {
+            "owner_reinstated"
+        },
+        "club_owner",
+        Some(owner_uuid),
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Refunds across every club, newest first
+pub async fn list_refunds(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(params): Query<AdminRefundParams>,
+) -> Result<Json<Vec<AdminRefundResponse>>, StatusCode> {
+    if let Some(status) = params.status.as_deref() {
+        if !REFUND_STATUSES.contains(&status) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let refunds =
+        refund_persistence::list_refunds(&state.db_pool, params.status.as_deref(), limit, offset)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to list refunds");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+    Ok(Json(
+        refunds.into_iter().map(AdminRefundResponse::from).collect(),
+    ))
+}
+
+/// Cancel a reservation from the back office and refund every paid share in
+/// full, whatever the club's cancellation policy.
+pub async fn cancel_reservation(
+    State(state): State<Arc<AppState>>,
+    AdminUser(actor): AdminUser,
+    Path(reservation_id): Path<String>,
+) -> Result<Json<CancelReservationResponse>, StatusCode> {
+    let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match table_persistence::get_reservation_by_id(&state.db_pool, reservation_uuid).await {
+        Ok(_) => {}
+        Err(sqlx::Error::RowNotFound) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    let cancelled =
+        refund_persistence::mark_reservation_cancelled(&state.db_pool, reservation_uuid)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::CONFLICT)?;
+
+    let (refund_percent, refunds) = match refund_persistence::refund_cancelled_reservation(
+        &state,
+        &cancelled,
+        CancellationActor::Admin,
+    )
+    .await
+    {
+        Ok(outcome) => outcome,
+        Err(status) => {
+            error!(reservation_id = %cancelled.id, status = %status, "Admin cancellation refunds failed");
+            refund_persistence::record_reservation_refund_failure(
+                &state,
+                &cancelled,
+                CancellationActor::Admin,
+                &format!("refund processing failed ({status})"),
+            )
+            .await;
+            refund_persistence::notify_refund_delayed(&state, &cancelled).await;
+            (Decimal::ZERO, Vec::new())
+        }
+    };
+
+    let total_refunded: Decimal = refunds
+        .iter()
+        .filter(|r| r.status != "failed")
+        .map(|r| r.amount)
+        .sum();
+
+    if let Err(error) = waitlist_persistence::offer_table(&state, cancelled.table_id).await {
+        warn!(error = %error, reservation_id = %cancelled.id, "Failed to offer freed table to waitlist");
+    }
+
+    if let Err(error) =
+        outbox_service::enqueue_reservation_cancelled_emails(&state.db_pool, cancelled.id).await
+    {
+        warn!(error = %error, reservation_id = %cancelled.id, "Failed to enqueue reservation cancellation emails");
+    }
+
+    admin_persistence::audit(
+        &state.db_pool,
+        actor,
+        "reservation_cancelled",
+        "reservation",
+        Some(cancelled.id),
+        serde_json::json!({
+            "refund_percent": refund_percent,
+            "total_refunded": total_refunded,
+            "refund_count": refunds.len(),
+        }),
+    )
+    .await;
+
+    Ok(Json(CancelReservationResponse {
+        reservation_id: cancelled.id.to_string(),
+        status: cancelled.status,
+        refund_percent,
+        total_refunded: format!("{:.2} €", total_refunded),
+        refunds: refunds.into_iter().map(Into::into).collect(),
+    }))
+}
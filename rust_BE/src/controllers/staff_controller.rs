@@ -0,0 +1,327 @@
+use crate::application::{
+    club_service as club_persistence, outbox_service, staff_service as staff_persistence,
+};
+use crate::middleware::auth::ClubOwnerUser;
+use crate::models::{
+    AcceptStaffInviteRequest, AppState, ClubResponse, ClubStaff, InviteStaffRequest,
+    StaffAuthResponse, StaffInviteResponse, StaffLoginRequest, StaffMemberResponse, StaffRole,
+    UpdateStaffRequest,
+};
+use crate::utils::jwt;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use bcrypt::{hash, verify, DEFAULT_COST};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+const STAFF_INVITE_TTL_HOURS: i64 = 72;
+
+/// List the staff of the authenticated owner's club, revoked members last
+pub async fn list_my_staff(
+    State(state): State<Arc<AppState>>,
+    ClubOwnerUser(claims): ClubOwnerUser,
+) -> Result<Json<Vec<StaffMemberResponse>>, StatusCode> {
+    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let staff = staff_persistence::get_staff_by_club(&state.db_pool, club.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        staff.into_iter().map(StaffMemberResponse::from).collect(),
+    ))
+}
+
+/// Invite a staff member. The activation link is emailed to them, also sent
+/// by SMS when a phone number is given, and always returned to the owner.
+pub async fn invite_staff_member(
+    State(state): State<Arc<AppState>>,
+    ClubOwnerUser(claims): ClubOwnerUser,
+    Json(payload): Json<InviteStaffRequest>,
+) -> Result<(StatusCode, Json<StaffInviteResponse>), StatusCode> {
+    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let role = StaffRole::parse(&payload.role).ok_or(StatusCode::BAD_REQUEST)?;
+    let name = payload.name.trim();
+    if !payload.email.contains('@') || name.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let phone_number = payload
+        .phone_number
+        .as_deref()
+        .map(str::trim)
+        .filter(|phone| !phone.is_empty());
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (token, token_hash) = staff_persistence::new_invite_token();
+    let staff = match staff_persistence::create_staff_invite(
+        &state.db_pool,
+        club.id,
+        owner_id,
+        &payload.email,
+        name,
+        phone_number,
+        role.as_str(),
+        &token_hash,
+        Utc::now() + Duration::hours(STAFF_INVITE_TTL_HOURS),
+    )
+    .await
+    {
+        Ok(staff) => staff,
+        // The email already belongs to a live staff account
+        Err(sqlx::Error::Database(db_error)) if db_error.is_unique_violation() => {
+            return Err(StatusCode::CONFLICT)
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to create staff invitation");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let invite_url = staff_persistence::invite_url(&state.config.owner_app_base_url, &token);
+    let body = format!(
+        "{} ti ha invitato nello staff. Attiva il tuo account entro {} ore: {}",
+        club.name, STAFF_INVITE_TTL_HOURS, invite_url
+    );
+    let email_sent = outbox_service::enqueue_email_notification(
+        &state.db_pool,
+        &staff.email,
+        &format!("Invito nello staff di {}", club.name),
+        &body,
+        None,
+        Some("club_staff"),
+        Some(staff.id),
+    )
+    .await
+    .is_ok();
+    let mut sms_sent = false;
+    if let Some(phone_number) = phone_number {
+        sms_sent = outbox_service::enqueue_sms_notification(
+            &state.db_pool,
+            phone_number,
+            &body,
+            Some("club_staff"),
+            Some(staff.id),
+        )
+        .await
+        .is_ok();
+    }
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_staff_invited",
+        Some(&claims.sub),
+        Some("club_staff"),
+        Some(staff.id),
+        serde_json::json!({
+            "owner_id": owner_id,
+            "club_id": club.id,
+            "staff_id": staff.id,
+            "role": staff.role,
+            "email_sent": email_sent,
+            "sms_sent": sms_sent,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(StaffInviteResponse {
+            staff: StaffMemberResponse::from(staff),
+            invite_url,
+            email_sent,
+            sms_sent,
+        }),
+    ))
+}
+
+/// Rename a staff member or change their role. Tokens issued with the old
+/// role stop working immediately.
+pub async fn update_staff_member(
+    State(state): State<Arc<AppState>>,
+    ClubOwnerUser(claims): ClubOwnerUser,
+    Path(staff_id): Path<String>,
+    Json(payload): Json<UpdateStaffRequest>,
+) -> Result<Json<StaffMemberResponse>, StatusCode> {
+    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let staff_uuid = Uuid::parse_str(&staff_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let role = payload
+        .role
+        .as_deref()
+        .map(|role| StaffRole::parse(role).ok_or(StatusCode::BAD_REQUEST))
+        .transpose()?;
+    let name = payload
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let staff = staff_persistence::update_staff(
+        &state.db_pool,
+        staff_uuid,
+        club.id,
+        name,
+        role.map(|role| role.as_str()),
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(StaffMemberResponse::from(staff)))
+}
+
+/// Revoke a staff member (or a pending invitation); their tokens are rejected
+/// from the next request on
+pub async fn revoke_staff_member(
+    State(state): State<Arc<AppState>>,
+    ClubOwnerUser(claims): ClubOwnerUser,
+    Path(staff_id): Path<String>,
+) -> Result<Json<StaffMemberResponse>, StatusCode> {
+    let owner_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let staff_uuid = Uuid::parse_str(&staff_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let staff = staff_persistence::revoke_staff(&state.db_pool, staff_uuid, club.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_staff_revoked",
+        Some(&claims.sub),
+        Some("club_staff"),
+        Some(staff.id),
+        serde_json::json!({
+            "owner_id": owner_id,
+            "club_id": club.id,
+            "staff_id": staff.id,
+            "role": staff.role,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok(Json(StaffMemberResponse::from(staff)))
+}
+
+/// Accept a staff invitation by choosing a password; logs the member in
+pub async fn accept_staff_invite(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<AcceptStaffInviteRequest>,
+) -> Result<Json<StaffAuthResponse>, StatusCode> {
+    if payload.token.trim().is_empty() || payload.password.len() < 8 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let password_hash =
+        hash(payload.password, DEFAULT_COST).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let staff = staff_persistence::accept_staff_invite(
+        &state.db_pool,
+        &staff_persistence::hash_invite_token(&payload.token),
+        &password_hash,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    // Unknown, expired, already used or revoked
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    staff_auth_response(&state, staff, "staff_invite_accepted").await
+}
+
+/// Login as a club staff member
+pub async fn login_staff(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<StaffLoginRequest>,
+) -> Result<Json<StaffAuthResponse>, StatusCode> {
+    let staff =
+        match staff_persistence::find_active_staff_by_email(&state.db_pool, &payload.email).await {
+            Ok(Some(staff)) => staff,
+            Ok(None) => return Err(StatusCode::UNAUTHORIZED),
+            Err(e) => {
+                error!("login_staff: DB error: {:?}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    let Some(password_hash) = staff.password_hash.as_deref() else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    let is_valid = verify(payload.password, password_hash).map_err(|e| {
+        error!("login_staff: bcrypt error: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if !is_valid {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    staff_auth_response(&state, staff, "staff_logged_in").await
+}
+
+async fn staff_auth_response(
+    state: &AppState,
+    staff: ClubStaff,
+    analytics_event: &str,
+) -> Result<Json<StaffAuthResponse>, StatusCode> {
+    let role = StaffRole::parse(&staff.role).ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let club = club_persistence::get_club_by_id(&state.db_pool, staff.club_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let owner_id = club.owner_id.ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let token = jwt::generate_staff_token(
+        staff.id,
+        staff.email.clone(),
+        role.claim_role(),
+        owner_id,
+        &state.jwt_secret,
+    )
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        analytics_event,
+        Some(&staff.id.to_string()),
+        Some("club_staff"),
+        Some(staff.id),
+        serde_json::json!({
+            "staff_id": staff.id,
+            "club_id": club.id,
+            "role": staff.role,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok(Json(StaffAuthResponse {
+        staff: StaffMemberResponse::from(staff),
+        club: Some(ClubResponse::from(club)),
+        token,
+    }))
+}
@@ -0,0 +1,103 @@
+use crate::application::stripe_webhook_service as stripe_webhook_persistence;
+use crate::infrastructure::logging::log_business_event;
+use crate::middleware::auth::AdminUser;
+use crate::models::{AppState, StripeWebhookEventResponse, WebhookEventParams};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+const STATUSES: [&str; 5] = [
+    "pending",
+    "processing",
+    "failed",
+    "processed",
+    "dead_lettered",
+];
+
+/// Stored Stripe webhooks, most recently received first
+pub async fn list_webhook_events(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(params): Query<WebhookEventParams>,
+) -> Result<Json<Vec<StripeWebhookEventResponse>>, StatusCode> {
+    if let Some(status) = params.status.as_deref() {
+        if !STATUSES.contains(&status) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let events = stripe_webhook_persistence::list_events(
+        &state.db_pool,
+        params.status.as_deref(),
+        params.event_type.as_deref(),
+        limit,
+        offset,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to list Stripe webhook events");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(
+        events
+            .into_iter()
+            .map(StripeWebhookEventResponse::from)
+            .collect(),
+    ))
+}
+
+/// One stored webhook with the payload Stripe sent
+pub async fn get_webhook_event(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(event_id): Path<String>,
+) -> Result<Json<StripeWebhookEventResponse>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let event = stripe_webhook_persistence::get_event(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(StripeWebhookEventResponse::with_payload(event)))
+}
+
+/// Run a stored webhook again with a fresh attempt budget, whether it was
+/// dead-lettered, failed or already processed. 409 while it is being processed.
+pub async fn replay_webhook_event(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(event_id): Path<String>,
+) -> Result<Json<StripeWebhookEventResponse>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let event = match stripe_webhook_persistence::requeue_event(&state.db_pool, event_uuid).await {
+        Ok(Some(event)) => event,
+        Ok(None) => {
+            return match stripe_webhook_persistence::get_event(&state.db_pool, event_uuid).await {
+                Ok(Some(_)) => Err(StatusCode::CONFLICT),
+                Ok(None) => Err(StatusCode::NOT_FOUND),
+                Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+            }
+        }
+        Err(e) => {
+            error!(event_id = %event_uuid, error = %e, "Failed to requeue Stripe webhook event");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    log_business_event(
+        "stripe_webhook_event_replayed",
+        "stripe_webhook_event",
+        &event_id,
+    );
+
+    Ok(Json(StripeWebhookEventResponse::from(event)))
+}
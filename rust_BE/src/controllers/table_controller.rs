@@ -1,21 +1,29 @@
 use crate::application::{
-    auth_service as user_persistence, outbox_service, reservation_service as table_persistence,
+    auth_service as user_persistence, club_service, event_service, outbox_service, payment_service,
+    promo_code_service::{self as promo_code_persistence, PromoQuote},
+    refund_service as refund_persistence, reservation_service as table_persistence,
+    waitlist_service as waitlist_persistence,
 };
-use crate::middleware::auth::ClubOwnerUser;
+use crate::middleware::auth::{AdminUser, AuthUser, ClubManager};
+use crate::models::age_restriction::check_min_age;
+use crate::models::split_payment::{equal_slot_price, plan_split, SplitMode, SplitPlanError};
 use crate::models::PaginationParams;
 use crate::models::{
-    AddPaymentToReservationRequest, AppState, CreateCheckoutRequest, CreateCheckoutResponse,
-    CreatePaymentIntentResponse, CreateSplitPaymentIntentRequest, CreateSplitReservationRequest,
-    CreateSplitReservationResponse, CreateTableRequest, CreateTableReservationRequest,
-    EventSummary, LinkTicketToReservationRequest, PaymentCaptureMethod, PaymentLinkPreviewResponse,
-    PaymentStatus, ReservationPaymentStatusResponse, TableReservationResponse,
+    AddPaymentToReservationRequest, AppState, CancelReservationResponse, CancellationActor, Claims,
+    CreateCheckoutRequest, CreateCheckoutResponse, CreatePaymentIntentResponse,
+    CreateSplitPaymentIntentRequest, CreateSplitReservationRequest, CreateSplitReservationResponse,
+    CreateTableRequest, CreateTableReservationRequest, EventSummary,
+    LinkTicketToReservationRequest, PaymentCaptureMethod, PaymentLinkPreviewParams,
+    PaymentLinkPreviewResponse, PaymentStatus, PromoCodeRejection,
+    ReservationPaymentStatusResponse, Table, TableReservation, TableReservationResponse,
     TableReservationWithDetailsResponse, TableReservationsResponse,
     TableReservationsWithDetailsResponse, TableResponse, TableSummary, TablesResponse,
     UpdateTableRequest, UpdateTableReservationRequest,
 };
+use crate::services::email_templates::EmailLocale;
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     Json,
 };
 use chrono::Utc;
@@ -27,63 +35,9 @@ use stripe::{
     CreateCustomer, CreatePaymentIntent, Currency, Customer, PaymentIntent,
     PaymentIntentCaptureMethod, PaymentIntentSetupFutureUsage, PaymentIntentStatus,
 };
-use tracing::warn;
+use tracing::{error, warn};
 use uuid::Uuid;
 
-#[derive(Clone, Debug, sqlx::FromRow)]
-struct ClubStripeConnectConfig {
-    stripe_connected_account_id: Option<String>,
-    stripe_onboarding_complete: Option<bool>,
-    stripe_charges_enabled: Option<bool>,
-    stripe_payouts_enabled: Option<bool>,
-    platform_commission_percent: Option<Decimal>,
-    platform_commission_fixed_fee: Option<Decimal>,
-}
-
-fn compute_application_fee_cents(
-    total_amount: Decimal,
-    commission_percent: Option<Decimal>,
-    commission_fixed_fee: Option<Decimal>,
-) -> i64 {
-    let percent = commission_percent.unwrap_or(Decimal::ZERO);
-    let fixed_fee = commission_fixed_fee.unwrap_or(Decimal::ZERO);
-    let percent_fee = (total_amount * percent / Decimal::new(100, 0)).round_dp(2);
-    let fee = (percent_fee + fixed_fee)
-        .max(Decimal::ZERO)
-        .min(total_amount);
-    ((fee.to_f64().unwrap_or(0.0) * 100.0).round()) as i64
-}
-
-async fn get_club_connect_config_for_event(
-    pool: &sqlx::PgPool,
-    event_id: Uuid,
-) -> Result<Option<ClubStripeConnectConfig>, sqlx::Error> {
-    sqlx::query_as::<_, ClubStripeConnectConfig>(
-        r#"
-        SELECT
-            c.stripe_connected_account_id,
-            c.stripe_onboarding_complete,
-            c.stripe_charges_enabled,
-            c.stripe_payouts_enabled,
-            c.platform_commission_percent,
-            c.platform_commission_fixed_fee
-        FROM events e
-        JOIN clubs c ON c.id = e.club_id
-        WHERE e.id = $1
-        "#,
-    )
-    .bind(event_id)
-    .fetch_optional(pool)
-    .await
-}
-
-fn can_route_funds_to_connected_account(config: &ClubStripeConnectConfig) -> bool {
-    config.stripe_connected_account_id.is_some()
-        && config.stripe_onboarding_complete.unwrap_or(false)
-        && config.stripe_charges_enabled.unwrap_or(false)
-        && config.stripe_payouts_enabled.unwrap_or(false)
-}
-
 // ============================================================================
 // Tables endpoints
 // ============================================================================
@@ -159,13 +113,14 @@ pub async fn get_table(
     }
 }
 
-/// Create a new table (requires club_owner JWT)
+/// Create a table for an event of a club the caller manages
 pub async fn create_table(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateTableRequest>,
 ) -> Result<Json<TableResponse>, StatusCode> {
     let event_id = Uuid::parse_str(&req.event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    club_service::ensure_manages_event(&state.db_pool, &manager, event_id).await?;
     let min_spend = Decimal::from_f64_retain(req.min_spend).ok_or(StatusCode::BAD_REQUEST)?;
 
     match table_persistence::create_table(
@@ -186,14 +141,15 @@ pub async fn create_table(
     }
 }
 
-/// Update a table (requires club_owner JWT)
+/// Update a table of a club the caller manages
 pub async fn update_table(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
     Json(req): Json<UpdateTableRequest>,
 ) -> Result<Json<TableResponse>, StatusCode> {
     let table_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_table(&state, &manager, table_id).await?;
 
     let min_spend = if let Some(ms) = req.min_spend {
         Some(Decimal::from_f64_retain(ms).ok_or(StatusCode::BAD_REQUEST)?)
@@ -221,13 +177,14 @@ pub async fn update_table(
     }
 }
 
-/// Delete a table (requires club_owner JWT)
+/// Delete a table of a club the caller manages
 pub async fn delete_table(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
     let table_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_table(&state, &manager, table_id).await?;
 
     match table_persistence::delete_table(&state.db_pool, table_id).await {
         Ok(true) => Ok(StatusCode::NO_CONTENT),
@@ -236,13 +193,62 @@ pub async fn delete_table(
     }
 }
 
+async fn ensure_manages_table(
+    state: &AppState,
+    manager: &ClubManager,
+    table_id: Uuid,
+) -> Result<(), StatusCode> {
+    let table = match table_persistence::get_table_by_id(&state.db_pool, table_id).await {
+        Ok(table) => table,
+        Err(sqlx::Error::RowNotFound) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    club_service::ensure_manages_event(&state.db_pool, manager, table.event_id).await?;
+    Ok(())
+}
+
+/// Load a reservation of an event the caller manages.
+async fn ensure_manages_reservation(
+    state: &AppState,
+    manager: &ClubManager,
+    reservation_id: Uuid,
+) -> Result<TableReservation, StatusCode> {
+    let reservation =
+        match table_persistence::get_reservation_by_id(&state.db_pool, reservation_id).await {
+            Ok(reservation) => reservation,
+            Err(sqlx::Error::RowNotFound) => return Err(StatusCode::NOT_FOUND),
+            Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        };
+    club_service::ensure_manages_event(&state.db_pool, manager, reservation.event_id).await?;
+    Ok(reservation)
+}
+
+/// Load a reservation the calling user organized.
+async fn get_own_reservation(
+    state: &AppState,
+    claims: &Claims,
+    reservation_id: Uuid,
+) -> Result<TableReservation, StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let reservation =
+        match table_persistence::get_reservation_by_id(&state.db_pool, reservation_id).await {
+            Ok(reservation) => reservation,
+            Err(sqlx::Error::RowNotFound) => return Err(StatusCode::NOT_FOUND),
+            Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        };
+    if reservation.user_id != user_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(reservation)
+}
+
 // ============================================================================
 // Table Reservations endpoints
 // ============================================================================
 
-/// Get all reservations (admin)
+/// Get all reservations (platform admin)
 pub async fn get_all_reservations(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(state): State<Arc<AppState>>,
     Query(pagination): Query<PaginationParams>,
 ) -> Result<Json<TableReservationsResponse>, StatusCode> {
@@ -265,12 +271,16 @@ pub async fn get_all_reservations(
     }
 }
 
-/// Get reservations for a user (with full details)
+/// Get the calling user's reservations (with full details)
 pub async fn get_user_reservations_with_details(
+    AuthUser(claims): AuthUser,
     State(state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
 ) -> Result<Json<TableReservationsWithDetailsResponse>, StatusCode> {
     let user_uuid = Uuid::parse_str(&user_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if claims.sub != user_uuid.to_string() {
+        return Err(StatusCode::FORBIDDEN);
+    }
 
     let reservations =
         table_persistence::list_user_reservations_with_details(&state.read_db_pool, user_uuid)
@@ -298,12 +308,14 @@ pub async fn get_user_reservations_with_details(
     Ok(Json(TableReservationsWithDetailsResponse { reservations }))
 }
 
-/// Get reservations by table ID
+/// Get reservations of a table of a club the caller manages
 pub async fn get_reservations_by_table(
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(table_id): Path<String>,
 ) -> Result<Json<TableReservationsResponse>, StatusCode> {
     let table_uuid = Uuid::parse_str(&table_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_table(&state, &manager, table_uuid).await?;
 
     match table_persistence::get_reservations_by_table_id(&state.db_pool, table_uuid).await {
         Ok(reservations) => {
@@ -318,18 +330,15 @@ pub async fn get_reservations_by_table(
     }
 }
 
-/// Get a single reservation by ID
+/// Get one of the calling user's reservations
 pub async fn get_reservation(
+    AuthUser(claims): AuthUser,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<Json<TableReservationResponse>, StatusCode> {
     let reservation_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
-
-    match table_persistence::get_reservation_by_id(&state.db_pool, reservation_id).await {
-        Ok(reservation) => Ok(Json(reservation.into())),
-        Err(sqlx::Error::RowNotFound) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    let reservation = get_own_reservation(&state, &claims, reservation_id).await?;
+    Ok(Json(reservation.into()))
 }
 
 /// Get a reservation by code (with full details)
@@ -425,13 +434,25 @@ pub async fn get_reservation_by_code(
 
 /// Create a new reservation
 pub async fn create_reservation(
+    AuthUser(claims): AuthUser,
     State(state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
     Json(req): Json<CreateTableReservationRequest>,
-) -> Result<Json<TableReservationResponse>, StatusCode> {
-    let user_uuid = Uuid::parse_str(&user_id).map_err(|_| StatusCode::BAD_REQUEST)?;
-    let table_id = Uuid::parse_str(&req.table_id).map_err(|_| StatusCode::BAD_REQUEST)?;
-    let event_id = Uuid::parse_str(&req.event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+) -> Result<Json<TableReservationResponse>, (StatusCode, String)> {
+    let user_uuid = Uuid::parse_str(&user_id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "ID utente non valido".to_string()))?;
+    ensure_caller_is(&claims, user_uuid)?;
+    let table_id = Uuid::parse_str(&req.table_id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "ID tavolo non valido".to_string()))?;
+    let event_id = Uuid::parse_str(&req.event_id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "ID evento non valido".to_string()))?;
+    event_service::ensure_user_meets_min_age(
+        &state.db_pool,
+        event_id,
+        user_uuid,
+        req.date_of_birth,
+    )
+    .await?;
 
     tracing::info!(user_id = %user_uuid, table_id = %table_id, event_id = %event_id, num_people = ?req.num_people, "Creating reservation");
 
@@ -454,18 +475,35 @@ pub async fn create_reservation(
         }
         Err(e) => {
             tracing::error!(error = %e, user_id = %user_uuid, table_id = %table_id, "Failed to create reservation");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore del database".to_string(),
+            ))
         }
     }
 }
 
-/// Update a reservation
+/// Bookings are made by the user they are for: the birth date saved with them
+/// belongs to that user.
+fn ensure_caller_is(claims: &Claims, user_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if claims.sub != user_id.to_string() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Non puoi prenotare per un altro utente".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Update a reservation of a club the caller manages
 pub async fn update_reservation(
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
     Json(req): Json<UpdateTableReservationRequest>,
 ) -> Result<Json<TableReservationResponse>, StatusCode> {
     let reservation_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_reservation(&state, &manager, reservation_id).await?;
 
     match table_persistence::update_reservation(
         &state.db_pool,
@@ -485,12 +523,14 @@ pub async fn update_reservation(
     }
 }
 
-/// Delete a reservation
+/// Delete a reservation of a club the caller manages
 pub async fn delete_reservation(
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
     let reservation_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_reservation(&state, &manager, reservation_id).await?;
 
     match table_persistence::delete_reservation(&state.db_pool, reservation_id).await {
         Ok(true) => Ok(StatusCode::NO_CONTENT),
@@ -499,17 +539,144 @@ pub async fn delete_reservation(
     }
 }
 
+/// Cancel a reservation as its organizer. Paid shares are refunded according
+/// to the club's cancellation policy.
+pub async fn cancel_reservation(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(reservation_id): Path<String>,
+) -> Result<Json<CancelReservationResponse>, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Sessione non valida".to_string()))?;
+    let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            "ID prenotazione non valido".to_string(),
+        )
+    })?;
+
+    let reservation =
+        match table_persistence::get_reservation_by_id(&state.db_pool, reservation_uuid).await {
+            Ok(r) => r,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    "Prenotazione non trovata".to_string(),
+                ))
+            }
+            Err(_) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Errore interno".to_string(),
+                ))
+            }
+        };
+
+    if reservation.user_id != user_id {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Solo l'organizzatore puo' annullare la prenotazione".to_string(),
+        ));
+    }
+
+    let cancelled =
+        refund_persistence::mark_reservation_cancelled(&state.db_pool, reservation_uuid)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Errore interno".to_string(),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::CONFLICT,
+                    "La prenotazione non puo' piu' essere annullata".to_string(),
+                )
+            })?;
+
+    let (refund_percent, refunds) = match refund_persistence::refund_cancelled_reservation(
+        &state,
+        &cancelled,
+        CancellationActor::User,
+    )
+    .await
+    {
+        Ok(outcome) => outcome,
+        Err(status) => {
+            error!(reservation_id = %cancelled.id, status = %status, "User cancellation refunds failed");
+            refund_persistence::record_reservation_refund_failure(
+                &state,
+                &cancelled,
+                CancellationActor::User,
+                &format!("refund processing failed ({status})"),
+            )
+            .await;
+            refund_persistence::notify_refund_delayed(&state, &cancelled).await;
+            (Decimal::ZERO, Vec::new())
+        }
+    };
+
+    let total_refunded: Decimal = refunds
+        .iter()
+        .filter(|r| r.status != "failed")
+        .map(|r| r.amount)
+        .sum();
+
+    tracing::info!(reservation_id = %cancelled.id, user_id = %user_id, refund_percent = %refund_percent, total_refunded = %total_refunded, "Reservation cancelled by user");
+
+    if let Err(error) = waitlist_persistence::offer_table(&state, cancelled.table_id).await {
+        warn!(error = %error, reservation_id = %cancelled.id, "Failed to offer freed table to waitlist");
+    }
+
+    if let Err(error) =
+        outbox_service::enqueue_reservation_cancelled_emails(&state.db_pool, cancelled.id).await
+    {
+        warn!(error = %error, reservation_id = %cancelled.id, "Failed to enqueue reservation cancellation emails");
+    }
+
+    if let Err(error) = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "reservation_cancelled_by_user",
+        Some(&claims.sub),
+        Some("reservation"),
+        Some(cancelled.id),
+        serde_json::json!({
+            "reservation_id": cancelled.id,
+            "refund_percent": refund_percent,
+            "total_refunded": total_refunded,
+            "refund_count": refunds.len(),
+            "outcome": "success",
+        }),
+    )
+    .await
+    {
+        warn!(error = %error, reservation_id = %cancelled.id, "Failed to enqueue reservation cancellation analytics event");
+    }
+
+    Ok(Json(CancelReservationResponse {
+        reservation_id: cancelled.id.to_string(),
+        status: cancelled.status,
+        refund_percent,
+        total_refunded: format!("{:.2} €", total_refunded),
+        refunds: refunds.into_iter().map(Into::into).collect(),
+    }))
+}
+
 // ============================================================================
 // Payment and ticket linking endpoints
 // ============================================================================
 
-/// Add a payment to a reservation
+/// Add a payment to a reservation of a club the caller manages
 pub async fn add_payment_to_reservation(
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(reservation_id): Path<String>,
     Json(req): Json<AddPaymentToReservationRequest>,
 ) -> Result<StatusCode, StatusCode> {
     let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_reservation(&state, &manager, reservation_uuid).await?;
     let payment_id = Uuid::parse_str(&req.payment_id).map_err(|_| StatusCode::BAD_REQUEST)?;
     let amount = Decimal::from_f64_retain(req.amount).ok_or(StatusCode::BAD_REQUEST)?;
 
@@ -526,13 +693,15 @@ pub async fn add_payment_to_reservation(
     }
 }
 
-/// Link a ticket to a reservation
+/// Link a ticket to a reservation of a club the caller manages
 pub async fn link_ticket_to_reservation(
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(reservation_id): Path<String>,
     Json(req): Json<LinkTicketToReservationRequest>,
 ) -> Result<StatusCode, StatusCode> {
     let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_reservation(&state, &manager, reservation_uuid).await?;
     let ticket_id = Uuid::parse_str(&req.ticket_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
     match table_persistence::add_ticket_to_reservation(&state.db_pool, reservation_uuid, ticket_id)
@@ -543,26 +712,23 @@ pub async fn link_ticket_to_reservation(
     }
 }
 
-/// Get tickets for a reservation
+/// Get tickets for one of the calling user's reservations
 pub async fn get_tickets_for_reservation(
+    AuthUser(claims): AuthUser,
     State(state): State<Arc<AppState>>,
     Path(reservation_id): Path<String>,
 ) -> Result<Json<Vec<String>>, StatusCode> {
     let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    // Get the reservation which contains ticket_ids array
-    match table_persistence::get_reservation_by_id(&state.db_pool, reservation_uuid).await {
-        Ok(reservation) => {
-            let ticket_id_strings: Vec<String> = reservation
-                .ticket_ids
-                .unwrap_or_default()
-                .iter()
-                .map(|id| id.to_string())
-                .collect();
-            Ok(Json(ticket_id_strings))
-        }
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    // The reservation carries the ticket_ids array
+    let reservation = get_own_reservation(&state, &claims, reservation_uuid).await?;
+    let ticket_id_strings: Vec<String> = reservation
+        .ticket_ids
+        .unwrap_or_default()
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    Ok(Json(ticket_id_strings))
 }
 
 // ============================================================================
@@ -570,8 +736,10 @@ pub async fn get_tickets_for_reservation(
 // ============================================================================
 
 /// Create Stripe PaymentIntent for table reservation (split payment - owner's share only).
-/// Share = table.total_cost / table.capacity. Owner pays their share upfront.
+/// The owner pays their part of `req.split` upfront: by default one slot of
+/// table.total_cost / table.capacity.
 pub async fn create_payment_intent(
+    AuthUser(claims): AuthUser,
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateSplitPaymentIntentRequest>,
 ) -> Result<Json<CreatePaymentIntentResponse>, (StatusCode, String)> {
@@ -581,6 +749,15 @@ pub async fn create_payment_intent(
         .map_err(|_| (StatusCode::BAD_REQUEST, "ID evento non valido".to_string()))?;
     let owner_user_id = Uuid::parse_str(&req.owner_user_id)
         .map_err(|_| (StatusCode::BAD_REQUEST, "ID utente non valido".to_string()))?;
+    ensure_caller_is(&claims, owner_user_id)?;
+
+    event_service::ensure_user_meets_min_age(
+        &state.db_pool,
+        event_id,
+        owner_user_id,
+        req.date_of_birth,
+    )
+    .await?;
 
     // Get table — capacity drives the per-person split
     let table = match table_persistence::get_table_by_id(&state.db_pool, table_id).await {
@@ -588,20 +765,37 @@ pub async fn create_payment_intent(
         Err(_) => return Err((StatusCode::NOT_FOUND, "Tavolo non trovato".to_string())),
     };
 
+    ensure_table_not_held_for_other_user(&state, table_id, owner_user_id).await?;
+
     let total_cost = table.total_cost;
-    let capacity = Decimal::from(table.capacity);
-    let club_connect_config = get_club_connect_config_for_event(&state.db_pool, event_id)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Errore del database".to_string(),
-            )
-        })?;
+    let club_connect_config =
+        payment_service::get_club_connect_config_for_event(&state.db_pool, event_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Errore del database".to_string(),
+                )
+            })?;
 
     // Per-person share: total_cost / capacity, owner absorbs rounding remainder
-    let per_person = (total_cost / capacity).round_dp(2);
-    let owner_share = total_cost - (per_person * Decimal::from(table.capacity - 1));
+    // and whatever the split leaves them
+    let plan =
+        plan_split(total_cost, table.capacity, req.split.as_ref()).map_err(split_plan_error)?;
+    let per_person = plan.slot_price;
+    let owner_share = plan.owner_amount;
+
+    // A promo code only discounts the owner's own share
+    let promo_quote = match req.promo_code.as_deref() {
+        Some(code) if !code.trim().is_empty() => Some(
+            quote_promo_for_table(&state, code, &table, Some(&req.contact_phone), owner_share)
+                .await?,
+        ),
+        _ => None,
+    };
+    let owner_charge = promo_quote
+        .as_ref()
+        .map_or(owner_share, |quote| quote.final_amount);
 
     // Get or create a Stripe Customer for this user (needed for off-session re-auth)
     let owner_user = match user_persistence::find_user_by_id(&state.db_pool, owner_user_id).await {
@@ -633,8 +827,8 @@ pub async fn create_payment_intent(
         })?;
 
     // Create Stripe PaymentIntent for owner's share with manual capture + saved payment method
-    let amount_in_cents = owner_share.to_f64().ok_or_else(|| {
-        tracing::error!(owner_share = %owner_share, "Invalid owner_share amount");
+    let amount_in_cents = owner_charge.to_f64().ok_or_else(|| {
+        tracing::error!(owner_charge = %owner_charge, "Invalid owner_charge amount");
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             "Importo non valido".to_string(),
@@ -658,17 +852,17 @@ pub async fn create_payment_intent(
 
     let connect_destination_for_on_behalf_of = club_connect_config
         .as_ref()
-        .filter(|cfg| can_route_funds_to_connected_account(cfg))
+        .filter(|cfg| cfg.can_route_funds())
         .and_then(|cfg| cfg.stripe_connected_account_id.clone());
     if let Some(config) = club_connect_config
         .as_ref()
-        .filter(|cfg| can_route_funds_to_connected_account(cfg))
+        .filter(|cfg| cfg.can_route_funds())
     {
         let destination = connect_destination_for_on_behalf_of
             .clone()
             .unwrap_or_default();
-        let application_fee_amount = compute_application_fee_cents(
-            owner_share,
+        let application_fee_amount = payment_service::compute_application_fee_cents(
+            owner_charge,
             config.platform_commission_percent,
             config.platform_commission_fixed_fee,
         );
@@ -688,10 +882,15 @@ pub async fn create_payment_intent(
             ("split_payment".to_string(), "true".to_string()),
             ("capacity".to_string(), table.capacity.to_string()),
             ("total_cost".to_string(), total_cost.to_string()),
+            ("split_mode".to_string(), plan.mode.as_str().to_string()),
         ]
         .into_iter()
         .collect(),
     );
+    if let (Some(metadata), Some(quote)) = (params.metadata.as_mut(), promo_quote.as_ref()) {
+        metadata.insert("promo_code_id".to_string(), quote.promo.id.to_string());
+        metadata.insert("promo_discount".to_string(), quote.discount.to_string());
+    }
 
     let payment_intent = PaymentIntent::create(&state.stripe_client, params)
         .await
@@ -726,6 +925,8 @@ pub async fn create_payment_intent(
             "event_id": event_id,
             "owner_share": owner_share,
             "per_person": per_person,
+            "split_mode": plan.mode.as_str(),
+            "promo_code_id": promo_quote.as_ref().map(|quote| quote.promo.id),
             "outcome": "success",
         }),
     )
@@ -735,17 +936,20 @@ pub async fn create_payment_intent(
         client_secret,
         payment_intent_id: payment_intent.id.to_string(),
         stripe_publishable_key: state.config.stripe.publishable_key.clone(),
-        amount: format!("{:.2} €", owner_share),
+        amount: format!("{:.2} €", owner_charge),
         total_cost: Some(format!("{:.2} €", total_cost)),
         per_person_amount: Some(format!("{:.2} €", per_person)),
         owner_share: Some(format!("{:.2} €", owner_share)),
+        discount: promo_quote
+            .as_ref()
+            .map(|quote| format!("{:.2} €", quote.discount)),
     }))
 }
 
 /// Create table reservation with split payment in a single transaction
-/// 1. Verifies the owner's PaymentIntent (for their share: total_cost / capacity)
+/// 1. Verifies the owner's PaymentIntent (for their part of the split)
 /// 2. Creates payment record + reservation with a single shared payment_link_token
-/// 3. Creates owner's payment share (paid) and owner ticket
+/// 3. Creates owner's payment share (paid), the split's named allocations and owner ticket
 /// Guests pay later via the shared link — no phone numbers required upfront
 pub async fn create_reservation_with_payment(
     State(state): State<Arc<AppState>>,
@@ -772,10 +976,9 @@ pub async fn create_reservation_with_payment(
         Err(_) => return Err((StatusCode::NOT_FOUND, "Tavolo non trovato".to_string())),
     };
 
+    ensure_table_not_held_for_other_user(&state, table_id, owner_user_id).await?;
+
     let total_cost = table.total_cost;
-    let capacity = table.capacity;
-    let per_person = (total_cost / Decimal::from(capacity)).round_dp(2);
-    let owner_share = total_cost - (per_person * Decimal::from(capacity - 1));
 
     // Verify owner's PaymentIntent with Stripe
     let pi_id: stripe::PaymentIntentId = req.stripe_payment_intent_id.parse().map_err(|_| {
@@ -788,6 +991,21 @@ pub async fn create_reservation_with_payment(
     // Run the rest; on any failure, cancel the Stripe authorization hold immediately.
     let result: Result<Json<CreateSplitReservationResponse>, (StatusCode, String)> = async {
 
+    // Checked again at booking time: the event's minimum age may have changed
+    event_service::ensure_user_meets_min_age(&state.db_pool, event_id, owner_user_id, None).await?;
+
+    let plan = plan_split(total_cost, table.capacity, req.split.as_ref()).map_err(split_plan_error)?;
+    let owner_share = plan.owner_amount;
+
+    let promo_quote = match req.promo_code.as_deref() {
+        Some(code) if !code.trim().is_empty() => Some(
+            quote_promo_for_table(&state, code, &table, Some(&req.contact_phone), owner_share).await?,
+        ),
+        _ => None,
+    };
+    let owner_charge = promo_quote.as_ref().map_or(owner_share, |quote| quote.final_amount);
+    let promo_discount = promo_quote.as_ref().map_or(Decimal::ZERO, |quote| quote.discount);
+
     let payment_intent = PaymentIntent::retrieve(&state.stripe_client, &pi_id, &[])
         .await
         .map_err(|e| {
@@ -804,7 +1022,7 @@ pub async fn create_reservation_with_payment(
     }
 
     // Verify amount matches owner's share in cents
-    let expected_cents = (owner_share.to_f64().unwrap_or(0.0) * 100.0) as i64;
+    let expected_cents = (owner_charge.to_f64().unwrap_or(0.0) * 100.0) as i64;
     if payment_intent.amount != expected_cents {
         tracing::error!(expected_cents = %expected_cents, actual = %payment_intent.amount, "PaymentIntent amount mismatch");
         return Err((StatusCode::BAD_REQUEST, "Importo del pagamento non corrispondente".to_string()));
@@ -837,7 +1055,7 @@ pub async fn create_reservation_with_payment(
     .bind(payment_id)
     .bind(owner_user_id)
     .bind(owner_user_id)
-    .bind(owner_share)
+    .bind(owner_charge)
     .bind(PaymentStatus::Pending)
     .bind(now)
     .bind(now)
@@ -854,31 +1072,32 @@ pub async fn create_reservation_with_payment(
         (StatusCode::INTERNAL_SERVER_ERROR, "Errore creazione pagamento".to_string())
     })?;
 
-    // Step 2: Create reservation — num_people starts at 1 (owner); guests increment as they pay
+    // Step 2: Create reservation — num_people starts at the owner's slots; guests increment as they pay
     let reservation_code = generate_alphanumeric_code("RES-");
     let reservation_id: Uuid = sqlx::query_scalar(
         r#"
         INSERT INTO table_reservations (
             table_id, user_id, event_id, num_people, total_amount, amount_paid,
             contact_name, contact_email, contact_phone, special_requests,
-            reservation_code, payment_link_token
+            reservation_code, payment_link_token, split_mode
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
         RETURNING id
         "#
     )
     .bind(table_id)
     .bind(owner_user_id)
     .bind(event_id)
-    .bind(1_i32)
-    .bind(total_cost)
-    .bind(owner_share)
+    .bind(plan.owner_slots)
+    .bind(total_cost - promo_discount)
+    .bind(owner_charge)
     .bind(&req.contact_name)
     .bind(&req.contact_email)
     .bind(&req.contact_phone)
     .bind(&req.special_requests)
     .bind(&reservation_code)
     .bind(&payment_link_token)
+    .bind(plan.mode.as_str())
     .fetch_one(&mut *tx)
     .await
     .map_err(|e| {
@@ -904,18 +1123,19 @@ pub async fn create_reservation_with_payment(
         r#"
         INSERT INTO reservation_payment_shares (
             reservation_id, user_id, phone_number, amount, status,
-            stripe_payment_intent_id, is_owner, payment_id
+            stripe_payment_intent_id, is_owner, payment_id, slots
         )
-        VALUES ($1, $2, $3, $4, 'paid', $5, true, $6)
+        VALUES ($1, $2, $3, $4, 'paid', $5, true, $6, $7)
         RETURNING *
         "#
     )
     .bind(reservation_id)
     .bind(owner_user_id)
     .bind(&req.contact_phone)
-    .bind(owner_share)
+    .bind(owner_charge)
     .bind(&req.stripe_payment_intent_id)
     .bind(payment_id)
+    .bind(plan.owner_slots)
     .fetch_one(&mut *tx)
     .await
     .map_err(|e| {
@@ -923,6 +1143,39 @@ pub async fn create_reservation_with_payment(
         (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
     })?;
 
+    // Named allocations of a custom or weighted split, paid later from the shared link
+    let mut payment_shares = vec![owner_share_row.clone()];
+    for allocation in &plan.allocations {
+        let share = table_persistence::create_planned_share(&mut *tx, reservation_id, allocation)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to create split allocation");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+            })?;
+        payment_shares.push(share);
+    }
+
+    // Consume the promo code use atomically with the booking
+    if let Some(quote) = promo_quote.as_ref() {
+        promo_code_persistence::redeem_promo_code(
+            &mut tx,
+            quote.promo.id,
+            reservation_id,
+            Some(owner_share_row.id),
+            Some(owner_user_id),
+            &req.contact_phone,
+            owner_share,
+            quote.discount,
+            "redeemed",
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to redeem promo code");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+        })?
+        .map_err(promo_rejection_error)?;
+    }
+
     // Step 5: Create owner's ticket
     let ticket_code = generate_alphanumeric_code("TKT-");
     let owner_ticket_id: Uuid = sqlx::query_scalar(
@@ -937,7 +1190,7 @@ pub async fn create_reservation_with_payment(
     .bind(owner_user_id)
     .bind(&ticket_code)
     .bind("table")
-    .bind(owner_share)
+    .bind(owner_charge)
     .bind("active")
     .fetch_one(&mut *tx)
     .await
@@ -973,6 +1226,10 @@ pub async fn create_reservation_with_payment(
             (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
         })?;
 
+    if let Err(e) = waitlist_persistence::mark_table_hold_fulfilled(&state.db_pool, table_id, owner_user_id).await {
+        tracing::warn!(error = %e, table_id = %table_id, "Failed to close waitlist hold");
+    }
+
     let app_base_url = state.config.app_base_url.clone();
     let share_link = format!("{}/pay/{}", app_base_url, payment_link_token);
 
@@ -998,6 +1255,11 @@ pub async fn create_reservation_with_payment(
             "event_id": event_id,
             "payment_id": payment_id,
             "owner_share": owner_share,
+            "split_mode": plan.mode.as_str(),
+            "owner_slots": plan.owner_slots,
+            "allocations": plan.allocations.len(),
+            "promo_code_id": promo_quote.as_ref().map(|quote| quote.promo.id),
+            "promo_discount": promo_discount,
             "share_link_present": true,
             "outcome": "success",
         }),
@@ -1005,7 +1267,7 @@ pub async fn create_reservation_with_payment(
 
     Ok(Json(CreateSplitReservationResponse {
         reservation: final_reservation.into(),
-        payment_shares: vec![owner_share_row.into()],
+        payment_shares: payment_shares.into_iter().map(Into::into).collect(),
         share_link,
     }))
 
@@ -1043,6 +1305,73 @@ pub async fn create_reservation_with_payment(
     result
 }
 
+/// Price a promo code against one share of `table`.
+async fn quote_promo_for_table(
+    state: &AppState,
+    code: &str,
+    table: &Table,
+    phone_number: Option<&str>,
+    amount: Decimal,
+) -> Result<PromoQuote, (StatusCode, String)> {
+    promo_code_persistence::quote_promo_code(
+        &state.db_pool,
+        code,
+        table.event_id,
+        table.area_id,
+        table.id,
+        phone_number,
+        amount,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to look up promo code");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Errore del database".to_string(),
+        )
+    })?
+    .map_err(promo_rejection_error)
+}
+
+fn split_plan_error(error: SplitPlanError) -> (StatusCode, String) {
+    tracing::info!(reason = error.as_str(), "Split plan rejected");
+    (StatusCode::BAD_REQUEST, error.message().to_string())
+}
+
+fn promo_rejection_error(rejection: PromoCodeRejection) -> (StatusCode, String) {
+    let status = match rejection {
+        PromoCodeRejection::Exhausted | PromoCodeRejection::AlreadyUsed => StatusCode::CONFLICT,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    tracing::info!(reason = rejection.as_str(), "Promo code rejected");
+    (status, rejection.message().to_string())
+}
+
+/// Reject the booking while the table is held for a different waitlisted user.
+async fn ensure_table_not_held_for_other_user(
+    state: &AppState,
+    table_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let hold = waitlist_persistence::get_active_table_hold(&state.db_pool, table_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to check waitlist hold");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore del database".to_string(),
+            )
+        })?;
+
+    match hold {
+        Some(entry) if entry.blocks(user_id, Utc::now()) => Err((
+            StatusCode::CONFLICT,
+            "Tavolo riservato a un utente in lista d'attesa".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
 fn generate_alphanumeric_code(prefix: &str) -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
@@ -1083,6 +1412,7 @@ fn generate_payment_link_token() -> String {
 pub async fn get_payment_link_preview(
     State(state): State<Arc<AppState>>,
     Path(token): Path<String>,
+    Query(params): Query<PaymentLinkPreviewParams>,
 ) -> Result<Json<PaymentLinkPreviewResponse>, StatusCode> {
     let reservation =
         table_persistence::get_reservation_by_payment_link_token(&state.db_pool, &token)
@@ -1093,28 +1423,72 @@ pub async fn get_payment_link_preview(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let event_name: String = sqlx::query_scalar("SELECT title FROM events WHERE id = $1")
-        .bind(reservation.event_id)
-        .fetch_one(&state.db_pool)
+    let (event_name, min_age): (String, Option<i32>) =
+        sqlx::query_as("SELECT title, min_age FROM events WHERE id = $1")
+            .bind(reservation.event_id)
+            .fetch_one(&state.db_pool)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Count slots already claimed (paid or in-flight checkout)
+    let split_mode = SplitMode::from_db(&reservation.split_mode);
+    let slot_counts = table_persistence::get_split_slot_counts(&state.db_pool, reservation.id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let slots_filled = slot_counts.claimed_slots;
+    let slots_total = slot_counts.guest_slots(split_mode, table.capacity);
 
-    // Count slots already claimed (paid or in-flight checkout)
-    let slots_filled: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM reservation_payment_shares WHERE reservation_id = $1 AND status IN ('paid', 'checkout_pending') AND is_owner = false"
-    )
-    .bind(reservation.id)
-    .fetch_one(&state.db_pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let allocations: Vec<_> = if split_mode.uses_allocations() {
+        table_persistence::get_payment_shares_by_reservation(&state.db_pool, reservation.id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .into_iter()
+            .filter(|share| !share.is_owner && share.planned_amount.is_some())
+            .collect()
+    } else {
+        Vec::new()
+    };
 
-    let slots_total = table.capacity.saturating_sub(1);
-    let per_person = (table.total_cost / Decimal::from(table.capacity)).round_dp(2);
-    let status = if slots_filled >= slots_total as i64 {
-        "full"
+    // Price what the guest would pay: the chosen allocation (the first open
+    // one by default), or the open slots they buy for themselves and friends
+    let per_person = if split_mode.uses_allocations() {
+        let allocation = match params.share_id {
+            Some(share_id) => allocations.iter().find(|share| share.id == share_id),
+            None => allocations.iter().find(|share| share.status == "reserved"),
+        };
+        allocation
+            .and_then(|share| share.planned_amount)
+            .unwrap_or_default()
     } else {
-        "open"
+        equal_slot_price(table.total_cost, table.capacity)
+            * Decimal::from(params.slots.unwrap_or(1).max(1))
     };
+    let (amount, discount, promo_code_error) = match params.promo_code.as_deref() {
+        Some(code) if !code.trim().is_empty() => {
+            match promo_code_persistence::quote_promo_code(
+                &state.db_pool,
+                code,
+                reservation.event_id,
+                table.area_id,
+                table.id,
+                None,
+                per_person,
+            )
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            {
+                Ok(quote) => (quote.final_amount, Some(quote.discount), None),
+                Err(rejection) => (per_person, None, Some(rejection.message().to_string())),
+            }
+        }
+        _ => (per_person, None, None),
+    };
+    let full = if split_mode.uses_allocations() {
+        !allocations.iter().any(|share| share.status == "reserved")
+    } else {
+        slots_filled >= slots_total
+    };
+    let status = if full { "full" } else { "open" };
 
     let _ = outbox_service::enqueue_analytics_event(
         &state.db_pool,
@@ -1128,6 +1502,7 @@ pub async fn get_payment_link_preview(
             "table_id": table.id,
             "slots_filled": slots_filled,
             "slots_total": slots_total,
+            "split_mode": split_mode.as_str(),
             "status": status,
             "outcome": "success",
         }),
@@ -1135,22 +1510,58 @@ pub async fn get_payment_link_preview(
     .await;
 
     Ok(Json(PaymentLinkPreviewResponse {
-        amount: format!("{:.2} €", per_person),
+        amount: format!("{:.2} €", amount),
         event_name,
         table_name: table.name,
         status: status.to_string(),
-        slots_filled: slots_filled as i32,
+        slots_filled,
         slots_total,
+        discount: discount.map(|discount| format!("{:.2} €", discount)),
+        promo_code_error,
+        min_age: min_age.filter(|age| *age > 0),
+        split_mode: split_mode.as_str().to_string(),
+        allocations: allocations.iter().map(Into::into).collect(),
     }))
 }
 
-/// POST /payment-links/:token/checkout — Guest claims a slot and starts Stripe Checkout
+async fn record_checkout_rejection(
+    state: &AppState,
+    token: &str,
+    reservation_id: Uuid,
+    reason: &str,
+) {
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "payment_link_checkout_rejected",
+        Some(token),
+        Some("reservation"),
+        Some(reservation_id),
+        serde_json::json!({
+            "reservation_id": reservation_id,
+            "reason": reason,
+            "outcome": "rejected",
+        }),
+    )
+    .await;
+}
+
+/// POST /payment-links/:token/checkout — Guest claims open slots, or pays a
+/// named allocation of the split, and starts Stripe Checkout
 /// Race-safe: uses SELECT FOR UPDATE + checkout_pending status to prevent double-booking
 pub async fn create_payment_link_checkout(
     State(state): State<Arc<AppState>>,
     Path(token): Path<String>,
+    headers: HeaderMap,
     Json(req): Json<CreateCheckoutRequest>,
 ) -> Result<Json<CreateCheckoutResponse>, (StatusCode, String)> {
+    // The receipt is written in the language of the guest's browser
+    let guest_locale = EmailLocale::from_accept_language(
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok()),
+    );
+
     // Begin transaction with row-level lock to prevent race conditions
     let mut tx = state.db_pool.begin().await.map_err(|e| {
         tracing::error!(error = %e, "Failed to begin transaction");
@@ -1183,8 +1594,8 @@ pub async fn create_payment_link_checkout(
     })?;
 
     // Fetch reservation and table for capacity/amount
-    let reservation_event_id: Uuid =
-        sqlx::query_scalar("SELECT event_id FROM table_reservations WHERE id = $1")
+    let (reservation_event_id, reservation_split_mode): (Uuid, String) =
+        sqlx::query_as("SELECT event_id, split_mode FROM table_reservations WHERE id = $1")
             .bind(reservation_id)
             .fetch_one(&mut *tx)
             .await
@@ -1192,6 +1603,22 @@ pub async fn create_payment_link_checkout(
                 tracing::error!(error = %e, "Failed to fetch reservation event_id");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
             })?;
+    let split_mode = SplitMode::from_db(&reservation_split_mode);
+
+    // Age-restricted events need the guest's birth date, checked on the event day
+    let (min_age, event_date) = event_service::get_event_min_age(&mut *tx, reservation_event_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to fetch event minimum age");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+        })?
+        .unwrap_or_default();
+    let event_day = event_date.unwrap_or_else(|| Utc::now().date_naive());
+    if let Err(rejection) = check_min_age(min_age, req.date_of_birth, event_day) {
+        let _ = tx.rollback().await;
+        record_checkout_rejection(&state, &token, reservation_id, rejection.as_str()).await;
+        return Err(event_service::age_rejection_error(rejection));
+    }
 
     let reservation_table_id: Uuid =
         sqlx::query_scalar("SELECT table_id FROM table_reservations WHERE id = $1")
@@ -1203,115 +1630,242 @@ pub async fn create_payment_link_checkout(
                 (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
             })?;
 
-    let (table_capacity, table_total_cost): (i32, rust_decimal::Decimal) =
-        sqlx::query_as("SELECT capacity, total_cost FROM tables WHERE id = $1")
-            .bind(reservation_table_id)
-            .fetch_one(&mut *tx)
+    let (table_capacity, table_total_cost, table_area_id): (
+        i32,
+        rust_decimal::Decimal,
+        Option<Uuid>,
+    ) = sqlx::query_as("SELECT capacity, total_cost, area_id FROM tables WHERE id = $1")
+        .bind(reservation_table_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to fetch table");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+        })?;
+
+    // A custom or weighted split is paid one named allocation at a time —
+    // the guest's own or a friend's. An equal split sells open slots, several
+    // at once when the guest pays for friends too.
+    let allocation = if split_mode.uses_allocations() {
+        let Some(share_id) = req.share_id else {
+            let _ = tx.rollback().await;
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Scegli la quota da pagare".to_string(),
+            ));
+        };
+        let share = table_persistence::lock_reserved_share(&mut *tx, reservation_id, share_id)
             .await
             .map_err(|e| {
-                tracing::error!(error = %e, "Failed to fetch table");
+                tracing::error!(error = %e, "Failed to lock split allocation");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
             })?;
+        let Some(share) = share else {
+            let _ = tx.rollback().await;
+            record_checkout_rejection(&state, &token, reservation_id, "allocation_taken").await;
+            return Err((
+                StatusCode::CONFLICT,
+                "Questa quota è già stata pagata".to_string(),
+            ));
+        };
+        Some(share)
+    } else {
+        None
+    };
+    let share_slots = allocation
+        .as_ref()
+        .map_or(req.slots.unwrap_or(1), |share| share.slots);
 
-    // Count current non-owner slots already taken (paid or in-flight)
-    let slots_filled: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM reservation_payment_shares WHERE reservation_id = $1 AND status IN ('paid', 'checkout_pending') AND is_owner = false"
-    )
-    .bind(reservation_id)
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, "Failed to count slots");
-        (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
-    })?;
+    if allocation.is_none() {
+        if share_slots < 1 {
+            let _ = tx.rollback().await;
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Numero di posti non valido".to_string(),
+            ));
+        }
 
-    let guest_slots = (table_capacity - 1) as i64; // capacity minus owner
-    if slots_filled >= guest_slots {
-        let _ = tx.rollback().await;
-        let _ = outbox_service::enqueue_analytics_event(
-            &state.db_pool,
-            &state.config,
-            "payment_link_checkout_rejected",
-            Some(&token),
-            Some("reservation"),
-            Some(reservation_id),
-            serde_json::json!({
-                "reservation_id": reservation_id,
-                "reason": "table_full",
-                "outcome": "rejected",
-            }),
+        // Count current non-owner slots already taken (paid or in-flight)
+        let slot_counts = table_persistence::get_split_slot_counts(&mut *tx, reservation_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to count slots");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+            })?;
+
+        // Slots held for waitlisted users count as taken, except for the holder
+        let slots_held =
+            waitlist_persistence::count_other_slot_holds(&mut *tx, reservation_id, &req.phone)
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Failed to count waitlist slot holds");
+                    (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+                })?;
+
+        let guest_slots = i64::from(slot_counts.guest_slots(split_mode, table_capacity));
+        if i64::from(slot_counts.claimed_slots) + slots_held + i64::from(share_slots) > guest_slots
+        {
+            let _ = tx.rollback().await;
+            record_checkout_rejection(&state, &token, reservation_id, "table_full").await;
+            return Err((StatusCode::CONFLICT, "Tavolo al completo".to_string()));
+        }
+
+        // Prevent the same phone number from paying twice for the same reservation
+        let already_exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(
+                SELECT 1 FROM reservation_payment_shares
+                WHERE reservation_id = $1
+                  AND phone_number = $2
+                  AND is_owner = false
+                  AND status IN ('paid', 'checkout_pending')
+            )",
         )
-        .await;
-        return Err((StatusCode::CONFLICT, "Tavolo al completo".to_string()));
+        .bind(reservation_id)
+        .bind(&req.phone)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to check duplicate phone on reservation");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore del database".to_string(),
+            )
+        })?;
+
+        if already_exists {
+            let _ = tx.rollback().await;
+            record_checkout_rejection(&state, &token, reservation_id, "duplicate_phone").await;
+            return Err((
+                StatusCode::CONFLICT,
+                "Hai già pagato per questo tavolo".to_string(),
+            ));
+        }
     }
 
-    // Prevent the same phone number from paying twice for the same reservation
-    let already_exists: bool = sqlx::query_scalar(
-        "SELECT EXISTS(
-            SELECT 1 FROM reservation_payment_shares
-            WHERE reservation_id = $1
-              AND phone_number = $2
-              AND is_owner = false
-              AND status IN ('paid', 'checkout_pending')
-        )",
-    )
-    .bind(reservation_id)
-    .bind(&req.phone)
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, "Failed to check duplicate phone on reservation");
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Errore del database".to_string(),
-        )
-    })?;
+    let base_amount = match allocation.as_ref() {
+        Some(share) => share.planned_amount.unwrap_or(share.amount),
+        None => equal_slot_price(table_total_cost, table_capacity) * Decimal::from(share_slots),
+    };
 
-    if already_exists {
-        let _ = tx.rollback().await;
-        let _ = outbox_service::enqueue_analytics_event(
-            &state.db_pool,
-            &state.config,
-            "payment_link_checkout_rejected",
-            Some(&token),
-            Some("reservation"),
-            Some(reservation_id),
-            serde_json::json!({
-                "reservation_id": reservation_id,
-                "reason": "duplicate_phone",
-                "outcome": "rejected",
-            }),
+    let promo_quote = match req.promo_code.as_deref() {
+        Some(code) if !code.trim().is_empty() => {
+            let quote = promo_code_persistence::quote_promo_code(
+                &state.db_pool,
+                code,
+                reservation_event_id,
+                table_area_id,
+                reservation_table_id,
+                Some(&req.phone),
+                base_amount,
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to look up promo code");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+            })?;
+            match quote {
+                Ok(quote) => Some(quote),
+                Err(rejection) => {
+                    let _ = tx.rollback().await;
+                    return Err(promo_rejection_error(rejection));
+                }
+            }
+        }
+        _ => None,
+    };
+    let guest_charge = promo_quote
+        .as_ref()
+        .map_or(base_amount, |quote| quote.final_amount);
+
+    // Hold the slot: the allocation, or a new guest share, is checkout_pending
+    let share_id: Uuid = match allocation.as_ref() {
+        Some(share) => {
+            table_persistence::start_reserved_share_checkout(
+                &mut *tx,
+                share.id,
+                guest_charge,
+                &req.phone,
+                req.email.as_deref(),
+                guest_locale.as_str(),
+                req.date_of_birth,
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to start split allocation checkout");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+            })?;
+            share.id
+        }
+        None => sqlx::query_scalar(
+            r#"
+            INSERT INTO reservation_payment_shares (
+                reservation_id, phone_number, guest_name, guest_email, guest_locale, amount, status,
+                is_owner, guest_date_of_birth, slots, checkout_started_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, 'checkout_pending', false, $7, $8, NOW())
+            RETURNING id
+            "#,
         )
-        .await;
-        return Err((
-            StatusCode::CONFLICT,
-            "Hai già pagato per questo tavolo".to_string(),
-        ));
-    }
+        .bind(reservation_id)
+        .bind(&req.phone)
+        .bind(&req.name)
+        .bind(&req.email)
+        .bind(guest_locale.as_str())
+        .bind(guest_charge)
+        .bind(req.date_of_birth)
+        .bind(share_slots)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to create guest payment share");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+        })?,
+    };
 
-    let per_person = (table_total_cost / Decimal::from(table_capacity)).round_dp(2);
+    // The use stays pending until the guest pays; an expired share gives it back.
+    // The discount comes off the reservation total so it still confirms once
+    // every share is paid.
+    if let Some(quote) = promo_quote.as_ref() {
+        let redemption = promo_code_persistence::redeem_promo_code(
+            &mut tx,
+            quote.promo.id,
+            reservation_id,
+            Some(share_id),
+            None,
+            &req.phone,
+            base_amount,
+            quote.discount,
+            "pending",
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to redeem promo code");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+        })?;
+        if let Err(rejection) = redemption {
+            let _ = tx.rollback().await;
+            return Err(promo_rejection_error(rejection));
+        }
 
-    // Insert guest share as checkout_pending to hold the slot
-    let share_id: Uuid = sqlx::query_scalar(
-        r#"
-        INSERT INTO reservation_payment_shares (
-            reservation_id, phone_number, guest_name, guest_email, amount, status, is_owner
+        sqlx::query(
+            "UPDATE table_reservations SET total_amount = total_amount - $1, updated_at = NOW() WHERE id = $2",
         )
-        VALUES ($1, $2, $3, $4, $5, 'checkout_pending', false)
-        RETURNING id
-        "#,
-    )
-    .bind(reservation_id)
-    .bind(&req.phone)
-    .bind(&req.name)
-    .bind(&req.email)
-    .bind(per_person)
-    .fetch_one(&mut *tx)
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, "Failed to create guest payment share");
-        (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
-    })?;
+        .bind(quote.discount)
+        .bind(reservation_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to apply promo discount to reservation");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+        })?;
+    }
+
+    waitlist_persistence::mark_slot_hold_fulfilled(&mut *tx, reservation_id, &req.phone)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to close waitlist slot hold");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+        })?;
 
     tx.commit().await.map_err(|e| {
         tracing::error!(error = %e, "Failed to commit transaction");
@@ -1328,8 +1882,8 @@ pub async fn create_payment_link_checkout(
         .await
         .unwrap_or_else(|_| "Evento".to_string());
 
-    let amount_in_cents = (per_person.to_f64().unwrap_or(0.0) * 100.0) as i64;
-    let club_connect_config = get_club_connect_config_for_event(&state.db_pool, reservation_event_id)
+    let amount_in_cents = (guest_charge.to_f64().unwrap_or(0.0) * 100.0) as i64;
+    let club_connect_config = payment_service::get_club_connect_config_for_event(&state.db_pool, reservation_event_id)
         .await
         .map_err(|e| {
             tracing::error!(error = %e, event_id = %reservation_event_id, "Failed to load club Connect config");
@@ -1345,7 +1899,11 @@ pub async fn create_payment_link_checkout(
         price_data: Some(stripe::CreateCheckoutSessionLineItemsPriceData {
             currency: Currency::EUR,
             product_data: Some(stripe::CreateCheckoutSessionLineItemsPriceDataProductData {
-                name: format!("Tavolo - {}", event_name),
+                name: if share_slots > 1 {
+                    format!("Tavolo - {} ({} posti)", event_name, share_slots)
+                } else {
+                    format!("Tavolo - {}", event_name)
+                },
                 ..Default::default()
             }),
             unit_amount: Some(amount_in_cents),
@@ -1379,14 +1937,14 @@ pub async fn create_payment_link_checkout(
 
     if let Some(config) = club_connect_config
         .as_ref()
-        .filter(|cfg| can_route_funds_to_connected_account(cfg))
+        .filter(|cfg| cfg.can_route_funds())
     {
         let destination = config
             .stripe_connected_account_id
             .clone()
             .unwrap_or_default();
-        let application_fee_amount = compute_application_fee_cents(
-            per_person,
+        let application_fee_amount = payment_service::compute_application_fee_cents(
+            guest_charge,
             config.platform_commission_percent,
             config.platform_commission_fixed_fee,
         );
@@ -1420,13 +1978,21 @@ pub async fn create_payment_link_checkout(
             )
         })?;
 
-    // Store checkout session ID on the share
+    // Store checkout session ID on the share. A named allocation keeps the
+    // name the organizer gave it, whoever pays it.
+    let (share_name, share_email) = match allocation {
+        Some(share) => (
+            share.guest_name.or_else(|| Some(req.name.clone())),
+            req.email.clone().or(share.guest_email),
+        ),
+        None => (Some(req.name.clone()), req.email.clone()),
+    };
     table_persistence::set_payment_share_checkout_session(
         &state.db_pool,
         share_id,
         &session.id.to_string(),
-        Some(req.name.clone()),
-        req.email.clone(),
+        share_name,
+        share_email,
     )
     .await
     .map_err(|e| {
@@ -1454,6 +2020,9 @@ pub async fn create_payment_link_checkout(
             "reservation_id": reservation_id,
             "share_id": share_id,
             "event_id": reservation_event_id,
+            "split_mode": split_mode.as_str(),
+            "slots": share_slots,
+            "promo_code_id": promo_quote.as_ref().map(|quote| quote.promo.id),
             "outcome": "success",
         }),
     )
@@ -1486,11 +2055,12 @@ pub async fn get_reservation_payment_status(
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let slots_filled = shares
-        .iter()
-        .filter(|s| !s.is_owner && (s.status == "paid" || s.status == "checkout_pending"))
-        .count() as i32;
-    let slots_total = table.capacity.saturating_sub(1);
+    let split_mode = SplitMode::from_db(&reservation.split_mode);
+    let slot_counts = table_persistence::get_split_slot_counts(&state.db_pool, reservation_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let slots_filled = slot_counts.claimed_slots;
+    let slots_total = slot_counts.guest_slots(split_mode, table.capacity);
 
     let amount_remaining = reservation.total_amount - reservation.amount_paid;
 
@@ -1526,6 +2096,7 @@ pub async fn get_reservation_payment_status(
         share_link,
         slots_filled,
         slots_total,
+        split_mode: split_mode.as_str().to_string(),
     }))
 }
 
@@ -1563,8 +2134,8 @@ pub async fn guest_payment_page(
     .value{{font-weight:600;font-size:15px}}
     .amount{{color:#ec4899;font-size:20px;font-weight:700}}
     label{{display:block;font-size:13px;color:#9ca3af;margin-bottom:6px}}
-    input{{width:100%;background:#262626;border:1px solid #333;border-radius:8px;padding:12px;color:#fff;font-size:15px;margin-bottom:12px;outline:none}}
-    input:focus{{border-color:#ec4899}}
+    input,select{{width:100%;background:#262626;border:1px solid #333;border-radius:8px;padding:12px;color:#fff;font-size:15px;margin-bottom:12px;outline:none}}
+    input:focus,select:focus{{border-color:#ec4899}}
     button{{width:100%;background:#ec4899;color:#fff;border:none;border-radius:10px;padding:14px;font-size:16px;font-weight:600;cursor:pointer;margin-top:4px}}
     button:disabled{{opacity:.5;cursor:not-allowed}}
     .error{{color:#f87171;font-size:13px;margin-top:8px;display:none}}
@@ -1616,12 +2187,24 @@ pub async fn guest_payment_page(
 
     <!-- Checkout form -->
     <div id="checkout-form">
+      <div id="share-field" style="display:none">
+        <label>Quota da pagare (la tua o di un amico)</label>
+        <select id="share-select" onchange="fetchPreview(true)"></select>
+      </div>
+      <div id="slots-field" style="display:none">
+        <label>Posti da pagare (tu e i tuoi amici)</label>
+        <input id="slots-input" type="number" min="1" value="1" onchange="fetchPreview(true)"/>
+      </div>
       <label>Nome *</label>
       <input id="name-input" type="text" placeholder="Il tuo nome" required/>
       <label>Telefono *</label>
       <input id="phone-input" type="tel" placeholder="+39 333 123 4567" required/>
       <label>Email per ricevuta (opzionale)</label>
       <input id="checkout-email" type="email" placeholder="La tua email"/>
+      <div id="dob-field" style="display:none">
+        <label id="dob-label">Data di nascita *</label>
+        <input id="dob-input" type="date"/>
+      </div>
       <button id="pay-btn" onclick="doPay()">Paga ora</button>
       <div class="error" id="pay-error"></div>
     </div>
@@ -1646,10 +2229,20 @@ pub async fn guest_payment_page(
 
   fetchPreview();
 
-  async function fetchPreview() {{
-    if (document.getElementById('preview').style.display === 'block') return;
+  function selection() {{
+    const shareField = document.getElementById('share-field');
+    if (shareField.style.display === 'block') {{
+      return {{ share_id: document.getElementById('share-select').value || null }};
+    }}
+    return {{ slots: parseInt(document.getElementById('slots-input').value, 10) || 1 }};
+  }}
+
+  async function fetchPreview(refresh) {{
+    if (!refresh && document.getElementById('preview').style.display === 'block') return;
     try {{
-      const res = await fetch(API + "/payment-links/" + TOKEN);
+      const sel = refresh ? selection() : {{}};
+      const query = sel.share_id ? "?share_id=" + sel.share_id : (sel.slots ? "?slots=" + sel.slots : "");
+      const res = await fetch(API + "/payment-links/" + TOKEN + query);
       if (!res.ok) {{ showError("Link non valido o scaduto."); return; }}
       const data = await res.json();
 
@@ -1663,6 +2256,29 @@ pub async fn guest_payment_page(
       const st = data.slotsTotal ?? data.slots_total ?? 0;
       document.getElementById('slots').textContent = sf + '/' + st + ' occupati';
 
+      const allocations = data.allocations || [];
+      if ((data.splitMode || 'equal') !== 'equal') {{
+        const select = document.getElementById('share-select');
+        if (!refresh) {{
+          select.innerHTML = '';
+          allocations.filter(function(a) {{ return a.status === 'open'; }}).forEach(function(a) {{
+            const option = document.createElement('option');
+            option.value = a.shareId;
+            option.textContent = (a.guestName || 'Ospite') + ' · ' + a.amount + (a.slots > 1 ? ' (' + a.slots + ' posti)' : '');
+            select.appendChild(option);
+          }});
+        }}
+        document.getElementById('share-field').style.display = 'block';
+      }} else if (st - sf > 1) {{
+        document.getElementById('slots-input').max = st - sf;
+        document.getElementById('slots-field').style.display = 'block';
+      }}
+
+      if (data.minAge) {{
+        document.getElementById('dob-label').textContent = 'Data di nascita * (evento ' + data.minAge + '+)';
+        document.getElementById('dob-field').style.display = 'block';
+      }}
+
       if ((data.status || '') === 'full') {{
         document.getElementById('full-msg').style.display = 'block';
         document.getElementById('checkout-form').style.display = 'none';
@@ -1678,19 +2294,27 @@ pub async fn guest_payment_page(
     const email = document.getElementById('checkout-email').value.trim();
     if (!name) {{ showPayError("Il nome è obbligatorio."); return; }}
     if (!phone) {{ showPayError("Il numero di telefono è obbligatorio."); return; }}
+    const dobRequired = document.getElementById('dob-field').style.display === 'block';
+    const dob = document.getElementById('dob-input').value;
+    if (dobRequired && !dob) {{ showPayError("La data di nascita è obbligatoria."); return; }}
     const btn = document.getElementById('pay-btn');
     btn.disabled = true; btn.textContent = "Reindirizzamento a Stripe...";
     try {{
       const res = await fetch(API + "/payment-links/" + TOKEN + "/checkout", {{
         method: "POST",
         headers: {{"Content-Type": "application/json"}},
-        body: JSON.stringify({{ name, phone, email: email || null }})
+        body: JSON.stringify(Object.assign({{ name, phone, email: email || null, date_of_birth: dob || null }}, selection()))
       }});
       if (res.status === 409) {{
         document.getElementById('checkout-form').style.display = 'none';
         document.getElementById('full-msg').style.display = 'block';
         return;
       }}
+      if (res.status === 403 || res.status === 422) {{
+        showPayError(await res.text());
+        btn.disabled = false; btn.textContent = "Paga ora";
+        return;
+      }}
       if (!res.ok) {{
         showPayError("Impossibile avviare il pagamento. Riprova.");
         btn.disabled = false; btn.textContent = "Paga ora";
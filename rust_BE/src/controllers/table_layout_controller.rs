@@ -0,0 +1,286 @@
+use crate::application::{
+    area_service as area_persistence, club_service as club_persistence,
+    event_service as event_persistence, outbox_service, reservation_service as table_persistence,
+    table_layout_service as table_layout_persistence,
+};
+use crate::middleware::auth::{ClubStaffUser, ManageClub};
+use crate::models::{
+    plan_table_layout, AppState, ApplyTableLayoutRequest, Claims, Club,
+    CreateTableLayoutTemplateRequest, TableLayoutTemplate, TableLayoutTemplateResponse,
+    TableResponse, TablesResponse, UpdateTableLayoutTemplateRequest,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+async fn my_club(state: &AppState, claims: &Claims) -> Result<Club, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn owned_template(
+    state: &AppState,
+    club: &Club,
+    template_id: &str,
+) -> Result<TableLayoutTemplate, StatusCode> {
+    let template_uuid = Uuid::parse_str(template_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let template = table_layout_persistence::get_template_by_id(&state.db_pool, template_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if template.club_id != club.id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(template)
+}
+
+async fn template_response(
+    state: &AppState,
+    template: TableLayoutTemplate,
+) -> Result<TableLayoutTemplateResponse, StatusCode> {
+    let details = table_layout_persistence::get_template_details(&state.db_pool, template)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(TableLayoutTemplateResponse::from(details))
+}
+
+fn map_template_write_error(error: sqlx::Error, template_id: Option<Uuid>) -> StatusCode {
+    match error {
+        sqlx::Error::Database(db_error) if db_error.is_unique_violation() => StatusCode::CONFLICT,
+        // A table id that is not part of the template
+        sqlx::Error::RowNotFound => StatusCode::BAD_REQUEST,
+        error => {
+            error!(error = %error, template_id = ?template_id, "Failed to save table layout template");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// List the club's table layout templates
+pub async fn get_my_table_layouts(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+) -> Result<Json<Vec<TableLayoutTemplateResponse>>, StatusCode> {
+    let club = my_club(&state, &claims).await?;
+
+    let templates = table_layout_persistence::get_templates_by_club(&state.db_pool, club.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut responses = Vec::with_capacity(templates.len());
+    for template in templates {
+        responses.push(template_response(&state, template).await?);
+    }
+    Ok(Json(responses))
+}
+
+/// Save the tables of one of the club's events as a layout template
+pub async fn create_my_table_layout(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Json(payload): Json<CreateTableLayoutTemplateRequest>,
+) -> Result<(StatusCode, Json<TableLayoutTemplateResponse>), StatusCode> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let club = my_club(&state, &claims).await?;
+    let event = event_persistence::get_event_by_id(&state.db_pool, payload.event_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if event.club_id != Some(club.id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let template = table_layout_persistence::create_template_from_event(
+        &state.db_pool,
+        club.id,
+        event.id,
+        name,
+        payload.description.as_deref(),
+    )
+    .await
+    .map_err(|e| map_template_write_error(e, None))?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_table_layout_created",
+        Some(&claims.sub),
+        Some("table_layout_template"),
+        Some(template.id),
+        serde_json::json!({
+            "club_id": club.id,
+            "template_id": template.id,
+            "source_event_id": event.id,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    let response = template_response(&state, template).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// A single layout template with its tables and images
+pub async fn get_my_table_layout(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(template_id): Path<String>,
+) -> Result<Json<TableLayoutTemplateResponse>, StatusCode> {
+    let club = my_club(&state, &claims).await?;
+    let template = owned_template(&state, &club, &template_id).await?;
+    Ok(Json(template_response(&state, template).await?))
+}
+
+/// Rename a template or replace its tables
+pub async fn update_my_table_layout(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(template_id): Path<String>,
+    Json(mut payload): Json<UpdateTableLayoutTemplateRequest>,
+) -> Result<Json<TableLayoutTemplateResponse>, StatusCode> {
+    if let Some(name) = payload.name.as_mut() {
+        *name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let club = my_club(&state, &claims).await?;
+    let existing = owned_template(&state, &club, &template_id).await?;
+
+    if let Some(tables) = &payload.tables {
+        let areas = area_persistence::get_areas_by_club(&state.db_pool, club.id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let invalid = tables.iter().any(|table| {
+            table.name.trim().is_empty()
+                || table.capacity <= 0
+                || !table.min_spend.is_finite()
+                || table.min_spend < 0.0
+                || table
+                    .area_id
+                    .is_some_and(|area_id| !areas.iter().any(|area| area.id == area_id))
+        });
+        if invalid {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let template = table_layout_persistence::update_template(&state.db_pool, existing.id, &payload)
+        .await
+        .map_err(|e| map_template_write_error(e, Some(existing.id)))?;
+
+    Ok(Json(template_response(&state, template).await?))
+}
+
+/// Delete a layout template. Events it was applied to keep their tables.
+pub async fn delete_my_table_layout(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(template_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    let club = my_club(&state, &claims).await?;
+    let template = owned_template(&state, &club, &template_id).await?;
+
+    let deleted = table_layout_persistence::delete_template(&state.db_pool, template.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+/// Create an event's tables from a layout template in one transaction, with
+/// optional per-table price and availability overrides
+pub async fn apply_table_layout_to_event(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(event_id): Path<String>,
+    Json(payload): Json<ApplyTableLayoutRequest>,
+) -> Result<(StatusCode, Json<TablesResponse>), (StatusCode, String)> {
+    let event_uuid = Uuid::parse_str(&event_id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Evento non valido".to_string()))?;
+
+    let club = my_club(&state, &claims)
+        .await
+        .map_err(|status| (status, "Club non trovato".to_string()))?;
+    let event = event_persistence::get_event_by_id(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, String::new()))?
+        .ok_or((StatusCode::NOT_FOUND, "Evento non trovato".to_string()))?;
+    if event.club_id != Some(club.id) {
+        return Err((StatusCode::FORBIDDEN, String::new()));
+    }
+
+    let template = owned_template(&state, &club, &payload.template_id.to_string())
+        .await
+        .map_err(|status| (status, "Layout non trovato".to_string()))?;
+    let details = table_layout_persistence::get_template_details(&state.db_pool, template)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, String::new()))?;
+
+    let planned = plan_table_layout(&details.tables, &payload.overrides).map_err(|rejection| {
+        info!(event_id = %event.id, template_id = %details.template.id, ?rejection, "Table layout apply rejected");
+        (StatusCode::BAD_REQUEST, rejection.message())
+    })?;
+
+    let created = table_layout_persistence::apply_template_to_event(
+        &state.db_pool,
+        &details,
+        &planned,
+        event.id,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, event_id = %event.id, template_id = %details.template.id, "Failed to apply table layout");
+        (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+    })?
+    .ok_or((
+        StatusCode::CONFLICT,
+        "L'evento ha già dei tavoli".to_string(),
+    ))?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_table_layout_applied",
+        Some(&claims.sub),
+        Some("event"),
+        Some(event.id),
+        serde_json::json!({
+            "club_id": club.id,
+            "event_id": event.id,
+            "template_id": details.template.id,
+            "tables_created": created,
+            "overrides": payload.overrides.len(),
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    let tables = table_persistence::get_tables_by_event_id(&state.db_pool, event.id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, String::new()))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(TablesResponse {
+            tables: tables.into_iter().map(TableResponse::from).collect(),
+        }),
+    ))
+}
@@ -0,0 +1,481 @@
+use crate::application::table_tab_service::{validate_tab_line, TAB_RESERVATION_STATUSES};
+use crate::application::{
+    club_service as club_persistence, event_service as event_persistence, outbox_service,
+    payment_service, table_tab_service as table_tab_persistence,
+};
+use crate::middleware::auth::{ClubStaffUser, ManageReservations};
+use crate::models::table_tab::{
+    TabLineResponse, TabSettlement, TabSettlementResponse, TAB_CHECKOUT_MINUTES,
+};
+use crate::models::{
+    AddTabLineRequest, AppState, Claims, SettleTabRequest, SettleTabResponse, TableTab,
+    TableTabResponse,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use stripe::{
+    CreateCheckoutSessionPaymentIntentData, CreateCheckoutSessionPaymentIntentDataTransferData,
+    Currency,
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Id of the caller's club.
+async fn owned_club_id(state: &AppState, claims: &Claims) -> Result<Uuid, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(club.id)
+}
+
+/// Load a tab of the caller's club (403 for other clubs' tabs).
+async fn owned_tab(
+    state: &AppState,
+    claims: &Claims,
+    tab_id: &str,
+) -> Result<TableTab, StatusCode> {
+    let tab_id = Uuid::parse_str(tab_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let club_id = owned_club_id(state, claims).await?;
+    let tab = table_tab_persistence::get_tab(&state.db_pool, tab_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if tab.club_id != club_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(tab)
+}
+
+/// The tab with its lines and settlements.
+async fn tab_details(state: &AppState, tab: TableTab) -> Result<TableTabResponse, StatusCode> {
+    let lines = table_tab_persistence::list_tab_lines(&state.db_pool, tab.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let settlements = table_tab_persistence::list_tab_settlements(&state.db_pool, tab.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut response = TableTabResponse::from(tab);
+    response.lines = Some(lines.into_iter().map(TabLineResponse::from).collect());
+    response.settlements = Some(
+        settlements
+            .into_iter()
+            .map(TabSettlementResponse::from)
+            .collect(),
+    );
+    Ok(response)
+}
+
+async fn reload_tab(state: &AppState, tab_id: Uuid) -> Result<TableTabResponse, StatusCode> {
+    let tab = table_tab_persistence::get_tab(&state.db_pool, tab_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    tab_details(state, tab).await
+}
+
+async fn track_tab_closed(state: &AppState, claims: &Claims, tab_id: Uuid) {
+    if let Ok(Some(tab)) = table_tab_persistence::get_tab(&state.db_pool, tab_id).await {
+        let _ = outbox_service::enqueue_analytics_event(
+            &state.db_pool,
+            &state.config,
+            "table_tab_closed",
+            Some(&claims.sub),
+            Some("table_tab"),
+            Some(tab.id),
+            serde_json::json!({
+                "tab_id": tab.id,
+                "reservation_id": tab.reservation_id,
+                "event_id": tab.event_id,
+                "table_id": tab.table_id,
+                "minimum_spend": tab.minimum_spend,
+                "consumed": tab.consumed,
+                "final_spend": tab.final_spend,
+            }),
+        )
+        .await;
+    }
+}
+
+/// POST /owner/reservations/:id/tab
+///
+/// Open a tab on a checked-in reservation, at its table's minimum spend.
+pub async fn open_tab_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(reservation_id): Path<String>,
+) -> Result<(StatusCode, Json<TableTabResponse>), StatusCode> {
+    let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let actor_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let club_id = owned_club_id(&state, &claims).await?;
+
+    let reservation = table_tab_persistence::get_tab_reservation(&state.db_pool, reservation_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if reservation.club_id != Some(club_id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if !TAB_RESERVATION_STATUSES.contains(&reservation.status.as_str())
+        || reservation.checked_in_count == 0
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let tab_id = table_tab_persistence::open_tab(&state.db_pool, reservation.id, club_id, actor_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, reservation_id = %reservation.id, "Failed to open table tab");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::CONFLICT)?;
+    let tab = reload_tab(&state, tab_id).await?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "table_tab_opened",
+        Some(&claims.sub),
+        Some("table_tab"),
+        Some(tab_id),
+        serde_json::json!({
+            "tab_id": tab_id,
+            "reservation_id": reservation.id,
+            "club_id": club_id,
+            "minimum_spend": tab.totals.minimum_spend,
+            "prepaid": tab.totals.prepaid,
+        }),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(tab)))
+}
+
+/// GET /owner/reservations/:id/tab
+pub async fn get_reservation_tab_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(reservation_id): Path<String>,
+) -> Result<Json<TableTabResponse>, StatusCode> {
+    let reservation_uuid = Uuid::parse_str(&reservation_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let club_id = owned_club_id(&state, &claims).await?;
+
+    let tab = table_tab_persistence::get_tab_by_reservation(&state.db_pool, reservation_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if tab.club_id != club_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(Json(tab_details(&state, tab).await?))
+}
+
+/// GET /owner/events/:event_id/tabs
+pub async fn get_event_tabs_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(event_id): Path<String>,
+) -> Result<Json<Vec<TableTabResponse>>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let club_id = owned_club_id(&state, &claims).await?;
+
+    let tabs = table_tab_persistence::list_event_tabs(&state.db_pool, club_id, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(tabs.into_iter().map(TableTabResponse::from).collect()))
+}
+
+/// POST /owner/tabs/:id/lines
+pub async fn add_tab_line_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(tab_id): Path<String>,
+    Json(request): Json<AddTabLineRequest>,
+) -> Result<(StatusCode, Json<TableTabResponse>), StatusCode> {
+    let actor_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let quantity = validate_tab_line(&request).ok_or(StatusCode::BAD_REQUEST)?;
+    let tab = owned_tab(&state, &claims, &tab_id).await?;
+
+    table_tab_persistence::add_tab_line(&state.db_pool, tab.id, &request, quantity, actor_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, tab_id = %tab.id, "Failed to add tab line");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        // Settling or closed
+        .ok_or(StatusCode::CONFLICT)?;
+
+    Ok((StatusCode::CREATED, Json(reload_tab(&state, tab.id).await?)))
+}
+
+/// DELETE /owner/tabs/:id/lines/:line_id
+pub async fn void_tab_line_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path((tab_id, line_id)): Path<(String, String)>,
+) -> Result<Json<TableTabResponse>, StatusCode> {
+    let actor_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let line_uuid = Uuid::parse_str(&line_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let tab = owned_tab(&state, &claims, &tab_id).await?;
+    if tab.status != "open" {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let voided = table_tab_persistence::void_tab_line(&state.db_pool, tab.id, line_uuid, actor_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !voided {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(reload_tab(&state, tab.id).await?))
+}
+
+/// POST /owner/tabs/:id/settle
+///
+/// Settle what is left at close: `cash` is recorded straight away, `stripe`
+/// opens a Checkout page for the guest and holds the tab until it ends. With
+/// nothing due the tab is simply closed.
+pub async fn settle_tab_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(tab_id): Path<String>,
+    Json(request): Json<SettleTabRequest>,
+) -> Result<Json<SettleTabResponse>, StatusCode> {
+    let actor_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if !matches!(request.method.as_str(), "cash" | "stripe") {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let tab = owned_tab(&state, &claims, &tab_id).await?;
+    if tab.status != "open" {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let balance_due = tab.totals().balance_due;
+    if balance_due == Decimal::ZERO {
+        let closed = table_tab_persistence::close_tab(&state.db_pool, tab.id, actor_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if !closed {
+            return Err(StatusCode::CONFLICT);
+        }
+        track_tab_closed(&state, &claims, tab.id).await;
+        return Ok(Json(SettleTabResponse {
+            tab: reload_tab(&state, tab.id).await?,
+            checkout_url: None,
+        }));
+    }
+
+    let amount = request.amount.unwrap_or(balance_due).round_dp(2);
+    if amount <= Decimal::ZERO || amount > balance_due {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if request.method == "cash" {
+        table_tab_persistence::record_cash_settlement(&state.db_pool, tab.id, amount, actor_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, tab_id = %tab.id, "Failed to record cash settlement");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::CONFLICT)?;
+        let response = reload_tab(&state, tab.id).await?;
+        if response.status == "closed" {
+            track_tab_closed(&state, &claims, tab.id).await;
+        }
+        return Ok(Json(SettleTabResponse {
+            tab: response,
+            checkout_url: None,
+        }));
+    }
+
+    let settlement =
+        table_tab_persistence::start_stripe_settlement(&state.db_pool, tab.id, amount, actor_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, tab_id = %tab.id, "Failed to start Stripe settlement");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::CONFLICT)?;
+
+    let checkout_url = match create_settlement_checkout(&state, &tab, &settlement).await {
+        Ok(url) => url,
+        Err(status) => {
+            if let Err(e) =
+                table_tab_persistence::release_stripe_settlement(&state.db_pool, settlement.id)
+                    .await
+            {
+                warn!(error = %e, settlement_id = %settlement.id, "Failed to release tab settlement");
+            }
+            return Err(status);
+        }
+    };
+
+    Ok(Json(SettleTabResponse {
+        tab: reload_tab(&state, tab.id).await?,
+        checkout_url: Some(checkout_url),
+    }))
+}
+
+/// Open the Stripe Checkout page the guest pays a settlement on, routed to the
+/// club's Connect account when it can receive funds.
+async fn create_settlement_checkout(
+    state: &AppState,
+    tab: &TableTab,
+    settlement: &TabSettlement,
+) -> Result<String, StatusCode> {
+    let event_title = event_persistence::get_event_by_id(&state.db_pool, tab.event_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|event| event.title)
+        .unwrap_or_else(|| "Evento".to_string());
+
+    let club_connect_config =
+        payment_service::get_club_connect_config_for_event(&state.db_pool, tab.event_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, event_id = %tab.event_id, "Failed to load club Connect config");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    let routing = club_connect_config
+        .as_ref()
+        .filter(|cfg| cfg.can_route_funds());
+    let application_fee_cents = routing.map(|config| {
+        payment_service::compute_application_fee_cents(
+            settlement.amount,
+            config.platform_commission_percent,
+            config.platform_commission_fixed_fee,
+        )
+    });
+
+    let metadata: std::collections::HashMap<String, String> = [
+        (
+            "table_tab_settlement_id".to_string(),
+            settlement.id.to_string(),
+        ),
+        ("table_tab_id".to_string(), tab.id.to_string()),
+        ("event_id".to_string(), tab.event_id.to_string()),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut checkout_params = stripe::CreateCheckoutSession::new();
+    checkout_params.mode = Some(stripe::CheckoutSessionMode::Payment);
+    checkout_params.line_items = Some(vec![stripe::CreateCheckoutSessionLineItems {
+        price_data: Some(stripe::CreateCheckoutSessionLineItemsPriceData {
+            currency: Currency::EUR,
+            product_data: Some(stripe::CreateCheckoutSessionLineItemsPriceDataProductData {
+                name: format!(
+                    "Tavolo {} - {}",
+                    tab.table_name.as_deref().unwrap_or(""),
+                    event_title
+                ),
+                ..Default::default()
+            }),
+            unit_amount: Some((settlement.amount.to_f64().unwrap_or(0.0) * 100.0).round() as i64),
+            ..Default::default()
+        }),
+        quantity: Some(1),
+        ..Default::default()
+    }]);
+
+    let app_base_url = state.config.app_base_url.clone();
+    let success_url = format!(
+        "{}/payment/success?session_id={{CHECKOUT_SESSION_ID}}",
+        app_base_url
+    );
+    let cancel_url = format!("{}/events/{}", app_base_url, tab.event_id);
+    checkout_params.success_url = Some(&success_url);
+    checkout_params.cancel_url = Some(&cancel_url);
+    checkout_params.expires_at =
+        Some((Utc::now() + Duration::minutes(TAB_CHECKOUT_MINUTES)).timestamp());
+    checkout_params.metadata = Some(metadata.clone());
+
+    if let (Some(config), Some(application_fee_amount)) = (routing, application_fee_cents) {
+        let destination = config
+            .stripe_connected_account_id
+            .clone()
+            .unwrap_or_default();
+        checkout_params.payment_intent_data = Some(CreateCheckoutSessionPaymentIntentData {
+            application_fee_amount: Some(application_fee_amount),
+            on_behalf_of: Some(destination.clone()),
+            transfer_data: Some(CreateCheckoutSessionPaymentIntentDataTransferData {
+                amount: None,
+                destination,
+            }),
+            metadata: Some(metadata),
+            ..Default::default()
+        });
+    }
+
+    let session = stripe::CheckoutSession::create(&state.stripe_client, checkout_params)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, settlement_id = %settlement.id, "Stripe Checkout session creation error");
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    table_tab_persistence::set_settlement_checkout_session(
+        &state.db_pool,
+        settlement.id,
+        session.id.as_str(),
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, settlement_id = %settlement.id, "Failed to store checkout session on tab settlement");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    info!(settlement_id = %settlement.id, checkout_session_id = %session.id, tab_id = %tab.id, "Stripe Checkout Session created for tab settlement");
+
+    session.url.ok_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// POST /owner/tabs/:id/settle/cancel
+///
+/// Give up on the Stripe payment a tab is waiting on (the guest pays another
+/// way): its Checkout page is expired and the tab reopens.
+pub async fn cancel_tab_settlement_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(tab_id): Path<String>,
+) -> Result<Json<TableTabResponse>, StatusCode> {
+    let tab = owned_tab(&state, &claims, &tab_id).await?;
+    let settlement = table_tab_persistence::get_pending_settlement(&state.db_pool, tab.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::CONFLICT)?;
+
+    if let Some(session_id) = settlement
+        .stripe_checkout_session_id
+        .as_deref()
+        .and_then(|id| id.parse::<stripe::CheckoutSessionId>().ok())
+    {
+        // A session the guest already paid cannot be expired; leave the tab to
+        // the completion webhook.
+        stripe::CheckoutSession::expire(&state.stripe_client, &session_id)
+            .await
+            .map_err(|e| {
+                warn!(error = ?e, settlement_id = %settlement.id, "Failed to expire tab settlement checkout");
+                StatusCode::CONFLICT
+            })?;
+    }
+
+    table_tab_persistence::release_stripe_settlement(&state.db_pool, settlement.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(reload_tab(&state, tab.id).await?))
+}
@@ -1,5 +1,5 @@
-use crate::application::ticket_service as ticket_persistence;
-use crate::middleware::auth::ClubOwnerUser;
+use crate::application::{club_service, ticket_service as ticket_persistence};
+use crate::middleware::auth::{AdminUser, ClubManager};
 use crate::models::{
     AppState, CreateTicketRequest, PaginationParams, TicketResponse, TicketWithEventResponse,
     UpdateTicketRequest,
@@ -23,9 +23,9 @@ pub struct TicketsWithEventsResponse {
     pub tickets: Vec<TicketWithEventResponse>,
 }
 
-/// Get all tickets (admin endpoint - paginated, default limit=50)
+/// Get all tickets (platform admin - paginated, default limit=50)
 pub async fn get_all_tickets(
-    _: ClubOwnerUser,
+    _: AdminUser,
     State(state): State<Arc<AppState>>,
     Query(pagination): Query<PaginationParams>,
 ) -> Result<Json<TicketsResponse>, StatusCode> {
@@ -85,26 +85,29 @@ pub async fn get_ticket_by_code(
     }
 }
 
-/// Create a new ticket (requires club_owner JWT)
+/// Create a ticket for an event of a club the caller manages
 pub async fn create_ticket(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Json(payload): Json<CreateTicketRequest>,
 ) -> Result<(StatusCode, Json<TicketResponse>), StatusCode> {
+    club_service::ensure_manages_event(&state.db_pool, &manager, payload.event_id).await?;
+
     match ticket_persistence::create_ticket(&state.db_pool, payload).await {
         Ok(ticket) => Ok((StatusCode::CREATED, Json(ticket.into()))),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
 
-/// Update a ticket (requires club_owner JWT)
+/// Update a ticket for an event of a club the caller manages
 pub async fn update_ticket(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateTicketRequest>,
 ) -> Result<Json<TicketResponse>, StatusCode> {
     let ticket_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_ticket(&state, &manager, ticket_id).await?;
 
     match ticket_persistence::update_ticket(&state.db_pool, ticket_id, payload).await {
         Ok(Some(ticket)) => Ok(Json(ticket.into())),
@@ -113,13 +116,14 @@ pub async fn update_ticket(
     }
 }
 
-/// Delete a ticket (requires club_owner JWT)
+/// Delete a ticket for an event of a club the caller manages
 pub async fn delete_ticket(
-    _: ClubOwnerUser,
+    manager: ClubManager,
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<StatusCode, StatusCode> {
     let ticket_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    ensure_manages_ticket(&state, &manager, ticket_id).await?;
 
     match ticket_persistence::delete_ticket(&state.db_pool, ticket_id).await {
         Ok(true) => Ok(StatusCode::NO_CONTENT),
@@ -127,3 +131,16 @@ pub async fn delete_ticket(
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
+
+async fn ensure_manages_ticket(
+    state: &AppState,
+    manager: &ClubManager,
+    ticket_id: Uuid,
+) -> Result<(), StatusCode> {
+    let ticket = ticket_persistence::get_ticket_by_id(&state.db_pool, ticket_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    club_service::ensure_manages_event(&state.db_pool, manager, ticket.event_id).await?;
+    Ok(())
+}
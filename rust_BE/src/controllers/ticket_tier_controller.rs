@@ -0,0 +1,460 @@
+use crate::application::{
+    club_service as club_persistence, event_service as event_persistence, outbox_service,
+    payment_service, refund_service, ticket_tier_service as ticket_tier_persistence,
+};
+use crate::middleware::auth::{AuthUser, ClubStaffUser, ManageClub, ViewFinance};
+use crate::models::ticket_tier::TICKET_CHECKOUT_HOLD_MINUTES;
+use crate::models::{
+    AppState, Claims, CreateTicketCheckoutRequest, CreateTicketTierRequest, Event,
+    TicketCheckoutResponse, TicketPurchaseRejection, TicketTier, TicketTierResponse,
+    UpdateTicketTierRequest,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use stripe::{
+    CreateCheckoutSessionPaymentIntentData, CreateCheckoutSessionPaymentIntentDataTransferData,
+    Currency,
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Load an event of the caller's club (403 for other clubs' events).
+async fn owned_event(
+    state: &AppState,
+    claims: &Claims,
+    event_id: Uuid,
+) -> Result<Event, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let event = event_persistence::get_event_by_id(&state.db_pool, event_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if event.club_id != Some(club.id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(event)
+}
+
+/// Load a tier whose event belongs to the caller's club.
+async fn owned_tier(
+    state: &AppState,
+    claims: &Claims,
+    tier_id: &str,
+) -> Result<TicketTier, StatusCode> {
+    let tier_uuid = Uuid::parse_str(tier_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let tier = ticket_tier_persistence::get_ticket_tier_by_id(&state.db_pool, tier_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    owned_event(state, claims, tier.event_id).await?;
+    Ok(tier)
+}
+
+fn valid_sale_window(
+    start: Option<chrono::DateTime<Utc>>,
+    end: Option<chrono::DateTime<Utc>>,
+) -> bool {
+    match (start, end) {
+        (Some(start), Some(end)) => start < end,
+        _ => true,
+    }
+}
+
+/// GET /events/:id/ticket-tiers — tiers on sale (or coming up) for an event
+pub async fn list_event_ticket_tiers(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<String>,
+) -> Result<Json<Vec<TicketTierResponse>>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let tiers = ticket_tier_persistence::get_tiers_by_event(&state.db_pool, event_uuid, true)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        tiers.into_iter().map(TicketTierResponse::from).collect(),
+    ))
+}
+
+/// List every tier of one of the owner's events, including inactive ones
+pub async fn get_my_event_ticket_tiers(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ViewFinance>,
+    Path(event_id): Path<String>,
+) -> Result<Json<Vec<TicketTierResponse>>, StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    owned_event(&state, &claims, event_uuid).await?;
+
+    let tiers = ticket_tier_persistence::get_tiers_by_event(&state.db_pool, event_uuid, false)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        tiers.into_iter().map(TicketTierResponse::from).collect(),
+    ))
+}
+
+/// Create a ticket tier for one of the owner's events
+pub async fn create_my_ticket_tier(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(event_id): Path<String>,
+    Json(payload): Json<CreateTicketTierRequest>,
+) -> Result<(StatusCode, Json<TicketTierResponse>), StatusCode> {
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let name = payload.name.trim();
+    if name.is_empty()
+        || name.len() > 100
+        || payload.price <= Decimal::ZERO
+        || payload.quantity_total < 1
+        || payload.max_per_user.is_some_and(|max| max < 1)
+        || !valid_sale_window(payload.sales_start_at, payload.sales_end_at)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let event = owned_event(&state, &claims, event_uuid).await?;
+
+    let tier = ticket_tier_persistence::create_ticket_tier(
+        &state.db_pool,
+        event.id,
+        name,
+        payload.description,
+        payload.price,
+        payload.quantity_total,
+        payload.sales_start_at,
+        payload.sales_end_at,
+        payload.max_per_user,
+        payload.sort_order.unwrap_or(0),
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, event_id = %event.id, "Failed to create ticket tier");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "owner_ticket_tier_created",
+        Some(&claims.sub),
+        Some("ticket_tier"),
+        Some(tier.id),
+        serde_json::json!({
+            "event_id": event.id,
+            "tier_id": tier.id,
+            "price": tier.price,
+            "quantity_total": tier.quantity_total,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(TicketTierResponse::from(tier))))
+}
+
+/// Update (or deactivate) a ticket tier. The quantity must stay at least 1 and
+/// cannot drop below the tickets already sold (400) or held (409).
+pub async fn update_my_ticket_tier(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(tier_id): Path<String>,
+    Json(payload): Json<UpdateTicketTierRequest>,
+) -> Result<Json<TicketTierResponse>, StatusCode> {
+    let name = payload.name.as_deref().map(str::trim);
+    if name.is_some_and(|name| name.is_empty() || name.len() > 100)
+        || payload.price.is_some_and(|price| price <= Decimal::ZERO)
+        || payload.quantity_total.is_some_and(|total| total < 1)
+        || payload.max_per_user.is_some_and(|max| max < 1)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let tier = owned_tier(&state, &claims, &tier_id).await?;
+    if payload
+        .quantity_total
+        .is_some_and(|total| total < tier.quantity_sold)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if !valid_sale_window(
+        payload.sales_start_at.or(tier.sales_start_at),
+        payload.sales_end_at.or(tier.sales_end_at),
+    ) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let updated = match ticket_tier_persistence::update_ticket_tier(
+        &state.db_pool,
+        tier.id,
+        name.map(str::to_string),
+        payload.description,
+        payload.price,
+        payload.quantity_total,
+        payload.sales_start_at,
+        payload.sales_end_at,
+        payload.max_per_user,
+        payload.sort_order,
+        payload.is_active,
+    )
+    .await
+    {
+        Ok(Some(tier)) => tier,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        // A total below sold + held tickets (held by an open checkout) violates
+        // ticket_tiers_inventory
+        Err(sqlx::Error::Database(db_error)) if db_error.is_check_violation() => {
+            return Err(StatusCode::CONFLICT)
+        }
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    Ok(Json(TicketTierResponse::from(updated)))
+}
+
+/// Delete a tier nobody has ordered from (409 otherwise; deactivate it instead)
+pub async fn delete_my_ticket_tier(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageClub>,
+    Path(tier_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    let tier = owned_tier(&state, &claims, &tier_id).await?;
+
+    match ticket_tier_persistence::delete_ticket_tier(&state.db_pool, tier.id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::CONFLICT),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+fn ticket_rejection_error(rejection: TicketPurchaseRejection) -> (StatusCode, String) {
+    let status = match rejection {
+        TicketPurchaseRejection::SoldOut | TicketPurchaseRejection::UserLimitReached => {
+            StatusCode::CONFLICT
+        }
+        _ => StatusCode::BAD_REQUEST,
+    };
+    info!(reason = rejection.as_str(), "Ticket purchase rejected");
+    (status, rejection.message().to_string())
+}
+
+/// POST /ticket-tiers/:id/checkout — hold tickets and open a Stripe Checkout
+/// session for them. Tickets are issued by the `checkout.session.completed`
+/// webhook; the hold is released when the session expires.
+pub async fn create_ticket_checkout(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(tier_id): Path<String>,
+    Json(req): Json<CreateTicketCheckoutRequest>,
+) -> Result<Json<TicketCheckoutResponse>, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Non autorizzato".to_string()))?;
+    let tier_uuid = Uuid::parse_str(&tier_id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "ID non valido".to_string()))?;
+
+    let tier = ticket_tier_persistence::get_ticket_tier_by_id(&state.db_pool, tier_uuid)
+        .await
+        .map_err(|e| {
+            error!(error = %e, tier_id = %tier_uuid, "Failed to load ticket tier");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore del database".to_string(),
+            )
+        })?
+        .ok_or((StatusCode::NOT_FOUND, "Biglietto non trovato".to_string()))?;
+
+    let event = event_persistence::get_event_by_id(&state.db_pool, tier.event_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, event_id = %tier.event_id, "Failed to load event");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Errore del database".to_string(),
+            )
+        })?
+        .ok_or((StatusCode::NOT_FOUND, "Evento non trovato".to_string()))?;
+
+    let now = Utc::now();
+    let event_start = refund_service::event_start(&event).map(|start| start.and_utc());
+    tier.check_purchasable(req.quantity, now, event_start)
+        .map_err(ticket_rejection_error)?;
+    event_persistence::ensure_user_meets_min_age(
+        &state.db_pool,
+        tier.event_id,
+        user_id,
+        req.date_of_birth,
+    )
+    .await?;
+
+    let event_name = event.title;
+
+    let club_connect_config =
+        payment_service::get_club_connect_config_for_event(&state.db_pool, tier.event_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, event_id = %tier.event_id, "Failed to load club Connect config");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Errore del database".to_string(),
+                )
+            })?;
+    let routing = club_connect_config
+        .as_ref()
+        .filter(|cfg| cfg.can_route_funds());
+
+    let total_amount = tier.price * Decimal::from(req.quantity);
+    let application_fee_cents = routing.map(|config| {
+        payment_service::compute_application_fee_cents(
+            total_amount,
+            config.platform_commission_percent,
+            config.platform_commission_fixed_fee,
+        )
+    });
+    let expires_at = now + Duration::minutes(TICKET_CHECKOUT_HOLD_MINUTES);
+
+    let order = ticket_tier_persistence::hold_tickets(
+        &state.db_pool,
+        tier.id,
+        user_id,
+        req.quantity,
+        tier.price,
+        total_amount,
+        application_fee_cents.map(|cents| Decimal::new(cents, 2)),
+        expires_at,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, tier_id = %tier.id, "Failed to hold tickets");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Errore del database".to_string(),
+        )
+    })?
+    .map_err(ticket_rejection_error)?;
+
+    let metadata: std::collections::HashMap<String, String> = [
+        ("ticket_order_id".to_string(), order.id.to_string()),
+        ("ticket_tier_id".to_string(), tier.id.to_string()),
+        ("event_id".to_string(), tier.event_id.to_string()),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut checkout_params = stripe::CreateCheckoutSession::new();
+    checkout_params.mode = Some(stripe::CheckoutSessionMode::Payment);
+    checkout_params.line_items = Some(vec![stripe::CreateCheckoutSessionLineItems {
+        price_data: Some(stripe::CreateCheckoutSessionLineItemsPriceData {
+            currency: Currency::EUR,
+            product_data: Some(stripe::CreateCheckoutSessionLineItemsPriceDataProductData {
+                name: format!("{} - {}", tier.name, event_name),
+                ..Default::default()
+            }),
+            unit_amount: Some((tier.price.to_f64().unwrap_or(0.0) * 100.0).round() as i64),
+            ..Default::default()
+        }),
+        quantity: Some(req.quantity as u64),
+        ..Default::default()
+    }]);
+
+    let app_base_url = state.config.app_base_url.clone();
+    let success_url = format!(
+        "{}/payment/success?session_id={{CHECKOUT_SESSION_ID}}",
+        app_base_url
+    );
+    let cancel_url = format!("{}/events/{}", app_base_url, tier.event_id);
+    checkout_params.success_url = Some(&success_url);
+    checkout_params.cancel_url = Some(&cancel_url);
+    checkout_params.customer_email = Some(&claims.email);
+    checkout_params.expires_at = Some(expires_at.timestamp());
+    checkout_params.metadata = Some(metadata.clone());
+
+    if let (Some(config), Some(application_fee_amount)) = (routing, application_fee_cents) {
+        let destination = config
+            .stripe_connected_account_id
+            .clone()
+            .unwrap_or_default();
+        checkout_params.payment_intent_data = Some(CreateCheckoutSessionPaymentIntentData {
+            application_fee_amount: Some(application_fee_amount),
+            on_behalf_of: Some(destination.clone()),
+            transfer_data: Some(CreateCheckoutSessionPaymentIntentDataTransferData {
+                amount: None,
+                destination,
+            }),
+            metadata: Some(metadata),
+            ..Default::default()
+        });
+    }
+
+    let session = match stripe::CheckoutSession::create(&state.stripe_client, checkout_params).await
+    {
+        Ok(session) => session,
+        Err(e) => {
+            error!(error = ?e, order_id = %order.id, "Stripe Checkout session creation error");
+            if let Err(e) =
+                ticket_tier_persistence::release_ticket_order(&state.db_pool, order.id).await
+            {
+                warn!(error = %e, order_id = %order.id, "Failed to release ticket hold");
+            }
+            return Err((
+                StatusCode::BAD_GATEWAY,
+                "Errore del servizio di pagamento".to_string(),
+            ));
+        }
+    };
+
+    ticket_tier_persistence::set_order_checkout_session(
+        &state.db_pool,
+        order.id,
+        session.id.as_str(),
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, order_id = %order.id, "Failed to store checkout session on ticket order");
+        (StatusCode::INTERNAL_SERVER_ERROR, "Errore".to_string())
+    })?;
+
+    let checkout_url = session.url.ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "URL di checkout non disponibile".to_string(),
+        )
+    })?;
+
+    info!(order_id = %order.id, checkout_session_id = %session.id, tier_id = %tier.id, "Stripe Checkout Session created for ticket order");
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "ticket_checkout_started",
+        Some(&claims.sub),
+        Some("ticket_order"),
+        Some(order.id),
+        serde_json::json!({
+            "order_id": order.id,
+            "tier_id": tier.id,
+            "event_id": tier.event_id,
+            "quantity": order.quantity,
+            "total_amount": order.total_amount,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok(Json(TicketCheckoutResponse {
+        order_id: order.id.to_string(),
+        checkout_url,
+        expires_at: order.expires_at.to_rfc3339(),
+    }))
+}
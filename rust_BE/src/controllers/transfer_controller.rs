@@ -0,0 +1,418 @@
+use crate::application::transfer_service::{TransferAcceptance, TRANSFER_EXPIRY_HOURS};
+use crate::application::{
+    auth_service as user_persistence, club_service as club_persistence,
+    event_service as event_persistence, outbox_service, transfer_service as transfer_persistence,
+};
+use crate::controllers::auth_controller::{is_valid_email, normalize_phone_number};
+use crate::middleware::auth::{AuthUser, ClubStaffUser, ManageReservations};
+use crate::models::{
+    AcceptTransferRequest, AcceptTransferResponse, AppState, Claims, CreateTransferRequest,
+    OwnershipTransfer, TransferAuditEntryResponse, TransferAuditParams, TransferClaim,
+    TransferResponse, TransfersResponse, User,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+type ApiResult<T> = Result<T, (StatusCode, String)>;
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    error!(error = %e, "Transfer: database error");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Errore del database".to_string(),
+    )
+}
+
+fn not_found() -> (StatusCode, String) {
+    (
+        StatusCode::NOT_FOUND,
+        "Trasferimento non trovato".to_string(),
+    )
+}
+
+fn transfer_blocked_error(reason: &str) -> (StatusCode, String) {
+    match reason {
+        "transfers_disabled" => (
+            StatusCode::FORBIDDEN,
+            "Il locale non consente trasferimenti per questo evento".to_string(),
+        ),
+        "event_over" => (StatusCode::CONFLICT, "L'evento è già passato".to_string()),
+        "checked_in" => (
+            StatusCode::CONFLICT,
+            "Il check-in è già stato effettuato".to_string(),
+        ),
+        _ => (
+            StatusCode::CONFLICT,
+            "Non è più possibile trasferirlo".to_string(),
+        ),
+    }
+}
+
+fn claim_error(claim: TransferClaim) -> (StatusCode, String) {
+    match claim {
+        TransferClaim::CodeRequired => (
+            StatusCode::BAD_REQUEST,
+            "Inserisci il codice ricevuto via email".to_string(),
+        ),
+        TransferClaim::WrongCode => (StatusCode::FORBIDDEN, "Codice non valido".to_string()),
+        TransferClaim::Locked => (
+            StatusCode::FORBIDDEN,
+            "Troppi tentativi: chiedi di inviarti di nuovo il trasferimento".to_string(),
+        ),
+        TransferClaim::Allowed | TransferClaim::NotAddressed => not_found(),
+    }
+}
+
+async fn load_user(state: &AppState, claims: &Claims) -> ApiResult<User> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Non autorizzato".to_string()))?;
+    user_persistence::find_user_by_id(&state.db_pool, user_id)
+        .await
+        .map_err(db_error)?
+        .ok_or((StatusCode::UNAUTHORIZED, "Non autorizzato".to_string()))
+}
+
+/// Recipient phone (normalized) or email (trimmed); exactly one must be given.
+fn parse_recipient(req: &CreateTransferRequest) -> ApiResult<(Option<String>, Option<String>)> {
+    let phone = req.phone_number.as_deref().filter(|p| !p.trim().is_empty());
+    let email = req
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty());
+    match (phone, email) {
+        (Some(phone), None) => normalize_phone_number(phone)
+            .map(|phone| (Some(phone), None))
+            .ok_or((
+                StatusCode::BAD_REQUEST,
+                "Numero di telefono non valido".to_string(),
+            )),
+        (None, Some(email)) if is_valid_email(email) => Ok((None, Some(email.to_string()))),
+        (None, Some(_)) => Err((
+            StatusCode::BAD_REQUEST,
+            "Formato email non valido".to_string(),
+        )),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            "Indica un numero di telefono oppure un'email".to_string(),
+        )),
+    }
+}
+
+async fn initiate_transfer(
+    state: &AppState,
+    claims: &Claims,
+    item_type: &str,
+    item_id: &str,
+    req: CreateTransferRequest,
+) -> ApiResult<(StatusCode, Json<TransferResponse>)> {
+    let sender = load_user(state, claims).await?;
+    let item_uuid = Uuid::parse_str(item_id)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "ID non valido".to_string()))?;
+    let (recipient_phone, recipient_email) = parse_recipient(&req)?;
+
+    let to_self = recipient_phone.is_some() && recipient_phone == sender.phone_number
+        || recipient_email
+            .as_deref()
+            .is_some_and(|email| email.eq_ignore_ascii_case(&sender.email));
+    if to_self {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Non puoi trasferirlo a te stesso".to_string(),
+        ));
+    }
+
+    transfer_persistence::expire_stale_transfers(&state.db_pool)
+        .await
+        .map_err(db_error)?;
+
+    let item = transfer_persistence::get_transferable_item(&state.db_pool, item_type, item_uuid)
+        .await
+        .map_err(db_error)?
+        .filter(|item| item.user_id == sender.id)
+        .ok_or((StatusCode::NOT_FOUND, "Non trovato".to_string()))?;
+    if let Some(reason) = item.blocking_reason(Utc::now().date_naive()) {
+        return Err(transfer_blocked_error(reason));
+    }
+
+    // Account emails are unverified: an email recipient proves the address
+    // with a code sent to it
+    let claim_code = recipient_email
+        .as_ref()
+        .map(|_| transfer_persistence::new_claim_code());
+    let created = match transfer_persistence::create_transfer(
+        &state.db_pool,
+        item_type,
+        &item,
+        recipient_phone.as_deref(),
+        recipient_email.as_deref(),
+        claim_code.as_ref().map(|(_, code_hash)| code_hash.as_str()),
+        Utc::now() + Duration::hours(TRANSFER_EXPIRY_HOURS),
+    )
+    .await
+    {
+        Ok(transfer) => transfer,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err((
+                StatusCode::CONFLICT,
+                "C'è già un trasferimento in corso".to_string(),
+            ))
+        }
+        Err(e) => return Err(db_error(e)),
+    };
+    let transfer = transfer_persistence::get_transfer(&state.db_pool, created.id)
+        .await
+        .map_err(db_error)?
+        .unwrap_or(created);
+
+    transfer_persistence::notify_recipient(
+        state,
+        &transfer,
+        claim_code.as_ref().map(|(code, _)| code.as_str()),
+    )
+    .await;
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "transfer_initiated",
+        Some(&claims.sub),
+        Some("ownership_transfer"),
+        Some(transfer.id),
+        serde_json::json!({
+            "item_type": item_type,
+            "item_id": item_uuid,
+            "event_id": transfer.event_id,
+            "recipient": if transfer.recipient_phone.is_some() { "phone" } else { "email" },
+        }),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(transfer.into())))
+}
+
+/// POST /tickets/:id/transfer — offer the caller's ticket to a phone number or email
+pub async fn transfer_ticket(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<String>,
+    Json(req): Json<CreateTransferRequest>,
+) -> ApiResult<(StatusCode, Json<TransferResponse>)> {
+    initiate_transfer(&state, &claims, "ticket", &id, req).await
+}
+
+/// POST /reservations/:id/transfer — offer the caller's reservation to a phone
+/// number or email
+pub async fn transfer_reservation(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<String>,
+    Json(req): Json<CreateTransferRequest>,
+) -> ApiResult<(StatusCode, Json<TransferResponse>)> {
+    initiate_transfer(&state, &claims, "reservation", &id, req).await
+}
+
+/// GET /transfers — pending transfers addressed to the caller and the ones they sent
+pub async fn list_my_transfers(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> ApiResult<Json<TransfersResponse>> {
+    let user = load_user(&state, &claims).await?;
+    transfer_persistence::expire_stale_transfers(&state.db_pool)
+        .await
+        .map_err(db_error)?;
+
+    let incoming = transfer_persistence::list_incoming_transfers(&state.db_pool, &user)
+        .await
+        .map_err(db_error)?;
+    let outgoing = transfer_persistence::list_outgoing_transfers(&state.db_pool, user.id, 50)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(TransfersResponse {
+        incoming: incoming.into_iter().map(TransferResponse::from).collect(),
+        outgoing: outgoing.into_iter().map(TransferResponse::from).collect(),
+    }))
+}
+
+/// POST /transfers/:id/accept — take over the ticket or reservation; the old
+/// code stops working and a new one is issued to the caller
+pub async fn accept_transfer(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<String>,
+    payload: Option<Json<AcceptTransferRequest>>,
+) -> ApiResult<Json<AcceptTransferResponse>> {
+    let user = load_user(&state, &claims).await?;
+    let transfer_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+    let req = payload.map(|Json(req)| req).unwrap_or_default();
+
+    let transfer = transfer_persistence::get_transfer(&state.db_pool, transfer_id)
+        .await
+        .map_err(db_error)?
+        .filter(|transfer| transfer.status == "pending" && transfer.is_addressed_to(&user))
+        .ok_or_else(not_found)?;
+    event_persistence::ensure_user_meets_min_age(
+        &state.db_pool,
+        transfer.event_id,
+        user.id,
+        req.date_of_birth,
+    )
+    .await?;
+
+    let claim_code_hash = req
+        .claim_code
+        .as_deref()
+        .filter(|code| !code.trim().is_empty())
+        .map(transfer_persistence::hash_claim_code);
+    let new_code = match transfer_persistence::accept_transfer(
+        &state.db_pool,
+        transfer_id,
+        &user,
+        claim_code_hash.as_deref(),
+    )
+    .await
+    .map_err(db_error)?
+    {
+        TransferAcceptance::Accepted { new_code } => new_code,
+        TransferAcceptance::NotFound => return Err(not_found()),
+        TransferAcceptance::Expired => {
+            return Err((StatusCode::GONE, "Il trasferimento è scaduto".to_string()))
+        }
+        TransferAcceptance::Unavailable(reason) => return Err(transfer_blocked_error(reason)),
+        TransferAcceptance::Claim(claim) => return Err(claim_error(claim)),
+    };
+
+    let transfer = transfer_persistence::get_transfer(&state.db_pool, transfer_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(not_found)?;
+    transfer_persistence::notify_sender(&state, &transfer).await;
+    record_response(&state, &claims, "transfer_accepted", &transfer).await;
+
+    Ok(Json(AcceptTransferResponse {
+        transfer: transfer.into(),
+        new_code,
+    }))
+}
+
+/// POST /transfers/:id/decline — turn down a transfer; the sender keeps the item
+pub async fn decline_transfer(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<String>,
+) -> ApiResult<Json<TransferResponse>> {
+    let user = load_user(&state, &claims).await?;
+    let transfer_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+
+    let transfer = transfer_persistence::get_transfer(&state.db_pool, transfer_id)
+        .await
+        .map_err(db_error)?
+        .filter(|transfer| transfer.is_addressed_to(&user))
+        .ok_or_else(not_found)?;
+    let transfer = close_transfer(&state, transfer, user.id, "declined").await?;
+
+    transfer_persistence::notify_sender(&state, &transfer).await;
+    record_response(&state, &claims, "transfer_declined", &transfer).await;
+
+    Ok(Json(transfer.into()))
+}
+
+/// POST /transfers/:id/cancel — withdraw a transfer the caller started
+pub async fn cancel_transfer(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<String>,
+) -> ApiResult<Json<TransferResponse>> {
+    let user = load_user(&state, &claims).await?;
+    let transfer_id = Uuid::parse_str(&id).map_err(|_| not_found())?;
+
+    let transfer = transfer_persistence::get_transfer(&state.db_pool, transfer_id)
+        .await
+        .map_err(db_error)?
+        .filter(|transfer| transfer.from_user_id == user.id)
+        .ok_or_else(not_found)?;
+    let transfer = close_transfer(&state, transfer, user.id, "cancelled").await?;
+
+    record_response(&state, &claims, "transfer_cancelled", &transfer).await;
+
+    Ok(Json(transfer.into()))
+}
+
+async fn close_transfer(
+    state: &AppState,
+    transfer: OwnershipTransfer,
+    actor_user_id: Uuid,
+    status: &str,
+) -> ApiResult<OwnershipTransfer> {
+    let closed =
+        transfer_persistence::close_transfer(&state.db_pool, &transfer, actor_user_id, status)
+            .await
+            .map_err(db_error)?
+            .ok_or((
+                StatusCode::CONFLICT,
+                "Il trasferimento non è più in attesa".to_string(),
+            ))?;
+    Ok(OwnershipTransfer {
+        event_title: transfer.event_title,
+        from_user_name: transfer.from_user_name,
+        ..closed
+    })
+}
+
+async fn record_response(
+    state: &AppState,
+    claims: &Claims,
+    event_name: &str,
+    transfer: &OwnershipTransfer,
+) {
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        event_name,
+        Some(&claims.sub),
+        Some("ownership_transfer"),
+        Some(transfer.id),
+        serde_json::json!({
+            "item_type": transfer.item_type,
+            "item_id": transfer.item_id(),
+            "event_id": transfer.event_id,
+        }),
+    )
+    .await;
+}
+
+/// GET /owner/events/:event_id/transfers — transfer audit trail of an event
+pub async fn get_event_transfers_handler(
+    State(state): State<Arc<AppState>>,
+    ClubStaffUser(claims, _): ClubStaffUser<ManageReservations>,
+    Path(event_id): Path<String>,
+    Query(params): Query<TransferAuditParams>,
+) -> Result<Json<Vec<TransferAuditEntryResponse>>, StatusCode> {
+    let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+
+    let club = club_persistence::get_club_by_owner_id(&state.db_pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let entries =
+        transfer_persistence::get_transfer_audit_log(&state.db_pool, club.id, event_uuid, limit)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(
+        entries
+            .into_iter()
+            .map(TransferAuditEntryResponse::from)
+            .collect(),
+    ))
+}
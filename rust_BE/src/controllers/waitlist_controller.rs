@@ -0,0 +1,158 @@
+use crate::application::{
+    area_service as area_persistence, event_service as event_persistence, outbox_service,
+    waitlist_service as waitlist_persistence,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::{AppState, JoinWaitlistRequest, WaitlistEntryResponse};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// POST /events/:id/waitlist — join the event's waitlist, optionally for a
+/// specific area or a minimum table size
+pub async fn join_waitlist(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(event_id): Path<String>,
+    payload: Option<Json<JoinWaitlistRequest>>,
+) -> Result<(StatusCode, Json<WaitlistEntryResponse>), StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let req = payload.map(|Json(req)| req).unwrap_or_default();
+
+    if req.min_capacity.is_some_and(|capacity| capacity < 1) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let event = event_persistence::get_event_by_id(&state.db_pool, event_uuid)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let area_id = match req.area_id.as_deref() {
+        Some(raw) => {
+            let area_uuid = Uuid::parse_str(raw).map_err(|_| StatusCode::BAD_REQUEST)?;
+            let area = area_persistence::get_area_by_id(&state.db_pool, area_uuid)
+                .await
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            if event.club_id != Some(area.club_id) {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            Some(area_uuid)
+        }
+        None => None,
+    };
+
+    let entry = match waitlist_persistence::insert_entry(
+        &state.db_pool,
+        event_uuid,
+        user_id,
+        area_id,
+        req.min_capacity,
+    )
+    .await
+    {
+        Ok(entry) => entry,
+        Err(sqlx::Error::Database(db_error)) if db_error.is_unique_violation() => {
+            return Err(StatusCode::CONFLICT)
+        }
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "waitlist_joined",
+        Some(&claims.sub),
+        Some("waitlist_entry"),
+        Some(entry.id),
+        serde_json::json!({
+            "event_id": event_uuid,
+            "area_id": area_id,
+            "min_capacity": req.min_capacity,
+            "position": entry.position,
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    let people_ahead = waitlist_persistence::count_entries_ahead(&state.db_pool, &entry)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut response = WaitlistEntryResponse::from(entry);
+    response.people_ahead = Some(people_ahead);
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// GET /events/:id/waitlist — the caller's active waitlist entry for the event
+pub async fn get_my_waitlist_entry(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(event_id): Path<String>,
+) -> Result<Json<WaitlistEntryResponse>, StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let entry =
+        waitlist_persistence::get_active_entry_for_user(&state.db_pool, event_uuid, user_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+
+    let people_ahead = waitlist_persistence::count_entries_ahead(&state.db_pool, &entry)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut response = WaitlistEntryResponse::from(entry);
+    response.people_ahead = Some(people_ahead);
+
+    Ok(Json(response))
+}
+
+/// DELETE /events/:id/waitlist — leave the waitlist. A hold the caller was
+/// offered is passed on to the next user in line.
+pub async fn leave_waitlist(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(event_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let event_uuid = Uuid::parse_str(&event_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let entry =
+        waitlist_persistence::get_active_entry_for_user(&state.db_pool, event_uuid, user_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+
+    let cancelled = waitlist_persistence::cancel_entry(&state.db_pool, entry.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if let Err(error) = waitlist_persistence::reoffer_released_hold(&state, &cancelled).await {
+        warn!(error = %error, entry_id = %cancelled.id, "Failed to re-offer released waitlist hold");
+    }
+
+    let _ = outbox_service::enqueue_analytics_event(
+        &state.db_pool,
+        &state.config,
+        "waitlist_left",
+        Some(&claims.sub),
+        Some("waitlist_entry"),
+        Some(cancelled.id),
+        serde_json::json!({
+            "event_id": event_uuid,
+            "had_hold": entry.status == "offered",
+            "outcome": "success",
+        }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,165 @@
+use chrono::Duration;
+use std::fmt;
+
+/// Provider HTTP API answered with a non-success status.
+#[derive(Debug)]
+pub struct HttpStatusError {
+    pub provider: &'static str,
+    pub status: u16,
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} returned non-success status {}",
+            self.provider, self.status
+        )
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// Why a dispatch attempt failed. Permanent failures are dead-lettered right
+/// away; transient ones are retried until the event type runs out of attempts.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DispatchError {
+    Transient(String),
+    Permanent(String),
+}
+
+impl DispatchError {
+    /// Classifies an error returned by a delivery provider:
+    ///   - HTTP 4xx other than 408 / 429 → permanent (bad payload, bad credentials,
+    ///     invalid destination)
+    ///   - SMTP 5xx replies and malformed addresses or messages → permanent
+    ///   - everything else (network, timeouts, 5xx, rate limits) → transient
+    pub fn classify(error: &(dyn std::error::Error + Send + Sync + 'static)) -> Self {
+        let message = error.to_string();
+
+        let is_permanent = if let Some(http) = error.downcast_ref::<HttpStatusError>() {
+            (400..500).contains(&http.status) && !matches!(http.status, 408 | 429)
+        } else if let Some(smtp) = error.downcast_ref::<lettre::transport::smtp::Error>() {
+            smtp.is_permanent()
+        } else {
+            error.is::<lettre::address::AddressError>() || error.is::<lettre::error::Error>()
+        };
+
+        if is_permanent {
+            DispatchError::Permanent(message)
+        } else {
+            DispatchError::Transient(message)
+        }
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        matches!(self, DispatchError::Permanent(_))
+    }
+}
+
+impl fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DispatchError::Transient(message) => write!(f, "transient: {message}"),
+            DispatchError::Permanent(message) => write!(f, "permanent: {message}"),
+        }
+    }
+}
+
+/// How often an event type is retried before it is dead-lettered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: i32,
+    pub base_delay_seconds: i64,
+    pub max_delay_seconds: i64,
+}
+
+pub fn retry_policy(event_type: &str) -> RetryPolicy {
+    match event_type {
+        // Ops alerts are worth chasing for about a day
+        "notification.alert_webhook" => RetryPolicy {
+            max_attempts: 12,
+            base_delay_seconds: 30,
+            max_delay_seconds: 6 * 60 * 60,
+        },
+        // Receipts and confirmations: a few hours of retries
+        "notification.email" => RetryPolicy {
+            max_attempts: 8,
+            base_delay_seconds: 60,
+            max_delay_seconds: 2 * 60 * 60,
+        },
+        // SMS carry short-lived codes and push is time-sensitive: give up quickly
+        "notification.sms" | "notification.push" => RetryPolicy {
+            max_attempts: 5,
+            base_delay_seconds: 30,
+            max_delay_seconds: 30 * 60,
+        },
+        // Inbound Stripe webhooks: money moved, so keep trying for about a day
+        "stripe.webhook" => RetryPolicy {
+            max_attempts: 12,
+            base_delay_seconds: 60,
+            max_delay_seconds: 6 * 60 * 60,
+        },
+        "analytics.capture" => RetryPolicy {
+            max_attempts: 6,
+            base_delay_seconds: 60,
+            max_delay_seconds: 60 * 60,
+        },
+        _ => RetryPolicy {
+            max_attempts: 5,
+            base_delay_seconds: 30,
+            max_delay_seconds: 60 * 60,
+        },
+    }
+}
+
+/// Exponential backoff with "equal jitter": after the n-th failed attempt the
+/// event waits between half and all of `base * 2^(n-1)` (capped at the policy
+/// maximum). `jitter` is a random value in `[0, 1)`.
+pub fn retry_delay(policy: RetryPolicy, attempts: i32, jitter: f64) -> Duration {
+    let exponent = (attempts.max(1) - 1).min(30) as u32;
+    let ceiling = policy
+        .base_delay_seconds
+        .saturating_mul(2_i64.saturating_pow(exponent))
+        .min(policy.max_delay_seconds);
+    let half = ceiling / 2;
+    let jittered = half + ((ceiling - half) as f64 * jitter.clamp(0.0, 1.0)) as i64;
+
+    Duration::seconds(jittered.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_grows_exponentially_and_is_capped() {
+        let policy = retry_policy("notification.sms");
+
+        assert_eq!(retry_delay(policy, 1, 0.0), Duration::seconds(15));
+        assert_eq!(retry_delay(policy, 1, 1.0), Duration::seconds(30));
+        assert_eq!(retry_delay(policy, 3, 1.0), Duration::seconds(120));
+        assert_eq!(retry_delay(policy, 20, 1.0), Duration::seconds(30 * 60));
+        assert_eq!(retry_delay(policy, 20, 0.0), Duration::seconds(15 * 60));
+    }
+
+    #[test]
+    fn test_classify_http_status() {
+        let classify = |status| {
+            let error: Box<dyn std::error::Error + Send + Sync> = Box::new(HttpStatusError {
+                provider: "SMS",
+                status,
+            });
+            DispatchError::classify(error.as_ref()).is_permanent()
+        };
+
+        assert!(classify(400));
+        assert!(classify(401));
+        assert!(!classify(408));
+        assert!(!classify(429));
+        assert!(!classify(503));
+
+        let other: Box<dyn std::error::Error + Send + Sync> = "connection reset".into();
+        assert!(!DispatchError::classify(other.as_ref()).is_permanent());
+    }
+}
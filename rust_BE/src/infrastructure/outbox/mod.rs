@@ -3,6 +3,8 @@ use serde_json::Value;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+pub mod delivery;
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct OutboxEvent {
     pub id: Uuid,
@@ -16,8 +18,15 @@ pub struct OutboxEvent {
     pub last_error: Option<String>,
     pub created_at: chrono::DateTime<Utc>,
     pub processed_at: Option<chrono::DateTime<Utc>>,
+    pub dead_lettered_at: Option<chrono::DateTime<Utc>>,
+    pub replay_count: i32,
 }
 
+const OUTBOX_EVENT_COLUMNS: &str = r#"
+    id, event_type, aggregate_type, aggregate_id, payload, status, attempts,
+    available_at, last_error, created_at, processed_at, dead_lettered_at, replay_count
+"#;
+
 pub async fn enqueue_event(
     pool: &PgPool,
     event_type: &str,
@@ -79,7 +88,9 @@ pub async fn claim_pending_events(
             oe.available_at,
             oe.last_error,
             oe.created_at,
-            oe.processed_at
+            oe.processed_at,
+            oe.dead_lettered_at,
+            oe.replay_count
         "#,
     )
     .bind(batch_size)
@@ -129,3 +140,108 @@ pub async fn mark_failed(
 
     Ok(())
 }
+
+/// Stops retrying an event: it stays in `dead_lettered` until an admin
+/// replays or discards it.
+pub async fn mark_dead_lettered(
+    pool: &PgPool,
+    event_id: Uuid,
+    error_message: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE outbox_events
+        SET status = 'dead_lettered',
+            last_error = $2,
+            dead_lettered_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(event_id)
+    .bind(error_message)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Dead-lettered events, most recent first, optionally of one event type
+pub async fn list_dead_lettered(
+    pool: &PgPool,
+    event_type: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<OutboxEvent>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEvent>(&format!(
+        r#"
+        SELECT {OUTBOX_EVENT_COLUMNS}
+        FROM outbox_events
+        WHERE status = 'dead_lettered'
+          AND ($1::TEXT IS NULL OR event_type = $1)
+        ORDER BY dead_lettered_at DESC
+        LIMIT $2 OFFSET $3
+        "#
+    ))
+    .bind(event_type)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_event(pool: &PgPool, event_id: Uuid) -> Result<Option<OutboxEvent>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEvent>(&format!(
+        "SELECT {OUTBOX_EVENT_COLUMNS} FROM outbox_events WHERE id = $1"
+    ))
+    .bind(event_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Puts a dead-lettered event back in the queue with a fresh attempt budget.
+/// Returns `None` when the event does not exist or is not dead-lettered.
+pub async fn replay_dead_lettered(
+    pool: &PgPool,
+    event_id: Uuid,
+) -> Result<Option<OutboxEvent>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEvent>(&format!(
+        r#"
+        UPDATE outbox_events
+        SET status = 'pending',
+            attempts = 0,
+            available_at = NOW(),
+            dead_lettered_at = NULL,
+            replay_count = replay_count + 1,
+            updated_at = NOW()
+        WHERE id = $1
+          AND status = 'dead_lettered'
+        RETURNING {OUTBOX_EVENT_COLUMNS}
+        "#
+    ))
+    .bind(event_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Drops a dead-lettered event for good; the row is kept for the record.
+/// Returns `None` when the event does not exist or is not dead-lettered.
+pub async fn discard_dead_lettered(
+    pool: &PgPool,
+    event_id: Uuid,
+) -> Result<Option<OutboxEvent>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEvent>(&format!(
+        r#"
+        UPDATE outbox_events
+        SET status = 'discarded',
+            discarded_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1
+          AND status = 'dead_lettered'
+        RETURNING {OUTBOX_EVENT_COLUMNS}
+        "#
+    ))
+    .bind(event_id)
+    .fetch_optional(pool)
+    .await
+}
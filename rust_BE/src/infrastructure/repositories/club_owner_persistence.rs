@@ -1,7 +1,10 @@
+use crate::models::age_restriction::age_on;
 use crate::models::club_owner::{
-    ClubImageRow, ClubOwner, EventStatRow, OwnerStats, ScanResult, TableImageRow,
+    CheckinAuditEntry, ClubImageRow, ClubOwner, EventStatRow, OwnerStats, ScanResult, TableImageRow,
 };
 use crate::models::table::TableReservation;
+use crate::models::AdminOwnerRow;
+use chrono::{NaiveDate, Utc};
 use rust_decimal::Decimal;
 use sqlx::{PgPool, Result};
 use uuid::Uuid;
@@ -86,6 +89,65 @@ pub async fn update_club_owner_password_hash(
     Ok(())
 }
 
+/// Whether an owner may log in or refresh (not suspended from the back office)
+pub async fn club_owner_is_active(pool: &PgPool, owner_id: Uuid) -> Result<bool> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM club_owners
+            WHERE id = $1 AND suspended_at IS NULL
+        )
+        "#,
+    )
+    .bind(owner_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Suspend or reinstate an owner. Returns false when the owner does not exist.
+pub async fn set_club_owner_suspended(
+    pool: &PgPool,
+    owner_id: Uuid,
+    suspended: bool,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE club_owners
+        SET suspended_at = CASE WHEN $2 THEN COALESCE(suspended_at, NOW()) END,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(owner_id)
+    .bind(suspended)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Every owner with the club they run, newest first
+pub async fn list_owners_with_clubs(
+    pool: &PgPool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<AdminOwnerRow>> {
+    sqlx::query_as::<_, AdminOwnerRow>(
+        r#"
+        SELECT o.id, o.email, o.name, o.phone_number, o.suspended_at, o.created_at,
+               c.id AS club_id, c.name AS club_name
+        FROM club_owners o
+        LEFT JOIN clubs c ON c.owner_id = o.id
+        ORDER BY o.created_at DESC
+        LIMIT $1 OFFSET $2
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
 // ============================================================================
 // Club images
 // ============================================================================
@@ -230,7 +292,7 @@ pub async fn get_event_reservations(
                tr.contact_phone, tr.special_requests, tr.reservation_code,
                tr.created_at, tr.updated_at,
                tr.guest_user_ids, tr.payment_ids, tr.ticket_ids,
-               tr.is_manual, tr.manual_notes, tr.payment_link_token
+               tr.is_manual, tr.manual_notes, tr.payment_link_token, tr.split_mode
         FROM table_reservations tr
         WHERE tr.event_id = $1
         ORDER BY tr.created_at DESC
@@ -336,156 +398,338 @@ pub async fn update_reservation_status(
 // QR scan / checkin
 // ============================================================================
 
-pub async fn scan_code(pool: &PgPool, code: &str) -> Result<Option<ScanResult>> {
-    // Try ticket first
-    let ticket_row = sqlx::query(
-        r#"
-        SELECT t.ticket_code, t.status, u.name as guest_name, e.title as event_title
-        FROM tickets t
-        JOIN users u ON u.id = t.user_id
-        JOIN events e ON e.id = t.event_id
-        WHERE t.ticket_code = $1
-        "#,
-    )
-    .bind(code)
-    .fetch_optional(pool)
-    .await?;
+/// A ticket or reservation code as seen from one club.
+#[derive(sqlx::FromRow)]
+struct ClubCode {
+    scan_type: String,
+    id: Uuid,
+    event_id: Uuid,
+    event_title: String,
+    event_date: Option<NaiveDate>,
+    status: String,
+    guest_name: Option<String>,
+    num_people: Option<i32>,
+    checked_in_count: Option<i32>,
+    table_name: Option<String>,
+    min_age: Option<i32>,
+    /// Ticket holder, or the user who booked the reservation
+    holder_date_of_birth: Option<NaiveDate>,
+}
+
+impl ClubCode {
+    /// Reason the code cannot be checked in at all, if any
+    fn blocking_reason(&self, valid_dates: &[NaiveDate]) -> Option<&'static str> {
+        if matches!(self.status.as_str(), "cancelled" | "refunded") {
+            return Some("cancelled");
+        }
+        if self
+            .event_date
+            .is_some_and(|date| !valid_dates.contains(&date))
+        {
+            return Some("wrong_date");
+        }
+        None
+    }
 
-    if let Some(row) = ticket_row {
-        use sqlx::Row;
-        let status: String = row.get("status");
-        return Ok(Some(ScanResult {
-            valid: true,
-            already_used: status == "used",
-            scan_type: "ticket".to_string(),
-            guest_name: row.try_get("guest_name").ok(),
-            num_people: None,
-            event_title: row.try_get("event_title").ok(),
-            table_name: None,
+    fn already_used(&self) -> bool {
+        match self.scan_type.as_str() {
+            "ticket" => self.status == "used",
+            _ => {
+                self.status == "completed"
+                    || self.checked_in_count.unwrap_or(0) >= self.num_people.unwrap_or(0)
+            }
+        }
+    }
+
+    fn into_scan_result(self, code: &str, reason: Option<&str>) -> ScanResult {
+        let min_age = self.min_age.filter(|age| *age > 0);
+        let event_day = self.event_date.unwrap_or_else(|| Utc::now().date_naive());
+        let holder_age = min_age
+            .and(self.holder_date_of_birth)
+            .map(|date_of_birth| age_on(date_of_birth, event_day));
+        ScanResult {
+            valid: !matches!(reason, Some("cancelled" | "wrong_date")),
+            already_used: self.already_used(),
+            scan_type: self.scan_type,
+            guest_name: self.guest_name,
+            num_people: self.num_people,
+            event_title: Some(self.event_title),
+            table_name: self.table_name,
+            checked_in_count: self.checked_in_count,
+            reason: reason.map(str::to_string),
+            min_age,
+            holder_age,
+            under_age: matches!((min_age, holder_age), (Some(min), Some(age)) if age < min),
             code: code.to_string(),
-        }));
+        }
     }
+}
 
-    // Try reservation
-    let res_row = sqlx::query(
+async fn find_club_code(
+    executor: impl sqlx::PgExecutor<'_>,
+    club_id: Uuid,
+    code: &str,
+) -> Result<Option<ClubCode>> {
+    sqlx::query_as::<_, ClubCode>(
         r#"
-        SELECT tr.reservation_code, tr.status, tr.contact_name, tr.num_people,
-               e.title as event_title, tbl.name as table_name
+        SELECT 'ticket' AS scan_type, t.id, t.event_id, e.title AS event_title, e.event_date,
+               t.status, u.name AS guest_name, NULL::INT AS num_people,
+               NULL::INT AS checked_in_count, NULL::VARCHAR AS table_name,
+               e.min_age, u.date_of_birth AS holder_date_of_birth
+        FROM tickets t
+        JOIN users u ON u.id = t.user_id
+        JOIN events e ON e.id = t.event_id
+        WHERE t.ticket_code = $1
+          AND e.club_id = $2
+        UNION ALL
+        SELECT 'reservation' AS scan_type, tr.id, tr.event_id, e.title AS event_title,
+               e.event_date, tr.status, tr.contact_name AS guest_name, tr.num_people,
+               tr.checked_in_count, tbl.name AS table_name,
+               e.min_age, u.date_of_birth AS holder_date_of_birth
         FROM table_reservations tr
         JOIN events e ON e.id = tr.event_id
         JOIN tables tbl ON tbl.id = tr.table_id
+        JOIN users u ON u.id = tr.user_id
         WHERE tr.reservation_code = $1
+          AND e.club_id = $2
+        LIMIT 1
         "#,
     )
     .bind(code)
-    .fetch_optional(pool)
-    .await?;
+    .bind(club_id)
+    .fetch_optional(executor)
+    .await
+}
 
-    if let Some(row) = res_row {
-        use sqlx::Row;
-        let status: String = row.get("status");
-        return Ok(Some(ScanResult {
-            valid: true,
-            already_used: status == "completed",
-            scan_type: "reservation".to_string(),
-            guest_name: row.try_get("contact_name").ok(),
-            num_people: row.try_get("num_people").ok(),
-            event_title: row.try_get("event_title").ok(),
-            table_name: row.try_get("table_name").ok(),
-            code: code.to_string(),
-        }));
-    }
+struct CheckinAudit<'a> {
+    club_id: Uuid,
+    scanned_by: Uuid,
+    code: &'a str,
+    action: &'a str,
+    people_count: i32,
+    outcome: &'a str,
+}
+
+async fn insert_checkin_audit(
+    executor: impl sqlx::PgExecutor<'_>,
+    audit: CheckinAudit<'_>,
+    found: Option<&ClubCode>,
+) -> Result<()> {
+    let scan_type = found.map_or("unknown", |c| c.scan_type.as_str());
+    let ticket_id = found.filter(|c| c.scan_type == "ticket").map(|c| c.id);
+    let reservation_id = found.filter(|c| c.scan_type == "reservation").map(|c| c.id);
 
-    Ok(None)
+    sqlx::query(
+        r#"
+        INSERT INTO checkin_audit_log (
+            club_id, event_id, scanned_by, code, action, scan_type,
+            ticket_id, reservation_id, people_count, outcome
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+    )
+    .bind(audit.club_id)
+    .bind(found.map(|c| c.event_id))
+    .bind(audit.scanned_by)
+    .bind(audit.code)
+    .bind(audit.action)
+    .bind(scan_type)
+    .bind(ticket_id)
+    .bind(reservation_id)
+    .bind(audit.people_count)
+    .bind(audit.outcome)
+    .execute(executor)
+    .await?;
+    Ok(())
 }
 
-pub async fn checkin_by_code(pool: &PgPool, code: &str) -> Result<Option<ScanResult>> {
-    // Try ticket checkin
-    let ticket_updated = sqlx::query(
-        r#"UPDATE tickets SET status = 'used', updated_at = NOW() WHERE ticket_code = $1 AND status != 'used'"#,
+/// Read-only lookup of a code belonging to `club_id`; codes of other clubs are
+/// reported as not found. The lookup is recorded in the check-in audit log.
+pub async fn scan_code(
+    pool: &PgPool,
+    club_id: Uuid,
+    scanned_by: Uuid,
+    code: &str,
+    valid_dates: &[NaiveDate],
+) -> Result<Option<ScanResult>> {
+    let found = find_club_code(pool, club_id, code).await?;
+
+    let reason = found.as_ref().and_then(|c| c.blocking_reason(valid_dates));
+    let outcome = match (&found, reason) {
+        (None, _) => "not_found",
+        (Some(_), Some(reason)) => reason,
+        (Some(c), None) if c.already_used() => "already_used",
+        (Some(_), None) => "success",
+    };
+    insert_checkin_audit(
+        pool,
+        CheckinAudit {
+            club_id,
+            scanned_by,
+            code,
+            action: "scan",
+            people_count: 0,
+            outcome,
+        },
+        found.as_ref(),
     )
-    .bind(code)
-    .execute(pool)
     .await?;
 
-    if ticket_updated.rows_affected() > 0 {
-        let row = sqlx::query(
-            r#"
-            SELECT t.ticket_code, u.name as guest_name, e.title as event_title
-            FROM tickets t
-            JOIN users u ON u.id = t.user_id
-            JOIN events e ON e.id = t.event_id
-            WHERE t.ticket_code = $1
-            "#,
+    Ok(found.map(|c| c.into_scan_result(code, reason)))
+}
+
+/// Check in a code belonging to `club_id` on its event day.
+///
+/// Tickets are marked used. Reservations admit `people` guests (default: everyone
+/// still missing) and become `completed` once all `num_people` are in. The attempt
+/// and its outcome are written to the audit log in the same transaction.
+pub async fn checkin_by_code(
+    pool: &PgPool,
+    club_id: Uuid,
+    scanned_by: Uuid,
+    code: &str,
+    people: Option<i32>,
+    valid_dates: &[NaiveDate],
+) -> Result<Option<ScanResult>> {
+    let mut tx = pool.begin().await?;
+
+    let Some(mut found) = find_club_code(&mut *tx, club_id, code).await? else {
+        insert_checkin_audit(
+            &mut *tx,
+            CheckinAudit {
+                club_id,
+                scanned_by,
+                code,
+                action: "checkin",
+                people_count: 0,
+                outcome: "not_found",
+            },
+            None,
         )
-        .bind(code)
-        .fetch_optional(pool)
         .await?;
-
-        if let Some(r) = row {
-            use sqlx::Row;
-            return Ok(Some(ScanResult {
-                valid: true,
-                already_used: false,
-                scan_type: "ticket".to_string(),
-                guest_name: r.try_get("guest_name").ok(),
-                num_people: None,
-                event_title: r.try_get("event_title").ok(),
-                table_name: None,
-                code: code.to_string(),
-            }));
+        tx.commit().await?;
+        return Ok(None);
+    };
+
+    let mut reason = found.blocking_reason(valid_dates);
+    let mut admitted = 0;
+    let mut already_used = false;
+
+    if reason.is_none() {
+        if found.scan_type == "ticket" {
+            let updated = sqlx::query(
+                "UPDATE tickets SET status = 'used', updated_at = NOW() WHERE id = $1 AND status = 'active'",
+            )
+            .bind(found.id)
+            .execute(&mut *tx)
+            .await?;
+            if updated.rows_affected() > 0 {
+                admitted = 1;
+            } else {
+                already_used = true;
+            }
+        } else {
+            let num_people = found.num_people.unwrap_or(0);
+            let remaining = num_people - found.checked_in_count.unwrap_or(0);
+            let requested = people.unwrap_or(remaining);
+
+            if remaining <= 0 {
+                already_used = true;
+            } else if requested > remaining {
+                reason = Some("exceeds_remaining");
+            } else {
+                // Conditional on the count we read so concurrent scans cannot over-admit
+                let updated: Option<(i32, String)> = sqlx::query_as(
+                    r#"
+                    UPDATE table_reservations
+                    SET checked_in_count = checked_in_count + $2,
+                        status = CASE WHEN checked_in_count + $2 >= num_people
+                                      THEN 'completed' ELSE status END,
+                        checked_in_at = COALESCE(checked_in_at, NOW()),
+                        updated_at = NOW()
+                    WHERE id = $1
+                      AND status IN ('pending', 'confirmed')
+                      AND checked_in_count + $2 <= num_people
+                    RETURNING checked_in_count, status
+                    "#,
+                )
+                .bind(found.id)
+                .bind(requested)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+                match updated {
+                    Some((checked_in_count, status)) => {
+                        admitted = requested;
+                        found.checked_in_count = Some(checked_in_count);
+                        found.status = status;
+                    }
+                    None => reason = Some("exceeds_remaining"),
+                }
+            }
         }
+    } else if found.already_used() {
+        already_used = true;
     }
 
-    // Try reservation checkin
-    let res_updated = sqlx::query(
-        r#"UPDATE table_reservations SET status = 'completed', updated_at = NOW() WHERE reservation_code = $1 AND status != 'completed'"#,
+    let outcome = match reason {
+        Some(reason) => reason,
+        None if already_used => "already_used",
+        None if found.scan_type == "reservation" && found.status != "completed" => "partial",
+        None => "success",
+    };
+    insert_checkin_audit(
+        &mut *tx,
+        CheckinAudit {
+            club_id,
+            scanned_by,
+            code,
+            action: "checkin",
+            people_count: admitted,
+            outcome,
+        },
+        Some(&found),
     )
-    .bind(code)
-    .execute(pool)
     .await?;
+    tx.commit().await?;
 
-    if res_updated.rows_affected() > 0 {
-        let row = sqlx::query(
-            r#"
-            SELECT tr.contact_name, tr.num_people, e.title as event_title, tbl.name as table_name
-            FROM table_reservations tr
-            JOIN events e ON e.id = tr.event_id
-            JOIN tables tbl ON tbl.id = tr.table_id
-            WHERE tr.reservation_code = $1
-            "#,
-        )
-        .bind(code)
-        .fetch_optional(pool)
-        .await?;
-
-        if let Some(r) = row {
-            use sqlx::Row;
-            return Ok(Some(ScanResult {
-                valid: true,
-                already_used: false,
-                scan_type: "reservation".to_string(),
-                guest_name: r.try_get("contact_name").ok(),
-                num_people: r.try_get("num_people").ok(),
-                event_title: r.try_get("event_title").ok(),
-                table_name: r.try_get("table_name").ok(),
-                code: code.to_string(),
-            }));
-        }
-    }
+    let mut result = found.into_scan_result(code, reason);
+    // A successful check-in reports the code as not previously used
+    result.already_used = already_used;
+    Ok(Some(result))
+}
 
-    // Code was valid but already used
-    let existing = scan_code(pool, code).await?;
-    Ok(existing.map(|mut r| {
-        r.already_used = true;
-        r
-    }))
+pub async fn get_checkin_audit_log(
+    pool: &PgPool,
+    club_id: Uuid,
+    event_id: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<CheckinAuditEntry>> {
+    sqlx::query_as::<_, CheckinAuditEntry>(
+        r#"
+        SELECT l.*, COALESCE(o.name, s.name) AS scanned_by_name
+        FROM checkin_audit_log l
+        LEFT JOIN club_owners o ON o.id = l.scanned_by
+        LEFT JOIN club_staff s ON s.id = l.scanned_by
+        WHERE l.club_id = $1
+          AND ($2::UUID IS NULL OR l.event_id = $2)
+        ORDER BY l.created_at DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(club_id)
+    .bind(event_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
 }
 
 // ============================================================================
 // Owner stats
 // ============================================================================
 
+/// All-time counters of the club; ranged figures are in the owner analytics
+/// repository.
 pub async fn get_owner_stats(pool: &PgPool, club_id: Uuid) -> Result<OwnerStats> {
     let active_reservations: i64 = sqlx::query_scalar(
         r#"
@@ -512,19 +756,32 @@ pub async fn get_owner_stats(pool: &PgPool, club_id: Uuid) -> Result<OwnerStats>
     .fetch_one(pool)
     .await?;
 
+    let (promo_redemptions, promo_discount_total): (i64, Decimal) = sqlx::query_as(
+        r#"
+        SELECT COUNT(r.id), COALESCE(SUM(r.discount_amount), 0)
+        FROM promo_code_redemptions r
+        JOIN promo_codes p ON p.id = r.promo_code_id
+        WHERE p.club_id = $1
+          AND r.status = 'redeemed'
+        "#,
+    )
+    .bind(club_id)
+    .fetch_one(pool)
+    .await?;
+
     let event_rows = sqlx::query(
         r#"
         SELECT
             e.id::text as event_id,
             e.title,
             e.date,
-            COUNT(tr.id) FILTER (WHERE tr.status IN ('confirmed', 'pending', 'completed')) as reserved_tables,
-            COUNT(t.id) as total_tables
+            (SELECT COUNT(DISTINCT tr.table_id)
+             FROM table_reservations tr
+             WHERE tr.event_id = e.id
+               AND tr.status IN ('confirmed', 'pending', 'completed')) as reserved_tables,
+            (SELECT COUNT(*) FROM tables t WHERE t.event_id = e.id) as total_tables
         FROM events e
-        LEFT JOIN tables t ON t.event_id = e.id
-        LEFT JOIN table_reservations tr ON tr.event_id = e.id
         WHERE e.club_id = $1
-        GROUP BY e.id, e.title, e.date
         ORDER BY e.created_at DESC
         "#,
     )
@@ -547,6 +804,8 @@ pub async fn get_owner_stats(pool: &PgPool, club_id: Uuid) -> Result<OwnerStats>
     Ok(OwnerStats {
         active_reservations,
         total_revenue: total_revenue.unwrap_or(Decimal::ZERO),
+        promo_redemptions,
+        promo_discount_total,
         events,
     })
 }
@@ -1,7 +1,19 @@
+use crate::models::club::NearbyClubRow;
 use crate::models::{Club, CreateClubRequest, UpdateClubRequest};
+use rust_decimal::Decimal;
 use sqlx::{PgPool, Result};
 use uuid::Uuid;
 
+/// Great-circle (haversine) distance in km between club `c` and an `origin`
+/// relation with `lat`/`lng` columns. NULL when the club has no coordinates.
+pub const CLUB_DISTANCE_KM: &str = r#"
+    (6371.0 * 2 * asin(sqrt(
+        power(sin(radians(c.latitude - origin.lat) / 2), 2)
+        + cos(radians(origin.lat)) * cos(radians(c.latitude))
+          * power(sin(radians(c.longitude - origin.lng) / 2), 2)
+    )))
+"#;
+
 /// Get all clubs
 pub async fn get_all_clubs(pool: &PgPool) -> Result<Vec<Club>> {
     let clubs = sqlx::query_as::<_, Club>(
@@ -9,7 +21,7 @@ pub async fn get_all_clubs(pool: &PgPool) -> Result<Vec<Club>> {
         SELECT id, name, subtitle, image, address, phone_number, website, owner_id,
                stripe_connected_account_id, stripe_onboarding_complete, stripe_charges_enabled,
                stripe_payouts_enabled, platform_commission_percent, platform_commission_fixed_fee,
-               created_at, updated_at
+               latitude, longitude, coordinates_source, created_at, updated_at
         FROM clubs
         ORDER BY name ASC
         "#,
@@ -27,7 +39,7 @@ pub async fn get_club_by_id(pool: &PgPool, club_id: Uuid) -> Result<Option<Club>
         SELECT id, name, subtitle, image, address, phone_number, website, owner_id,
                stripe_connected_account_id, stripe_onboarding_complete, stripe_charges_enabled,
                stripe_payouts_enabled, platform_commission_percent, platform_commission_fixed_fee,
-               created_at, updated_at
+               latitude, longitude, coordinates_source, created_at, updated_at
         FROM clubs
         WHERE id = $1
         "#,
@@ -46,7 +58,7 @@ pub async fn get_club_by_owner_id(pool: &PgPool, owner_id: Uuid) -> Result<Optio
         SELECT id, name, subtitle, image, address, phone_number, website, owner_id,
                stripe_connected_account_id, stripe_onboarding_complete, stripe_charges_enabled,
                stripe_payouts_enabled, platform_commission_percent, platform_commission_fixed_fee,
-               created_at, updated_at
+               latitude, longitude, coordinates_source, created_at, updated_at
         FROM clubs
         WHERE owner_id = $1
         "#,
@@ -72,7 +84,7 @@ pub async fn create_club(pool: &PgPool, request: CreateClubRequest) -> Result<Cl
         RETURNING id, name, subtitle, image, address, phone_number, website, owner_id,
                   stripe_connected_account_id, stripe_onboarding_complete, stripe_charges_enabled,
                   stripe_payouts_enabled, platform_commission_percent, platform_commission_fixed_fee,
-                  created_at, updated_at
+                  latitude, longitude, coordinates_source, created_at, updated_at
         "#,
     )
     .bind(Uuid::new_v4())
@@ -118,12 +130,16 @@ pub async fn update_club(
             stripe_payouts_enabled = COALESCE($11, stripe_payouts_enabled),
             platform_commission_percent = COALESCE($12, platform_commission_percent),
             platform_commission_fixed_fee = COALESCE($13, platform_commission_fixed_fee),
+            -- A new address drops the old coordinates; the geocoding job looks it up again
+            latitude = CASE WHEN $4 IS DISTINCT FROM address AND $4 IS NOT NULL THEN NULL ELSE latitude END,
+            longitude = CASE WHEN $4 IS DISTINCT FROM address AND $4 IS NOT NULL THEN NULL ELSE longitude END,
+            coordinates_source = CASE WHEN $4 IS DISTINCT FROM address AND $4 IS NOT NULL THEN NULL ELSE coordinates_source END,
             updated_at = NOW()
         WHERE id = $14
         RETURNING id, name, subtitle, image, address, phone_number, website, owner_id,
                   stripe_connected_account_id, stripe_onboarding_complete, stripe_charges_enabled,
                   stripe_payouts_enabled, platform_commission_percent, platform_commission_fixed_fee,
-                  created_at, updated_at
+                  latitude, longitude, coordinates_source, created_at, updated_at
         "#,
     )
     .bind(request.name)
@@ -146,6 +162,154 @@ pub async fn update_club(
     Ok(club)
 }
 
+/// Set the platform commission charged on a club's payments
+pub async fn set_club_commission(
+    pool: &PgPool,
+    club_id: Uuid,
+    commission_percent: Decimal,
+    fixed_fee: Decimal,
+) -> Result<Option<Club>> {
+    let club = sqlx::query_as::<_, Club>(
+        r#"
+        UPDATE clubs
+        SET platform_commission_percent = $1,
+            platform_commission_fixed_fee = $2,
+            updated_at = NOW()
+        WHERE id = $3
+        RETURNING id, name, subtitle, image, address, phone_number, website, owner_id,
+                  stripe_connected_account_id, stripe_onboarding_complete, stripe_charges_enabled,
+                  stripe_payouts_enabled, platform_commission_percent, platform_commission_fixed_fee,
+                  latitude, longitude, coordinates_source, created_at, updated_at
+        "#,
+    )
+    .bind(commission_percent)
+    .bind(fixed_fee)
+    .bind(club_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(club)
+}
+
+/// Pin a club's coordinates by hand; the geocoder leaves them alone until the
+/// address changes.
+pub async fn set_manual_coordinates(
+    pool: &PgPool,
+    club_id: Uuid,
+    latitude: f64,
+    longitude: f64,
+) -> Result<Option<Club>> {
+    let club = sqlx::query_as::<_, Club>(
+        r#"
+        UPDATE clubs
+        SET latitude = $1,
+            longitude = $2,
+            coordinates_source = 'manual',
+            updated_at = NOW()
+        WHERE id = $3
+        RETURNING id, name, subtitle, image, address, phone_number, website, owner_id,
+                  stripe_connected_account_id, stripe_onboarding_complete, stripe_charges_enabled,
+                  stripe_payouts_enabled, platform_commission_percent, platform_commission_fixed_fee,
+                  latitude, longitude, coordinates_source, created_at, updated_at
+        "#,
+    )
+    .bind(latitude)
+    .bind(longitude)
+    .bind(club_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(club)
+}
+
+/// Clubs whose current address has not been looked up yet (new clubs, changed
+/// addresses), oldest change first. Manually pinned clubs are skipped.
+pub async fn get_clubs_to_geocode(pool: &PgPool, limit: i64) -> Result<Vec<(Uuid, String)>> {
+    sqlx::query_as(
+        r#"
+        SELECT id, address
+        FROM clubs
+        WHERE address IS NOT NULL
+          AND btrim(address) <> ''
+          AND coordinates_source IS DISTINCT FROM 'manual'
+          AND geocoded_address IS DISTINCT FROM address
+        ORDER BY updated_at ASC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Store the geocoder's answer for `address` (`None`: not found, so it is not
+/// retried until the address changes). Ignored if the address changed or the
+/// owner pinned the club in the meantime.
+pub async fn record_geocoding_result(
+    pool: &PgPool,
+    club_id: Uuid,
+    address: &str,
+    coordinates: Option<(f64, f64)>,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE clubs
+        SET latitude = $3,
+            longitude = $4,
+            coordinates_source = CASE WHEN $3::float8 IS NULL THEN NULL ELSE 'geocoder' END,
+            geocoded_address = $2,
+            geocoded_at = NOW()
+        WHERE id = $1
+          AND address = $2
+          AND coordinates_source IS DISTINCT FROM 'manual'
+        "#,
+    )
+    .bind(club_id)
+    .bind(address)
+    .bind(coordinates.map(|(latitude, _)| latitude))
+    .bind(coordinates.map(|(_, longitude)| longitude))
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Clubs within `radius_km` of (`latitude`, `longitude`), closest first.
+pub async fn get_nearby_clubs(
+    pool: &PgPool,
+    latitude: f64,
+    longitude: f64,
+    radius_km: f64,
+    limit: i64,
+) -> Result<Vec<NearbyClubRow>> {
+    sqlx::query_as::<_, NearbyClubRow>(&format!(
+        r#"
+        SELECT * FROM (
+            SELECT c.id, c.name, c.subtitle, c.image, c.address, c.phone_number, c.website,
+                   c.owner_id, c.stripe_connected_account_id, c.stripe_onboarding_complete,
+                   c.stripe_charges_enabled, c.stripe_payouts_enabled,
+                   c.platform_commission_percent, c.platform_commission_fixed_fee,
+                   c.latitude, c.longitude, c.coordinates_source, c.created_at, c.updated_at,
+                   {CLUB_DISTANCE_KM} AS distance_km
+            FROM clubs c
+            CROSS JOIN (SELECT $1::float8 AS lat, $2::float8 AS lng) origin
+            WHERE c.latitude IS NOT NULL
+              -- 1° of latitude is ~111 km: cheap prefilter on idx_clubs_coordinates
+              AND c.latitude BETWEEN origin.lat - $3 / 111.0 AND origin.lat + $3 / 111.0
+        ) nearby
+        WHERE distance_km <= $3
+        ORDER BY distance_km ASC, id ASC
+        LIMIT $4
+        "#
+    ))
+    .bind(latitude)
+    .bind(longitude)
+    .bind(radius_km)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
 /// Delete a club
 pub async fn delete_club(pool: &PgPool, club_id: Uuid) -> Result<bool> {
     let result = sqlx::query(
@@ -160,3 +324,34 @@ pub async fn delete_club(pool: &PgPool, club_id: Uuid) -> Result<bool> {
 
     Ok(result.rows_affected() > 0)
 }
+
+/// Apply a Connect account's capabilities from `account.updated`. Returns the
+/// club and whether it could take charges before, or `None` for an account
+/// no club is linked to.
+pub async fn sync_stripe_account_status(
+    pool: &PgPool,
+    stripe_account_id: &str,
+    onboarding_complete: bool,
+    charges_enabled: bool,
+    payouts_enabled: bool,
+) -> Result<Option<(Uuid, bool)>> {
+    sqlx::query_as::<_, (Uuid, bool)>(
+        r#"
+        UPDATE clubs c
+        SET stripe_onboarding_complete = $2,
+            stripe_charges_enabled = $3,
+            stripe_payouts_enabled = $4,
+            updated_at = NOW()
+        FROM clubs previous
+        WHERE c.stripe_connected_account_id = $1
+          AND previous.id = c.id
+        RETURNING c.id, previous.stripe_charges_enabled
+        "#,
+    )
+    .bind(stripe_account_id)
+    .bind(onboarding_complete)
+    .bind(charges_enabled)
+    .bind(payouts_enabled)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,355 @@
+use crate::models::connect_reconciliation::{
+    cents_to_decimal, ClubConnectTotalsRow, ClubPaymentRow, ConnectActivity, ConnectPayoutRow,
+    ConnectSyncTarget, ReconciliationPairRow,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+/// Payments with a payment intent and the club they were made to, one row
+/// each: reservation shares, legacy reservation payments and ticket orders.
+const CLUB_PAYMENTS: &str = r#"
+    WITH club_payments AS (
+        SELECT DISTINCT ON (p.id)
+               p.id AS payment_id, l.club_id, p.stripe_payment_intent_id, p.status,
+               p.amount, p.refunded_amount,
+               COALESCE(p.insert_date, p.update_date) AT TIME ZONE 'UTC' AS paid_at
+        FROM payments p
+        JOIN (
+            SELECT s.payment_id, e.club_id
+            FROM reservation_payment_shares s
+            JOIN table_reservations tr ON tr.id = s.reservation_id
+            JOIN events e ON e.id = tr.event_id
+            UNION ALL
+            SELECT trp.payment_id, e.club_id
+            FROM table_reservation_payments trp
+            JOIN table_reservations tr ON tr.id = trp.reservation_id
+            JOIN events e ON e.id = tr.event_id
+            UNION ALL
+            SELECT o.payment_id, e.club_id
+            FROM ticket_orders o
+            JOIN events e ON e.id = o.event_id
+        ) l ON l.payment_id = p.id
+        WHERE p.stripe_payment_intent_id IS NOT NULL
+          AND l.club_id IS NOT NULL
+        ORDER BY p.id
+    )
+"#;
+
+/// Clubs with a connected account, with where their last sync stopped.
+pub async fn get_sync_targets(pool: &PgPool) -> Result<Vec<ConnectSyncTarget>> {
+    sqlx::query_as::<_, ConnectSyncTarget>(
+        r#"
+        SELECT c.id AS club_id, c.stripe_connected_account_id AS connected_account_id,
+               ss.synced_until
+        FROM clubs c
+        LEFT JOIN connect_sync_state ss ON ss.club_id = c.id
+        WHERE c.stripe_connected_account_id IS NOT NULL
+          AND c.stripe_connected_account_id <> ''
+        ORDER BY ss.synced_until ASC NULLS FIRST
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Completed payments of the club since `since`, with its commission.
+pub async fn get_club_payments_since(
+    pool: &PgPool,
+    club_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<Vec<ClubPaymentRow>> {
+    sqlx::query_as::<_, ClubPaymentRow>(&format!(
+        r#"
+        {CLUB_PAYMENTS}
+        SELECT cp.stripe_payment_intent_id, cp.amount, cp.refunded_amount,
+               cp.paid_at, c.platform_commission_percent, c.platform_commission_fixed_fee
+        FROM club_payments cp
+        JOIN clubs c ON c.id = cp.club_id
+        WHERE cp.club_id = $1
+          AND cp.status = 'completed'
+          AND cp.paid_at >= $2
+        ORDER BY cp.paid_at ASC
+        "#
+    ))
+    .bind(club_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Upsert what the provider reported for a club and move its sync cursor.
+pub async fn save_connect_activity(
+    pool: &PgPool,
+    club_id: Uuid,
+    connected_account_id: &str,
+    activity: &ConnectActivity,
+    synced_until: DateTime<Utc>,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    for transfer in &activity.transfers {
+        sqlx::query(
+            r#"
+            INSERT INTO connect_transfers (
+                id, club_id, connected_account_id, amount, amount_reversed, currency,
+                charge_id, payment_intent_id, charge_amount, application_fee_amount,
+                stripe_created_at, synced_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                amount = EXCLUDED.amount,
+                amount_reversed = EXCLUDED.amount_reversed,
+                charge_id = EXCLUDED.charge_id,
+                payment_intent_id = EXCLUDED.payment_intent_id,
+                charge_amount = EXCLUDED.charge_amount,
+                application_fee_amount = EXCLUDED.application_fee_amount,
+                synced_at = NOW()
+            "#,
+        )
+        .bind(&transfer.id)
+        .bind(club_id)
+        .bind(connected_account_id)
+        .bind(cents_to_decimal(transfer.amount_cents))
+        .bind(cents_to_decimal(transfer.amount_reversed_cents))
+        .bind(&transfer.currency)
+        .bind(&transfer.charge_id)
+        .bind(&transfer.payment_intent_id)
+        .bind(transfer.charge_amount_cents.map(cents_to_decimal))
+        .bind(transfer.application_fee_cents.map(cents_to_decimal))
+        .bind(transfer.created_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for payout in &activity.payouts {
+        sqlx::query(
+            r#"
+            INSERT INTO connect_payouts (
+                id, club_id, connected_account_id, amount, currency, status, arrival_date,
+                failure_message, stripe_created_at, synced_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                amount = EXCLUDED.amount,
+                status = EXCLUDED.status,
+                arrival_date = EXCLUDED.arrival_date,
+                failure_message = EXCLUDED.failure_message,
+                synced_at = NOW()
+            "#,
+        )
+        .bind(&payout.id)
+        .bind(club_id)
+        .bind(connected_account_id)
+        .bind(cents_to_decimal(payout.amount_cents))
+        .bind(&payout.currency)
+        .bind(&payout.status)
+        .bind(payout.arrival_date)
+        .bind(&payout.failure_message)
+        .bind(payout.created_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for transaction in &activity.balance_transactions {
+        sqlx::query(
+            r#"
+            INSERT INTO connect_balance_transactions (
+                id, club_id, connected_account_id, type, amount, fee, net, currency,
+                source_id, available_on, stripe_created_at, synced_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                available_on = EXCLUDED.available_on,
+                synced_at = NOW()
+            "#,
+        )
+        .bind(&transaction.id)
+        .bind(club_id)
+        .bind(connected_account_id)
+        .bind(&transaction.kind)
+        .bind(cents_to_decimal(transaction.amount_cents))
+        .bind(cents_to_decimal(transaction.fee_cents))
+        .bind(cents_to_decimal(transaction.net_cents))
+        .bind(&transaction.currency)
+        .bind(&transaction.source_id)
+        .bind(transaction.available_on)
+        .bind(transaction.created_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO connect_sync_state (club_id, synced_until, last_error, updated_at)
+        VALUES ($1, $2, NULL, NOW())
+        ON CONFLICT (club_id) DO UPDATE SET
+            synced_until = EXCLUDED.synced_until,
+            last_error = NULL,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(club_id)
+    .bind(synced_until)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// Keep the club's sync cursor and remember why the last sync failed.
+pub async fn record_sync_error(pool: &PgPool, club_id: Uuid, error: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO connect_sync_state (club_id, last_error, updated_at)
+        VALUES ($1, $2, NOW())
+        ON CONFLICT (club_id) DO UPDATE SET
+            last_error = EXCLUDED.last_error,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(club_id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Period totals per club: transfers by creation day, payouts by arrival day,
+/// local payments by payment day. Without `club_id`, every club that has or
+/// had a connected account.
+pub async fn get_club_totals(
+    pool: &PgPool,
+    club_id: Option<Uuid>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<ClubConnectTotalsRow>> {
+    sqlx::query_as::<_, ClubConnectTotalsRow>(&format!(
+        r#"
+        {CLUB_PAYMENTS}
+        SELECT c.id AS club_id, c.name AS club_name,
+               c.stripe_connected_account_id AS connected_account_id,
+               ss.synced_until, ss.last_error AS last_sync_error,
+               COALESCE(t.gross, 0) AS gross,
+               COALESCE(t.platform_fees, 0) AS platform_fees,
+               COALESCE(t.transferred, 0) AS transferred,
+               COALESCE(t.reversed, 0) AS reversed,
+               t.transfers,
+               COALESCE(po.paid_out, 0) AS paid_out,
+               po.payouts,
+               po.failed_payouts,
+               COALESCE(lp.local_gross, 0) AS local_gross,
+               COALESCE(lp.local_refunded, 0) AS local_refunded
+        FROM clubs c
+        LEFT JOIN connect_sync_state ss ON ss.club_id = c.id
+        CROSS JOIN LATERAL (
+            SELECT SUM(COALESCE(ct.charge_amount,
+                                ct.amount + COALESCE(ct.application_fee_amount, 0))) AS gross,
+                   SUM(COALESCE(ct.application_fee_amount, 0)) AS platform_fees,
+                   SUM(ct.amount) AS transferred,
+                   SUM(ct.amount_reversed) AS reversed,
+                   COUNT(*) AS transfers
+            FROM connect_transfers ct
+            WHERE ct.club_id = c.id
+              AND (ct.stripe_created_at AT TIME ZONE 'UTC')::date BETWEEN $2 AND $3
+        ) t
+        CROSS JOIN LATERAL (
+            SELECT SUM(cpo.amount) FILTER (WHERE cpo.status = 'paid') AS paid_out,
+                   COUNT(*) AS payouts,
+                   COUNT(*) FILTER (WHERE cpo.status = 'failed') AS failed_payouts
+            FROM connect_payouts cpo
+            WHERE cpo.club_id = c.id
+              AND cpo.arrival_date BETWEEN $2 AND $3
+        ) po
+        CROSS JOIN LATERAL (
+            SELECT SUM(cp.amount) AS local_gross, SUM(cp.refunded_amount) AS local_refunded
+            FROM club_payments cp
+            WHERE cp.club_id = c.id
+              AND cp.status = 'completed'
+              AND (cp.paid_at AT TIME ZONE 'UTC')::date BETWEEN $2 AND $3
+        ) lp
+        WHERE ($1::uuid IS NULL OR c.id = $1)
+          AND ($1::uuid IS NOT NULL
+               OR c.stripe_connected_account_id IS NOT NULL
+               OR ss.club_id IS NOT NULL)
+        ORDER BY COALESCE(t.gross, 0) DESC, c.name ASC
+        "#
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Payouts arriving in the period, newest first.
+pub async fn get_payouts(
+    pool: &PgPool,
+    club_id: Option<Uuid>,
+    from: NaiveDate,
+    to: NaiveDate,
+    failed_only: bool,
+) -> Result<Vec<ConnectPayoutRow>> {
+    sqlx::query_as::<_, ConnectPayoutRow>(
+        r#"
+        SELECT club_id, id, amount, currency, status, arrival_date, failure_message
+        FROM connect_payouts
+        WHERE ($1::uuid IS NULL OR club_id = $1)
+          AND arrival_date BETWEEN $2 AND $3
+          AND (NOT $4 OR status = 'failed')
+        ORDER BY arrival_date DESC, stripe_created_at DESC
+        "#,
+    )
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .bind(failed_only)
+    .fetch_all(pool)
+    .await
+}
+
+/// Local payments and synced transfers of the period matched by payment
+/// intent. Payments that are not completed and have no transfer are left out.
+pub async fn get_reconciliation_pairs(
+    pool: &PgPool,
+    club_id: Option<Uuid>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<ReconciliationPairRow>> {
+    sqlx::query_as::<_, ReconciliationPairRow>(&format!(
+        r#"
+        {CLUB_PAYMENTS}
+        SELECT COALESCE(cp.club_id, ct.club_id) AS club_id,
+               cp.payment_id,
+               COALESCE(cp.stripe_payment_intent_id, ct.payment_intent_id) AS payment_intent_id,
+               cp.status AS local_status,
+               cp.amount AS local_amount,
+               cp.refunded_amount AS local_refunded,
+               cp.paid_at,
+               ct.id AS transfer_id,
+               ct.charge_amount,
+               ct.amount_reversed,
+               ct.stripe_created_at AS transferred_at,
+               ft.first_transfer_at
+        FROM club_payments cp
+        FULL JOIN connect_transfers ct
+          ON ct.payment_intent_id = cp.stripe_payment_intent_id
+         AND ct.club_id = cp.club_id
+        LEFT JOIN LATERAL (
+            SELECT MIN(f.stripe_created_at) AS first_transfer_at
+            FROM connect_transfers f
+            WHERE f.club_id = COALESCE(cp.club_id, ct.club_id)
+        ) ft ON TRUE
+        WHERE ($1::uuid IS NULL OR COALESCE(cp.club_id, ct.club_id) = $1)
+          AND (ct.id IS NOT NULL OR cp.status = 'completed')
+          AND (COALESCE(cp.paid_at, ct.stripe_created_at) AT TIME ZONE 'UTC')::date
+              BETWEEN $2 AND $3
+        ORDER BY COALESCE(cp.paid_at, ct.stripe_created_at) ASC
+        "#
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
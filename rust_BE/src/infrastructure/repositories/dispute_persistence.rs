@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+use crate::models::dispute::{DisputeContext, RecordedDispute, StripeDispute};
+
+/// Payment, share or ticket order paid with `stripe_payment_intent_id`, and
+/// the club it belongs to. Every field is `None` for charges made elsewhere.
+pub async fn find_dispute_context(
+    pool: &PgPool,
+    stripe_payment_intent_id: &str,
+) -> Result<DisputeContext> {
+    sqlx::query_as::<_, DisputeContext>(
+        r#"
+        SELECT p.id            AS payment_id,
+               s.id            AS payment_share_id,
+               s.reservation_id,
+               o.id            AS ticket_order_id,
+               e.club_id
+        FROM (SELECT $1::TEXT AS intent_id) k
+        LEFT JOIN LATERAL (
+            SELECT id FROM payments WHERE stripe_payment_intent_id = k.intent_id LIMIT 1
+        ) p ON TRUE
+        LEFT JOIN LATERAL (
+            SELECT id, reservation_id
+            FROM reservation_payment_shares
+            WHERE stripe_payment_intent_id = k.intent_id OR payment_id = p.id
+            ORDER BY created_at
+            LIMIT 1
+        ) s ON TRUE
+        LEFT JOIN LATERAL (
+            SELECT id, event_id FROM ticket_orders WHERE stripe_payment_intent_id = k.intent_id LIMIT 1
+        ) o ON TRUE
+        LEFT JOIN table_reservations tr ON tr.id = s.reservation_id
+        LEFT JOIN events e ON e.id = COALESCE(tr.event_id, o.event_id)
+        "#,
+    )
+    .bind(stripe_payment_intent_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Inserts or updates a dispute from a webhook. A closed dispute keeps its
+/// outcome if an older `charge.dispute.created` is applied after it.
+pub async fn record_dispute(
+    pool: &PgPool,
+    dispute: &StripeDispute,
+    evidence_due_by: Option<DateTime<Utc>>,
+    context: &DisputeContext,
+) -> Result<RecordedDispute> {
+    sqlx::query_as::<_, RecordedDispute>(
+        r#"
+        WITH previous AS (
+            SELECT status, closed_at FROM payment_disputes WHERE stripe_dispute_id = $1
+        )
+        INSERT INTO payment_disputes (
+            stripe_dispute_id, stripe_charge_id, stripe_payment_intent_id, payment_id,
+            payment_share_id, reservation_id, ticket_order_id, club_id, amount, currency,
+            reason, status, evidence_due_by, closed_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,
+                CASE WHEN $14 THEN NOW() END)
+        ON CONFLICT (stripe_dispute_id) DO UPDATE
+        SET status          = CASE WHEN payment_disputes.closed_at IS NULL
+                                   THEN EXCLUDED.status ELSE payment_disputes.status END,
+            reason          = COALESCE(EXCLUDED.reason, payment_disputes.reason),
+            evidence_due_by = COALESCE(EXCLUDED.evidence_due_by, payment_disputes.evidence_due_by),
+            closed_at       = COALESCE(payment_disputes.closed_at, EXCLUDED.closed_at),
+            updated_at      = NOW()
+        RETURNING payment_disputes.id, payment_disputes.stripe_dispute_id,
+                  payment_disputes.payment_id, payment_disputes.payment_share_id,
+                  payment_disputes.reservation_id, payment_disputes.ticket_order_id,
+                  payment_disputes.club_id, payment_disputes.amount, payment_disputes.currency,
+                  payment_disputes.reason, payment_disputes.status,
+                  payment_disputes.evidence_due_by, payment_disputes.closed_at,
+                  (SELECT status FROM previous)              AS previous_status,
+                  COALESCE((SELECT closed_at IS NOT NULL FROM previous), FALSE) AS was_closed
+        "#,
+    )
+    .bind(&dispute.id)
+    .bind(&dispute.charge_id)
+    .bind(&dispute.payment_intent_id)
+    .bind(context.payment_id)
+    .bind(context.payment_share_id)
+    .bind(context.reservation_id)
+    .bind(context.ticket_order_id)
+    .bind(context.club_id)
+    .bind(dispute.amount)
+    .bind(&dispute.currency)
+    .bind(&dispute.reason)
+    .bind(&dispute.status)
+    .bind(evidence_due_by)
+    .bind(dispute.is_closed())
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn freeze_share(pool: &PgPool, share_id: Uuid) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE reservation_payment_shares
+        SET frozen_at = COALESCE(frozen_at, NOW()), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(share_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Unfreezes a share once no dispute against it is open any more.
+pub async fn release_share(pool: &PgPool, share_id: Uuid) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE reservation_payment_shares
+        SET frozen_at = NULL, updated_at = NOW()
+        WHERE id = $1
+          AND frozen_at IS NOT NULL
+          AND NOT EXISTS (
+              SELECT 1 FROM payment_disputes
+              WHERE payment_share_id = $1 AND closed_at IS NULL
+          )
+        "#,
+    )
+    .bind(share_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Email, phone and club name of the owner to tell about a dispute
+pub async fn get_club_owner_contact(
+    pool: &PgPool,
+    club_id: Uuid,
+) -> Result<Option<(String, Option<String>, String)>> {
+    sqlx::query_as::<_, (String, Option<String>, String)>(
+        r#"
+        SELECT co.email, co.phone_number, c.name
+        FROM clubs c
+        JOIN club_owners co ON co.id = c.owner_id
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(club_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -1,3 +1,5 @@
+use crate::infrastructure::repositories::{club_repository, event_series_repository};
+use crate::models::event_search::{EventSearchFilters, EventSearchRow, EventSearchSort};
 use crate::models::{CreateEventRequest, Event, GenreResponse, UpdateEventRequest};
 use chrono::NaiveDate;
 use sqlx::{PgPool, QueryBuilder, Result};
@@ -100,7 +102,7 @@ pub async fn set_event_genres(pool: &PgPool, event_id: Uuid, genre_ids: &[Uuid])
 pub async fn get_all_events(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<Event>> {
     let events = sqlx::query_as::<_, Event>(
         r#"
-        SELECT id, title, venue, date, image, status, time, age_limit, end_time, price, description, club_id,
+        SELECT id, title, venue, date, image, status, time, age_limit, min_age, transfers_enabled, end_time, price, description, club_id,
                tour_provider, marzipano_config, event_date, created_at, updated_at
         FROM events
         ORDER BY created_at DESC
@@ -115,11 +117,148 @@ pub async fn get_all_events(pool: &PgPool, limit: i64, offset: i64) -> Result<Ve
     Ok(events)
 }
 
+/// Discovery search over dated events from `filters.date_from` on. Returns up
+/// to `filters.limit + 1` rows so the caller can tell whether a next page exists.
+pub async fn search_events(
+    pool: &PgPool,
+    filters: &EventSearchFilters,
+) -> Result<Vec<EventSearchRow>> {
+    let mut qb: QueryBuilder<sqlx::Postgres> = QueryBuilder::new(
+        r#"
+        SELECT * FROM (
+            SELECT e.id, e.title, e.venue, e.date, e.image, e.status, e.time, e.age_limit, e.min_age,
+                   e.transfers_enabled, e.end_time, e.price, e.description, e.club_id, e.tour_provider,
+                   e.marzipano_config, e.event_date, e.created_at, e.updated_at,
+                   (SELECT COUNT(*) FROM table_reservations r
+                    WHERE r.event_id = e.id AND r.status <> 'cancelled')
+                   + (SELECT COUNT(*) FROM tickets tk WHERE tk.event_id = e.id) AS popularity,
+        "#,
+    );
+    match filters.origin {
+        Some(_) => qb.push(club_repository::CLUB_DISTANCE_KM),
+        None => qb.push("NULL::float8"),
+    };
+    qb.push(" AS distance_km FROM events e LEFT JOIN clubs c ON c.id = e.club_id ");
+    if let Some((lat, lng)) = filters.origin {
+        qb.push("CROSS JOIN (SELECT ")
+            .push_bind(lat)
+            .push("::float8 AS lat, ")
+            .push_bind(lng)
+            .push("::float8 AS lng) origin ");
+    }
+
+    qb.push("WHERE e.event_date IS NOT NULL AND e.event_date >= ")
+        .push_bind(filters.date_from);
+    if let Some(date_to) = filters.date_to {
+        qb.push(" AND e.event_date <= ").push_bind(date_to);
+    }
+    if let Some(text) = &filters.text {
+        qb.push(" AND e.search_vector @@ websearch_to_tsquery('italian', ")
+            .push_bind(text)
+            .push(")");
+    }
+    if !filters.genre_ids.is_empty() {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM event_genres eg WHERE eg.event_id = e.id AND eg.genre_id = ANY(",
+        )
+        .push_bind(&filters.genre_ids)
+        .push("))");
+    }
+    if let Some(club_id) = filters.club_id {
+        qb.push(" AND e.club_id = ").push_bind(club_id);
+    }
+    if let Some(min_price) = filters.min_price {
+        qb.push(" AND e.price_amount >= ").push_bind(min_price);
+    }
+    if let Some(max_price) = filters.max_price {
+        qb.push(" AND e.price_amount <= ").push_bind(max_price);
+    }
+    if let Some(max_age_limit) = filters.max_age_limit {
+        qb.push(" AND (e.min_age IS NULL OR e.min_age <= ")
+            .push_bind(max_age_limit)
+            .push(")");
+    }
+    if let Some(party_size) = filters.party_size {
+        // Same notion of a free table as the waitlist
+        qb.push(
+            r#"
+            AND EXISTS (
+                SELECT 1 FROM tables t
+                WHERE t.event_id = e.id
+                  AND t.available = true
+                  AND t.capacity >= "#,
+        )
+        .push_bind(party_size)
+        .push(
+            r#"
+                  AND NOT EXISTS (
+                      SELECT 1 FROM table_reservations r
+                      WHERE r.table_id = t.id
+                        AND r.status NOT IN ('cancelled', 'completed')
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM event_waitlist_entries w
+                      WHERE w.held_table_id = t.id
+                        AND w.status = 'offered'
+                  )
+            )
+            "#,
+        );
+    }
+    qb.push(") s WHERE TRUE");
+
+    if filters.sort == EventSearchSort::Distance {
+        qb.push(" AND s.distance_km IS NOT NULL");
+    }
+    if let Some(radius_km) = filters.radius_km {
+        qb.push(" AND s.distance_km <= ").push_bind(radius_km);
+    }
+    if let Some(after) = &filters.after {
+        match filters.sort {
+            EventSearchSort::Date => {
+                qb.push(" AND (s.event_date, s.id) > (")
+                    .push_bind(after.event_date)
+                    .push(", ")
+                    .push_bind(after.id)
+                    .push(")");
+            }
+            EventSearchSort::Popularity => {
+                let popularity = after.popularity.unwrap_or_default();
+                qb.push(" AND (s.popularity < ")
+                    .push_bind(popularity)
+                    .push(" OR (s.popularity = ")
+                    .push_bind(popularity)
+                    .push(" AND (s.event_date, s.id) > (")
+                    .push_bind(after.event_date)
+                    .push(", ")
+                    .push_bind(after.id)
+                    .push(")))");
+            }
+            EventSearchSort::Distance => {
+                qb.push(" AND (s.distance_km, s.id) > (")
+                    .push_bind(after.distance_km.unwrap_or_default())
+                    .push(", ")
+                    .push_bind(after.id)
+                    .push(")");
+            }
+        }
+    }
+
+    qb.push(match filters.sort {
+        EventSearchSort::Date => " ORDER BY s.event_date ASC, s.id ASC",
+        EventSearchSort::Popularity => " ORDER BY s.popularity DESC, s.event_date ASC, s.id ASC",
+        EventSearchSort::Distance => " ORDER BY s.distance_km ASC, s.id ASC",
+    });
+    qb.push(" LIMIT ").push_bind(filters.limit + 1);
+
+    qb.build_query_as::<EventSearchRow>().fetch_all(pool).await
+}
+
 /// Get a single event by ID
 pub async fn get_event_by_id(pool: &PgPool, event_id: Uuid) -> Result<Option<Event>> {
     let event = sqlx::query_as::<_, Event>(
         r#"
-        SELECT id, title, venue, date, image, status, time, age_limit, end_time, price, description, club_id,
+        SELECT id, title, venue, date, image, status, time, age_limit, min_age, transfers_enabled, end_time, price, description, club_id,
                tour_provider, marzipano_config, event_date, created_at, updated_at
         FROM events
         WHERE id = $1
@@ -132,14 +271,26 @@ pub async fn get_event_by_id(pool: &PgPool, event_id: Uuid) -> Result<Option<Eve
     Ok(event)
 }
 
+/// Minimum age of an event (NULL when unrestricted) and its machine-readable
+/// date, the day a holder's age is checked on.
+pub async fn get_event_min_age(
+    executor: impl sqlx::PgExecutor<'_>,
+    event_id: Uuid,
+) -> Result<Option<(Option<i32>, Option<NaiveDate>)>> {
+    sqlx::query_as("SELECT min_age, event_date FROM events WHERE id = $1")
+        .bind(event_id)
+        .fetch_optional(executor)
+        .await
+}
+
 /// Create a new event
 pub async fn create_event(pool: &PgPool, request: CreateEventRequest) -> Result<Event> {
     let event = sqlx::query_as::<_, Event>(
         r#"
         INSERT INTO events (id, title, venue, date, image, status, time, age_limit, end_time, price, description, club_id,
-                           tour_provider, marzipano_config, event_date, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, NOW(), NOW())
-        RETURNING id, title, venue, date, image, status, time, age_limit, end_time, price, description, club_id,
+                           tour_provider, marzipano_config, event_date, transfers_enabled, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, COALESCE($16, TRUE), NOW(), NOW())
+        RETURNING id, title, venue, date, image, status, time, age_limit, min_age, transfers_enabled, end_time, price, description, club_id,
                   tour_provider, marzipano_config, event_date, created_at, updated_at
         "#,
     )
@@ -158,6 +309,7 @@ pub async fn create_event(pool: &PgPool, request: CreateEventRequest) -> Result<
     .bind(request.tour_provider)
     .bind(request.marzipano_config)
     .bind(request.event_date)
+    .bind(request.transfers_enabled)
     .fetch_one(pool)
     .await?;
 
@@ -188,9 +340,10 @@ pub async fn update_event(
             tour_provider = COALESCE($12, tour_provider),
             marzipano_config = COALESCE($13, marzipano_config),
             event_date = COALESCE($14, event_date),
+            transfers_enabled = COALESCE($15, transfers_enabled),
             updated_at = NOW()
-        WHERE id = $15
-        RETURNING id, title, venue, date, image, status, time, age_limit, end_time, price, description, club_id,
+        WHERE id = $16
+        RETURNING id, title, venue, date, image, status, time, age_limit, min_age, transfers_enabled, end_time, price, description, club_id,
                   tour_provider, marzipano_config, event_date, created_at, updated_at
         "#,
     )
@@ -208,6 +361,7 @@ pub async fn update_event(
     .bind(request.tour_provider)
     .bind(request.marzipano_config)
     .bind(request.event_date)
+    .bind(request.transfers_enabled)
     .bind(event_id)
     .fetch_optional(pool)
     .await?;
@@ -224,7 +378,7 @@ pub async fn get_events_by_club_id(
     let events = if let Some(date) = from_date {
         sqlx::query_as::<_, Event>(
             r#"
-            SELECT id, title, venue, date, image, status, time, age_limit, end_time, price, description, club_id,
+            SELECT id, title, venue, date, image, status, time, age_limit, min_age, transfers_enabled, end_time, price, description, club_id,
                    tour_provider, marzipano_config, event_date, created_at, updated_at
             FROM events
             WHERE club_id = $1
@@ -244,7 +398,7 @@ pub async fn get_events_by_club_id(
     } else {
         sqlx::query_as::<_, Event>(
             r#"
-            SELECT id, title, venue, date, image, status, time, age_limit, end_time, price, description, club_id,
+            SELECT id, title, venue, date, image, status, time, age_limit, min_age, transfers_enabled, end_time, price, description, club_id,
                    tour_provider, marzipano_config, event_date, created_at, updated_at
             FROM events
             WHERE club_id = $1
@@ -261,6 +415,11 @@ pub async fn get_events_by_club_id(
 
 /// Delete an event
 pub async fn delete_event(pool: &PgPool, event_id: Uuid) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    // A deleted occurrence of a series must not be materialized again
+    event_series_repository::exclude_event_occurrence(&mut *tx, event_id).await?;
+
     let result = sqlx::query(
         r#"
         DELETE FROM events
@@ -268,8 +427,9 @@ pub async fn delete_event(pool: &PgPool, event_id: Uuid) -> Result<bool> {
         "#,
     )
     .bind(event_id)
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
 
+    tx.commit().await?;
     Ok(result.rows_affected() > 0)
 }
@@ -0,0 +1,342 @@
+use crate::models::event_series::{
+    CreateEventSeriesRequest, EventSeries, UpdateEventSeriesRequest,
+};
+use crate::models::Event;
+use chrono::NaiveDate;
+use sqlx::{PgPool, QueryBuilder};
+use uuid::Uuid;
+
+/// Occurrence filter: no live table reservation, ticket or open ticket order.
+/// Occurrences with bookings are never rewritten or removed by the series.
+const UNBOOKED: &str = r#"
+    NOT EXISTS (
+        SELECT 1 FROM table_reservations r
+        WHERE r.event_id = e.id AND r.status <> 'cancelled'
+    )
+    AND NOT EXISTS (SELECT 1 FROM tickets t WHERE t.event_id = e.id)
+    AND NOT EXISTS (
+        SELECT 1 FROM ticket_orders o
+        WHERE o.event_id = e.id AND o.status <> 'expired'
+    )
+"#;
+
+pub async fn create_series(
+    pool: &PgPool,
+    club_id: Uuid,
+    request: CreateEventSeriesRequest,
+) -> Result<EventSeries, sqlx::Error> {
+    sqlx::query_as::<_, EventSeries>(
+        r#"
+        INSERT INTO event_series (
+            club_id, title, venue, image, time, end_time, age_limit, price, description,
+            genre_ids, frequency, start_date, end_date, excluded_dates, template_event_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        RETURNING *
+        "#,
+    )
+    .bind(club_id)
+    .bind(request.title)
+    .bind(request.venue)
+    .bind(request.image)
+    .bind(request.time)
+    .bind(request.end_time)
+    .bind(request.age_limit)
+    .bind(request.price)
+    .bind(request.description)
+    .bind(request.genre_ids.unwrap_or_default())
+    .bind(request.frequency)
+    .bind(request.start_date)
+    .bind(request.end_date)
+    .bind(request.excluded_dates.unwrap_or_default())
+    .bind(request.template_event_id)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_series_by_club(
+    pool: &PgPool,
+    club_id: Uuid,
+) -> Result<Vec<EventSeries>, sqlx::Error> {
+    sqlx::query_as::<_, EventSeries>(
+        "SELECT * FROM event_series WHERE club_id = $1 ORDER BY is_active DESC, created_at DESC",
+    )
+    .bind(club_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_series_by_id(
+    pool: &PgPool,
+    series_id: Uuid,
+) -> Result<Option<EventSeries>, sqlx::Error> {
+    sqlx::query_as::<_, EventSeries>("SELECT * FROM event_series WHERE id = $1")
+        .bind(series_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn get_active_series(pool: &PgPool) -> Result<Vec<EventSeries>, sqlx::Error> {
+    sqlx::query_as::<_, EventSeries>(
+        r#"
+        SELECT * FROM event_series
+        WHERE is_active
+          AND (end_date IS NULL OR end_date >= CURRENT_DATE)
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn update_series(
+    pool: &PgPool,
+    series_id: Uuid,
+    request: &UpdateEventSeriesRequest,
+) -> Result<Option<EventSeries>, sqlx::Error> {
+    sqlx::query_as::<_, EventSeries>(
+        r#"
+        UPDATE event_series
+        SET title             = COALESCE($2, title),
+            venue             = COALESCE($3, venue),
+            image             = COALESCE($4, image),
+            time              = COALESCE($5, time),
+            end_time          = COALESCE($6, end_time),
+            age_limit         = COALESCE($7, age_limit),
+            price             = COALESCE($8, price),
+            description       = COALESCE($9, description),
+            genre_ids         = COALESCE($10, genre_ids),
+            frequency         = COALESCE($11, frequency),
+            end_date          = COALESCE($12, end_date),
+            excluded_dates    = COALESCE($13, excluded_dates),
+            template_event_id = COALESCE($14, template_event_id),
+            is_active         = COALESCE($15, is_active),
+            updated_at        = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(series_id)
+    .bind(&request.title)
+    .bind(&request.venue)
+    .bind(&request.image)
+    .bind(&request.time)
+    .bind(&request.end_time)
+    .bind(&request.age_limit)
+    .bind(&request.price)
+    .bind(&request.description)
+    .bind(&request.genre_ids)
+    .bind(&request.frequency)
+    .bind(request.end_date)
+    .bind(&request.excluded_dates)
+    .bind(request.template_event_id)
+    .bind(request.is_active)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Create the event for one occurrence, with the series' genres and a copy of
+/// the template event's tables. Returns `None` when it already exists.
+pub async fn materialize_occurrence(
+    pool: &PgPool,
+    series: &EventSeries,
+    date: NaiveDate,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let event_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        INSERT INTO events (
+            id, title, venue, date, image, time, age_limit, end_time, price, description,
+            club_id, event_date, series_id, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NOW(), NOW())
+        ON CONFLICT (series_id, event_date) WHERE series_id IS NOT NULL DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&series.title)
+    .bind(&series.venue)
+    .bind(series.occurrence_date_label(date))
+    .bind(&series.image)
+    .bind(&series.time)
+    .bind(&series.age_limit)
+    .bind(&series.end_time)
+    .bind(&series.price)
+    .bind(&series.description)
+    .bind(series.club_id)
+    .bind(date)
+    .bind(series.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(event_id) = event_id else {
+        tx.rollback().await?;
+        return Ok(None);
+    };
+
+    if !series.genre_ids.is_empty() {
+        let mut qb: QueryBuilder<sqlx::Postgres> =
+            QueryBuilder::new("INSERT INTO event_genres (event_id, genre_id) ");
+        qb.push_values(series.genre_ids.iter(), |mut b, genre_id| {
+            b.push_bind(event_id).push_bind(genre_id);
+        });
+        qb.push(" ON CONFLICT DO NOTHING");
+        qb.build().execute(&mut *tx).await?;
+    }
+
+    if let Some(template_event_id) = series.template_event_id {
+        sqlx::query(
+            r#"
+            INSERT INTO tables (
+                event_id, name, zone, capacity, min_spend, total_cost, available,
+                location_description, features, marzipano_position, area_id
+            )
+            SELECT $1, name, zone, capacity, min_spend, total_cost, available,
+                   location_description, features, marzipano_position, area_id
+            FROM tables
+            WHERE event_id = $2
+            "#,
+        )
+        .bind(event_id)
+        .bind(template_event_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(Some(event_id))
+}
+
+/// Copy the series details onto its occurrences from `from` on that have no
+/// bookings. Returns (updated, skipped because booked).
+pub async fn propagate_series_details(
+    pool: &PgPool,
+    series: &EventSeries,
+    from: NaiveDate,
+) -> Result<(u64, u64), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let event_ids: Vec<Uuid> = sqlx::query_scalar(&format!(
+        r#"
+        UPDATE events e
+        SET title = $3, venue = $4, image = $5, time = $6, end_time = $7,
+            age_limit = $8, price = $9, description = $10,
+            date = CASE WHEN $6::text IS NULL THEN e.event_date::text
+                        ELSE e.event_date::text || 'T' || $6 || ':00' END,
+            updated_at = NOW()
+        WHERE e.series_id = $1
+          AND e.event_date >= $2
+          AND {UNBOOKED}
+        RETURNING e.id
+        "#
+    ))
+    .bind(series.id)
+    .bind(from)
+    .bind(&series.title)
+    .bind(&series.venue)
+    .bind(&series.image)
+    .bind(series.time.as_deref().map(str::trim))
+    .bind(&series.end_time)
+    .bind(&series.age_limit)
+    .bind(&series.price)
+    .bind(&series.description)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if !event_ids.is_empty() {
+        sqlx::query("DELETE FROM event_genres WHERE event_id = ANY($1)")
+            .bind(&event_ids)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO event_genres (event_id, genre_id)
+            SELECT e, g FROM UNNEST($1::uuid[]) e CROSS JOIN UNNEST($2::uuid[]) g
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&event_ids)
+        .bind(&series.genre_ids)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let skipped: i64 = sqlx::query_scalar(&format!(
+        r#"
+        SELECT COUNT(*) FROM events e
+        WHERE e.series_id = $1
+          AND e.event_date >= $2
+          AND NOT ({UNBOOKED})
+        "#
+    ))
+    .bind(series.id)
+    .bind(from)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok((event_ids.len() as u64, skipped as u64))
+}
+
+/// Delete the given occurrences unless they have bookings. Returns how many
+/// were removed.
+pub async fn remove_occurrences(pool: &PgPool, event_ids: &[Uuid]) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(&format!(
+        r#"
+        DELETE FROM events e
+        WHERE e.id = ANY($1)
+          AND {UNBOOKED}
+        "#
+    ))
+    .bind(event_ids)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Occurrences of a series from `from` on, in date order.
+pub async fn get_series_events(
+    pool: &PgPool,
+    series_id: Uuid,
+    from: NaiveDate,
+) -> Result<Vec<Event>, sqlx::Error> {
+    sqlx::query_as::<_, Event>(
+        r#"
+        SELECT id, title, venue, date, image, status, time, age_limit, min_age, transfers_enabled, end_time, price, description, club_id,
+               tour_provider, marzipano_config, event_date, created_at, updated_at
+        FROM events
+        WHERE series_id = $1
+          AND event_date >= $2
+        ORDER BY event_date
+        "#,
+    )
+    .bind(series_id)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
+/// Record a deleted occurrence as an exception of its series, so the
+/// materialization job does not create it again.
+pub async fn exclude_event_occurrence(
+    executor: impl sqlx::PgExecutor<'_>,
+    event_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE event_series s
+        SET excluded_dates = array_append(s.excluded_dates, e.event_date),
+            updated_at = NOW()
+        FROM events e
+        WHERE e.id = $1
+          AND s.id = e.series_id
+          AND e.event_date IS NOT NULL
+          AND NOT (e.event_date = ANY(s.excluded_dates))
+        "#,
+    )
+    .bind(event_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
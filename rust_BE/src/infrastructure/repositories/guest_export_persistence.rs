@@ -0,0 +1,129 @@
+use crate::models::guest_export::{
+    ExportSort, GuestExportRow, ReservationExportRow, TicketExportRow,
+};
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+/// `checked_in` filter on reservations: fully in (`true`) or still expected
+const RESERVATION_CHECKED_IN: &str =
+    "($2::boolean IS NULL OR (tr.checked_in_count >= tr.num_people) = $2)";
+
+fn table_order(sort: ExportSort, name: &str) -> String {
+    match sort {
+        ExportSort::Table => format!("t.name ASC, {name} ASC"),
+        ExportSort::Area => format!("a.name ASC NULLS LAST, t.name ASC, {name} ASC"),
+        ExportSort::Name => format!("{name} ASC, t.name ASC"),
+    }
+}
+
+/// Non-cancelled reservations of the event with their table and area.
+pub async fn get_reservation_rows(
+    pool: &PgPool,
+    event_id: Uuid,
+    sort: ExportSort,
+    checked_in: Option<bool>,
+) -> Result<Vec<ReservationExportRow>> {
+    sqlx::query_as::<_, ReservationExportRow>(&format!(
+        r#"
+        SELECT tr.reservation_code, tr.contact_name, tr.contact_phone, tr.contact_email,
+               t.name AS table_name, a.name AS area_name, tr.num_people, tr.checked_in_count,
+               tr.status, tr.total_amount, tr.amount_paid, tr.special_requests
+        FROM table_reservations tr
+        JOIN tables t ON t.id = tr.table_id
+        LEFT JOIN areas a ON a.id = t.area_id
+        WHERE tr.event_id = $1
+          AND tr.status <> 'cancelled'
+          AND {RESERVATION_CHECKED_IN}
+        ORDER BY {}
+        "#,
+        table_order(sort, "tr.contact_name")
+    ))
+    .bind(event_id)
+    .bind(checked_in)
+    .fetch_all(pool)
+    .await
+}
+
+/// Everyone on the event's non-cancelled reservations: paying participants
+/// (payment shares) followed by free guests of each reservation.
+pub async fn get_guest_rows(
+    pool: &PgPool,
+    event_id: Uuid,
+    sort: ExportSort,
+    checked_in: Option<bool>,
+) -> Result<Vec<GuestExportRow>> {
+    sqlx::query_as::<_, GuestExportRow>(&format!(
+        r#"
+        SELECT g.* FROM (
+            SELECT tr.reservation_code, t.name AS table_name, a.name AS area_name,
+                   'payer' AS kind,
+                   COALESCE(s.guest_name, u.name) AS name,
+                   COALESCE(s.phone_number, u.phone_number) AS phone,
+                   COALESCE(s.guest_email, u.email) AS email,
+                   s.status AS payment_status, s.amount,
+                   tr.checked_in_count, tr.num_people, s.created_at, 0 AS kind_order
+            FROM reservation_payment_shares s
+            JOIN table_reservations tr ON tr.id = s.reservation_id
+            JOIN tables t ON t.id = tr.table_id
+            LEFT JOIN areas a ON a.id = t.area_id
+            LEFT JOIN users u ON u.id = s.user_id
+            WHERE tr.event_id = $1
+              AND tr.status <> 'cancelled'
+              AND s.status <> 'cancelled'
+              AND {RESERVATION_CHECKED_IN}
+            UNION ALL
+            SELECT tr.reservation_code, t.name, a.name,
+                   'guest',
+                   COALESCE(rg.name, u.name),
+                   rg.phone_number,
+                   COALESCE(rg.email, u.email),
+                   NULL, NULL,
+                   tr.checked_in_count, tr.num_people, rg.created_at, 1
+            FROM reservation_guests rg
+            JOIN table_reservations tr ON tr.id = rg.reservation_id
+            JOIN tables t ON t.id = tr.table_id
+            LEFT JOIN areas a ON a.id = t.area_id
+            LEFT JOIN users u ON u.id = rg.user_id
+            WHERE tr.event_id = $1
+              AND tr.status <> 'cancelled'
+              AND {RESERVATION_CHECKED_IN}
+        ) g
+        ORDER BY {}, g.reservation_code ASC, g.kind_order ASC, g.created_at ASC
+        "#,
+        match sort {
+            ExportSort::Table => "g.table_name ASC, g.name ASC NULLS LAST",
+            ExportSort::Area => "g.area_name ASC NULLS LAST, g.table_name ASC",
+            ExportSort::Name => "g.name ASC NULLS LAST, g.table_name ASC",
+        }
+    ))
+    .bind(event_id)
+    .bind(checked_in)
+    .fetch_all(pool)
+    .await
+}
+
+/// The event's valid tickets (active or used) with their holder.
+pub async fn get_ticket_rows(
+    pool: &PgPool,
+    event_id: Uuid,
+    checked_in: Option<bool>,
+) -> Result<Vec<TicketExportRow>> {
+    sqlx::query_as::<_, TicketExportRow>(
+        r#"
+        SELECT tk.ticket_code, tk.ticket_type, tt.name AS tier_name,
+               u.name AS holder_name, u.phone_number AS holder_phone, u.email AS holder_email,
+               tk.price, tk.status, tk.purchase_date
+        FROM tickets tk
+        LEFT JOIN ticket_tiers tt ON tt.id = tk.tier_id
+        LEFT JOIN users u ON u.id = tk.user_id
+        WHERE tk.event_id = $1
+          AND tk.status IN ('active', 'used')
+          AND ($2::boolean IS NULL OR (tk.status = 'used') = $2)
+        ORDER BY u.name ASC NULLS LAST, tk.ticket_code ASC
+        "#,
+    )
+    .bind(event_id)
+    .bind(checked_in)
+    .fetch_all(pool)
+    .await
+}
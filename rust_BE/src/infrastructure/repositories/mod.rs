@@ -4,15 +4,51 @@ pub mod area_repository;
 pub mod club_owner_repository;
 #[path = "club_persistence.rs"]
 pub mod club_repository;
+#[path = "connect_reconciliation_persistence.rs"]
+pub mod connect_reconciliation_repository;
+#[path = "dispute_persistence.rs"]
+pub mod dispute_repository;
 #[path = "event_persistence.rs"]
 pub mod event_repository;
+#[path = "event_series_persistence.rs"]
+pub mod event_series_repository;
 #[path = "genre_persistence.rs"]
 pub mod genre_repository;
+#[path = "guest_export_persistence.rs"]
+pub mod guest_export_repository;
+#[path = "owner_analytics_persistence.rs"]
+pub mod owner_analytics_repository;
+#[path = "password_reset_persistence.rs"]
+pub mod password_reset_repository;
 #[path = "payment_persistence.rs"]
 pub mod payment_repository;
+#[path = "platform_admin_persistence.rs"]
+pub mod platform_admin_repository;
+#[path = "promo_code_persistence.rs"]
+pub mod promo_code_repository;
+#[path = "push_persistence.rs"]
+pub mod push_repository;
+#[path = "refund_persistence.rs"]
+pub mod refund_repository;
+#[path = "session_persistence.rs"]
+pub mod session_repository;
+#[path = "staff_persistence.rs"]
+pub mod staff_repository;
+#[path = "stripe_webhook_persistence.rs"]
+pub mod stripe_webhook_repository;
+#[path = "table_layout_persistence.rs"]
+pub mod table_layout_repository;
 #[path = "table_persistence.rs"]
 pub mod table_repository;
+#[path = "table_tab_persistence.rs"]
+pub mod table_tab_repository;
 #[path = "ticket_persistence.rs"]
 pub mod ticket_repository;
+#[path = "ticket_tier_persistence.rs"]
+pub mod ticket_tier_repository;
+#[path = "transfer_persistence.rs"]
+pub mod transfer_repository;
 #[path = "user_persistence.rs"]
 pub mod user_repository;
+#[path = "waitlist_persistence.rs"]
+pub mod waitlist_repository;
@@ -0,0 +1,184 @@
+use crate::models::owner_analytics::{
+    AnalyticsBreakdownRow, AnalyticsBucketRow, AnalyticsGranularity, AnalyticsTotalsRow,
+    PaymentFunnelRow,
+};
+use chrono::NaiveDate;
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+/// The club's reservations whose night falls in [$2, $3], one row each:
+/// joining the table, event and tab (one per reservation) is 1:1 so sums are
+/// never multiplied. A reservation's night is its event date, or the booking
+/// date for legacy events without one.
+const SCOPED_RESERVATIONS: &str = r#"
+    WITH scoped AS (
+        SELECT tr.id, tr.event_id, tr.table_id, t.name AS table_name, t.area_id,
+               tr.status, tr.num_people, tr.checked_in_count, tr.amount_paid,
+               tab.final_spend,
+               COALESCE(e.event_date, (tr.created_at AT TIME ZONE 'UTC')::date) AS night
+        FROM table_reservations tr
+        JOIN events e ON e.id = tr.event_id
+        JOIN tables t ON t.id = tr.table_id
+        LEFT JOIN table_tabs tab ON tab.reservation_id = tr.id AND tab.status = 'closed'
+        WHERE e.club_id = $1
+          AND COALESCE(e.event_date, (tr.created_at AT TIME ZONE 'UTC')::date) BETWEEN $2 AND $3
+    )
+"#;
+
+/// `AnalyticsTotalsRow` columns over `s` (may be NULL-extended by a LEFT JOIN).
+/// Refunds are already deducted from `amount_paid`; `final_spend` is only set
+/// for reservations whose tab was closed.
+const METRICS: &str = r#"
+    COALESCE(SUM(s.amount_paid), 0) AS revenue,
+    COUNT(s.id) FILTER (WHERE s.status <> 'cancelled') AS reservations,
+    COALESCE(SUM(s.num_people) FILTER (WHERE s.status <> 'cancelled'), 0)::bigint AS guests,
+    COALESCE(SUM(s.checked_in_count) FILTER (WHERE s.status <> 'cancelled'), 0)::bigint AS checked_in,
+    COALESCE(SUM(s.final_spend), 0) AS tab_spend,
+    COUNT(s.final_spend) AS tabs_closed
+"#;
+
+pub async fn get_totals(
+    pool: &PgPool,
+    club_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<AnalyticsTotalsRow> {
+    sqlx::query_as::<_, AnalyticsTotalsRow>(&format!(
+        "{SCOPED_RESERVATIONS} SELECT {METRICS} FROM scoped s"
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await
+}
+
+/// One bucket per day/week/month of the range, empty ones included.
+pub async fn get_series(
+    pool: &PgPool,
+    club_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+    granularity: AnalyticsGranularity,
+) -> Result<Vec<AnalyticsBucketRow>> {
+    sqlx::query_as::<_, AnalyticsBucketRow>(&format!(
+        r#"
+        {SCOPED_RESERVATIONS}
+        SELECT b.period_start::date AS period_start, {METRICS}
+        FROM generate_series(
+            date_trunc($4, $2::date::timestamp),
+            $3::date::timestamp,
+            ('1 ' || $4)::interval
+        ) AS b(period_start)
+        LEFT JOIN scoped s ON date_trunc($4, s.night::timestamp) = b.period_start
+        GROUP BY b.period_start
+        ORDER BY b.period_start ASC
+        "#
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .bind(granularity.sql_unit())
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_breakdown_by_event(
+    pool: &PgPool,
+    club_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<AnalyticsBreakdownRow>> {
+    sqlx::query_as::<_, AnalyticsBreakdownRow>(&format!(
+        r#"
+        {SCOPED_RESERVATIONS}
+        SELECT e.id, e.title AS name, e.event_date, NULL::text AS area_name, {METRICS}
+        FROM scoped s
+        JOIN events e ON e.id = s.event_id
+        GROUP BY e.id, e.title, e.event_date
+        ORDER BY revenue DESC, e.event_date ASC NULLS LAST
+        "#
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_breakdown_by_area(
+    pool: &PgPool,
+    club_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<AnalyticsBreakdownRow>> {
+    sqlx::query_as::<_, AnalyticsBreakdownRow>(&format!(
+        r#"
+        {SCOPED_RESERVATIONS}
+        SELECT s.area_id AS id, a.name, NULL::date AS event_date, NULL::text AS area_name,
+               {METRICS}
+        FROM scoped s
+        LEFT JOIN areas a ON a.id = s.area_id
+        GROUP BY s.area_id, a.name
+        ORDER BY revenue DESC, a.name ASC NULLS LAST
+        "#
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_breakdown_by_table(
+    pool: &PgPool,
+    club_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<AnalyticsBreakdownRow>> {
+    sqlx::query_as::<_, AnalyticsBreakdownRow>(&format!(
+        r#"
+        {SCOPED_RESERVATIONS}
+        SELECT NULL::uuid AS id, s.table_name AS name, NULL::date AS event_date,
+               a.name AS area_name, {METRICS}
+        FROM scoped s
+        LEFT JOIN areas a ON a.id = s.area_id
+        GROUP BY s.table_name, a.name
+        ORDER BY revenue DESC, s.table_name ASC
+        "#
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Payment link funnel of the range's reservations, from the analytics events
+/// kept in the outbox.
+pub async fn get_payment_funnel(
+    pool: &PgPool,
+    club_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<PaymentFunnelRow> {
+    sqlx::query_as::<_, PaymentFunnelRow>(&format!(
+        r#"
+        {SCOPED_RESERVATIONS}
+        SELECT
+            COUNT(*) FILTER (WHERE o.payload->>'event' = 'payment_link_preview_viewed') AS link_views,
+            COUNT(*) FILTER (WHERE o.payload->>'event' = 'payment_link_checkout_started') AS checkouts_started,
+            COUNT(*) FILTER (WHERE o.payload->>'event' = 'split_payment_checkout_completed') AS paid_shares
+        FROM scoped s
+        JOIN outbox_events o
+          ON o.aggregate_type = 'reservation'
+         AND o.aggregate_id = s.id
+        WHERE o.event_type = 'analytics.capture'
+        "#
+    ))
+    .bind(club_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,198 @@
+use crate::models::{PasswordResetChannel, PasswordResetCode, SessionSubject};
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+fn subject_column(subject: SessionSubject) -> &'static str {
+    match subject {
+        SessionSubject::User(_) => "user_id",
+        SessionSubject::ClubOwner(_) => "club_owner_id",
+    }
+}
+
+/// Codes issued to an account since `since`
+pub async fn count_reset_codes_for_subject(
+    pool: &PgPool,
+    subject: SessionSubject,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    let column = subject_column(subject);
+    sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM password_reset_codes WHERE {column} = $1 AND created_at >= $2"
+    ))
+    .bind(subject.id())
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
+
+/// Codes requested from an IP address since `since`, across all accounts
+pub async fn count_reset_codes_for_ip(
+    pool: &PgPool,
+    requested_ip: &str,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM password_reset_codes WHERE requested_ip = $1 AND created_at >= $2",
+    )
+    .bind(requested_ip)
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
+
+/// Store a new code for an account, invalidating any code still outstanding.
+pub async fn create_reset_code(
+    pool: &PgPool,
+    subject: SessionSubject,
+    channel: PasswordResetChannel,
+    code_hash: &str,
+    requested_ip: Option<&str>,
+    expires_at: DateTime<Utc>,
+) -> Result<Uuid, sqlx::Error> {
+    let column = subject_column(subject);
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(&format!(
+        r#"
+        UPDATE password_reset_codes
+        SET invalidated_at = NOW()
+        WHERE {column} = $1
+          AND used_at IS NULL
+          AND invalidated_at IS NULL
+        "#
+    ))
+    .bind(subject.id())
+    .execute(&mut *tx)
+    .await?;
+
+    let id = sqlx::query_scalar(&format!(
+        r#"
+        INSERT INTO password_reset_codes ({column}, channel, code_hash, requested_ip, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#
+    ))
+    .bind(subject.id())
+    .bind(channel.as_str())
+    .bind(code_hash)
+    .bind(requested_ip)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Lock the latest code issued to an account, if any.
+pub async fn find_latest_reset_code_for_update(
+    conn: &mut PgConnection,
+    subject: SessionSubject,
+) -> Result<Option<PasswordResetCode>, sqlx::Error> {
+    let column = subject_column(subject);
+    sqlx::query_as::<_, PasswordResetCode>(&format!(
+        r#"
+        SELECT id, code_hash, failed_attempts, expires_at, used_at, invalidated_at
+        FROM password_reset_codes
+        WHERE {column} = $1
+        ORDER BY created_at DESC
+        LIMIT 1
+        FOR UPDATE
+        "#
+    ))
+    .bind(subject.id())
+    .fetch_optional(conn)
+    .await
+}
+
+/// Count a wrong guess against a code, invalidating it when `lock` is set.
+pub async fn record_failed_reset_attempt(
+    executor: impl sqlx::PgExecutor<'_>,
+    code_id: Uuid,
+    lock: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE password_reset_codes
+        SET failed_attempts = failed_attempts + 1,
+            invalidated_at = CASE WHEN $2 THEN NOW() ELSE invalidated_at END
+        WHERE id = $1
+        "#,
+    )
+    .bind(code_id)
+    .bind(lock)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Log a rejected redemption against the caller's IP address
+pub async fn record_reset_failure(
+    executor: impl sqlx::PgExecutor<'_>,
+    requested_ip: &str,
+    code_id: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO password_reset_failures (requested_ip, code_id) VALUES ($1, $2)")
+        .bind(requested_ip)
+        .bind(code_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Rejected redemptions from an IP address since `since`, across all accounts
+pub async fn count_reset_failures_for_ip(
+    pool: &PgPool,
+    requested_ip: &str,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM password_reset_failures WHERE requested_ip = $1 AND created_at >= $2",
+    )
+    .bind(requested_ip)
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn mark_reset_code_used(
+    executor: impl sqlx::PgExecutor<'_>,
+    code_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE password_reset_codes SET used_at = NOW() WHERE id = $1")
+        .bind(code_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Set the new password of the account that redeemed a code.
+pub async fn update_password_hash(
+    executor: impl sqlx::PgExecutor<'_>,
+    subject: SessionSubject,
+    password_hash: &str,
+) -> Result<(), sqlx::Error> {
+    let query = match subject {
+        SessionSubject::User(_) => {
+            r#"
+            UPDATE users
+            SET password_hash = $1, updated_at = NOW()
+            WHERE id = $2 AND deleted_at IS NULL
+            "#
+        }
+        SessionSubject::ClubOwner(_) => {
+            r#"
+            UPDATE club_owners
+            SET password_hash = $1, updated_at = NOW()
+            WHERE id = $2
+            "#
+        }
+    };
+    sqlx::query(query)
+        .bind(password_hash)
+        .bind(subject.id())
+        .execute(executor)
+        .await?;
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::idempotency::IdempotencyCheckResult;
 use crate::models::{
-    AppState, PaymentCaptureMethod, PaymentEntity, PaymentFilter, PaymentRequest, PaymentStatus,
+    AppState, ClubStripeConnectConfig, PaymentCaptureMethod, PaymentEntity, PaymentFilter,
+    PaymentRequest, PaymentStatus,
 };
 use axum::http::StatusCode;
 use rust_decimal::prelude::ToPrimitive;
@@ -697,3 +698,75 @@ pub async fn erase_payment_service(id: Uuid, app_state: &AppState) -> Result<u64
             rows
         })
 }
+
+/// Record that an authorized hold was released on Stripe in full.
+pub async fn mark_payment_authorization_released(
+    pool: &sqlx::PgPool,
+    payment_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE payments
+        SET status = 'cancelled', authorization_status = 'cancelled',
+            cancelled_at = NOW(), update_date = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(payment_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Connect configuration of the club running `event_id` (None for events without a club)
+pub async fn get_club_connect_config_for_event(
+    pool: &sqlx::PgPool,
+    event_id: Uuid,
+) -> Result<Option<ClubStripeConnectConfig>, sqlx::Error> {
+    sqlx::query_as::<_, ClubStripeConnectConfig>(
+        r#"
+        SELECT
+            c.stripe_connected_account_id,
+            c.stripe_onboarding_complete,
+            c.stripe_charges_enabled,
+            c.stripe_payouts_enabled,
+            c.platform_commission_percent,
+            c.platform_commission_fixed_fee
+        FROM events e
+        JOIN clubs c ON c.id = e.club_id
+        WHERE e.id = $1
+        "#,
+    )
+    .bind(event_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Record that Stripe cancelled a PaymentIntent we did not cancel ourselves (an
+/// expired authorization, a dashboard cancel). Returns the payment and whether
+/// it was an authorized hold, or `None` when nothing changed.
+pub async fn mark_payment_intent_cancelled(
+    pool: &sqlx::PgPool,
+    stripe_payment_intent_id: &str,
+) -> Result<Option<(Uuid, bool)>, sqlx::Error> {
+    sqlx::query_as::<_, (Uuid, bool)>(
+        r#"
+        UPDATE payments p
+        SET status = 'cancelled',
+            authorization_status = CASE
+                WHEN p.authorization_status IN ('pending', 'authorized') THEN 'cancelled'
+                ELSE p.authorization_status
+            END,
+            cancelled_at = COALESCE(p.cancelled_at, NOW()),
+            update_date = NOW()
+        FROM payments previous
+        WHERE p.stripe_payment_intent_id = $1
+          AND previous.id = p.id
+          AND p.status NOT IN ('cancelled', 'completed')
+        RETURNING p.id, COALESCE(previous.authorization_status = 'authorized', FALSE)
+        "#,
+    )
+    .bind(stripe_payment_intent_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,118 @@
+use crate::models::PlatformAdmin;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn create_platform_admin(
+    pool: &PgPool,
+    email: &str,
+    name: &str,
+    password_hash: &str,
+) -> Result<PlatformAdmin, sqlx::Error> {
+    sqlx::query_as::<_, PlatformAdmin>(
+        r#"
+        INSERT INTO platform_admins (email, name, password_hash)
+        VALUES (LOWER($1), $2, $3)
+        RETURNING *
+        "#,
+    )
+    .bind(email.trim())
+    .bind(name)
+    .bind(password_hash)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_active_admin_by_email(
+    pool: &PgPool,
+    email: &str,
+) -> Result<Option<PlatformAdmin>, sqlx::Error> {
+    sqlx::query_as::<_, PlatformAdmin>(
+        r#"
+        SELECT * FROM platform_admins
+        WHERE email = LOWER($1)
+          AND is_active = TRUE
+        "#,
+    )
+    .bind(email.trim())
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_platform_admins(pool: &PgPool) -> Result<Vec<PlatformAdmin>, sqlx::Error> {
+    sqlx::query_as::<_, PlatformAdmin>(
+        r#"
+        SELECT * FROM platform_admins
+        ORDER BY is_active DESC, created_at
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Enable or disable an admin. Disabled admins' tokens stop working on their next request.
+pub async fn set_platform_admin_active(
+    pool: &PgPool,
+    admin_id: Uuid,
+    is_active: bool,
+) -> Result<Option<PlatformAdmin>, sqlx::Error> {
+    sqlx::query_as::<_, PlatformAdmin>(
+        r#"
+        UPDATE platform_admins
+        SET is_active = $2, updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(admin_id)
+    .bind(is_active)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn touch_admin_login(pool: &PgPool, admin_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE platform_admins SET last_login_at = NOW() WHERE id = $1")
+        .bind(admin_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Whether an admin token may still be used.
+pub async fn admin_is_active(pool: &PgPool, admin_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM platform_admins
+            WHERE id = $1 AND is_active = TRUE
+        )
+        "#,
+    )
+    .bind(admin_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Record a back-office write. `admin_id` is None for the ops API key.
+pub async fn record_admin_action(
+    pool: &PgPool,
+    admin_id: Option<Uuid>,
+    action: &str,
+    target_type: &str,
+    target_id: Option<Uuid>,
+    details: serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO admin_audit_log (admin_id, action, target_type, target_id, details)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(admin_id)
+    .bind(action)
+    .bind(target_type)
+    .bind(target_id)
+    .bind(details)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
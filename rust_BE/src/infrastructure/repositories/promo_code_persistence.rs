@@ -0,0 +1,270 @@
+use crate::models::{PromoCode, PromoCodeRedemption, PromoCodeRejection};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_promo_code(
+    pool: &PgPool,
+    club_id: Uuid,
+    code: &str,
+    description: Option<String>,
+    discount_type: &str,
+    discount_value: Decimal,
+    event_id: Option<Uuid>,
+    area_id: Option<Uuid>,
+    table_id: Option<Uuid>,
+    max_redemptions: Option<i32>,
+    valid_from: Option<DateTime<Utc>>,
+    valid_until: Option<DateTime<Utc>>,
+    single_use_per_phone: bool,
+) -> Result<PromoCode, sqlx::Error> {
+    sqlx::query_as::<_, PromoCode>(
+        r#"
+        INSERT INTO promo_codes (
+            club_id, code, description, discount_type, discount_value,
+            event_id, area_id, table_id, max_redemptions, valid_from, valid_until,
+            single_use_per_phone
+        )
+        VALUES ($1, UPPER($2), $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING *
+        "#,
+    )
+    .bind(club_id)
+    .bind(code)
+    .bind(description)
+    .bind(discount_type)
+    .bind(discount_value)
+    .bind(event_id)
+    .bind(area_id)
+    .bind(table_id)
+    .bind(max_redemptions)
+    .bind(valid_from)
+    .bind(valid_until)
+    .bind(single_use_per_phone)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_promo_codes_by_club(
+    pool: &PgPool,
+    club_id: Uuid,
+) -> Result<Vec<PromoCode>, sqlx::Error> {
+    sqlx::query_as::<_, PromoCode>(
+        "SELECT * FROM promo_codes WHERE club_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(club_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_promo_code(
+    pool: &PgPool,
+    promo_code_id: Uuid,
+    club_id: Uuid,
+    description: Option<String>,
+    max_redemptions: Option<i32>,
+    valid_from: Option<DateTime<Utc>>,
+    valid_until: Option<DateTime<Utc>>,
+    active: Option<bool>,
+) -> Result<Option<PromoCode>, sqlx::Error> {
+    sqlx::query_as::<_, PromoCode>(
+        r#"
+        UPDATE promo_codes
+        SET description     = COALESCE($3, description),
+            max_redemptions = COALESCE($4, max_redemptions),
+            valid_from      = COALESCE($5, valid_from),
+            valid_until     = COALESCE($6, valid_until),
+            active          = COALESCE($7, active),
+            updated_at      = NOW()
+        WHERE id = $1
+          AND club_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(promo_code_id)
+    .bind(club_id)
+    .bind(description)
+    .bind(max_redemptions)
+    .bind(valid_from)
+    .bind(valid_until)
+    .bind(active)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Look up a code of the club that runs `event_id` (case-insensitive).
+pub async fn find_promo_code_for_event(
+    pool: &PgPool,
+    event_id: Uuid,
+    code: &str,
+) -> Result<Option<PromoCode>, sqlx::Error> {
+    sqlx::query_as::<_, PromoCode>(
+        r#"
+        SELECT p.*
+        FROM promo_codes p
+        JOIN events e ON e.club_id = p.club_id
+        WHERE e.id = $1
+          AND p.code = UPPER($2)
+        "#,
+    )
+    .bind(event_id)
+    .bind(code.trim())
+    .fetch_optional(pool)
+    .await
+}
+
+/// Whether this phone number already holds a live redemption of the code.
+pub async fn phone_has_redeemed(
+    executor: impl sqlx::PgExecutor<'_>,
+    promo_code_id: Uuid,
+    phone_number: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM promo_code_redemptions
+            WHERE promo_code_id = $1
+              AND phone_number = $2
+              AND status IN ('pending', 'redeemed')
+        )
+        "#,
+    )
+    .bind(promo_code_id)
+    .bind(phone_number)
+    .fetch_one(executor)
+    .await
+}
+
+/// Consume one use of a promo code inside the caller's transaction.
+///
+/// The counter bump doubles as a row lock on the code, so the cap and the
+/// per-phone check cannot be raced by a concurrent checkout. The caller must
+/// roll back when this returns a rejection.
+#[allow(clippy::too_many_arguments)]
+pub async fn redeem_promo_code(
+    conn: &mut PgConnection,
+    promo_code_id: Uuid,
+    reservation_id: Uuid,
+    payment_share_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    phone_number: &str,
+    original_amount: Decimal,
+    discount_amount: Decimal,
+    status: &str,
+) -> Result<Result<PromoCodeRedemption, PromoCodeRejection>, sqlx::Error> {
+    let single_use_per_phone: Option<bool> = sqlx::query_scalar(
+        r#"
+        UPDATE promo_codes
+        SET redemption_count = redemption_count + 1, updated_at = NOW()
+        WHERE id = $1
+          AND active = true
+          AND (max_redemptions IS NULL OR redemption_count < max_redemptions)
+        RETURNING single_use_per_phone
+        "#,
+    )
+    .bind(promo_code_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(single_use_per_phone) = single_use_per_phone else {
+        return Ok(Err(PromoCodeRejection::Exhausted));
+    };
+
+    if single_use_per_phone && phone_has_redeemed(&mut *conn, promo_code_id, phone_number).await? {
+        return Ok(Err(PromoCodeRejection::AlreadyUsed));
+    }
+
+    let redemption = sqlx::query_as::<_, PromoCodeRedemption>(
+        r#"
+        INSERT INTO promo_code_redemptions (
+            promo_code_id, reservation_id, payment_share_id, user_id, phone_number,
+            original_amount, discount_amount, final_amount, status
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $6 - $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(promo_code_id)
+    .bind(reservation_id)
+    .bind(payment_share_id)
+    .bind(user_id)
+    .bind(phone_number)
+    .bind(original_amount)
+    .bind(discount_amount)
+    .bind(status)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(Ok(redemption))
+}
+
+/// A guest paid the share their code was applied to.
+pub async fn confirm_share_redemption(
+    executor: impl sqlx::PgExecutor<'_>,
+    payment_share_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE promo_code_redemptions
+        SET status = 'redeemed', updated_at = NOW()
+        WHERE payment_share_id = $1
+          AND status = 'pending'
+        "#,
+    )
+    .bind(payment_share_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// A guest share expired unpaid: give the use back to the code and restore the
+/// reservation total the discount was taken from.
+pub async fn release_share_redemption(
+    pool: &PgPool,
+    payment_share_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let released: Option<(Uuid, Uuid, Decimal)> = sqlx::query_as(
+        r#"
+        UPDATE promo_code_redemptions
+        SET status = 'released', updated_at = NOW()
+        WHERE payment_share_id = $1
+          AND status = 'pending'
+        RETURNING promo_code_id, reservation_id, discount_amount
+        "#,
+    )
+    .bind(payment_share_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((promo_code_id, reservation_id, discount_amount)) = released else {
+        tx.rollback().await?;
+        return Ok(());
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE promo_codes
+        SET redemption_count = GREATEST(redemption_count - 1, 0), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(promo_code_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE table_reservations SET total_amount = total_amount + $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(discount_amount)
+    .bind(reservation_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
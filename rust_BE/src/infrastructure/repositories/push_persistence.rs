@@ -0,0 +1,207 @@
+use crate::models::PushDeliveryMetrics;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Registers a device token for a user. A token already registered to another
+/// account (shared device, new login) moves to this user.
+pub async fn upsert_push_device(
+    pool: &PgPool,
+    user_id: Uuid,
+    expo_push_token: &str,
+    platform: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO push_devices (user_id, expo_push_token, platform)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (expo_push_token) DO UPDATE
+        SET user_id = EXCLUDED.user_id,
+            platform = COALESCE(EXCLUDED.platform, push_devices.platform),
+            last_registered_at = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .bind(expo_push_token)
+    .bind(platform)
+    .execute(&mut *tx)
+    .await?;
+
+    // users.expo_push_token mirrors the latest device; the previous owner of a
+    // moved token stops pointing at it
+    sqlx::query(
+        "UPDATE users SET expo_push_token = NULL, updated_at = NOW() WHERE expo_push_token = $1 AND id <> $2",
+    )
+    .bind(expo_push_token)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE users SET expo_push_token = $1, updated_at = NOW() WHERE id = $2")
+        .bind(expo_push_token)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
+
+/// Forgets a device token, for one user (logout) or for everyone (`user_id`
+/// None: Expo reported the device as unregistered). Returns whether a device
+/// was removed.
+pub async fn remove_push_device(
+    pool: &PgPool,
+    expo_push_token: &str,
+    user_id: Option<Uuid>,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let removed = sqlx::query(
+        "DELETE FROM push_devices WHERE expo_push_token = $1 AND ($2::UUID IS NULL OR user_id = $2)",
+    )
+    .bind(expo_push_token)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET expo_push_token = NULL,
+            updated_at = NOW()
+        WHERE expo_push_token = $1
+          AND ($2::UUID IS NULL OR id = $2)
+        "#,
+    )
+    .bind(expo_push_token)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(removed > 0)
+}
+
+/// Push tokens of every device of an active user, most recent first
+pub async fn get_user_push_tokens(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT pd.expo_push_token
+        FROM push_devices pd
+        JOIN users u ON u.id = pd.user_id
+        WHERE pd.user_id = $1
+          AND u.deleted_at IS NULL
+        ORDER BY pd.last_registered_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn insert_push_ticket(
+    pool: &PgPool,
+    outbox_event_id: Uuid,
+    expo_push_token: &str,
+    ticket_id: Option<&str>,
+    status: &str,
+    error_code: Option<&str>,
+    error_message: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO push_tickets (
+            outbox_event_id, expo_push_token, ticket_id, status, error_code, error_message
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(outbox_event_id)
+    .bind(expo_push_token)
+    .bind(ticket_id)
+    .bind(status)
+    .bind(error_code)
+    .bind(error_message)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Tickets sent before `created_before` whose receipt is still unknown, oldest
+/// first, as (row id, Expo ticket id, token, created_at).
+pub async fn get_tickets_awaiting_receipt(
+    pool: &PgPool,
+    created_before: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<(Uuid, String, String, DateTime<Utc>)>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT id, ticket_id, expo_push_token, created_at
+        FROM push_tickets
+        WHERE status = 'pending_receipt'
+          AND ticket_id IS NOT NULL
+          AND created_at <= $1
+        ORDER BY created_at ASC
+        LIMIT $2
+        "#,
+    )
+    .bind(created_before)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn resolve_push_ticket(
+    pool: &PgPool,
+    ticket_row_id: Uuid,
+    status: &str,
+    error_code: Option<&str>,
+    error_message: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE push_tickets
+        SET status = $2,
+            error_code = $3,
+            error_message = $4,
+            receipt_checked_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(ticket_row_id)
+    .bind(status)
+    .bind(error_code)
+    .bind(error_message)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Ticket counts by outcome since `since`
+pub async fn get_push_delivery_metrics(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<PushDeliveryMetrics, sqlx::Error> {
+    sqlx::query_as::<_, PushDeliveryMetrics>(
+        r#"
+        SELECT
+            COUNT(*)                                                    AS sent,
+            COUNT(*) FILTER (WHERE status = 'delivered')                AS delivered,
+            COUNT(*) FILTER (WHERE status = 'failed')                   AS failed,
+            COUNT(*) FILTER (WHERE status = 'pending_receipt')          AS pending_receipt,
+            COUNT(*) FILTER (WHERE status = 'receipt_expired')          AS receipt_expired,
+            COUNT(*) FILTER (WHERE error_code = 'DeviceNotRegistered')  AS device_not_registered,
+            (SELECT COUNT(*) FROM push_devices)                         AS registered_devices
+        FROM push_tickets
+        WHERE created_at >= $1
+        "#,
+    )
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,317 @@
+use crate::models::{AdminRefundRow, CancellationPolicy, Refund, TableReservation};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A paid share joined with the payment that funded it.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct RefundableShare {
+    pub share_id: Uuid,
+    pub reservation_id: Uuid,
+    pub payment_id: Uuid,
+    pub share_amount: Decimal,
+    pub payment_amount: Decimal,
+    pub refunded_amount: Decimal,
+    pub stripe_payment_intent_id: Option<String>,
+    pub capture_method: Option<String>,
+    pub authorization_status: Option<String>,
+}
+
+pub async fn get_cancellation_policy(
+    pool: &PgPool,
+    club_id: Uuid,
+) -> Result<Option<CancellationPolicy>, sqlx::Error> {
+    sqlx::query_as::<_, CancellationPolicy>(
+        r#"
+        SELECT club_id, full_refund_hours, partial_refund_percent
+        FROM club_cancellation_policies
+        WHERE club_id = $1
+        "#,
+    )
+    .bind(club_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn upsert_cancellation_policy(
+    pool: &PgPool,
+    club_id: Uuid,
+    full_refund_hours: i32,
+    partial_refund_percent: Decimal,
+) -> Result<CancellationPolicy, sqlx::Error> {
+    sqlx::query_as::<_, CancellationPolicy>(
+        r#"
+        INSERT INTO club_cancellation_policies (club_id, full_refund_hours, partial_refund_percent)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (club_id) DO UPDATE
+        SET full_refund_hours      = EXCLUDED.full_refund_hours,
+            partial_refund_percent = EXCLUDED.partial_refund_percent,
+            updated_at             = NOW()
+        RETURNING club_id, full_refund_hours, partial_refund_percent
+        "#,
+    )
+    .bind(club_id)
+    .bind(full_refund_hours)
+    .bind(partial_refund_percent)
+    .fetch_one(pool)
+    .await
+}
+
+/// Flip a reservation to `cancelled` unless it is already cancelled or completed.
+/// Returns `None` when the reservation was not in a cancellable state.
+pub async fn mark_reservation_cancelled(
+    pool: &PgPool,
+    reservation_id: Uuid,
+) -> Result<Option<TableReservation>, sqlx::Error> {
+    sqlx::query_as::<_, TableReservation>(
+        r#"
+        UPDATE table_reservations
+        SET status = 'cancelled', updated_at = NOW()
+        WHERE id = $1
+          AND status NOT IN ('cancelled', 'completed')
+        RETURNING *
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Paid shares of a reservation that still have money left to refund. Shares
+/// frozen by a dispute are left out.
+pub async fn get_refundable_shares(
+    pool: &PgPool,
+    reservation_id: Uuid,
+) -> Result<Vec<RefundableShare>, sqlx::Error> {
+    sqlx::query_as::<_, RefundableShare>(
+        r#"
+        SELECT s.id                   AS share_id,
+               s.reservation_id,
+               p.id                   AS payment_id,
+               s.amount               AS share_amount,
+               p.amount               AS payment_amount,
+               p.refunded_amount,
+               p.stripe_payment_intent_id,
+               p.capture_method,
+               p.authorization_status
+        FROM reservation_payment_shares s
+        JOIN payments p ON p.id = s.payment_id
+        WHERE s.reservation_id = $1
+          AND s.status = 'paid'
+          AND s.frozen_at IS NULL
+          AND p.refunded_amount < p.amount
+        ORDER BY s.is_owner DESC, s.created_at ASC
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_refund(
+    pool: &PgPool,
+    payment_id: Uuid,
+    reservation_id: Option<Uuid>,
+    payment_share_id: Option<Uuid>,
+    stripe_refund_id: Option<&str>,
+    amount: Decimal,
+    status: &str,
+    reason: &str,
+    refund_percent: Option<Decimal>,
+    application_fee_refunded: bool,
+    failure_reason: Option<&str>,
+) -> Result<Refund, sqlx::Error> {
+    sqlx::query_as::<_, Refund>(
+        r#"
+        INSERT INTO refunds (
+            payment_id, reservation_id, payment_share_id, stripe_refund_id, amount,
+            status, reason, refund_percent, application_fee_refunded, failure_reason
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING *
+        "#,
+    )
+    .bind(payment_id)
+    .bind(reservation_id)
+    .bind(payment_share_id)
+    .bind(stripe_refund_id)
+    .bind(amount)
+    .bind(status)
+    .bind(reason)
+    .bind(refund_percent)
+    .bind(application_fee_refunded)
+    .bind(failure_reason)
+    .fetch_one(pool)
+    .await
+}
+
+/// Record a refund reported by Stripe (e.g. issued from the dashboard).
+/// Existing rows are only updated with the latest Stripe status.
+pub async fn upsert_stripe_refund(
+    pool: &PgPool,
+    payment_id: Uuid,
+    reservation_id: Option<Uuid>,
+    stripe_refund_id: &str,
+    amount: Decimal,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO refunds (payment_id, reservation_id, stripe_refund_id, amount, status, reason)
+        VALUES ($1, $2, $3, $4, $5, 'stripe_dashboard')
+        ON CONFLICT (stripe_refund_id) DO UPDATE
+        SET status = EXCLUDED.status, updated_at = NOW()
+        "#,
+    )
+    .bind(payment_id)
+    .bind(reservation_id)
+    .bind(stripe_refund_id)
+    .bind(amount)
+    .bind(status)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Find the payment funded by a Stripe PaymentIntent, the reservation it belongs
+/// to (if any) and the part of the authorization that was never captured.
+pub async fn find_payment_for_intent(
+    pool: &PgPool,
+    stripe_payment_intent_id: &str,
+) -> Result<Option<(Uuid, Option<Uuid>, Decimal)>, sqlx::Error> {
+    sqlx::query_as::<_, (Uuid, Option<Uuid>, Decimal)>(
+        r#"
+        SELECT p.id,
+               s.reservation_id,
+               GREATEST(p.amount - COALESCE(p.captured_amount, p.amount), 0)
+        FROM payments p
+        LEFT JOIN reservation_payment_shares s ON s.payment_id = p.id
+        WHERE p.stripe_payment_intent_id = $1
+        LIMIT 1
+        "#,
+    )
+    .bind(stripe_payment_intent_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Bring `payments.refunded_amount` up to `target_refunded` and release the
+/// difference from the funded reservation's `amount_paid`.
+///
+/// Both the cancellation flow and the `charge.refunded` webhook call this with
+/// the cumulative refunded total they know about, so whichever arrives second
+/// is a no-op. Returns the amount newly applied.
+pub async fn apply_payment_refund_total(
+    pool: &PgPool,
+    payment_id: Uuid,
+    target_refunded: Decimal,
+) -> Result<Decimal, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let (amount, refunded_amount) = sqlx::query_as::<_, (Decimal, Decimal)>(
+        "SELECT amount, refunded_amount FROM payments WHERE id = $1 FOR UPDATE",
+    )
+    .bind(payment_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let target = target_refunded.min(amount);
+    let delta = target - refunded_amount;
+    if delta <= Decimal::ZERO {
+        tx.rollback().await?;
+        return Ok(Decimal::ZERO);
+    }
+
+    sqlx::query("UPDATE payments SET refunded_amount = $1, update_date = NOW() WHERE id = $2")
+        .bind(target)
+        .bind(payment_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE table_reservations r
+        SET amount_paid = GREATEST(r.amount_paid - $1, 0),
+            updated_at  = NOW()
+        FROM reservation_payment_shares s
+        WHERE s.payment_id = $2
+          AND s.reservation_id = r.id
+        "#,
+    )
+    .bind(delta)
+    .bind(payment_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if target >= amount {
+        sqlx::query(
+            r#"
+            UPDATE reservation_payment_shares
+            SET status = 'refunded', updated_at = NOW()
+            WHERE payment_id = $1
+              AND status = 'paid'
+            "#,
+        )
+        .bind(payment_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(delta)
+}
+
+/// Record a reduced capture of a manual-capture payment (the released part is
+/// tracked as a refund by the caller).
+pub async fn mark_payment_partially_captured(
+    pool: &PgPool,
+    payment_id: Uuid,
+    captured_amount: Decimal,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE payments
+        SET status = 'completed',
+            authorization_status = 'captured',
+            captured_at = NOW(),
+            captured_amount = $1,
+            update_date = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(captured_amount)
+    .bind(payment_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Refunds across every club, newest first, optionally filtered by status
+/// (pending, succeeded, failed, released).
+pub async fn list_refunds(
+    pool: &PgPool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<AdminRefundRow>, sqlx::Error> {
+    sqlx::query_as::<_, AdminRefundRow>(
+        r#"
+        SELECT r.id, r.payment_id, r.reservation_id, r.stripe_refund_id, r.amount, r.status,
+               r.reason, r.refund_percent, r.failure_reason, r.created_at,
+               c.id AS club_id, c.name AS club_name
+        FROM refunds r
+        LEFT JOIN table_reservations tr ON tr.id = r.reservation_id
+        LEFT JOIN events e ON e.id = tr.event_id
+        LEFT JOIN clubs c ON c.id = e.club_id
+        WHERE ($1::text IS NULL OR r.status = $1)
+        ORDER BY r.created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,123 @@
+use crate::models::{SessionSubject, UserSession};
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub async fn insert_session(
+    executor: impl sqlx::PgExecutor<'_>,
+    subject: SessionSubject,
+    family_id: Uuid,
+    refresh_token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let (user_id, club_owner_id) = match subject {
+        SessionSubject::User(id) => (Some(id), None),
+        SessionSubject::ClubOwner(id) => (None, Some(id)),
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_sessions (user_id, club_owner_id, family_id, refresh_token_hash, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(user_id)
+    .bind(club_owner_id)
+    .bind(family_id)
+    .bind(refresh_token_hash)
+    .bind(expires_at)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Lock the session row of a presented refresh token.
+pub async fn find_session_for_update(
+    conn: &mut PgConnection,
+    refresh_token_hash: &str,
+) -> Result<Option<UserSession>, sqlx::Error> {
+    sqlx::query_as::<_, UserSession>(
+        r#"
+        SELECT id, user_id, club_owner_id, family_id, expires_at, rotated_at, revoked_at
+        FROM user_sessions
+        WHERE refresh_token_hash = $1
+        FOR UPDATE
+        "#,
+    )
+    .bind(refresh_token_hash)
+    .fetch_optional(conn)
+    .await
+}
+
+pub async fn mark_session_rotated(
+    executor: impl sqlx::PgExecutor<'_>,
+    session_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE user_sessions SET rotated_at = NOW() WHERE id = $1")
+        .bind(session_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+pub async fn revoke_session_family(
+    executor: impl sqlx::PgExecutor<'_>,
+    family_id: Uuid,
+    reason: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE user_sessions
+        SET revoked_at = NOW(), revoked_reason = $2
+        WHERE family_id = $1
+          AND revoked_at IS NULL
+        "#,
+    )
+    .bind(family_id)
+    .bind(reason)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Revoke every session of a user or club owner (all devices).
+pub async fn revoke_all_sessions(
+    pool: &PgPool,
+    subject: SessionSubject,
+    reason: &str,
+) -> Result<u64, sqlx::Error> {
+    let column = match subject {
+        SessionSubject::User(_) => "user_id",
+        SessionSubject::ClubOwner(_) => "club_owner_id",
+    };
+    let result = sqlx::query(&format!(
+        r#"
+        UPDATE user_sessions
+        SET revoked_at = NOW(), revoked_reason = $2
+        WHERE {column} = $1
+          AND revoked_at IS NULL
+        "#
+    ))
+    .bind(subject.id())
+    .bind(reason)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Whether an access token's session family can still be used.
+pub async fn session_family_is_active(pool: &PgPool, family_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM user_sessions
+            WHERE family_id = $1
+              AND revoked_at IS NULL
+              AND expires_at > NOW()
+        )
+        "#,
+    )
+    .bind(family_id)
+    .fetch_one(pool)
+    .await
+}
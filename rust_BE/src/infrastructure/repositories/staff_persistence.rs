@@ -0,0 +1,170 @@
+use crate::models::ClubStaff;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_staff_invite(
+    pool: &PgPool,
+    club_id: Uuid,
+    invited_by: Uuid,
+    email: &str,
+    name: &str,
+    phone_number: Option<&str>,
+    role: &str,
+    invite_token_hash: &str,
+    invite_expires_at: DateTime<Utc>,
+) -> Result<ClubStaff, sqlx::Error> {
+    sqlx::query_as::<_, ClubStaff>(
+        r#"
+        INSERT INTO club_staff (
+            club_id, invited_by, email, name, phone_number, role,
+            invite_token_hash, invite_expires_at
+        )
+        VALUES ($1, $2, LOWER($3), $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(club_id)
+    .bind(invited_by)
+    .bind(email.trim())
+    .bind(name)
+    .bind(phone_number)
+    .bind(role)
+    .bind(invite_token_hash)
+    .bind(invite_expires_at)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_staff_by_club(
+    pool: &PgPool,
+    club_id: Uuid,
+) -> Result<Vec<ClubStaff>, sqlx::Error> {
+    sqlx::query_as::<_, ClubStaff>(
+        "SELECT * FROM club_staff WHERE club_id = $1 ORDER BY status = 'revoked', created_at DESC",
+    )
+    .bind(club_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Rename or change the role of a member who has not been revoked.
+pub async fn update_staff(
+    pool: &PgPool,
+    staff_id: Uuid,
+    club_id: Uuid,
+    name: Option<String>,
+    role: Option<&str>,
+) -> Result<Option<ClubStaff>, sqlx::Error> {
+    sqlx::query_as::<_, ClubStaff>(
+        r#"
+        UPDATE club_staff
+        SET name = COALESCE($3, name),
+            role = COALESCE($4, role),
+            updated_at = NOW()
+        WHERE id = $1
+          AND club_id = $2
+          AND status <> 'revoked'
+        RETURNING *
+        "#,
+    )
+    .bind(staff_id)
+    .bind(club_id)
+    .bind(name)
+    .bind(role)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Revoke a member; their tokens stop working on the next request.
+pub async fn revoke_staff(
+    pool: &PgPool,
+    staff_id: Uuid,
+    club_id: Uuid,
+) -> Result<Option<ClubStaff>, sqlx::Error> {
+    sqlx::query_as::<_, ClubStaff>(
+        r#"
+        UPDATE club_staff
+        SET status = 'revoked',
+            revoked_at = NOW(),
+            invite_token_hash = NULL,
+            updated_at = NOW()
+        WHERE id = $1
+          AND club_id = $2
+          AND status <> 'revoked'
+        RETURNING *
+        "#,
+    )
+    .bind(staff_id)
+    .bind(club_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Accept an unexpired invitation: set the password and activate the account.
+pub async fn accept_staff_invite(
+    pool: &PgPool,
+    invite_token_hash: &str,
+    password_hash: &str,
+) -> Result<Option<ClubStaff>, sqlx::Error> {
+    sqlx::query_as::<_, ClubStaff>(
+        r#"
+        UPDATE club_staff
+        SET password_hash = $2,
+            status = 'active',
+            accepted_at = NOW(),
+            invite_token_hash = NULL,
+            invite_expires_at = NULL,
+            updated_at = NOW()
+        WHERE invite_token_hash = $1
+          AND status = 'invited'
+          AND invite_expires_at > NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(invite_token_hash)
+    .bind(password_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn find_active_staff_by_email(
+    pool: &PgPool,
+    email: &str,
+) -> Result<Option<ClubStaff>, sqlx::Error> {
+    sqlx::query_as::<_, ClubStaff>(
+        "SELECT * FROM club_staff WHERE email = LOWER($1) AND status = 'active'",
+    )
+    .bind(email.trim())
+    .fetch_optional(pool)
+    .await
+}
+
+/// Whether a staff token is still honoured: the member is active, still has
+/// `role`, and their club still belongs to `owner_id`.
+pub async fn staff_is_active(
+    pool: &PgPool,
+    staff_id: Uuid,
+    owner_id: Uuid,
+    role: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1
+            FROM club_staff s
+            JOIN clubs c ON c.id = s.club_id
+            WHERE s.id = $1
+              AND c.owner_id = $2
+              AND s.role = $3
+              AND s.status = 'active'
+        )
+        "#,
+    )
+    .bind(staff_id)
+    .bind(owner_id)
+    .bind(role)
+    .fetch_one(pool)
+    .await
+}
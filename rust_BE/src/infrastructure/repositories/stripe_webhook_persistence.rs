@@ -0,0 +1,187 @@
+use chrono::{Duration, Utc};
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+use crate::models::stripe_webhook::{StripeEventEnvelope, StripeWebhookEvent};
+
+const WEBHOOK_EVENT_COLUMNS: &str = r#"
+    id, stripe_event_id, event_type, payload, status, attempts, available_at, last_error,
+    received_at, processed_at, dead_lettered_at, replay_count
+"#;
+
+/// Stores a verified webhook for the worker. Returns `None` for an event
+/// already in the inbox, or handled before the inbox existed.
+pub async fn store_received_event(
+    pool: &PgPool,
+    envelope: &StripeEventEnvelope,
+    payload: &str,
+) -> Result<Option<Uuid>> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO stripe_webhook_events (id, stripe_event_id, event_type, payload)
+        SELECT $1, $2, $3, $4
+        WHERE NOT EXISTS (
+            SELECT 1 FROM processed_stripe_events WHERE stripe_event_id = $2
+        )
+        ON CONFLICT (stripe_event_id) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&envelope.id)
+    .bind(&envelope.event_type)
+    .bind(payload)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Claims due events, oldest first. Events left in `processing` for longer
+/// than `stale_after` (a worker that died mid-event) are claimed again.
+pub async fn claim_due_events(
+    pool: &PgPool,
+    batch_size: i64,
+    stale_after: Duration,
+) -> Result<Vec<StripeWebhookEvent>> {
+    sqlx::query_as::<_, StripeWebhookEvent>(&format!(
+        r#"
+        WITH next_events AS (
+            SELECT id
+            FROM stripe_webhook_events
+            WHERE (status IN ('pending', 'failed') AND available_at <= NOW())
+               OR (status = 'processing' AND updated_at < $2)
+            ORDER BY received_at ASC
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE stripe_webhook_events swe
+        SET status = 'processing',
+            attempts = attempts + 1,
+            updated_at = NOW()
+        FROM next_events
+        WHERE swe.id = next_events.id
+        RETURNING {WEBHOOK_EVENT_COLUMNS}
+        "#
+    ))
+    .bind(batch_size)
+    .bind(Utc::now() - stale_after)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_processed(pool: &PgPool, event_id: Uuid) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE stripe_webhook_events
+        SET status = 'processed',
+            processed_at = NOW(),
+            last_error = NULL,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(event_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn mark_failed(
+    pool: &PgPool,
+    event_id: Uuid,
+    error_message: &str,
+    retry_delay: Duration,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE stripe_webhook_events
+        SET status = 'failed',
+            last_error = $2,
+            available_at = $3,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(event_id)
+    .bind(error_message)
+    .bind(Utc::now() + retry_delay)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Stops retrying an event: it stays in `dead_lettered` until an admin re-runs it.
+pub async fn mark_dead_lettered(pool: &PgPool, event_id: Uuid, error_message: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE stripe_webhook_events
+        SET status = 'dead_lettered',
+            last_error = $2,
+            dead_lettered_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(event_id)
+    .bind(error_message)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Inbox events, most recently received first, optionally of one status and type
+pub async fn list_events(
+    pool: &PgPool,
+    status: Option<&str>,
+    event_type: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<StripeWebhookEvent>> {
+    sqlx::query_as::<_, StripeWebhookEvent>(&format!(
+        r#"
+        SELECT {WEBHOOK_EVENT_COLUMNS}
+        FROM stripe_webhook_events
+        WHERE ($1::TEXT IS NULL OR status = $1)
+          AND ($2::TEXT IS NULL OR event_type = $2)
+        ORDER BY received_at DESC
+        LIMIT $3 OFFSET $4
+        "#
+    ))
+    .bind(status)
+    .bind(event_type)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_event(pool: &PgPool, event_id: Uuid) -> Result<Option<StripeWebhookEvent>> {
+    sqlx::query_as::<_, StripeWebhookEvent>(&format!(
+        "SELECT {WEBHOOK_EVENT_COLUMNS} FROM stripe_webhook_events WHERE id = $1"
+    ))
+    .bind(event_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Queues a stored event to run again with a fresh attempt budget, whatever
+/// its outcome so far. Returns `None` when the event does not exist or is
+/// being processed right now.
+pub async fn requeue_event(pool: &PgPool, event_id: Uuid) -> Result<Option<StripeWebhookEvent>> {
+    sqlx::query_as::<_, StripeWebhookEvent>(&format!(
+        r#"
+        UPDATE stripe_webhook_events
+        SET status = 'pending',
+            attempts = 0,
+            available_at = NOW(),
+            dead_lettered_at = NULL,
+            replay_count = replay_count + 1,
+            updated_at = NOW()
+        WHERE id = $1
+          AND status <> 'processing'
+        RETURNING {WEBHOOK_EVENT_COLUMNS}
+        "#
+    ))
+    .bind(event_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,384 @@
+use crate::models::table_layout::{
+    PlannedTable, TableLayoutTemplate, TableLayoutTemplateDetails, TableLayoutTemplateImage,
+    TableLayoutTemplateTable, UpdateTableLayoutTemplateRequest,
+};
+use crate::models::Table;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Save the tables of `event_id` (with areas, hotspots and images) and the
+/// event's virtual tour as a new template of `club_id`.
+pub async fn create_template_from_event(
+    pool: &PgPool,
+    club_id: Uuid,
+    event_id: Uuid,
+    name: &str,
+    description: Option<&str>,
+) -> Result<TableLayoutTemplate, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let template = sqlx::query_as::<_, TableLayoutTemplate>(
+        r#"
+        INSERT INTO table_layout_templates (club_id, name, description, tour_provider, marzipano_config)
+        SELECT $1, $2, $3, e.tour_provider, e.marzipano_config
+        FROM events e
+        WHERE e.id = $4
+        RETURNING *
+        "#,
+    )
+    .bind(club_id)
+    .bind(name)
+    .bind(description)
+    .bind(event_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let tables = sqlx::query_as::<_, Table>(
+        r#"
+        SELECT id, event_id, name, zone, capacity, min_spend, total_cost, available,
+               location_description, features, marzipano_position, area_id, created_at, updated_at
+        FROM tables
+        WHERE event_id = $1
+        ORDER BY created_at ASC, name ASC
+        "#,
+    )
+    .bind(event_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (position, table) in tables.iter().enumerate() {
+        let template_table_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO table_layout_template_tables (
+                template_id, name, zone, capacity, min_spend, available,
+                location_description, features, marzipano_position, area_id, sort_order
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id
+            "#,
+        )
+        .bind(template.id)
+        .bind(&table.name)
+        .bind(&table.zone)
+        .bind(table.capacity)
+        .bind(table.min_spend)
+        .bind(table.available)
+        .bind(&table.location_description)
+        .bind(&table.features)
+        .bind(&table.marzipano_position)
+        .bind(table.area_id)
+        .bind(position as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO table_layout_template_images (template_table_id, url, display_order, alt_text)
+            SELECT $1, url, display_order, alt_text
+            FROM table_images
+            WHERE table_id = $2
+            "#,
+        )
+        .bind(template_table_id)
+        .bind(table.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(template)
+}
+
+pub async fn get_templates_by_club(
+    pool: &PgPool,
+    club_id: Uuid,
+) -> Result<Vec<TableLayoutTemplate>, sqlx::Error> {
+    sqlx::query_as::<_, TableLayoutTemplate>(
+        "SELECT * FROM table_layout_templates WHERE club_id = $1 ORDER BY name ASC",
+    )
+    .bind(club_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_template_by_id(
+    pool: &PgPool,
+    template_id: Uuid,
+) -> Result<Option<TableLayoutTemplate>, sqlx::Error> {
+    sqlx::query_as::<_, TableLayoutTemplate>("SELECT * FROM table_layout_templates WHERE id = $1")
+        .bind(template_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The template's tables in display order, with all their images.
+pub async fn get_template_details(
+    pool: &PgPool,
+    template: TableLayoutTemplate,
+) -> Result<TableLayoutTemplateDetails, sqlx::Error> {
+    let tables = sqlx::query_as::<_, TableLayoutTemplateTable>(
+        r#"
+        SELECT * FROM table_layout_template_tables
+        WHERE template_id = $1
+        ORDER BY sort_order ASC, created_at ASC
+        "#,
+    )
+    .bind(template.id)
+    .fetch_all(pool)
+    .await?;
+
+    let images = sqlx::query_as::<_, TableLayoutTemplateImage>(
+        r#"
+        SELECT i.* FROM table_layout_template_images i
+        JOIN table_layout_template_tables t ON t.id = i.template_table_id
+        WHERE t.template_id = $1
+        ORDER BY i.display_order ASC, i.created_at ASC
+        "#,
+    )
+    .bind(template.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(TableLayoutTemplateDetails {
+        template,
+        tables,
+        images,
+    })
+}
+
+/// Edit a template. When `request.tables` is given the template tables are
+/// replaced as a whole: listed ids are updated, new entries inserted and the
+/// rest removed. Fails with `RowNotFound` if an id is not one of the
+/// template's tables.
+pub async fn update_template(
+    pool: &PgPool,
+    template_id: Uuid,
+    request: &UpdateTableLayoutTemplateRequest,
+) -> Result<TableLayoutTemplate, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let template = sqlx::query_as::<_, TableLayoutTemplate>(
+        r#"
+        UPDATE table_layout_templates
+        SET name             = COALESCE($2, name),
+            description      = COALESCE($3, description),
+            marzipano_config = COALESCE($4, marzipano_config),
+            updated_at       = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(template_id)
+    .bind(&request.name)
+    .bind(&request.description)
+    .bind(&request.marzipano_config)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let Some(tables) = &request.tables {
+        let kept: Vec<Uuid> = tables.iter().filter_map(|table| table.id).collect();
+        sqlx::query(
+            "DELETE FROM table_layout_template_tables WHERE template_id = $1 AND NOT (id = ANY($2))",
+        )
+        .bind(template_id)
+        .bind(&kept)
+        .execute(&mut *tx)
+        .await?;
+
+        for (position, table) in tables.iter().enumerate() {
+            // Prices are validated by the controller before reaching here
+            let min_spend = Decimal::from_f64_retain(table.min_spend)
+                .unwrap_or_default()
+                .round_dp(2);
+            let template_table_id: Uuid = match table.id {
+                Some(id) => {
+                    sqlx::query_scalar(
+                        r#"
+                        UPDATE table_layout_template_tables
+                        SET name = $3, zone = $4, capacity = $5, min_spend = $6,
+                            available = COALESCE($7, available),
+                            location_description = $8, features = $9,
+                            marzipano_position = $10, area_id = $11, sort_order = $12,
+                            updated_at = NOW()
+                        WHERE id = $1 AND template_id = $2
+                        RETURNING id
+                        "#,
+                    )
+                    .bind(id)
+                    .bind(template_id)
+                    .bind(&table.name)
+                    .bind(&table.zone)
+                    .bind(table.capacity)
+                    .bind(min_spend)
+                    .bind(table.available)
+                    .bind(&table.location_description)
+                    .bind(&table.features)
+                    .bind(&table.marzipano_position)
+                    .bind(table.area_id)
+                    .bind(position as i32)
+                    .fetch_one(&mut *tx)
+                    .await?
+                }
+                None => {
+                    sqlx::query_scalar(
+                        r#"
+                        INSERT INTO table_layout_template_tables (
+                            template_id, name, zone, capacity, min_spend, available,
+                            location_description, features, marzipano_position, area_id, sort_order
+                        )
+                        VALUES ($1, $2, $3, $4, $5, COALESCE($6, TRUE), $7, $8, $9, $10, $11)
+                        RETURNING id
+                        "#,
+                    )
+                    .bind(template_id)
+                    .bind(&table.name)
+                    .bind(&table.zone)
+                    .bind(table.capacity)
+                    .bind(min_spend)
+                    .bind(table.available)
+                    .bind(&table.location_description)
+                    .bind(&table.features)
+                    .bind(&table.marzipano_position)
+                    .bind(table.area_id)
+                    .bind(position as i32)
+                    .fetch_one(&mut *tx)
+                    .await?
+                }
+            };
+
+            if let Some(images) = &table.images {
+                sqlx::query(
+                    "DELETE FROM table_layout_template_images WHERE template_table_id = $1",
+                )
+                .bind(template_table_id)
+                .execute(&mut *tx)
+                .await?;
+                for image in images {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO table_layout_template_images (template_table_id, url, display_order, alt_text)
+                        VALUES ($1, $2, $3, $4)
+                        "#,
+                    )
+                    .bind(template_table_id)
+                    .bind(&image.url)
+                    .bind(image.display_order.unwrap_or(0))
+                    .bind(&image.alt_text)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+    }
+
+    tx.commit().await?;
+    Ok(template)
+}
+
+pub async fn delete_template(pool: &PgPool, template_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM table_layout_templates WHERE id = $1")
+        .bind(template_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Create the template's tables (and images) on `event_id` in one
+/// transaction, using the prices and availability of `planned`. The template's
+/// tour is copied onto the event only if it has none. Returns `None` without
+/// changes when the event already has tables.
+pub async fn apply_template_to_event(
+    pool: &PgPool,
+    details: &TableLayoutTemplateDetails,
+    planned: &[PlannedTable],
+    event_id: Uuid,
+) -> Result<Option<u64>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Serialize concurrent applies on the same event
+    sqlx::query("SELECT id FROM events WHERE id = $1 FOR UPDATE")
+        .bind(event_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let has_tables: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM tables WHERE event_id = $1)")
+            .bind(event_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    if has_tables {
+        tx.rollback().await?;
+        return Ok(None);
+    }
+
+    let mut created = 0;
+    for plan in planned {
+        let Some(table) = details
+            .tables
+            .iter()
+            .find(|table| table.id == plan.template_table_id)
+        else {
+            continue;
+        };
+        // A NULL area falls back to the club's default one (ensure_table_area_default)
+        let table_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO tables (
+                event_id, name, zone, capacity, min_spend, total_cost, available,
+                location_description, features, marzipano_position, area_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id
+            "#,
+        )
+        .bind(event_id)
+        .bind(&table.name)
+        .bind(&table.zone)
+        .bind(table.capacity)
+        .bind(plan.min_spend)
+        .bind(plan.total_cost)
+        .bind(plan.available)
+        .bind(&table.location_description)
+        .bind(&table.features)
+        .bind(&table.marzipano_position)
+        .bind(table.area_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO table_images (id, table_id, url, display_order, alt_text, created_at)
+            SELECT gen_random_uuid(), $1, url, display_order, alt_text, NOW()
+            FROM table_layout_template_images
+            WHERE template_table_id = $2
+            "#,
+        )
+        .bind(table_id)
+        .bind(table.id)
+        .execute(&mut *tx)
+        .await?;
+
+        created += 1;
+    }
+
+    if details.template.marzipano_config.is_some() {
+        sqlx::query(
+            r#"
+            UPDATE events
+            SET tour_provider = COALESCE($2, tour_provider),
+                marzipano_config = $3,
+                updated_at = NOW()
+            WHERE id = $1 AND marzipano_config IS NULL
+            "#,
+        )
+        .bind(event_id)
+        .bind(&details.template.tour_provider)
+        .bind(&details.template.marzipano_config)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(Some(created))
+}
@@ -1,3 +1,4 @@
+use crate::models::split_payment::{PlannedShare, SplitSlotCounts};
 use crate::models::{ReservationGuest, ReservationPaymentShare, Table, TableReservation};
 use rust_decimal::Decimal;
 use serde_json::Value as JsonValue;
@@ -222,7 +223,7 @@ pub async fn delete_table(pool: &PgPool, table_id: Uuid) -> Result<bool, sqlx::E
 // ============================================================================
 
 /// Generate unique reservation code
-fn generate_reservation_code() -> String {
+pub(crate) fn generate_reservation_code() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     let random_part: String = (0..8)
@@ -754,6 +755,35 @@ pub async fn get_payment_share_by_checkout_session(
     .await
 }
 
+/// Release a guest share whose Checkout session expired unpaid: an open-slot
+/// share expires, a named allocation goes back to `reserved` at its planned
+/// amount. Returns `None` when the session has no share still waiting for
+/// payment.
+pub async fn expire_checkout_pending_share(
+    pool: &PgPool,
+    session_id: &str,
+) -> Result<Option<ReservationPaymentShare>, sqlx::Error> {
+    sqlx::query_as::<_, ReservationPaymentShare>(
+        r#"
+        UPDATE reservation_payment_shares
+        SET status = CASE WHEN planned_amount IS NULL THEN 'expired' ELSE 'reserved' END,
+            amount = COALESCE(planned_amount, amount),
+            stripe_checkout_session_id = CASE
+                WHEN planned_amount IS NULL THEN stripe_checkout_session_id
+            END,
+            paid_by_phone = NULL,
+            checkout_started_at = NULL,
+            updated_at = NOW()
+        WHERE stripe_checkout_session_id = $1
+          AND status = 'checkout_pending'
+        RETURNING *
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await
+}
+
 /// Get all payment shares for a reservation
 pub async fn get_payment_shares_by_reservation(
     pool: &PgPool,
@@ -814,6 +844,114 @@ pub async fn set_payment_share_checkout_session(
     .await
 }
 
+/// Slots of a shared reservation: the organizer's, the guest slots claimed
+/// so far and those of named allocations
+pub async fn get_split_slot_counts(
+    executor: impl sqlx::PgExecutor<'_>,
+    reservation_id: Uuid,
+) -> Result<SplitSlotCounts, sqlx::Error> {
+    sqlx::query_as::<_, SplitSlotCounts>(
+        r#"
+        SELECT
+            COALESCE(MAX(slots) FILTER (WHERE is_owner), 1)::INT AS owner_slots,
+            COALESCE(SUM(slots) FILTER (
+                WHERE NOT is_owner AND status IN ('paid', 'checkout_pending')
+            ), 0)::INT AS claimed_slots,
+            COALESCE(SUM(slots) FILTER (
+                WHERE NOT is_owner AND planned_amount IS NOT NULL
+                  AND status IN ('reserved', 'checkout_pending', 'paid')
+            ), 0)::INT AS allocated_slots
+        FROM reservation_payment_shares
+        WHERE reservation_id = $1
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// Create a named allocation of a custom or weighted split, waiting for
+/// anyone with the shared link to pay it
+pub async fn create_planned_share(
+    executor: impl sqlx::PgExecutor<'_>,
+    reservation_id: Uuid,
+    share: &PlannedShare,
+) -> Result<ReservationPaymentShare, sqlx::Error> {
+    sqlx::query_as::<_, ReservationPaymentShare>(
+        r#"
+        INSERT INTO reservation_payment_shares (
+            reservation_id, phone_number, guest_name, guest_email, amount, planned_amount,
+            weight, slots, status, is_owner
+        )
+        VALUES ($1, $2, $3, $4, $5, $5, $6, $7, 'reserved', false)
+        RETURNING *
+        "#,
+    )
+    .bind(reservation_id)
+    .bind(&share.phone)
+    .bind(&share.name)
+    .bind(&share.email)
+    .bind(share.amount)
+    .bind(share.weight)
+    .bind(share.slots)
+    .fetch_one(executor)
+    .await
+}
+
+/// Lock a named allocation of the reservation that nobody is paying yet
+pub async fn lock_reserved_share(
+    executor: impl sqlx::PgExecutor<'_>,
+    reservation_id: Uuid,
+    share_id: Uuid,
+) -> Result<Option<ReservationPaymentShare>, sqlx::Error> {
+    sqlx::query_as::<_, ReservationPaymentShare>(
+        r#"
+        SELECT * FROM reservation_payment_shares
+        WHERE id = $1 AND reservation_id = $2 AND status = 'reserved'
+        FOR UPDATE
+        "#,
+    )
+    .bind(share_id)
+    .bind(reservation_id)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Hold a named allocation while `payer_phone` pays it through Checkout
+pub async fn start_reserved_share_checkout(
+    executor: impl sqlx::PgExecutor<'_>,
+    share_id: Uuid,
+    amount: Decimal,
+    payer_phone: &str,
+    payer_email: Option<&str>,
+    guest_locale: &str,
+    date_of_birth: Option<chrono::NaiveDate>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE reservation_payment_shares
+        SET status = 'checkout_pending',
+            amount = $2,
+            paid_by_phone = $3,
+            guest_email = COALESCE($4, guest_email),
+            guest_locale = $5,
+            guest_date_of_birth = $6,
+            checkout_started_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1 AND status = 'reserved'
+        "#,
+    )
+    .bind(share_id)
+    .bind(amount)
+    .bind(payer_phone)
+    .bind(payer_email)
+    .bind(guest_locale)
+    .bind(date_of_birth)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
 // ============================================================================
 // Reservation Guests (Free / Non-paying)
 // ============================================================================
@@ -0,0 +1,455 @@
+use crate::models::payment::PaymentStatus;
+use crate::models::table_tab::{AddTabLineRequest, TabLine, TabSettlement, TableTab};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Result, Transaction};
+use uuid::Uuid;
+
+/// Tabs with the running sums their totals are computed from. Lines and
+/// settlements are summed in subqueries so the row stays one per tab.
+const TAB_WITH_TOTALS: &str = r#"
+    SELECT
+        tab.*,
+        t.name AS table_name,
+        r.contact_name,
+        r.amount_paid AS prepaid,
+        COALESCE((
+            SELECT SUM(l.amount) FROM table_tab_lines l
+            WHERE l.tab_id = tab.id AND l.voided_at IS NULL
+        ), 0) AS consumed,
+        COALESCE((
+            SELECT SUM(st.amount) FROM table_tab_settlements st
+            WHERE st.tab_id = tab.id AND st.status = 'paid'
+        ), 0) AS settled
+    FROM table_tabs tab
+    JOIN tables t ON t.id = tab.table_id
+    JOIN table_reservations r ON r.id = tab.reservation_id
+"#;
+
+/// Reservation fields that decide whether a tab can be opened on it.
+#[derive(Debug, sqlx::FromRow)]
+pub struct TabReservation {
+    pub id: Uuid,
+    pub club_id: Option<Uuid>,
+    pub status: String,
+    pub checked_in_count: i32,
+}
+
+pub async fn get_tab_reservation(
+    pool: &PgPool,
+    reservation_id: Uuid,
+) -> Result<Option<TabReservation>> {
+    sqlx::query_as::<_, TabReservation>(
+        r#"
+        SELECT r.id, e.club_id, r.status, r.checked_in_count
+        FROM table_reservations r
+        JOIN events e ON e.id = r.event_id
+        WHERE r.id = $1
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Open the reservation's tab at its table's total minimum spend. `None` when
+/// the reservation already has one.
+pub async fn open_tab(
+    pool: &PgPool,
+    reservation_id: Uuid,
+    club_id: Uuid,
+    opened_by: Uuid,
+) -> Result<Option<Uuid>> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO table_tabs (reservation_id, table_id, event_id, club_id, minimum_spend, opened_by)
+        SELECT r.id, r.table_id, r.event_id, $2, t.total_cost, $3
+        FROM table_reservations r
+        JOIN tables t ON t.id = r.table_id
+        WHERE r.id = $1
+        ON CONFLICT (reservation_id) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(reservation_id)
+    .bind(club_id)
+    .bind(opened_by)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn get_tab(pool: &PgPool, tab_id: Uuid) -> Result<Option<TableTab>> {
+    sqlx::query_as::<_, TableTab>(&format!("{TAB_WITH_TOTALS} WHERE tab.id = $1"))
+        .bind(tab_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn get_tab_by_reservation(
+    pool: &PgPool,
+    reservation_id: Uuid,
+) -> Result<Option<TableTab>> {
+    sqlx::query_as::<_, TableTab>(&format!("{TAB_WITH_TOTALS} WHERE tab.reservation_id = $1"))
+        .bind(reservation_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Tabs of an event of `club_id`, open ones first.
+pub async fn list_event_tabs(
+    pool: &PgPool,
+    club_id: Uuid,
+    event_id: Uuid,
+) -> Result<Vec<TableTab>> {
+    sqlx::query_as::<_, TableTab>(&format!(
+        r#"{TAB_WITH_TOTALS}
+        WHERE tab.event_id = $1
+          AND tab.club_id = $2
+        ORDER BY tab.status = 'closed', t.name
+        "#
+    ))
+    .bind(event_id)
+    .bind(club_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_tab_lines(pool: &PgPool, tab_id: Uuid) -> Result<Vec<TabLine>> {
+    sqlx::query_as::<_, TabLine>(
+        "SELECT * FROM table_tab_lines WHERE tab_id = $1 ORDER BY created_at",
+    )
+    .bind(tab_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_tab_settlements(pool: &PgPool, tab_id: Uuid) -> Result<Vec<TabSettlement>> {
+    sqlx::query_as::<_, TabSettlement>(
+        "SELECT * FROM table_tab_settlements WHERE tab_id = $1 ORDER BY created_at",
+    )
+    .bind(tab_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Record a consumption line; `None` unless the tab is open.
+pub async fn add_tab_line(
+    pool: &PgPool,
+    tab_id: Uuid,
+    request: &AddTabLineRequest,
+    quantity: i32,
+    added_by: Uuid,
+) -> Result<Option<TabLine>> {
+    sqlx::query_as::<_, TabLine>(
+        r#"
+        INSERT INTO table_tab_lines (tab_id, category, description, quantity, unit_price, amount, added_by)
+        SELECT id, $2, $3, $4, $5, $5 * $4, $6
+        FROM table_tabs
+        WHERE id = $1
+          AND status = 'open'
+        RETURNING *
+        "#,
+    )
+    .bind(tab_id)
+    .bind(&request.category)
+    .bind(request.description.trim())
+    .bind(quantity)
+    .bind(request.unit_price)
+    .bind(added_by)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Void a line of an open tab; it stays on record but leaves the totals.
+pub async fn void_tab_line(
+    pool: &PgPool,
+    tab_id: Uuid,
+    line_id: Uuid,
+    voided_by: Uuid,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE table_tab_lines l
+        SET voided_at = NOW(), voided_by = $3
+        FROM table_tabs tab
+        WHERE l.id = $2
+          AND l.tab_id = $1
+          AND l.voided_at IS NULL
+          AND tab.id = l.tab_id
+          AND tab.status = 'open'
+        "#,
+    )
+    .bind(tab_id)
+    .bind(line_id)
+    .bind(voided_by)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+async fn lock_tab(tx: &mut Transaction<'_, Postgres>, tab_id: Uuid) -> Result<Option<TableTab>> {
+    // Row lock on the tab alone; the sums are read in the same statement
+    sqlx::query_as::<_, TableTab>(&format!(
+        "{TAB_WITH_TOTALS} WHERE tab.id = $1 FOR UPDATE OF tab"
+    ))
+    .bind(tab_id)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
+/// Close the tab at its final spend once nothing is left to pay. Returns
+/// whether it closed.
+async fn close_if_settled(
+    tx: &mut Transaction<'_, Postgres>,
+    tab_id: Uuid,
+    closed_by: Uuid,
+) -> Result<bool> {
+    let Some(tab) = lock_tab(tx, tab_id).await? else {
+        return Ok(false);
+    };
+    let totals = tab.totals();
+    if tab.status != "open" || totals.balance_due > Decimal::ZERO {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE table_tabs
+        SET status = 'closed', final_spend = $2, closed_by = $3, closed_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(tab_id)
+    .bind(totals.final_spend)
+    .bind(closed_by)
+    .execute(&mut **tx)
+    .await?;
+    Ok(true)
+}
+
+/// Close an open tab that owes nothing. `false` when it is not open or still
+/// has a balance.
+pub async fn close_tab(pool: &PgPool, tab_id: Uuid, closed_by: Uuid) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let closed = close_if_settled(&mut tx, tab_id, closed_by).await?;
+    tx.commit().await?;
+    Ok(closed)
+}
+
+/// Record cash taken by staff against an open tab, closing it when that
+/// covers the balance. `None` when the tab is not open or the amount is more
+/// than what is due.
+pub async fn record_cash_settlement(
+    pool: &PgPool,
+    tab_id: Uuid,
+    amount: Decimal,
+    recorded_by: Uuid,
+) -> Result<Option<TabSettlement>> {
+    let mut tx = pool.begin().await?;
+
+    let Some(tab) = lock_tab(&mut tx, tab_id).await? else {
+        return Ok(None);
+    };
+    if tab.status != "open" || amount > tab.totals().balance_due {
+        return Ok(None);
+    }
+
+    let settlement = sqlx::query_as::<_, TabSettlement>(
+        r#"
+        INSERT INTO table_tab_settlements (tab_id, method, amount, status, recorded_by, paid_at)
+        VALUES ($1, 'cash', $2, 'paid', $3, NOW())
+        RETURNING *
+        "#,
+    )
+    .bind(tab_id)
+    .bind(amount)
+    .bind(recorded_by)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    close_if_settled(&mut tx, tab_id, recorded_by).await?;
+    tx.commit().await?;
+
+    Ok(Some(settlement))
+}
+
+/// Put an open tab on hold for a Stripe payment of `amount`. Lines cannot be
+/// added until the payment completes or its checkout ends unpaid.
+pub async fn start_stripe_settlement(
+    pool: &PgPool,
+    tab_id: Uuid,
+    amount: Decimal,
+    recorded_by: Uuid,
+) -> Result<Option<TabSettlement>> {
+    let mut tx = pool.begin().await?;
+
+    let Some(tab) = lock_tab(&mut tx, tab_id).await? else {
+        return Ok(None);
+    };
+    if tab.status != "open" || amount > tab.totals().balance_due {
+        return Ok(None);
+    }
+
+    let settlement = sqlx::query_as::<_, TabSettlement>(
+        r#"
+        INSERT INTO table_tab_settlements (tab_id, method, amount, status, recorded_by)
+        VALUES ($1, 'stripe', $2, 'pending', $3)
+        RETURNING *
+        "#,
+    )
+    .bind(tab_id)
+    .bind(amount)
+    .bind(recorded_by)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE table_tabs SET status = 'settling', updated_at = NOW() WHERE id = $1")
+        .bind(tab_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Some(settlement))
+}
+
+pub async fn get_settlement(pool: &PgPool, settlement_id: Uuid) -> Result<Option<TabSettlement>> {
+    sqlx::query_as::<_, TabSettlement>("SELECT * FROM table_tab_settlements WHERE id = $1")
+        .bind(settlement_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The Stripe settlement a `settling` tab is waiting on
+pub async fn get_pending_settlement(pool: &PgPool, tab_id: Uuid) -> Result<Option<TabSettlement>> {
+    sqlx::query_as::<_, TabSettlement>(
+        "SELECT * FROM table_tab_settlements WHERE tab_id = $1 AND status = 'pending'",
+    )
+    .bind(tab_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn set_settlement_checkout_session(
+    pool: &PgPool,
+    settlement_id: Uuid,
+    session_id: &str,
+) -> Result<()> {
+    sqlx::query("UPDATE table_tab_settlements SET stripe_checkout_session_id = $2 WHERE id = $1")
+        .bind(settlement_id)
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Mark a Stripe settlement paid, record the payment against the reservation's
+/// booker and close the tab when nothing is left. `None` when the settlement
+/// was not pending.
+pub async fn complete_stripe_settlement(
+    pool: &PgPool,
+    settlement_id: Uuid,
+    stripe_payment_intent_id: Option<&str>,
+) -> Result<Option<(TabSettlement, bool)>> {
+    let mut tx = pool.begin().await?;
+
+    let Some(mut settlement) = sqlx::query_as::<_, TabSettlement>(
+        r#"
+        UPDATE table_tab_settlements
+        SET status = 'paid',
+            stripe_payment_intent_id = COALESCE($2, stripe_payment_intent_id),
+            paid_at = NOW()
+        WHERE id = $1
+          AND status = 'pending'
+        RETURNING *
+        "#,
+    )
+    .bind(settlement_id)
+    .bind(stripe_payment_intent_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        tx.rollback().await?;
+        return Ok(None);
+    };
+
+    let user_id: Uuid = sqlx::query_scalar(
+        r#"
+        SELECT r.user_id
+        FROM table_tabs tab
+        JOIN table_reservations r ON r.id = tab.reservation_id
+        WHERE tab.id = $1
+        "#,
+    )
+    .bind(settlement.tab_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let now = Utc::now().naive_utc();
+    let payment_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO payments (id, sender_id, receiver_id, amount, status, insert_date, update_date, stripe_payment_intent_id, user_ids)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(payment_id)
+    .bind(user_id)
+    .bind(user_id)
+    .bind(settlement.amount)
+    .bind(PaymentStatus::Completed)
+    .bind(now)
+    .bind(now)
+    .bind(stripe_payment_intent_id.unwrap_or(""))
+    .bind(vec![user_id])
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE table_tab_settlements SET payment_id = $2 WHERE id = $1")
+        .bind(settlement.id)
+        .bind(payment_id)
+        .execute(&mut *tx)
+        .await?;
+    settlement.payment_id = Some(payment_id);
+    sqlx::query(
+        "UPDATE table_tabs SET status = 'open', updated_at = NOW() WHERE id = $1 AND status = 'settling'",
+    )
+    .bind(settlement.tab_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let closed = close_if_settled(&mut tx, settlement.tab_id, settlement.recorded_by).await?;
+    tx.commit().await?;
+
+    Ok(Some((settlement, closed)))
+}
+
+/// Drop a Stripe settlement whose checkout ended unpaid (or never opened) and
+/// reopen its tab. Returns whether anything was pending.
+pub async fn release_stripe_settlement(pool: &PgPool, settlement_id: Uuid) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    let tab_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        UPDATE table_tab_settlements
+        SET status = 'expired'
+        WHERE id = $1
+          AND status = 'pending'
+        RETURNING tab_id
+        "#,
+    )
+    .bind(settlement_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(tab_id) = tab_id {
+        sqlx::query(
+            "UPDATE table_tabs SET status = 'open', updated_at = NOW() WHERE id = $1 AND status = 'settling'",
+        )
+        .bind(tab_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(tab_id.is_some())
+}
@@ -112,7 +112,7 @@ pub async fn get_tickets_with_events_by_user_id(
 }
 
 /// Generate a unique ticket code
-fn generate_ticket_code() -> String {
+pub(crate) fn generate_ticket_code() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     let random_part: String = (0..8)
@@ -0,0 +1,420 @@
+use crate::infrastructure::repositories::ticket_repository::generate_ticket_code;
+use crate::models::{PaymentStatus, TicketOrder, TicketPurchaseRejection, TicketTier};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_ticket_tier(
+    pool: &PgPool,
+    event_id: Uuid,
+    name: &str,
+    description: Option<String>,
+    price: Decimal,
+    quantity_total: i32,
+    sales_start_at: Option<DateTime<Utc>>,
+    sales_end_at: Option<DateTime<Utc>>,
+    max_per_user: Option<i32>,
+    sort_order: i32,
+) -> Result<TicketTier, sqlx::Error> {
+    sqlx::query_as::<_, TicketTier>(
+        r#"
+        INSERT INTO ticket_tiers (
+            event_id, name, description, price, quantity_total,
+            sales_start_at, sales_end_at, max_per_user, sort_order
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(event_id)
+    .bind(name)
+    .bind(description)
+    .bind(price)
+    .bind(quantity_total)
+    .bind(sales_start_at)
+    .bind(sales_end_at)
+    .bind(max_per_user)
+    .bind(sort_order)
+    .fetch_one(pool)
+    .await
+}
+
+/// Tiers of an event in display order; `active_only` hides deactivated tiers
+/// from the public listing.
+pub async fn get_tiers_by_event(
+    pool: &PgPool,
+    event_id: Uuid,
+    active_only: bool,
+) -> Result<Vec<TicketTier>, sqlx::Error> {
+    sqlx::query_as::<_, TicketTier>(
+        r#"
+        SELECT * FROM ticket_tiers
+        WHERE event_id = $1
+          AND (is_active OR NOT $2)
+        ORDER BY sort_order, price, created_at
+        "#,
+    )
+    .bind(event_id)
+    .bind(active_only)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_ticket_tier_by_id(
+    pool: &PgPool,
+    tier_id: Uuid,
+) -> Result<Option<TicketTier>, sqlx::Error> {
+    sqlx::query_as::<_, TicketTier>("SELECT * FROM ticket_tiers WHERE id = $1")
+        .bind(tier_id)
+        .fetch_optional(pool)
+        .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_ticket_tier(
+    pool: &PgPool,
+    tier_id: Uuid,
+    name: Option<String>,
+    description: Option<String>,
+    price: Option<Decimal>,
+    quantity_total: Option<i32>,
+    sales_start_at: Option<DateTime<Utc>>,
+    sales_end_at: Option<DateTime<Utc>>,
+    max_per_user: Option<i32>,
+    sort_order: Option<i32>,
+    is_active: Option<bool>,
+) -> Result<Option<TicketTier>, sqlx::Error> {
+    sqlx::query_as::<_, TicketTier>(
+        r#"
+        UPDATE ticket_tiers
+        SET name           = COALESCE($2, name),
+            description    = COALESCE($3, description),
+            price          = COALESCE($4, price),
+            quantity_total = COALESCE($5, quantity_total),
+            sales_start_at = COALESCE($6, sales_start_at),
+            sales_end_at   = COALESCE($7, sales_end_at),
+            max_per_user   = COALESCE($8, max_per_user),
+            sort_order     = COALESCE($9, sort_order),
+            is_active      = COALESCE($10, is_active),
+            updated_at     = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(tier_id)
+    .bind(name)
+    .bind(description)
+    .bind(price)
+    .bind(quantity_total)
+    .bind(sales_start_at)
+    .bind(sales_end_at)
+    .bind(max_per_user)
+    .bind(sort_order)
+    .bind(is_active)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Delete a tier nobody has ordered from yet. Returns false when orders exist;
+/// such tiers can only be deactivated.
+pub async fn delete_ticket_tier(pool: &PgPool, tier_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM ticket_tiers
+        WHERE id = $1
+          AND NOT EXISTS (SELECT 1 FROM ticket_orders WHERE tier_id = $1)
+        "#,
+    )
+    .bind(tier_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Hold `quantity` tickets of a tier for a new pending order.
+///
+/// The tier row is locked for the whole transaction, so the per-user limit
+/// and the inventory check cannot be raced by a concurrent checkout.
+#[allow(clippy::too_many_arguments)]
+pub async fn hold_tickets(
+    pool: &PgPool,
+    tier_id: Uuid,
+    user_id: Uuid,
+    quantity: i32,
+    unit_price: Decimal,
+    total_amount: Decimal,
+    application_fee_amount: Option<Decimal>,
+    expires_at: DateTime<Utc>,
+) -> Result<Result<TicketOrder, TicketPurchaseRejection>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let tier =
+        sqlx::query_as::<_, TicketTier>("SELECT * FROM ticket_tiers WHERE id = $1 FOR UPDATE")
+            .bind(tier_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    let Some(tier) = tier else {
+        tx.rollback().await?;
+        return Ok(Err(TicketPurchaseRejection::Inactive));
+    };
+
+    if let Some(max_per_user) = tier.max_per_user {
+        let already_ordered: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(quantity), 0)
+            FROM ticket_orders
+            WHERE tier_id = $1
+              AND user_id = $2
+              AND status IN ('pending', 'paid')
+            "#,
+        )
+        .bind(tier_id)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if already_ordered + i64::from(quantity) > i64::from(max_per_user) {
+            tx.rollback().await?;
+            return Ok(Err(TicketPurchaseRejection::UserLimitReached));
+        }
+    }
+
+    let held = sqlx::query(
+        r#"
+        UPDATE ticket_tiers
+        SET quantity_held = quantity_held + $2, updated_at = NOW()
+        WHERE id = $1
+          AND is_active
+          AND quantity_sold + quantity_held + $2 <= quantity_total
+        "#,
+    )
+    .bind(tier_id)
+    .bind(quantity)
+    .execute(&mut *tx)
+    .await?;
+    if held.rows_affected() == 0 {
+        tx.rollback().await?;
+        return Ok(Err(TicketPurchaseRejection::SoldOut));
+    }
+
+    let order = sqlx::query_as::<_, TicketOrder>(
+        r#"
+        INSERT INTO ticket_orders (
+            tier_id, event_id, user_id, quantity, unit_price, total_amount,
+            application_fee_amount, expires_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(tier_id)
+    .bind(tier.event_id)
+    .bind(user_id)
+    .bind(quantity)
+    .bind(unit_price)
+    .bind(total_amount)
+    .bind(application_fee_amount)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Ok(order))
+}
+
+pub async fn set_order_checkout_session(
+    pool: &PgPool,
+    order_id: Uuid,
+    checkout_session_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE ticket_orders
+        SET stripe_checkout_session_id = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(order_id)
+    .bind(checkout_session_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_ticket_order_by_id(
+    pool: &PgPool,
+    order_id: Uuid,
+) -> Result<Option<TicketOrder>, sqlx::Error> {
+    sqlx::query_as::<_, TicketOrder>("SELECT * FROM ticket_orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Expire a pending order and give its held tickets back to the tier.
+/// Returns false when the order was no longer pending.
+pub async fn release_ticket_order(pool: &PgPool, order_id: Uuid) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let released: Option<(Uuid, i32)> = sqlx::query_as(
+        r#"
+        UPDATE ticket_orders
+        SET status = 'expired', updated_at = NOW()
+        WHERE id = $1
+          AND status = 'pending'
+        RETURNING tier_id, quantity
+        "#,
+    )
+    .bind(order_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((tier_id, quantity)) = released else {
+        tx.rollback().await?;
+        return Ok(false);
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE ticket_tiers
+        SET quantity_held = GREATEST(quantity_held - $2, 0), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(tier_id)
+    .bind(quantity)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Pending orders whose checkout window closed more than `grace_minutes` ago.
+/// The grace period leaves room for a late `checkout.session.completed`.
+pub async fn get_stale_pending_orders(
+    pool: &PgPool,
+    grace_minutes: i64,
+) -> Result<Vec<TicketOrder>, sqlx::Error> {
+    sqlx::query_as::<_, TicketOrder>(
+        r#"
+        SELECT * FROM ticket_orders
+        WHERE status = 'pending'
+          AND expires_at < NOW() - make_interval(mins => $1::int)
+        ORDER BY expires_at
+        LIMIT 200
+        "#,
+    )
+    .bind(grace_minutes)
+    .fetch_all(pool)
+    .await
+}
+
+/// Mark a pending order paid: record the payment, move the held tickets to
+/// sold and issue one ticket per seat. Returns `None` when the order was not
+/// pending any more (duplicate delivery or already released).
+pub async fn complete_ticket_order(
+    pool: &PgPool,
+    order_id: Uuid,
+    stripe_payment_intent_id: Option<&str>,
+) -> Result<Option<(TicketOrder, Vec<Uuid>)>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let order = sqlx::query_as::<_, TicketOrder>(
+        r#"
+        UPDATE ticket_orders
+        SET status = 'paid',
+            stripe_payment_intent_id = COALESCE($2, stripe_payment_intent_id),
+            paid_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1
+          AND status = 'pending'
+        RETURNING *
+        "#,
+    )
+    .bind(order_id)
+    .bind(stripe_payment_intent_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(order) = order else {
+        tx.rollback().await?;
+        return Ok(None);
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE ticket_tiers
+        SET quantity_held = GREATEST(quantity_held - $2, 0),
+            quantity_sold = quantity_sold + $2,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(order.tier_id)
+    .bind(order.quantity)
+    .execute(&mut *tx)
+    .await?;
+
+    let now = Utc::now().naive_utc();
+    let payment_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO payments (id, sender_id, receiver_id, amount, status, insert_date, update_date, stripe_payment_intent_id, user_ids)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(payment_id)
+    .bind(order.user_id)
+    .bind(order.user_id)
+    .bind(order.total_amount)
+    .bind(PaymentStatus::Completed)
+    .bind(now)
+    .bind(now)
+    .bind(stripe_payment_intent_id.unwrap_or(""))
+    .bind(vec![order.user_id])
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE ticket_orders SET payment_id = $2 WHERE id = $1")
+        .bind(order.id)
+        .bind(payment_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let mut ticket_ids = Vec::with_capacity(order.quantity as usize);
+    for _ in 0..order.quantity {
+        let ticket_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO tickets (
+                id, event_id, user_id, ticket_code, ticket_type, price, status,
+                purchase_date, qr_code, tier_id, order_id, created_at, updated_at
+            )
+            SELECT $1, $2, $3, $4, t.name, $5, 'active', NOW(), NULL, t.id, $6, NOW(), NOW()
+            FROM ticket_tiers t
+            WHERE t.id = $7
+            RETURNING id
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(order.event_id)
+        .bind(order.user_id)
+        .bind(generate_ticket_code())
+        .bind(order.unit_price)
+        .bind(order.id)
+        .bind(order.tier_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        ticket_ids.push(ticket_id);
+    }
+
+    tx.commit().await?;
+    Ok(Some((
+        TicketOrder {
+            payment_id: Some(payment_id),
+            ..order
+        },
+        ticket_ids,
+    )))
+}
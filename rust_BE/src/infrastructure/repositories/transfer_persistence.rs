@@ -0,0 +1,480 @@
+use crate::infrastructure::repositories::table_repository::generate_reservation_code;
+use crate::infrastructure::repositories::ticket_repository::generate_ticket_code;
+use crate::models::transfer::{TransferAuditEntry, TransferClaim, TransferableItem};
+use crate::models::{OwnershipTransfer, User};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+const TRANSFER_WITH_DETAILS: &str = r#"
+    SELECT
+        tr.*,
+        e.title AS event_title,
+        u.name  AS from_user_name
+    FROM ownership_transfers tr
+    JOIN events e ON e.id = tr.event_id
+    JOIN users u ON u.id = tr.from_user_id
+"#;
+
+async fn find_transferable_item(
+    executor: impl sqlx::PgExecutor<'_>,
+    item_type: &str,
+    item_id: Uuid,
+    lock: bool,
+) -> Result<Option<TransferableItem>> {
+    let query = match item_type {
+        "ticket" => {
+            r#"
+            SELECT item.id, item.event_id, item.user_id, item.status,
+                   item.ticket_code AS code, 0 AS checked_in_count,
+                   e.transfers_enabled, e.event_date
+            FROM tickets item
+            JOIN events e ON e.id = item.event_id
+            WHERE item.id = $1
+            "#
+        }
+        _ => {
+            r#"
+            SELECT item.id, item.event_id, item.user_id, item.status,
+                   item.reservation_code AS code, item.checked_in_count,
+                   e.transfers_enabled, e.event_date
+            FROM table_reservations item
+            JOIN events e ON e.id = item.event_id
+            WHERE item.id = $1
+            "#
+        }
+    };
+    let query = if lock {
+        format!("{query} FOR UPDATE OF item")
+    } else {
+        query.to_string()
+    };
+
+    sqlx::query_as::<_, TransferableItem>(&query)
+        .bind(item_id)
+        .fetch_optional(executor)
+        .await
+}
+
+/// The ticket or reservation a user wants to hand over.
+pub async fn get_transferable_item(
+    pool: &PgPool,
+    item_type: &str,
+    item_id: Uuid,
+) -> Result<Option<TransferableItem>> {
+    find_transferable_item(pool, item_type, item_id, false).await
+}
+
+/// Expire pending transfers past their deadline, auditing each one.
+pub async fn expire_stale_transfers(pool: &PgPool) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        WITH expired AS (
+            UPDATE ownership_transfers
+            SET status = 'expired', updated_at = NOW()
+            WHERE status = 'pending'
+              AND expires_at <= NOW()
+            RETURNING *
+        )
+        INSERT INTO transfer_audit_log (
+            transfer_id, event_id, item_type, ticket_id, reservation_id, actor_user_id,
+            action, from_user_id, recipient_phone, recipient_email
+        )
+        SELECT id, event_id, item_type, ticket_id, reservation_id, NULL,
+               'expired', from_user_id, recipient_phone, recipient_email
+        FROM expired
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+async fn insert_transfer_audit(
+    executor: impl sqlx::PgExecutor<'_>,
+    transfer: &OwnershipTransfer,
+    actor_user_id: Option<Uuid>,
+    action: &str,
+    codes: Option<(&str, &str)>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO transfer_audit_log (
+            transfer_id, event_id, item_type, ticket_id, reservation_id, actor_user_id,
+            action, from_user_id, to_user_id, recipient_phone, recipient_email,
+            old_code, new_code
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        "#,
+    )
+    .bind(transfer.id)
+    .bind(transfer.event_id)
+    .bind(&transfer.item_type)
+    .bind(transfer.ticket_id)
+    .bind(transfer.reservation_id)
+    .bind(actor_user_id)
+    .bind(action)
+    .bind(transfer.from_user_id)
+    .bind(transfer.to_user_id)
+    .bind(&transfer.recipient_phone)
+    .bind(&transfer.recipient_email)
+    .bind(codes.map(|(old_code, _)| old_code))
+    .bind(codes.map(|(_, new_code)| new_code))
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Open a transfer of `item` to whoever owns the phone number or email (with
+/// the hash of the code emailed there). Fails with a unique violation while
+/// another transfer of the item is pending.
+pub async fn create_transfer(
+    pool: &PgPool,
+    item_type: &str,
+    item: &TransferableItem,
+    recipient_phone: Option<&str>,
+    recipient_email: Option<&str>,
+    claim_code_hash: Option<&str>,
+    expires_at: DateTime<Utc>,
+) -> Result<OwnershipTransfer> {
+    let mut tx = pool.begin().await?;
+
+    let transfer = sqlx::query_as::<_, OwnershipTransfer>(
+        r#"
+        INSERT INTO ownership_transfers (
+            item_type, ticket_id, reservation_id, event_id, from_user_id,
+            recipient_phone, recipient_email, claim_code_hash, expires_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(item_type)
+    .bind((item_type == "ticket").then_some(item.id))
+    .bind((item_type == "reservation").then_some(item.id))
+    .bind(item.event_id)
+    .bind(item.user_id)
+    .bind(recipient_phone)
+    .bind(recipient_email)
+    .bind(claim_code_hash)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    insert_transfer_audit(&mut *tx, &transfer, Some(item.user_id), "initiated", None).await?;
+    tx.commit().await?;
+
+    Ok(transfer)
+}
+
+pub async fn get_transfer(pool: &PgPool, transfer_id: Uuid) -> Result<Option<OwnershipTransfer>> {
+    sqlx::query_as::<_, OwnershipTransfer>(&format!("{TRANSFER_WITH_DETAILS} WHERE tr.id = $1"))
+        .bind(transfer_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Pending transfers addressed to the user's verified phone number or account
+/// email (accepting an email transfer still takes its claim code).
+pub async fn list_incoming_transfers(
+    pool: &PgPool,
+    recipient: &User,
+) -> Result<Vec<OwnershipTransfer>> {
+    sqlx::query_as::<_, OwnershipTransfer>(&format!(
+        r#"{TRANSFER_WITH_DETAILS}
+        WHERE tr.status = 'pending'
+          AND tr.expires_at > NOW()
+          AND (tr.recipient_phone = $1 OR LOWER(tr.recipient_email) = LOWER($2))
+        ORDER BY tr.created_at DESC
+        "#
+    ))
+    .bind(verified_phone(recipient))
+    .bind(&recipient.email)
+    .fetch_all(pool)
+    .await
+}
+
+/// Transfers the user started, most recent first.
+pub async fn list_outgoing_transfers(
+    pool: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<OwnershipTransfer>> {
+    sqlx::query_as::<_, OwnershipTransfer>(&format!(
+        r#"{TRANSFER_WITH_DETAILS}
+        WHERE tr.from_user_id = $1
+        ORDER BY tr.created_at DESC
+        LIMIT $2
+        "#
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+fn verified_phone(user: &User) -> Option<&str> {
+    user.phone_number.as_deref().filter(|_| user.phone_verified)
+}
+
+/// Close a pending transfer without moving the item: `declined` by the
+/// recipient or `cancelled` by the sender.
+pub async fn close_transfer(
+    pool: &PgPool,
+    transfer: &OwnershipTransfer,
+    actor_user_id: Uuid,
+    status: &str,
+) -> Result<Option<OwnershipTransfer>> {
+    let mut tx = pool.begin().await?;
+
+    let closed = sqlx::query_as::<_, OwnershipTransfer>(
+        r#"
+        UPDATE ownership_transfers
+        SET status = $2, responded_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+          AND status = 'pending'
+        RETURNING *
+        "#,
+    )
+    .bind(transfer.id)
+    .bind(status)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(closed) = &closed {
+        insert_transfer_audit(&mut *tx, closed, Some(actor_user_id), status, None).await?;
+    }
+    tx.commit().await?;
+
+    Ok(closed)
+}
+
+pub enum TransferAcceptance {
+    /// Carries the code issued to the recipient
+    Accepted {
+        new_code: String,
+    },
+    /// No pending transfer with this id is addressed to the user
+    NotFound,
+    Expired,
+    /// The item changed since the transfer was opened; carries the blocking reason
+    Unavailable(&'static str),
+    /// The emailed claim code is missing, wrong or locked
+    Claim(TransferClaim),
+}
+
+/// Hand the item over to `recipient`, who entered the claim code hashing to
+/// `claim_code_hash` if the transfer went to an email. A wrong code is counted
+/// against the transfer.
+///
+/// The item moves to the recipient with a freshly issued code, so the old code
+/// (and its QR) stops scanning. A reservation also takes along the tickets the
+/// sender holds for it. Everything happens in one transaction with its audit
+/// row; a transfer the item no longer qualifies for is cancelled instead.
+pub async fn accept_transfer(
+    pool: &PgPool,
+    transfer_id: Uuid,
+    recipient: &User,
+    claim_code_hash: Option<&str>,
+) -> Result<TransferAcceptance> {
+    let mut tx = pool.begin().await?;
+
+    let Some(transfer) = sqlx::query_as::<_, OwnershipTransfer>(
+        r#"
+        SELECT * FROM ownership_transfers
+        WHERE id = $1
+          AND status = 'pending'
+          AND (recipient_phone = $2 OR LOWER(recipient_email) = LOWER($3))
+        FOR UPDATE
+        "#,
+    )
+    .bind(transfer_id)
+    .bind(verified_phone(recipient))
+    .bind(&recipient.email)
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(TransferAcceptance::NotFound);
+    };
+
+    match transfer.claim(recipient, claim_code_hash) {
+        TransferClaim::Allowed => {}
+        TransferClaim::NotAddressed => return Ok(TransferAcceptance::NotFound),
+        TransferClaim::WrongCode => {
+            sqlx::query(
+                r#"
+                UPDATE ownership_transfers
+                SET failed_claim_attempts = failed_claim_attempts + 1, updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(transfer.id)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Ok(TransferAcceptance::Claim(TransferClaim::WrongCode));
+        }
+        claim => return Ok(TransferAcceptance::Claim(claim)),
+    }
+
+    if transfer.expires_at <= Utc::now() {
+        let expired = set_transfer_status(&mut *tx, transfer.id, "expired", None).await?;
+        insert_transfer_audit(&mut *tx, &expired, None, "expired", None).await?;
+        tx.commit().await?;
+        return Ok(TransferAcceptance::Expired);
+    }
+
+    let item_id = transfer.item_id().unwrap_or_default();
+    let item = find_transferable_item(&mut *tx, &transfer.item_type, item_id, true)
+        .await?
+        .filter(|item| item.user_id == transfer.from_user_id);
+    let reason = match &item {
+        Some(item) => item.blocking_reason(Utc::now().date_naive()),
+        None => Some("not_transferable"),
+    };
+    let (Some(item), None) = (item, reason) else {
+        let cancelled = set_transfer_status(&mut *tx, transfer.id, "cancelled", None).await?;
+        insert_transfer_audit(&mut *tx, &cancelled, None, "cancelled", None).await?;
+        tx.commit().await?;
+        return Ok(TransferAcceptance::Unavailable(
+            reason.unwrap_or("not_transferable"),
+        ));
+    };
+    let old_code = item.code;
+
+    let new_code = if transfer.item_type == "ticket" {
+        let new_code = generate_ticket_code();
+        reissue_ticket(&mut tx, item_id, recipient.id, &new_code).await?;
+        new_code
+    } else {
+        let new_code = generate_reservation_code();
+        sqlx::query(
+            r#"
+            UPDATE table_reservations
+            SET user_id = $2,
+                reservation_code = $3,
+                contact_name = $4,
+                contact_email = $5,
+                contact_phone = COALESCE($6, contact_phone),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(item_id)
+        .bind(recipient.id)
+        .bind(&new_code)
+        .bind(&recipient.name)
+        .bind(&recipient.email)
+        .bind(&recipient.phone_number)
+        .execute(&mut *tx)
+        .await?;
+
+        let ticket_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT t.id
+            FROM tickets t
+            JOIN table_reservations r ON t.id = ANY(r.ticket_ids)
+            WHERE r.id = $1
+              AND t.user_id = $2
+              AND t.status = 'active'
+            FOR UPDATE OF t
+            "#,
+        )
+        .bind(item_id)
+        .bind(transfer.from_user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        for ticket_id in ticket_ids {
+            reissue_ticket(&mut tx, ticket_id, recipient.id, &generate_ticket_code()).await?;
+        }
+        new_code
+    };
+
+    let accepted =
+        set_transfer_status(&mut *tx, transfer.id, "accepted", Some(recipient.id)).await?;
+    insert_transfer_audit(
+        &mut *tx,
+        &accepted,
+        Some(recipient.id),
+        "accepted",
+        Some((&old_code, &new_code)),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(TransferAcceptance::Accepted { new_code })
+}
+
+/// Move a ticket to a new holder under a new code; the stored QR belonged to
+/// the old code and is dropped.
+async fn reissue_ticket(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ticket_id: Uuid,
+    user_id: Uuid,
+    new_code: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE tickets
+        SET user_id = $2, ticket_code = $3, qr_code = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(ticket_id)
+    .bind(user_id)
+    .bind(new_code)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+async fn set_transfer_status(
+    executor: impl sqlx::PgExecutor<'_>,
+    transfer_id: Uuid,
+    status: &str,
+    to_user_id: Option<Uuid>,
+) -> Result<OwnershipTransfer> {
+    sqlx::query_as::<_, OwnershipTransfer>(
+        r#"
+        UPDATE ownership_transfers
+        SET status = $2,
+            to_user_id = COALESCE($3, to_user_id),
+            responded_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(transfer_id)
+    .bind(status)
+    .bind(to_user_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// Transfer history of an event of `club_id`, most recent first.
+pub async fn get_transfer_audit_log(
+    pool: &PgPool,
+    club_id: Uuid,
+    event_id: Uuid,
+    limit: i64,
+) -> Result<Vec<TransferAuditEntry>> {
+    sqlx::query_as::<_, TransferAuditEntry>(
+        r#"
+        SELECT l.*, f.name AS from_user_name, t.name AS to_user_name
+        FROM transfer_audit_log l
+        JOIN events e ON e.id = l.event_id
+        LEFT JOIN users f ON f.id = l.from_user_id
+        LEFT JOIN users t ON t.id = l.to_user_id
+        WHERE l.event_id = $1
+          AND e.club_id = $2
+        ORDER BY l.created_at DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(event_id)
+    .bind(club_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
@@ -11,11 +11,12 @@ pub async fn create_user(
     name: String,
     phone_number: String,
     date_of_birth: NaiveDate,
+    preferred_locale: &str,
 ) -> Result<User> {
     let user = sqlx::query_as::<_, User>(
         r#"
-        INSERT INTO users (id, email, password_hash, name, phone_number, date_of_birth, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+        INSERT INTO users (id, email, password_hash, name, phone_number, date_of_birth, preferred_locale, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
         RETURNING id, email, password_hash, name, phone_number, phone_verified, avatar_url, date_of_birth, created_at, updated_at
         "#,
     )
@@ -25,6 +26,7 @@ pub async fn create_user(
     .bind(name)
     .bind(phone_number)
     .bind(date_of_birth)
+    .bind(preferred_locale)
     .fetch_one(pool)
     .await?;
 
@@ -65,6 +67,30 @@ pub async fn find_user_by_id(pool: &PgPool, user_id: Uuid) -> Result<Option<User
     Ok(user)
 }
 
+/// Stores a birth date for a user who has none yet (accounts created before
+/// it was asked at registration) and returns the one on file.
+pub async fn set_date_of_birth_if_missing(
+    pool: &PgPool,
+    user_id: Uuid,
+    date_of_birth: NaiveDate,
+) -> Result<Option<NaiveDate>> {
+    sqlx::query_scalar(
+        r#"
+        UPDATE users
+        SET date_of_birth = COALESCE(date_of_birth, $2),
+            updated_at = CASE WHEN date_of_birth IS NULL THEN NOW() ELSE updated_at END
+        WHERE id = $1
+          AND deleted_at IS NULL
+        RETURNING date_of_birth
+        "#,
+    )
+    .bind(user_id)
+    .bind(date_of_birth)
+    .fetch_optional(pool)
+    .await
+    .map(Option::flatten)
+}
+
 /// Find a user by phone number
 pub async fn find_user_by_phone(pool: &PgPool, phone_number: &str) -> Result<Option<User>> {
     let user = sqlx::query_as::<_, User>(
@@ -206,6 +232,11 @@ pub async fn anonymize_and_delete_user_account(
     .execute(&mut *tx)
     .await?;
 
+    sqlx::query("DELETE FROM push_devices WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
     sqlx::query(
         r#"
         UPDATE users
@@ -0,0 +1,455 @@
+use crate::models::WaitlistEntry;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// What a waitlisted user needs to be told about a fresh hold.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct WaitlistHoldContext {
+    pub event_title: String,
+    pub table_name: Option<String>,
+    pub user_phone: Option<String>,
+    pub payment_link_token: Option<String>,
+}
+
+const ENTRY_WITH_DETAILS: &str = r#"
+    SELECT
+        w.*,
+        u.name          AS user_name,
+        u.phone_number  AS user_phone,
+        a.name          AS area_name,
+        t.name          AS held_table_name
+    FROM event_waitlist_entries w
+    JOIN users u ON u.id = w.user_id
+    LEFT JOIN areas a ON a.id = w.area_id
+    LEFT JOIN tables t ON t.id = w.held_table_id
+"#;
+
+/// Append a user to the end of an event's queue.
+pub async fn insert_entry(
+    pool: &PgPool,
+    event_id: Uuid,
+    user_id: Uuid,
+    area_id: Option<Uuid>,
+    min_capacity: Option<i32>,
+) -> Result<WaitlistEntry, sqlx::Error> {
+    sqlx::query_as::<_, WaitlistEntry>(
+        r#"
+        INSERT INTO event_waitlist_entries (event_id, user_id, area_id, min_capacity, position)
+        VALUES (
+            $1, $2, $3, $4,
+            (SELECT COALESCE(MAX(position), 0) + 1 FROM event_waitlist_entries WHERE event_id = $1)
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(event_id)
+    .bind(user_id)
+    .bind(area_id)
+    .bind(min_capacity)
+    .fetch_one(pool)
+    .await
+}
+
+/// The user's waiting or offered entry for an event, if any.
+pub async fn get_active_entry_for_user(
+    pool: &PgPool,
+    event_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    sqlx::query_as::<_, WaitlistEntry>(&format!(
+        "{ENTRY_WITH_DETAILS} WHERE w.event_id = $1 AND w.user_id = $2 AND w.status IN ('waiting', 'offered')"
+    ))
+    .bind(event_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Number of active entries ahead of `entry` in its event's queue.
+pub async fn count_entries_ahead(pool: &PgPool, entry: &WaitlistEntry) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM event_waitlist_entries
+        WHERE event_id = $1
+          AND status IN ('waiting', 'offered')
+          AND (position, created_at) < ($2, $3)
+        "#,
+    )
+    .bind(entry.event_id)
+    .bind(entry.position)
+    .bind(entry.created_at)
+    .fetch_one(pool)
+    .await
+}
+
+/// Leave the queue. Returns the entry as it was before cancelling so callers can
+/// pass on any hold it carried.
+pub async fn cancel_entry(
+    pool: &PgPool,
+    entry_id: Uuid,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    sqlx::query_as::<_, WaitlistEntry>(
+        r#"
+        UPDATE event_waitlist_entries
+        SET status = 'cancelled', updated_at = NOW()
+        WHERE id = $1
+          AND status IN ('waiting', 'offered')
+        RETURNING *
+        "#,
+    )
+    .bind(entry_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Active entries of an event in queue order.
+pub async fn get_event_waitlist(
+    pool: &PgPool,
+    event_id: Uuid,
+) -> Result<Vec<WaitlistEntry>, sqlx::Error> {
+    sqlx::query_as::<_, WaitlistEntry>(&format!(
+        "{ENTRY_WITH_DETAILS} WHERE w.event_id = $1 AND w.status IN ('waiting', 'offered') ORDER BY w.position ASC, w.created_at ASC"
+    ))
+    .bind(event_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Rewrite queue positions so they follow `ordered_ids`. The list must contain
+/// exactly the event's active entries; returns `false` (and changes nothing)
+/// otherwise.
+pub async fn reorder_entries(
+    pool: &PgPool,
+    event_id: Uuid,
+    ordered_ids: &[Uuid],
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let mut active_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id
+        FROM event_waitlist_entries
+        WHERE event_id = $1
+          AND status IN ('waiting', 'offered')
+        FOR UPDATE
+        "#,
+    )
+    .bind(event_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut requested = ordered_ids.to_vec();
+    active_ids.sort();
+    requested.sort();
+    requested.dedup();
+    if active_ids != requested || requested.len() != ordered_ids.len() {
+        tx.rollback().await?;
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE event_waitlist_entries w
+        SET position = o.position::INTEGER, updated_at = NOW()
+        FROM UNNEST($1::UUID[]) WITH ORDINALITY AS o(id, position)
+        WHERE w.id = o.id
+        "#,
+    )
+    .bind(ordered_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// The live hold on a whole table, if someone has one.
+pub async fn get_active_table_hold(
+    pool: &PgPool,
+    table_id: Uuid,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    sqlx::query_as::<_, WaitlistEntry>(
+        r#"
+        SELECT *
+        FROM event_waitlist_entries
+        WHERE held_table_id = $1
+          AND status = 'offered'
+          AND hold_expires_at > NOW()
+        "#,
+    )
+    .bind(table_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Close the holder's entry once they booked the table they were offered.
+pub async fn mark_table_hold_fulfilled(
+    pool: &PgPool,
+    table_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE event_waitlist_entries
+        SET status = 'fulfilled', updated_at = NOW()
+        WHERE held_table_id = $1
+          AND user_id = $2
+          AND status = 'offered'
+        "#,
+    )
+    .bind(table_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Close the entry holding a guest slot on `reservation_id` for the user with
+/// this phone number, once they started paying for it.
+pub async fn mark_slot_hold_fulfilled(
+    executor: impl sqlx::PgExecutor<'_>,
+    reservation_id: Uuid,
+    phone_number: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE event_waitlist_entries w
+        SET status = 'fulfilled', updated_at = NOW()
+        FROM users u
+        WHERE u.id = w.user_id
+          AND u.phone_number = $2
+          AND w.held_reservation_id = $1
+          AND w.status = 'offered'
+        "#,
+    )
+    .bind(reservation_id)
+    .bind(phone_number)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// If the table is bookable and nobody holds it, give a hold on it to the first
+/// waiting entry whose area and minimum capacity it satisfies.
+pub async fn claim_next_entry_for_table(
+    pool: &PgPool,
+    table_id: Uuid,
+    hold_expires_at: DateTime<Utc>,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let table: Option<(Uuid, Option<Uuid>, i32)> = sqlx::query_as(
+        r#"
+        SELECT t.event_id, t.area_id, t.capacity
+        FROM tables t
+        WHERE t.id = $1
+          AND t.available = true
+          AND NOT EXISTS (
+              SELECT 1 FROM table_reservations r
+              WHERE r.table_id = t.id
+                AND r.status NOT IN ('cancelled', 'completed')
+          )
+          AND NOT EXISTS (
+              SELECT 1 FROM event_waitlist_entries w
+              WHERE w.held_table_id = t.id
+                AND w.status = 'offered'
+          )
+        FOR UPDATE OF t
+        "#,
+    )
+    .bind(table_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((event_id, area_id, capacity)) = table else {
+        tx.rollback().await?;
+        return Ok(None);
+    };
+
+    let entry = sqlx::query_as::<_, WaitlistEntry>(
+        r#"
+        UPDATE event_waitlist_entries
+        SET status = 'offered',
+            held_table_id = $2,
+            held_reservation_id = NULL,
+            hold_expires_at = $5,
+            offered_at = NOW(),
+            updated_at = NOW()
+        WHERE id = (
+            SELECT id
+            FROM event_waitlist_entries
+            WHERE event_id = $1
+              AND status = 'waiting'
+              AND (area_id IS NULL OR area_id = $3)
+              AND (min_capacity IS NULL OR min_capacity <= $4)
+            ORDER BY position ASC, created_at ASC
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(event_id)
+    .bind(table_id)
+    .bind(area_id)
+    .bind(capacity)
+    .bind(hold_expires_at)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// If the reservation still has an unclaimed guest slot, hold it for the first
+/// waiting entry that is happy with a single spot at that table.
+pub async fn claim_next_entry_for_slot(
+    pool: &PgPool,
+    reservation_id: Uuid,
+    hold_expires_at: DateTime<Utc>,
+) -> Result<Option<WaitlistEntry>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Same lock the payment-link checkout takes before counting slots
+    let reservation: Option<(Uuid, Uuid, Option<Uuid>, i32)> = sqlx::query_as(
+        r#"
+        SELECT r.event_id, r.user_id, t.area_id, t.capacity
+        FROM table_reservations r
+        JOIN tables t ON t.id = r.table_id
+        WHERE r.id = $1
+          AND r.status NOT IN ('cancelled', 'completed')
+          AND r.payment_link_token IS NOT NULL
+          AND r.split_mode = 'equal'
+        FOR UPDATE OF r
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((event_id, organizer_id, area_id, capacity)) = reservation else {
+        tx.rollback().await?;
+        return Ok(None);
+    };
+
+    // The organizer's share counts with the slots they cover
+    let taken: i64 = sqlx::query_scalar(
+        r#"
+        SELECT
+            (SELECT COALESCE(SUM(slots), 0) FROM reservation_payment_shares
+             WHERE reservation_id = $1 AND status IN ('paid', 'checkout_pending'))
+          + (SELECT COUNT(*) FROM event_waitlist_entries
+             WHERE held_reservation_id = $1 AND status = 'offered')
+        "#,
+    )
+    .bind(reservation_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if taken >= i64::from(capacity) {
+        tx.rollback().await?;
+        return Ok(None);
+    }
+
+    let entry = sqlx::query_as::<_, WaitlistEntry>(
+        r#"
+        UPDATE event_waitlist_entries
+        SET status = 'offered',
+            held_table_id = NULL,
+            held_reservation_id = $2,
+            hold_expires_at = $5,
+            offered_at = NOW(),
+            updated_at = NOW()
+        WHERE id = (
+            SELECT id
+            FROM event_waitlist_entries
+            WHERE event_id = $1
+              AND status = 'waiting'
+              AND user_id <> $4
+              AND (area_id IS NULL OR area_id = $3)
+              AND (min_capacity IS NULL OR min_capacity <= 1)
+            ORDER BY position ASC, created_at ASC
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(event_id)
+    .bind(reservation_id)
+    .bind(area_id)
+    .bind(organizer_id)
+    .bind(hold_expires_at)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// Count live slot holds on a reservation, ignoring the one held by the user
+/// with `phone_number` (they are allowed to use it).
+pub async fn count_other_slot_holds(
+    executor: impl sqlx::PgExecutor<'_>,
+    reservation_id: Uuid,
+    phone_number: &str,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM event_waitlist_entries w
+        JOIN users u ON u.id = w.user_id
+        WHERE w.held_reservation_id = $1
+          AND w.status = 'offered'
+          AND w.hold_expires_at > NOW()
+          AND u.phone_number IS DISTINCT FROM $2
+        "#,
+    )
+    .bind(reservation_id)
+    .bind(phone_number)
+    .fetch_one(executor)
+    .await
+}
+
+/// Expire holds that ran out. The returned entries still carry the table or
+/// reservation they were holding so it can be offered to the next in line.
+pub async fn expire_stale_holds(pool: &PgPool) -> Result<Vec<WaitlistEntry>, sqlx::Error> {
+    sqlx::query_as::<_, WaitlistEntry>(
+        r#"
+        UPDATE event_waitlist_entries
+        SET status = 'expired', updated_at = NOW()
+        WHERE status = 'offered'
+          AND hold_expires_at <= NOW()
+        RETURNING *
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_hold_context(
+    pool: &PgPool,
+    entry_id: Uuid,
+) -> Result<WaitlistHoldContext, sqlx::Error> {
+    sqlx::query_as::<_, WaitlistHoldContext>(
+        r#"
+        SELECT
+            e.title                AS event_title,
+            COALESCE(t.name, rt.name) AS table_name,
+            u.phone_number         AS user_phone,
+            r.payment_link_token
+        FROM event_waitlist_entries w
+        JOIN events e ON e.id = w.event_id
+        JOIN users u ON u.id = w.user_id
+        LEFT JOIN tables t ON t.id = w.held_table_id
+        LEFT JOIN table_reservations r ON r.id = w.held_reservation_id
+        LEFT JOIN tables rt ON rt.id = r.table_id
+        WHERE w.id = $1
+        "#,
+    )
+    .bind(entry_id)
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::application::club_service;
+use crate::bootstrap::state::AppState;
+use crate::services::geocoding_service;
+
+/// Clubs looked up per run
+const GEOCODING_BATCH_SIZE: i64 = 20;
+
+/// Nominatim's public instance allows one request per second
+const REQUEST_SPACING: tokio::time::Duration = tokio::time::Duration::from_millis(1100);
+
+/// Geocodes the address of clubs that are new or whose address changed.
+/// Manually pinned clubs are left alone; provider errors are retried on the
+/// next run, addresses without results only after they change.
+pub async fn run(state: Arc<AppState>) {
+    if !geocoding_service::geocoding_enabled(&state.config.geocoding) {
+        info!("Club geocoding disabled (GEOCODER_PROVIDER=none)");
+        return;
+    }
+
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        state.config.jobs.geocoding_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        match club_service::get_clubs_to_geocode(&state.db_pool, GEOCODING_BATCH_SIZE).await {
+            Ok(clubs) => {
+                let (mut located, mut not_found, mut failed) = (0, 0, 0);
+                for (index, (club_id, address)) in clubs.iter().enumerate() {
+                    if index > 0 {
+                        tokio::time::sleep(REQUEST_SPACING).await;
+                    }
+                    let coordinates = match geocoding_service::geocode_address(
+                        &state.config.geocoding,
+                        &state.config.analytics.service_name,
+                        address,
+                    )
+                    .await
+                    {
+                        Ok(coordinates) => coordinates,
+                        Err(e) => {
+                            warn!(club_id = %club_id, error = %e, "Geocoding: lookup failed");
+                            failed += 1;
+                            continue;
+                        }
+                    };
+                    match club_service::record_geocoding_result(
+                        &state.db_pool,
+                        *club_id,
+                        address,
+                        coordinates,
+                    )
+                    .await
+                    {
+                        Ok(_) if coordinates.is_some() => located += 1,
+                        Ok(_) => not_found += 1,
+                        Err(e) => {
+                            warn!(club_id = %club_id, error = %e, "Geocoding: failed to save result");
+                            failed += 1;
+                        }
+                    }
+                }
+                if !clubs.is_empty() {
+                    info!(located, not_found, failed, "Club geocoding completed");
+                }
+                crate::jobs::record_job_run(
+                    &state,
+                    "club_geocoding",
+                    "success",
+                    json!({
+                        "pending": clubs.len(),
+                        "located": located,
+                        "not_found": not_found,
+                        "failed": failed,
+                    }),
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!(error = %e, "Club geocoding failed");
+                crate::jobs::record_job_run(
+                    &state,
+                    "club_geocoding",
+                    "failure",
+                    json!({}),
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::application::connect_reconciliation_service;
+use crate::bootstrap::state::AppState;
+use crate::models::connect_reconciliation::ConnectSyncTarget;
+use crate::services::stripe_finance_service;
+
+/// How far back the first sync of a club reaches
+const INITIAL_SYNC_DAYS: i64 = 90;
+
+/// Later syncs restart this far before the previous one ended, so reversals
+/// and payout status changes on recent objects are picked up.
+const RESYNC_WINDOW_DAYS: i64 = 30;
+
+type SyncResult = Result<(usize, usize), Box<dyn std::error::Error + Send + Sync>>;
+
+/// Fetches and stores the club's activity, returning how many transfers and
+/// payouts were synced.
+async fn sync_club(state: &AppState, target: &ConnectSyncTarget) -> SyncResult {
+    let started_at = Utc::now();
+    let since = match target.synced_until {
+        Some(synced_until) => synced_until - Duration::days(RESYNC_WINDOW_DAYS),
+        None => started_at - Duration::days(INITIAL_SYNC_DAYS),
+    };
+    let activity = stripe_finance_service::fetch_connect_activity(
+        state,
+        target.club_id,
+        &target.connected_account_id,
+        since,
+    )
+    .await?;
+    connect_reconciliation_service::save_connect_activity(
+        &state.db_pool,
+        target.club_id,
+        &target.connected_account_id,
+        &activity,
+        started_at,
+    )
+    .await?;
+    Ok((activity.transfers.len(), activity.payouts.len()))
+}
+
+/// Mirrors the Connect transfers, payouts and balance transactions of every
+/// club with a connected account. A club that fails keeps its sync cursor
+/// and the error, and is retried on the next run.
+pub async fn run(state: Arc<AppState>) {
+    if !stripe_finance_service::reconciliation_enabled(&state.config.stripe) {
+        info!("Connect reconciliation disabled (STRIPE_RECONCILIATION_PROVIDER=none)");
+        return;
+    }
+
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        state.config.jobs.connect_reconciliation_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        match connect_reconciliation_service::get_sync_targets(&state.db_pool).await {
+            Ok(targets) => {
+                let (mut synced, mut failed, mut transfers, mut payouts) = (0, 0, 0, 0);
+                for target in &targets {
+                    match sync_club(&state, target).await {
+                        Ok((club_transfers, club_payouts)) => {
+                            synced += 1;
+                            transfers += club_transfers;
+                            payouts += club_payouts;
+                        }
+                        Err(e) => {
+                            warn!(club_id = %target.club_id, error = %e, "Connect reconciliation: sync failed");
+                            failed += 1;
+                            if let Err(e) = connect_reconciliation_service::record_sync_error(
+                                &state.db_pool,
+                                target.club_id,
+                                &e.to_string(),
+                            )
+                            .await
+                            {
+                                warn!(club_id = %target.club_id, error = %e, "Connect reconciliation: failed to save sync error");
+                            }
+                        }
+                    }
+                }
+                if !targets.is_empty() {
+                    info!(
+                        synced,
+                        failed, transfers, payouts, "Connect reconciliation completed"
+                    );
+                }
+                crate::jobs::record_job_run(
+                    &state,
+                    "connect_reconciliation",
+                    "success",
+                    json!({
+                        "clubs": targets.len(),
+                        "synced": synced,
+                        "failed": failed,
+                        "transfers": transfers,
+                        "payouts": payouts,
+                    }),
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!(error = %e, "Connect reconciliation failed");
+                crate::jobs::record_job_run(
+                    &state,
+                    "connect_reconciliation",
+                    "failure",
+                    json!({}),
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::application::event_series_service;
+use crate::bootstrap::state::AppState;
+
+/// Keeps every active event series materialized `EVENT_SERIES_HORIZON_DAYS`
+/// ahead, creating the events of occurrences that came into range.
+pub async fn run(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        state.config.jobs.event_series_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        match event_series_service::get_active_series(&state.db_pool).await {
+            Ok(all_series) => {
+                let today = Utc::now().date_naive();
+                let mut created = 0;
+                for series in &all_series {
+                    match event_series_service::materialize_series(
+                        &state.db_pool,
+                        series,
+                        today,
+                        state.config.jobs.event_series_horizon_days,
+                    )
+                    .await
+                    {
+                        Ok(count) => created += count,
+                        Err(e) => {
+                            warn!(series_id = %series.id, error = %e, "Event series: failed to materialize occurrences")
+                        }
+                    }
+                }
+                if created > 0 {
+                    info!(
+                        series = all_series.len(),
+                        created, "Event series materialization completed"
+                    );
+                }
+                crate::jobs::record_job_run(
+                    &state,
+                    "event_series_materialization",
+                    "success",
+                    json!({ "series": all_series.len(), "created_events": created }),
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!(error = %e, "Event series materialization failed");
+                crate::jobs::record_job_run(
+                    &state,
+                    "event_series_materialization",
+                    "failure",
+                    json!({}),
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+}
@@ -5,9 +5,16 @@ use tracing::{error, info};
 
 use crate::bootstrap::state::AppState;
 
+pub mod club_geocoding;
+pub mod connect_reconciliation;
+pub mod event_series;
 pub mod idempotency_cleanup;
 pub mod outbox_dispatcher;
 pub mod payment_maintenance;
+pub mod push_receipts;
+pub mod stripe_webhooks;
+pub mod ticket_holds;
+pub mod waitlist_holds;
 
 pub fn start_background_jobs(app_state: Arc<AppState>) {
     let payment_state = Arc::clone(&app_state);
@@ -27,6 +34,48 @@ pub fn start_background_jobs(app_state: Arc<AppState>) {
         outbox_dispatcher::run(outbox_state).await;
     });
     info!("Outbox dispatcher job started");
+
+    let waitlist_state = Arc::clone(&app_state);
+    tokio::spawn(async move {
+        waitlist_holds::run(waitlist_state).await;
+    });
+    info!("Waitlist hold expiry job started");
+
+    let push_receipts_state = Arc::clone(&app_state);
+    tokio::spawn(async move {
+        push_receipts::run(push_receipts_state).await;
+    });
+    info!("Push receipt job started");
+
+    let ticket_holds_state = Arc::clone(&app_state);
+    tokio::spawn(async move {
+        ticket_holds::run(ticket_holds_state).await;
+    });
+    info!("Ticket hold expiry job started");
+
+    let event_series_state = Arc::clone(&app_state);
+    tokio::spawn(async move {
+        event_series::run(event_series_state).await;
+    });
+    info!("Event series materialization job started");
+
+    let geocoding_state = Arc::clone(&app_state);
+    tokio::spawn(async move {
+        club_geocoding::run(geocoding_state).await;
+    });
+    info!("Club geocoding job started");
+
+    let reconciliation_state = Arc::clone(&app_state);
+    tokio::spawn(async move {
+        connect_reconciliation::run(reconciliation_state).await;
+    });
+    info!("Connect reconciliation job started");
+
+    let stripe_webhooks_state = Arc::clone(&app_state);
+    tokio::spawn(async move {
+        stripe_webhooks::run(stripe_webhooks_state).await;
+    });
+    info!("Stripe webhook processing job started");
 }
 
 pub async fn record_job_run(
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
-use chrono::Duration;
 use serde_json::{json, Value};
 use tracing::{error, warn};
 
 use crate::bootstrap::state::AppState;
+use crate::infrastructure::outbox::delivery::{self, DispatchError, HttpStatusError};
 use crate::infrastructure::outbox::{self, OutboxEvent};
+use crate::services::notification_service::PushStatus;
 
 pub async fn run(state: Arc<AppState>) {
     let interval_seconds = state.config.analytics.outbox_poll_interval_seconds;
@@ -41,6 +42,7 @@ pub async fn run(state: Arc<AppState>) {
 
         let mut delivered = 0;
         let mut failed = 0;
+        let mut dead_lettered = 0;
 
         for event in claimed {
             match dispatch_event(&state, &event).await {
@@ -51,21 +53,39 @@ pub async fn run(state: Arc<AppState>) {
                     }
                 }
                 Err(dispatch_error) => {
-                    failed += 1;
-                    warn!(
-                        event_id = %event.id,
-                        event_type = %event.event_type,
-                        error = %dispatch_error,
-                        "Outbox dispatch failed"
-                    );
-                    if let Err(mark_error) = outbox::mark_failed(
-                        &state.db_pool,
-                        event.id,
-                        &dispatch_error,
-                        Duration::seconds((event.attempts.max(1) as i64) * 30),
-                    )
-                    .await
+                    let policy = delivery::retry_policy(&event.event_type);
+                    let error_message = dispatch_error.to_string();
+
+                    let marked = if dispatch_error.is_permanent()
+                        || event.attempts >= policy.max_attempts
                     {
+                        dead_lettered += 1;
+                        error!(
+                            event_id = %event.id,
+                            event_type = %event.event_type,
+                            attempts = event.attempts,
+                            error = %dispatch_error,
+                            "Outbox event dead-lettered"
+                        );
+                        outbox::mark_dead_lettered(&state.db_pool, event.id, &error_message).await
+                    } else {
+                        failed += 1;
+                        warn!(
+                            event_id = %event.id,
+                            event_type = %event.event_type,
+                            attempts = event.attempts,
+                            error = %dispatch_error,
+                            "Outbox dispatch failed"
+                        );
+                        outbox::mark_failed(
+                            &state.db_pool,
+                            event.id,
+                            &error_message,
+                            delivery::retry_delay(policy, event.attempts, rand::random::<f64>()),
+                        )
+                        .await
+                    };
+                    if let Err(mark_error) = marked {
                         error!(event_id = %event.id, error = %mark_error, "Failed to mark outbox event failed");
                     }
                 }
@@ -75,15 +95,16 @@ pub async fn run(state: Arc<AppState>) {
         crate::jobs::record_job_run(
             &state,
             "outbox_dispatcher",
-            if failed == 0 {
+            if failed == 0 && dead_lettered == 0 {
                 "success"
             } else {
                 "partial_failure"
             },
             json!({
-                "claimed": delivered + failed,
+                "claimed": delivered + failed + dead_lettered,
                 "delivered": delivered,
                 "failed": failed,
+                "dead_lettered": dead_lettered,
             }),
             None,
         )
@@ -91,27 +112,30 @@ pub async fn run(state: Arc<AppState>) {
     }
 }
 
-async fn dispatch_event(state: &AppState, event: &OutboxEvent) -> Result<(), String> {
+async fn dispatch_event(state: &AppState, event: &OutboxEvent) -> Result<(), DispatchError> {
     match event.event_type.as_str() {
         "notification.alert_webhook" => dispatch_alert_webhook(state, &event.payload).await,
-        "notification.push" => dispatch_push_notification(state, &event.payload).await,
+        "notification.push" => dispatch_push_notification(state, event).await,
         "notification.sms" => dispatch_sms_notification(state, &event.payload).await,
+        "notification.email" => dispatch_email_notification(state, &event.payload).await,
         "analytics.capture" => dispatch_analytics_event(state, &event.payload).await,
-        unsupported => Err(format!("Unsupported outbox event type: {unsupported}")),
+        unsupported => Err(DispatchError::Permanent(format!(
+            "Unsupported outbox event type: {unsupported}"
+        ))),
     }
 }
 
-async fn dispatch_alert_webhook(state: &AppState, payload: &Value) -> Result<(), String> {
+async fn dispatch_alert_webhook(state: &AppState, payload: &Value) -> Result<(), DispatchError> {
     let url = state
         .config
         .notifications
         .alert_webhook_url
         .clone()
-        .ok_or_else(|| "ALERT_WEBHOOK_URL not configured".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("ALERT_WEBHOOK_URL not configured".to_string()))?;
     let message = payload
         .get("message")
         .and_then(Value::as_str)
-        .ok_or_else(|| "Missing alert webhook message".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("Missing alert webhook message".to_string()))?;
 
     let payload = json!({
         "content": message,
@@ -123,57 +147,120 @@ async fn dispatch_alert_webhook(state: &AppState, payload: &Value) -> Result<(),
         .json(&payload)
         .send()
         .await
-        .map_err(|error| error.to_string())?;
+        .map_err(|error| DispatchError::Transient(error.to_string()))?;
 
     if response.status().is_success() {
         Ok(())
     } else {
-        Err(format!(
-            "Alert webhook returned status {}",
-            response.status()
-        ))
+        Err(DispatchError::classify(&HttpStatusError {
+            provider: "Alert webhook",
+            status: response.status().as_u16(),
+        }))
     }
 }
 
-async fn dispatch_push_notification(state: &AppState, payload: &Value) -> Result<(), String> {
+async fn dispatch_push_notification(
+    state: &AppState,
+    event: &OutboxEvent,
+) -> Result<(), DispatchError> {
+    let payload = &event.payload;
     let token = payload
         .get("token")
         .and_then(Value::as_str)
-        .ok_or_else(|| "Missing push token".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("Missing push token".to_string()))?;
     let title = payload
         .get("title")
         .and_then(Value::as_str)
-        .ok_or_else(|| "Missing push title".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("Missing push title".to_string()))?;
     let body = payload
         .get("body")
         .and_then(Value::as_str)
-        .ok_or_else(|| "Missing push body".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("Missing push body".to_string()))?;
 
-    crate::services::notification_service::send_push_notification(&state.config, token, title, body)
-        .await
-        .map_err(|error| error.to_string())
+    let Some(ticket) = crate::services::notification_service::send_push_notification(
+        &state.config,
+        token,
+        title,
+        body,
+    )
+    .await
+    .map_err(|error| DispatchError::classify(error.as_ref()))?
+    else {
+        return Ok(());
+    };
+
+    if let Err(error) =
+        crate::application::push_service::record_push_ticket(state, event.id, token, &ticket).await
+    {
+        error!(event_id = %event.id, error = %error, "Failed to record push ticket");
+    }
+
+    // The device is gone and has just been forgotten: nothing left to retry
+    if ticket.status.is_device_not_registered() {
+        return Ok(());
+    }
+    match ticket.status {
+        PushStatus::Ok => Ok(()),
+        PushStatus::Error { message, code } => {
+            let message = format!("{}: {message}", code.as_deref().unwrap_or("Expo error"));
+            if code.as_deref() == Some("MessageRateExceeded") {
+                Err(DispatchError::Transient(message))
+            } else {
+                Err(DispatchError::Permanent(message))
+            }
+        }
+    }
 }
 
-async fn dispatch_sms_notification(state: &AppState, payload: &Value) -> Result<(), String> {
+async fn dispatch_sms_notification(state: &AppState, payload: &Value) -> Result<(), DispatchError> {
     let to = payload
         .get("to")
         .and_then(Value::as_str)
-        .ok_or_else(|| "Missing sms destination".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("Missing sms destination".to_string()))?;
     let body = payload
         .get("body")
         .and_then(Value::as_str)
-        .ok_or_else(|| "Missing sms body".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("Missing sms body".to_string()))?;
 
     crate::services::notification_service::send_sms(&state.config, to, body)
         .await
-        .map_err(|error| error.to_string())
+        .map_err(|error| DispatchError::classify(error.as_ref()))
+}
+
+async fn dispatch_email_notification(
+    state: &AppState,
+    payload: &Value,
+) -> Result<(), DispatchError> {
+    let to = payload
+        .get("to")
+        .and_then(Value::as_str)
+        .ok_or_else(|| DispatchError::Permanent("Missing email destination".to_string()))?;
+    let subject = payload
+        .get("subject")
+        .and_then(Value::as_str)
+        .ok_or_else(|| DispatchError::Permanent("Missing email subject".to_string()))?;
+    let text = payload
+        .get("text")
+        .and_then(Value::as_str)
+        .ok_or_else(|| DispatchError::Permanent("Missing email text".to_string()))?;
+    let html = payload.get("html").and_then(Value::as_str);
+
+    let email = crate::services::email_service::OutgoingEmail {
+        to: to.to_string(),
+        subject: subject.to_string(),
+        text: text.to_string(),
+        html: html.map(str::to_string),
+    };
+    crate::services::email_service::send_email(&state.config.email, &email)
+        .await
+        .map_err(|error| DispatchError::classify(error.as_ref()))
 }
 
-async fn dispatch_analytics_event(state: &AppState, payload: &Value) -> Result<(), String> {
+async fn dispatch_analytics_event(state: &AppState, payload: &Value) -> Result<(), DispatchError> {
     let event_name = payload
         .get("event")
         .and_then(Value::as_str)
-        .ok_or_else(|| "Missing analytics event name".to_string())?;
+        .ok_or_else(|| DispatchError::Permanent("Missing analytics event name".to_string()))?;
     let distinct_id = payload
         .get("distinct_id")
         .and_then(Value::as_str)
@@ -190,4 +277,5 @@ async fn dispatch_analytics_event(state: &AppState, payload: &Value) -> Result<(
         properties,
     )
     .await
+    .map_err(DispatchError::Transient)
 }
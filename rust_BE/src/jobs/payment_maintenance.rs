@@ -10,8 +10,8 @@ use stripe::{
 };
 
 use crate::application::{
-    outbox_service, payment_service::capture_payment_service,
-    reservation_service::check_and_confirm_reservation,
+    outbox_service, payment_service::capture_payment_service, promo_code_service,
+    reservation_service::check_and_confirm_reservation, waitlist_service,
 };
 use crate::bootstrap::state::AppState;
 
@@ -22,7 +22,6 @@ struct AuthorizedPaymentRow {
     payment_id: Uuid,
     reservation_id: Uuid,
     owner_user_id: Uuid,
-    owner_push_token: Option<String>,
     event_name: String,
     table_name: String,
     stripe_payment_intent_id: Option<String>,
@@ -40,6 +39,7 @@ struct StaleCheckoutShare {
     reservation_id: Uuid,
     user_id: Option<Uuid>,
     amount: rust_decimal::Decimal,
+    slots: i32,
     stripe_checkout_session_id: String,
 }
 
@@ -50,11 +50,14 @@ struct ExpiredShareRow {
     share_id: Uuid,
     reservation_id: Uuid,
     phone_number: Option<String>,
+    guest_name: Option<String>,
     amount: rust_decimal::Decimal,
+    /// A named allocation of a custom or weighted split
+    is_allocation: bool,
+    stripe_checkout_session_id: Option<String>,
     owner_user_id: Uuid,
     owner_contact_name: String,
     owner_phone: Option<String>,
-    owner_push_token: Option<String>,
     event_name: String,
     table_name: String,
 }
@@ -158,7 +161,6 @@ async fn run_capture(state: &Arc<AppState>, tomorrow: chrono::NaiveDate) {
             p.id                        AS payment_id,
             tr.id                       AS reservation_id,
             tr.user_id                  AS owner_user_id,
-            u.expo_push_token           AS owner_push_token,
             e.title                     AS event_name,
             t.name                      AS table_name,
             p.stripe_payment_intent_id,
@@ -171,7 +173,6 @@ async fn run_capture(state: &Arc<AppState>, tomorrow: chrono::NaiveDate) {
         JOIN table_reservations tr ON p.id = ANY(tr.payment_ids)
         JOIN events e ON e.id = tr.event_id
         JOIN tables t ON t.id = tr.table_id
-        LEFT JOIN users u ON u.id = tr.user_id
         WHERE p.authorization_status = 'authorized'
           AND p.capture_method       = 'manual'
           AND e.event_date IS NOT NULL
@@ -235,7 +236,6 @@ async fn run_reauth(state: &Arc<AppState>, reauth_threshold: chrono::NaiveDate)
             p.id                        AS payment_id,
             tr.id                       AS reservation_id,
             tr.user_id                  AS owner_user_id,
-            u.expo_push_token           AS owner_push_token,
             e.title                     AS event_name,
             t.name                      AS table_name,
             p.stripe_payment_intent_id,
@@ -248,7 +248,6 @@ async fn run_reauth(state: &Arc<AppState>, reauth_threshold: chrono::NaiveDate)
         JOIN table_reservations tr ON p.id = ANY(tr.payment_ids)
         JOIN events e ON e.id = tr.event_id
         JOIN tables t ON t.id = tr.table_id
-        LEFT JOIN users u ON u.id = tr.user_id
         WHERE p.authorization_status = 'authorized'
           AND p.capture_method       = 'manual'
           AND e.event_date IS NOT NULL
@@ -350,11 +349,17 @@ async fn run_checkout_reconciliation(state: &Arc<AppState>) {
             rps.reservation_id,
             rps.user_id,
             rps.amount,
+            rps.slots,
             rps.stripe_checkout_session_id
         FROM reservation_payment_shares rps
-        WHERE rps.status IN ('pending', 'checkout_pending')
-          AND rps.stripe_checkout_session_id IS NOT NULL
+        WHERE rps.stripe_checkout_session_id IS NOT NULL
           AND rps.updated_at < NOW() - INTERVAL '30 minutes'
+          AND (
+              rps.status IN ('pending', 'checkout_pending')
+              -- a named allocation whose checkout timed out; its session may
+              -- still complete until Stripe expires it (24 hours at most)
+              OR (rps.status = 'reserved' AND rps.updated_at > NOW() - INTERVAL '24 hours')
+          )
         "#,
     )
     .fetch_all(&state.db_pool)
@@ -478,8 +483,10 @@ async fn run_checkout_reconciliation(state: &Arc<AppState>) {
 
         // Mark share as paid. If another worker already moved it out of the
         // waiting state, skip the reservation increment to avoid double counts.
+        // A named allocation put back to `reserved` after its checkout timed
+        // out is still paid by this session.
         let updated_share = match sqlx::query(
-            "UPDATE reservation_payment_shares SET status = 'paid', payment_id = $1, stripe_payment_intent_id = COALESCE($2, stripe_payment_intent_id), updated_at = NOW() WHERE id = $3 AND status IN ('pending', 'checkout_pending')"
+            "UPDATE reservation_payment_shares SET status = 'paid', payment_id = $1, stripe_payment_intent_id = COALESCE($2, stripe_payment_intent_id), updated_at = NOW() WHERE id = $3 AND status IN ('pending', 'checkout_pending', 'reserved')"
         )
         .bind(payment_id)
         .bind(if stripe_pi_id.is_empty() { None } else { Some(&stripe_pi_id) })
@@ -499,12 +506,19 @@ async fn run_checkout_reconciliation(state: &Arc<AppState>) {
             continue;
         }
 
+        if let Err(e) =
+            promo_code_service::confirm_share_redemption(&state.db_pool, share.share_id).await
+        {
+            error!(share_id = %share.share_id, error = %e, "Reconciliation: failed to confirm promo code redemption");
+        }
+
         // Update reservation amount_paid and num_people together so recovered
         // checkouts restore the same counters as the live webhook path.
         let _ = sqlx::query(
-            "UPDATE table_reservations SET amount_paid = amount_paid + $1, num_people = num_people + 1, payment_ids = array_append(COALESCE(payment_ids, '{}'), $2), updated_at = NOW() WHERE id = $3"
+            "UPDATE table_reservations SET amount_paid = amount_paid + $1, num_people = num_people + $2, payment_ids = array_append(COALESCE(payment_ids, '{}'), $3), updated_at = NOW() WHERE id = $4"
         )
         .bind(share.amount)
+        .bind(share.slots)
         .bind(payment_id)
         .bind(share.reservation_id)
         .execute(&state.db_pool)
@@ -561,8 +575,27 @@ async fn run_checkout_reconciliation(state: &Arc<AppState>) {
 // 4. Payment share expiry
 // ============================================================================
 
+/// Make sure a Checkout session can no longer be paid. True once it is
+/// expired, by us or by Stripe; false when it completed or could not be reached.
+async fn expire_checkout_session(state: &AppState, session_id: &stripe::CheckoutSessionId) -> bool {
+    match stripe::CheckoutSession::expire(&state.stripe_client, session_id).await {
+        Ok(_) => true,
+        // Only open sessions can be expired: check whether it already was
+        Err(e) => {
+            match stripe::CheckoutSession::retrieve(&state.stripe_client, session_id, &[]).await {
+                Ok(session) => session.status == Some(stripe::CheckoutSessionStatus::Expired),
+                Err(_) => {
+                    warn!(session_id = %session_id, error = ?e, "Failed to expire checkout session");
+                    false
+                }
+            }
+        }
+    }
+}
+
 /// Expires guest payment shares that have been waiting longer than the
-/// configured TTL, and alerts the reservation owner.
+/// configured TTL, and alerts the reservation owner. A named allocation is
+/// not expired but reopened, so someone else can pay it.
 async fn run_payment_share_expiry(state: &Arc<AppState>) {
     let ttl_hours = state.payment_share_ttl_hours;
 
@@ -572,12 +605,14 @@ async fn run_payment_share_expiry(state: &Arc<AppState>) {
             rps.id                  AS share_id,
             rps.reservation_id,
             rps.phone_number,
-            rps.amount,
+            rps.guest_name,
+            COALESCE(rps.planned_amount, rps.amount) AS amount,
+            rps.planned_amount IS NOT NULL AS is_allocation,
+            rps.stripe_checkout_session_id,
             tr.user_id              AS owner_user_id,
             tr.contact_name         AS owner_contact_name,
             u.phone_number          AS owner_phone,
-            u.expo_push_token       AS owner_push_token,
-            e.name                  AS event_name,
+            e.title                 AS event_name,
             t.name                  AS table_name
         FROM reservation_payment_shares rps
         JOIN table_reservations tr ON tr.id = rps.reservation_id
@@ -586,7 +621,7 @@ async fn run_payment_share_expiry(state: &Arc<AppState>) {
         LEFT JOIN users u ON u.id = tr.user_id
         WHERE rps.status IN ('pending', 'checkout_pending')
           AND rps.is_owner = false
-          AND rps.created_at < NOW() - ($1 || ' hours')::INTERVAL
+          AND COALESCE(rps.checkout_started_at, rps.created_at) < NOW() - ($1 || ' hours')::INTERVAL
         "#,
     )
     .bind(ttl_hours)
@@ -612,9 +647,38 @@ async fn run_payment_share_expiry(state: &Arc<AppState>) {
     );
 
     for share in &expired {
-        // Mark share as expired
+        // A reopened allocation gets a new Checkout session from its next payer,
+        // which would replace the stored one. Expire the old session first so
+        // it cannot be paid too; a session Stripe will not expire may already be
+        // paid, so the share is left to its completion webhook.
+        if share.is_allocation {
+            if let Some(session_id) = share
+                .stripe_checkout_session_id
+                .as_deref()
+                .and_then(|id| id.parse::<stripe::CheckoutSessionId>().ok())
+            {
+                if !expire_checkout_session(state, &session_id).await {
+                    warn!(share_id = %share.share_id, session_id = %session_id, "Share expiry: allocation checkout still payable, retrying next run");
+                    continue;
+                }
+            }
+        }
+
+        // Mark share as expired, or reopen the allocation at its planned amount
+        // without its expired session.
         if let Err(e) = sqlx::query(
-            "UPDATE reservation_payment_shares SET status = 'expired', updated_at = NOW() WHERE id = $1 AND status IN ('pending', 'checkout_pending')"
+            r#"
+            UPDATE reservation_payment_shares
+            SET status = CASE WHEN planned_amount IS NULL THEN 'expired' ELSE 'reserved' END,
+                amount = COALESCE(planned_amount, amount),
+                paid_by_phone = NULL,
+                checkout_started_at = NULL,
+                stripe_checkout_session_id = CASE
+                    WHEN planned_amount IS NULL THEN stripe_checkout_session_id
+                END,
+                updated_at = NOW()
+            WHERE id = $1 AND status IN ('pending', 'checkout_pending')
+            "#,
         )
         .bind(share.share_id)
         .execute(&state.db_pool)
@@ -624,27 +688,47 @@ async fn run_payment_share_expiry(state: &Arc<AppState>) {
             continue;
         }
 
+        // Give back any promo code use held by the unpaid share
+        if let Err(e) =
+            promo_code_service::release_share_redemption(&state.db_pool, share.share_id).await
+        {
+            error!(share_id = %share.share_id, error = %e, "Share expiry: failed to release promo code redemption");
+        }
+
         let guest_phone = share.phone_number.as_deref().unwrap_or("unknown");
         info!(
             share_id = %share.share_id,
             guest_phone = %guest_phone,
             reservation_id = %share.reservation_id,
+            is_allocation = share.is_allocation,
             "Share expiry: payment share expired"
         );
+        let guest = if share.is_allocation {
+            share.guest_name.as_deref().unwrap_or(guest_phone)
+        } else {
+            guest_phone
+        };
 
         let msg = format!(
             "Payment share expired: guest {} did not pay {:.2}€ for {} at {} (reservation {}). Owner: {}",
-            guest_phone, share.amount, share.event_name, share.table_name,
+            guest, share.amount, share.event_name, share.table_name,
             share.reservation_id, share.owner_contact_name,
         );
         send_alert(state, &msg).await;
 
         // SMS owner if they have a phone number
         if let Some(ref owner_phone) = share.owner_phone {
-            let sms = format!(
-                "Un ospite ({}) non ha pagato la sua parte di €{:.2} per {} a {}. Puoi riassegnare il posto.",
-                guest_phone, share.amount, share.event_name, share.table_name,
-            );
+            let sms = if share.is_allocation {
+                format!(
+                    "La quota di {} (€{:.2}) per {} a {} non è stata pagata: è di nuovo disponibile sul link del tavolo.",
+                    guest, share.amount, share.event_name, share.table_name,
+                )
+            } else {
+                format!(
+                    "Un ospite ({}) non ha pagato la sua parte di €{:.2} per {} a {}. Puoi riassegnare il posto.",
+                    guest, share.amount, share.event_name, share.table_name,
+                )
+            };
             if let Err(error) = outbox_service::enqueue_sms_notification(
                 &state.db_pool,
                 owner_phone,
@@ -658,25 +742,34 @@ async fn run_payment_share_expiry(state: &Arc<AppState>) {
             }
         }
 
-        // Push notification to owner if they have a push token
-        if let Some(ref push_token) = share.owner_push_token {
-            let title = "Pagamento scaduto".to_string();
-            let body = format!(
-                "L'ospite {} non ha pagato la sua parte (€{:.2}) per {}.",
-                guest_phone, share.amount, share.event_name,
-            );
-            if let Err(error) = outbox_service::enqueue_push_notification(
-                &state.db_pool,
-                push_token,
-                &title,
-                &body,
-                Some("reservation"),
-                Some(share.reservation_id),
-            )
-            .await
-            {
-                error!(reservation_id = %share.reservation_id, error = %error, "Failed to enqueue owner push notification");
-            }
+        // Push notification to the owner's devices
+        let title = "Pagamento scaduto";
+        let body = format!(
+            "L'ospite {} non ha pagato la sua parte (€{:.2}) per {}.",
+            guest, share.amount, share.event_name,
+        );
+        if let Err(error) = outbox_service::enqueue_push_notification_for_user(
+            &state.db_pool,
+            share.owner_user_id,
+            title,
+            &body,
+            Some("reservation"),
+            Some(share.reservation_id),
+        )
+        .await
+        {
+            error!(reservation_id = %share.reservation_id, error = %error, "Failed to enqueue owner push notification");
+        }
+
+        // The freed slot goes to the next waitlisted user, if any. A reopened
+        // allocation still belongs to its named guest.
+        if share.is_allocation {
+            continue;
+        }
+        if let Err(error) =
+            waitlist_service::offer_reservation_slot(state, share.reservation_id).await
+        {
+            error!(reservation_id = %share.reservation_id, error = %error, "Failed to offer expired slot to waitlist");
         }
     }
 }
@@ -699,7 +792,7 @@ async fn run_upcoming_reservation_reminders(
         JOIN users u ON u.id = tr.user_id
         WHERE e.event_date = $1
           AND tr.status IN ('confirmed', 'pending')
-          AND u.expo_push_token IS NOT NULL
+          AND EXISTS (SELECT 1 FROM push_devices pd WHERE pd.user_id = u.id)
           AND u.deleted_at IS NULL
         "#,
     )
@@ -764,10 +857,6 @@ async fn notify_payment_action_required(
     title: &str,
     body: &str,
 ) {
-    if row.owner_push_token.as_deref().is_none() {
-        return;
-    }
-
     let already_sent: Result<bool, sqlx::Error> = sqlx::query_scalar(
         r#"
         SELECT EXISTS (
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use tracing::{error, info};
+
+use crate::application::push_service;
+use crate::bootstrap::state::AppState;
+
+/// Fetches Expo push receipts for sent notifications, records whether they
+/// reached the device and removes devices that are no longer registered.
+pub async fn run(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        state.config.jobs.push_receipt_check_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        match push_service::check_push_receipts(&state).await {
+            Ok(summary) => {
+                if summary.checked == 0 {
+                    continue;
+                }
+                info!(
+                    checked = summary.checked,
+                    delivered = summary.delivered,
+                    failed = summary.failed,
+                    expired = summary.expired,
+                    devices_removed = summary.devices_removed,
+                    "Push receipt check completed"
+                );
+                crate::jobs::record_job_run(
+                    &state,
+                    "push_receipts",
+                    "success",
+                    json!({
+                        "checked": summary.checked,
+                        "delivered": summary.delivered,
+                        "failed": summary.failed,
+                        "expired": summary.expired,
+                        "devices_removed": summary.devices_removed,
+                    }),
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!(error = %e, "Push receipt check failed");
+                crate::jobs::record_job_run(
+                    &state,
+                    "push_receipts",
+                    "failure",
+                    json!({}),
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+use serde_json::json;
+use tracing::{error, warn};
+
+use crate::application::stripe_webhook_service;
+use crate::bootstrap::state::AppState;
+use crate::infrastructure::outbox::delivery;
+use crate::models::stripe_webhook::StripeWebhookEvent;
+
+const BATCH_SIZE: i64 = 20;
+
+/// An event still `processing` after this long belonged to a worker that
+/// died mid-event and is claimed again.
+const STALE_PROCESSING_MINUTES: i64 = 10;
+
+/// Retry and dead-letter policy, shared with the outbox backoff
+const RETRY_POLICY_KEY: &str = "stripe.webhook";
+
+enum Outcome {
+    Processed,
+    Failed,
+    DeadLettered,
+}
+
+async fn process(state: &AppState, event: &StripeWebhookEvent) -> Outcome {
+    let error_message = match serde_json::from_str::<serde_json::Value>(&event.payload) {
+        Ok(payload) => {
+            let status = stripe_webhook_service::process_event(state, &payload).await;
+            if status.is_success() {
+                if let Err(e) =
+                    stripe_webhook_service::mark_processed(&state.db_pool, event.id).await
+                {
+                    error!(event_id = %event.id, error = %e, "Failed to mark Stripe webhook processed");
+                }
+                return Outcome::Processed;
+            }
+            format!("handler returned {status}")
+        }
+        // Stored payloads passed signature checks, so this cannot heal on retry
+        Err(e) => {
+            let message = format!("unreadable payload: {e}");
+            mark_dead_lettered(state, event, &message).await;
+            return Outcome::DeadLettered;
+        }
+    };
+
+    let policy = delivery::retry_policy(RETRY_POLICY_KEY);
+    if event.attempts >= policy.max_attempts {
+        mark_dead_lettered(state, event, &error_message).await;
+        return Outcome::DeadLettered;
+    }
+
+    warn!(
+        event_id = %event.id,
+        stripe_event_id = %event.stripe_event_id,
+        event_type = %event.event_type,
+        attempts = event.attempts,
+        error = %error_message,
+        "Stripe webhook processing failed"
+    );
+    if let Err(e) = stripe_webhook_service::mark_failed(
+        &state.db_pool,
+        event.id,
+        &error_message,
+        delivery::retry_delay(policy, event.attempts, rand::random::<f64>()),
+    )
+    .await
+    {
+        error!(event_id = %event.id, error = %e, "Failed to mark Stripe webhook failed");
+    }
+    Outcome::Failed
+}
+
+async fn mark_dead_lettered(state: &AppState, event: &StripeWebhookEvent, error_message: &str) {
+    error!(
+        event_id = %event.id,
+        stripe_event_id = %event.stripe_event_id,
+        event_type = %event.event_type,
+        attempts = event.attempts,
+        error = %error_message,
+        "Stripe webhook dead-lettered"
+    );
+    if let Err(e) =
+        stripe_webhook_service::mark_dead_lettered(&state.db_pool, event.id, error_message).await
+    {
+        error!(event_id = %event.id, error = %e, "Failed to dead-letter Stripe webhook");
+    }
+}
+
+pub async fn run(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        state.config.jobs.stripe_webhook_poll_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        let claimed = match stripe_webhook_service::claim_due_events(
+            &state.db_pool,
+            BATCH_SIZE,
+            Duration::minutes(STALE_PROCESSING_MINUTES),
+        )
+        .await
+        {
+            Ok(events) => events,
+            Err(e) => {
+                error!(error = %e, "Failed to claim Stripe webhooks");
+                crate::jobs::record_job_run(
+                    &state,
+                    "stripe_webhooks",
+                    "failure",
+                    json!({}),
+                    Some(&e.to_string()),
+                )
+                .await;
+                continue;
+            }
+        };
+
+        if claimed.is_empty() {
+            continue;
+        }
+
+        let mut processed = 0;
+        let mut failed = 0;
+        let mut dead_lettered = 0;
+
+        // One at a time and in order of receipt: Stripe events about the
+        // same object often depend on each other.
+        for event in &claimed {
+            match process(&state, event).await {
+                Outcome::Processed => processed += 1,
+                Outcome::Failed => failed += 1,
+                Outcome::DeadLettered => dead_lettered += 1,
+            }
+        }
+
+        crate::jobs::record_job_run(
+            &state,
+            "stripe_webhooks",
+            if failed == 0 && dead_lettered == 0 {
+                "success"
+            } else {
+                "partial_failure"
+            },
+            json!({
+                "claimed": claimed.len(),
+                "processed": processed,
+                "failed": failed,
+                "dead_lettered": dead_lettered,
+            }),
+            None,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::application::ticket_tier_service;
+use crate::bootstrap::state::AppState;
+
+/// Minutes past a checkout's expiry before the job releases its hold. The
+/// `checkout.session.expired` webhook normally gets there first.
+const RELEASE_GRACE_MINUTES: i64 = 15;
+
+/// Backstop for missed `checkout.session.expired` webhooks: releases the
+/// tickets held by ticket orders whose checkout was abandoned.
+pub async fn run(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        state.config.jobs.ticket_hold_check_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        match ticket_tier_service::get_stale_pending_orders(&state.db_pool, RELEASE_GRACE_MINUTES)
+            .await
+        {
+            Ok(orders) => {
+                let mut released = 0;
+                for order in &orders {
+                    match ticket_tier_service::release_ticket_order(&state.db_pool, order.id).await
+                    {
+                        Ok(true) => released += 1,
+                        Ok(false) => {}
+                        Err(e) => {
+                            warn!(order_id = %order.id, error = %e, "Tickets: failed to release stale hold")
+                        }
+                    }
+                }
+                if released > 0 {
+                    info!(released, "Ticket hold expiry completed");
+                }
+                crate::jobs::record_job_run(
+                    &state,
+                    "ticket_hold_expiry",
+                    "success",
+                    json!({ "stale_orders": orders.len(), "released": released }),
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!(error = %e, "Ticket hold expiry failed");
+                crate::jobs::record_job_run(
+                    &state,
+                    "ticket_hold_expiry",
+                    "failure",
+                    json!({}),
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use tracing::{error, info, warn};
+
+use crate::application::waitlist_service;
+use crate::bootstrap::state::AppState;
+
+/// Expires waitlist holds nobody acted on and passes the table or guest slot
+/// on to the next entry in the queue.
+pub async fn run(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        state.config.jobs.waitlist_hold_check_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        match waitlist_service::expire_stale_holds(&state.db_pool).await {
+            Ok(expired) => {
+                let mut reoffered = 0;
+                for entry in &expired {
+                    match waitlist_service::reoffer_released_hold(&state, entry).await {
+                        Ok(Some(_)) => reoffered += 1,
+                        Ok(None) => {}
+                        Err(e) => {
+                            warn!(entry_id = %entry.id, error = %e, "Waitlist: failed to re-offer expired hold")
+                        }
+                    }
+                }
+                if !expired.is_empty() {
+                    info!(
+                        expired_holds = expired.len(),
+                        reoffered, "Waitlist hold expiry completed"
+                    );
+                }
+                crate::jobs::record_job_run(
+                    &state,
+                    "waitlist_hold_expiry",
+                    "success",
+                    json!({ "expired_holds": expired.len(), "reoffered": reoffered }),
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!(error = %e, "Waitlist hold expiry failed");
+                crate::jobs::record_job_run(
+                    &state,
+                    "waitlist_hold_expiry",
+                    "failure",
+                    json!({}),
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+}
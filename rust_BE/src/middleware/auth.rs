@@ -1,10 +1,12 @@
 use crate::bootstrap::state::AppState;
-use crate::models::Claims;
+use crate::models::{Claims, StaffRole};
 use crate::utils::jwt;
 use axum::{
     extract::FromRequestParts,
     http::{request::Parts, StatusCode},
 };
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -67,6 +69,172 @@ impl FromRequestParts<Arc<AppState>> for ClubOwnerUser {
     }
 }
 
+/// A capability on the `/owner/*` routes. The club owner holds every
+/// permission; staff hold the ones their role grants.
+pub trait StaffPermission: Send + Sync + 'static {
+    fn allows(role: StaffRole) -> bool;
+}
+
+/// Read-only club basics (club profile, event list) every staff member needs
+pub struct AnyStaff;
+/// Scanning and checking in codes at the door
+pub struct ScanCodes;
+/// Reservations, tables, the waitlist and the check-in log
+pub struct ManageReservations;
+/// Club profile, events, tables, images, promo codes and policies
+pub struct ManageClub;
+/// Revenue stats, promo usage and payout status
+pub struct ViewFinance;
+
+impl StaffPermission for AnyStaff {
+    fn allows(_role: StaffRole) -> bool {
+        true
+    }
+}
+
+impl StaffPermission for ScanCodes {
+    fn allows(role: StaffRole) -> bool {
+        matches!(role, StaffRole::Door | StaffRole::Host | StaffRole::Manager)
+    }
+}
+
+impl StaffPermission for ManageReservations {
+    fn allows(role: StaffRole) -> bool {
+        matches!(role, StaffRole::Host | StaffRole::Manager)
+    }
+}
+
+impl StaffPermission for ManageClub {
+    fn allows(role: StaffRole) -> bool {
+        matches!(role, StaffRole::Manager)
+    }
+}
+
+impl StaffPermission for ViewFinance {
+    fn allows(role: StaffRole) -> bool {
+        matches!(role, StaffRole::Manager | StaffRole::Finance)
+    }
+}
+
+/// Extractor for `/owner/*` routes staff may use: accepts the club owner, or a
+/// staff member whose role grants `P`. Use `Claims::acting_owner_id` to find the club.
+pub struct ClubStaffUser<P: StaffPermission>(pub Claims, pub PhantomData<P>);
+
+#[axum::async_trait]
+impl<P: StaffPermission> FromRequestParts<Arc<AppState>> for ClubStaffUser<P> {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let claims = extract_claims(parts, state).await?;
+        if claims.role != "club_owner" {
+            let role = StaffRole::from_claim_role(&claims.role).ok_or(StatusCode::FORBIDDEN)?;
+            if !P::allows(role) {
+                return Err(StatusCode::FORBIDDEN);
+            }
+        }
+        Ok(ClubStaffUser(claims, PhantomData))
+    }
+}
+
+/// Who is acting on a back-office route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminActor {
+    /// A platform admin logged in at `/auth/admin/login`
+    Admin(Uuid),
+    /// Automation holding the `ADMIN_API_KEY` ops key
+    ApiKey,
+}
+
+impl AdminActor {
+    /// The admin account behind the request; None for the ops key
+    pub fn admin_id(&self) -> Option<Uuid> {
+        match self {
+            AdminActor::Admin(id) => Some(*id),
+            AdminActor::ApiKey => None,
+        }
+    }
+}
+
+/// Extractor for the `/admin/*` back office: a platform admin token, or the
+/// `X-Admin-Key` header matching `ADMIN_API_KEY`.
+pub struct AdminUser(pub AdminActor);
+
+#[axum::async_trait]
+impl FromRequestParts<Arc<AppState>> for AdminUser {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        if parts.headers.contains_key("x-admin-key") {
+            verify_admin_key(parts, state)?;
+            return Ok(AdminUser(AdminActor::ApiKey));
+        }
+
+        let claims = extract_claims(parts, state).await?;
+        if claims.role != "platform_admin" {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+        Ok(AdminUser(AdminActor::Admin(admin_id)))
+    }
+}
+
+/// Caller of the legacy club / event / ticket / table CRUD routes. Admins may
+/// act on every club; owners only on their own (see `club_service::ensure_manages_club`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClubManager {
+    Admin(AdminActor),
+    Owner(Uuid),
+}
+
+#[axum::async_trait]
+impl FromRequestParts<Arc<AppState>> for ClubManager {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        if parts.headers.contains_key("x-admin-key") {
+            verify_admin_key(parts, state)?;
+            return Ok(ClubManager::Admin(AdminActor::ApiKey));
+        }
+
+        let claims = extract_claims(parts, state).await?;
+        let id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+        match claims.role.as_str() {
+            "platform_admin" => Ok(ClubManager::Admin(AdminActor::Admin(id))),
+            "club_owner" => Ok(ClubManager::Owner(id)),
+            _ => Err(StatusCode::FORBIDDEN),
+        }
+    }
+}
+
+fn verify_admin_key(parts: &Parts, state: &AppState) -> Result<(), StatusCode> {
+    let expected = state
+        .config
+        .auth
+        .admin_api_key
+        .as_deref()
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let provided = parts
+        .headers
+        .get("x-admin-key")
+        .and_then(|v| v.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    // Compare digests so the check takes the same time wherever the keys differ
+    if Sha256::digest(provided.as_bytes()) != Sha256::digest(expected.as_bytes()) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
 async fn extract_claims(parts: &Parts, state: &Arc<AppState>) -> Result<Claims, StatusCode> {
     let auth_header = parts
         .headers
@@ -92,5 +260,53 @@ async fn extract_claims(parts: &Parts, state: &Arc<AppState>) -> Result<Claims,
         }
     }
 
+    // Disabling an admin locks them out immediately
+    if claims.role == "platform_admin" {
+        let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let is_active =
+            crate::application::platform_admin_service::admin_is_active(&state.db_pool, admin_id)
+                .await
+                .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+        if !is_active {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    // Access tokens issued with a refresh token die with their session family
+    // (logout, password change, refresh-token reuse).
+    if let Some(sid) = claims.sid.as_deref() {
+        let family_id = Uuid::parse_str(sid).map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let is_active = crate::application::session_service::session_family_is_active(
+            &state.db_pool,
+            family_id,
+        )
+        .await
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+        if !is_active {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    // Staff tokens are re-checked on every request so revocation and role
+    // changes apply immediately.
+    if let Some(role) = StaffRole::from_claim_role(&claims.role) {
+        let staff_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let owner_id = claims.acting_owner_id().ok_or(StatusCode::UNAUTHORIZED)?;
+        let is_active = crate::application::staff_service::staff_is_active(
+            &state.db_pool,
+            staff_id,
+            owner_id,
+            role.as_str(),
+        )
+        .await
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+        if !is_active {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+
     Ok(claims)
 }
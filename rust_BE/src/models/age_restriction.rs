@@ -0,0 +1,139 @@
+use chrono::{Datelike, NaiveDate};
+
+/// Highest minimum age an event can require
+pub const MAX_MIN_AGE: i32 = 99;
+
+/// Minimum age in a free-text `age_limit` ("18+", "Vietato ai minori di 21
+/// anni"): its first number, read the same way as the generated
+/// `events.min_age` column.
+pub fn parse_age_limit(text: &str) -> Option<i32> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = text[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .take(3)
+        .collect();
+    digits.parse().ok()
+}
+
+/// `age_limit` to store for a request that may carry the structured
+/// `min_age`: when given it wins and is written as "N+" (0 clears the
+/// restriction), otherwise the free text is kept as is.
+pub fn resolve_age_limit(
+    min_age: Option<i32>,
+    age_limit: Option<String>,
+) -> Result<Option<String>, &'static str> {
+    match min_age {
+        Some(age) if !(0..=MAX_MIN_AGE).contains(&age) => Err("min_age must be between 0 and 99"),
+        Some(0) => Ok(Some(String::new())),
+        Some(age) => Ok(Some(format!("{age}+"))),
+        None => Ok(age_limit),
+    }
+}
+
+/// Completed years of someone born on `date_of_birth` at `on`
+pub fn age_on(date_of_birth: NaiveDate, on: NaiveDate) -> i32 {
+    let mut age = on.year() - date_of_birth.year();
+    if (on.month(), on.day()) < (date_of_birth.month(), date_of_birth.day()) {
+        age -= 1;
+    }
+    age
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgeRejection {
+    /// The event has a minimum age and no birth date is known
+    BirthDateRequired {
+        min_age: i32,
+    },
+    UnderAge {
+        min_age: i32,
+    },
+}
+
+impl AgeRejection {
+    pub fn message(&self) -> String {
+        match self {
+            AgeRejection::BirthDateRequired { min_age } => format!(
+                "Indica la tua data di nascita: l'evento è riservato ai maggiori di {min_age} anni"
+            ),
+            AgeRejection::UnderAge { min_age } => {
+                format!("Devi avere almeno {min_age} anni per partecipare a questo evento")
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgeRejection::BirthDateRequired { .. } => "birth_date_required",
+            AgeRejection::UnderAge { .. } => "under_age",
+        }
+    }
+}
+
+/// Checks a birth date against an event's minimum age, with the age the
+/// person will have on `event_day`.
+pub fn check_min_age(
+    min_age: Option<i32>,
+    date_of_birth: Option<NaiveDate>,
+    event_day: NaiveDate,
+) -> Result<(), AgeRejection> {
+    let Some(min_age) = min_age.filter(|age| *age > 0) else {
+        return Ok(());
+    };
+    match date_of_birth {
+        None => Err(AgeRejection::BirthDateRequired { min_age }),
+        Some(date_of_birth) if age_on(date_of_birth, event_day) < min_age => {
+            Err(AgeRejection::UnderAge { min_age })
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn age_limit_text_and_min_age_agree() {
+        assert_eq!(parse_age_limit("18+"), Some(18));
+        assert_eq!(parse_age_limit("Vietato ai minori di 21 anni"), Some(21));
+        assert_eq!(parse_age_limit("Tutte le età"), None);
+        assert_eq!(
+            resolve_age_limit(Some(18), Some("x".into())),
+            Ok(Some("18+".into()))
+        );
+        assert_eq!(resolve_age_limit(Some(0), None), Ok(Some(String::new())));
+        assert_eq!(
+            resolve_age_limit(None, Some("16+".into())),
+            Ok(Some("16+".into()))
+        );
+        assert!(resolve_age_limit(Some(100), None).is_err());
+    }
+
+    #[test]
+    fn age_is_checked_on_the_event_day() {
+        let born = date(2008, 11, 20);
+        assert_eq!(age_on(born, date(2026, 11, 19)), 17);
+        assert_eq!(age_on(born, date(2026, 11, 20)), 18);
+
+        assert_eq!(
+            check_min_age(Some(18), Some(born), date(2026, 11, 19)),
+            Err(AgeRejection::UnderAge { min_age: 18 })
+        );
+        assert_eq!(
+            check_min_age(Some(18), Some(born), date(2026, 11, 20)),
+            Ok(())
+        );
+        assert_eq!(
+            check_min_age(Some(18), None, date(2026, 11, 20)),
+            Err(AgeRejection::BirthDateRequired { min_age: 18 })
+        );
+        assert_eq!(check_min_age(None, None, date(2026, 11, 20)), Ok(()));
+        assert_eq!(check_min_age(Some(0), None, date(2026, 11, 20)), Ok(()));
+    }
+}
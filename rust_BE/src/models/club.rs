@@ -20,6 +20,10 @@ pub struct Club {
     pub stripe_payouts_enabled: Option<bool>,
     pub platform_commission_percent: Option<Decimal>,
     pub platform_commission_fixed_fee: Option<Decimal>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// "geocoder" or "manual"; NULL while the address has not been geocoded
+    pub coordinates_source: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -78,6 +82,10 @@ pub struct ClubResponse {
     pub platform_commission_percent: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub platform_commission_fixed_fee: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
 }
 
 impl From<Club> for ClubResponse {
@@ -94,6 +102,62 @@ impl From<Club> for ClubResponse {
             stripe_payouts_enabled: club.stripe_payouts_enabled,
             platform_commission_percent: club.platform_commission_percent,
             platform_commission_fixed_fee: club.platform_commission_fixed_fee,
+            latitude: club.latitude,
+            longitude: club.longitude,
         }
     }
 }
+
+/// Query string of GET /clubs/nearby
+#[derive(Debug, Deserialize)]
+pub struct NearbyParams {
+    pub lat: f64,
+    pub lng: f64,
+    pub radius_km: Option<f64>,
+    pub limit: Option<i64>,
+}
+
+pub const DEFAULT_NEARBY_RADIUS_KM: f64 = 10.0;
+pub const MAX_NEARBY_RADIUS_KM: f64 = 100.0;
+pub const MAX_NEARBY_RESULTS: i64 = 50;
+
+impl NearbyParams {
+    /// `(lat, lng, radius_km, limit)` once validated.
+    pub fn validate(&self) -> Option<(f64, f64, f64, i64)> {
+        let radius_km = self.radius_km.unwrap_or(DEFAULT_NEARBY_RADIUS_KM);
+        let valid = (-90.0..=90.0).contains(&self.lat)
+            && (-180.0..=180.0).contains(&self.lng)
+            && radius_km > 0.0
+            && radius_km <= MAX_NEARBY_RADIUS_KM;
+        valid.then(|| {
+            (
+                self.lat,
+                self.lng,
+                radius_km,
+                self.limit.unwrap_or(20).clamp(1, MAX_NEARBY_RESULTS),
+            )
+        })
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct NearbyClubRow {
+    #[sqlx(flatten)]
+    pub club: Club,
+    pub distance_km: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NearbyClubResponse {
+    #[serde(flatten)]
+    pub club: ClubResponse,
+    #[serde(rename = "distanceKm")]
+    pub distance_km: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NearbyResponse {
+    pub clubs: Vec<NearbyClubResponse>,
+    /// Upcoming events at clubs in the radius, closest first
+    pub events: Vec<crate::models::EventSearchResult>,
+}
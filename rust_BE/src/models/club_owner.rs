@@ -1,5 +1,5 @@
 use super::club::ClubResponse;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -63,6 +63,7 @@ pub struct ClubOwnerAuthResponse {
     pub owner: ClubOwnerResponse,
     pub club: Option<ClubResponse>,
     pub token: String,
+    pub refresh_token: String,
 }
 
 // ── Club update ──────────────────────────────────────────────────────────────
@@ -74,6 +75,10 @@ pub struct OwnerUpdateClubRequest {
     pub address: Option<String>,
     pub phone_number: Option<String>,
     pub website: Option<String>,
+    /// Pin the club on the map by hand (both or neither); overrides geocoding
+    /// until the address changes again
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -158,9 +163,120 @@ pub struct ScanResult {
     pub num_people: Option<i32>,
     pub event_title: Option<String>,
     pub table_name: Option<String>,
+    /// Guests of a reservation already admitted (partial check-in)
+    pub checked_in_count: Option<i32>,
+    /// Why the code cannot be (fully) checked in:
+    /// "wrong_date" | "cancelled" | "exceeds_remaining"
+    pub reason: Option<String>,
+    /// Minimum age of the event, when it has one
+    pub min_age: Option<i32>,
+    /// Holder's age on the event day; None when unrestricted or unknown
+    pub holder_age: Option<i32>,
+    /// The holder is younger than `min_age`: door staff must not admit them
+    pub under_age: bool,
     pub code: String,
 }
 
+impl ScanResult {
+    pub fn not_found(code: String) -> Self {
+        ScanResult {
+            valid: false,
+            already_used: false,
+            scan_type: "unknown".to_string(),
+            guest_name: None,
+            num_people: None,
+            event_title: None,
+            table_name: None,
+            checked_in_count: None,
+            reason: None,
+            min_age: None,
+            holder_age: None,
+            under_age: false,
+            code,
+        }
+    }
+}
+
+/// Optional body for POST /owner/checkin/:code
+#[derive(Debug, Default, Deserialize)]
+pub struct CheckinRequest {
+    /// Guests arriving now for a reservation; defaults to everyone still missing
+    pub people: Option<i32>,
+}
+
+/// Event dates whose codes can be checked in at `now` (UTC): today, plus
+/// yesterday during the first `grace_hours` so nights running past midnight work.
+pub fn checkin_event_dates(now: DateTime<Utc>, grace_hours: i64) -> Vec<NaiveDate> {
+    let today = now.date_naive();
+    let mut dates = vec![today];
+    let yesterday_still_open = now - Duration::hours(grace_hours.max(0));
+    if yesterday_still_open.date_naive() < today {
+        dates.push(today - Duration::days(1));
+    }
+    dates
+}
+
+// ── Check-in audit log ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, FromRow)]
+pub struct CheckinAuditEntry {
+    pub id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub scanned_by: Uuid,
+    pub code: String,
+    pub action: String,
+    pub scan_type: String,
+    pub ticket_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    pub people_count: i32,
+    pub outcome: String,
+    pub created_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub scanned_by_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckinAuditParams {
+    pub event_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckinAuditEntryResponse {
+    pub id: String,
+    pub event_id: Option<String>,
+    pub scanned_by: String,
+    pub scanned_by_name: Option<String>,
+    pub code: String,
+    pub action: String,
+    pub scan_type: String,
+    pub ticket_id: Option<String>,
+    pub reservation_id: Option<String>,
+    pub people_count: i32,
+    pub outcome: String,
+    pub created_at: String,
+}
+
+impl From<CheckinAuditEntry> for CheckinAuditEntryResponse {
+    fn from(entry: CheckinAuditEntry) -> Self {
+        CheckinAuditEntryResponse {
+            id: entry.id.to_string(),
+            event_id: entry.event_id.map(|id| id.to_string()),
+            scanned_by: entry.scanned_by.to_string(),
+            scanned_by_name: entry.scanned_by_name,
+            code: entry.code,
+            action: entry.action,
+            scan_type: entry.scan_type,
+            ticket_id: entry.ticket_id.map(|id| id.to_string()),
+            reservation_id: entry.reservation_id.map(|id| id.to_string()),
+            people_count: entry.people_count,
+            outcome: entry.outcome,
+            created_at: entry.created_at.to_rfc3339(),
+        }
+    }
+}
+
 // ── Owner stats ──────────────────────────────────────────────────────────────
 
 #[derive(Debug, Serialize)]
@@ -178,5 +294,28 @@ pub struct EventStatRow {
 pub struct OwnerStats {
     pub active_reservations: i64,
     pub total_revenue: Decimal,
+    pub promo_redemptions: i64,
+    pub promo_discount_total: Decimal,
     pub events: Vec<EventStatRow>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn checkin_dates_include_previous_night_within_grace() {
+        let today = NaiveDate::from_ymd_opt(2026, 4, 5).unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2026, 4, 4).unwrap();
+
+        let after_midnight = Utc.with_ymd_and_hms(2026, 4, 5, 3, 0, 0).unwrap();
+        assert_eq!(
+            checkin_event_dates(after_midnight, 8),
+            vec![today, yesterday]
+        );
+
+        let afternoon = Utc.with_ymd_and_hms(2026, 4, 5, 15, 0, 0).unwrap();
+        assert_eq!(checkin_event_dates(afternoon, 8), vec![today]);
+    }
+}
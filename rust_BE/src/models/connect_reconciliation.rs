@@ -0,0 +1,424 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Longest period one statement or report can cover
+pub const MAX_FINANCE_PERIOD_DAYS: i64 = 366;
+
+/// How long a completed payment may go without a Connect transfer before it
+/// is flagged; Stripe creates the transfer with the charge, the sync lags.
+pub const MISSING_TRANSFER_GRACE_HOURS: i64 = 24;
+
+/// Stripe amounts are integer cents
+pub fn cents_to_decimal(cents: i64) -> Decimal {
+    Decimal::new(cents, 2)
+}
+
+/// Query string of GET /owner/finance/statement and GET /admin/finance/report
+#[derive(Debug, Default, Deserialize)]
+pub struct FinancePeriodParams {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// Validated period, both ends inclusive (UTC days)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinancePeriod {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl FinancePeriodParams {
+    /// Defaults to the current month up to `today`.
+    pub fn into_period(self, today: NaiveDate) -> Result<FinancePeriod, &'static str> {
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or_else(|| to.with_day(1).unwrap_or(to));
+        if to < from {
+            return Err("Intervallo di date non valido");
+        }
+        if (to - from).num_days() + 1 > MAX_FINANCE_PERIOD_DAYS {
+            return Err("Intervallo di date troppo ampio (massimo 1 anno)");
+        }
+        Ok(FinancePeriod { from, to })
+    }
+}
+
+/// A transfer from the platform to a connected account, as returned by the
+/// reconciliation provider. With destination charges there is one per charge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectTransferRecord {
+    pub id: String,
+    pub amount_cents: i64,
+    pub amount_reversed_cents: i64,
+    pub currency: String,
+    pub charge_id: Option<String>,
+    pub payment_intent_id: Option<String>,
+    pub charge_amount_cents: Option<i64>,
+    pub application_fee_cents: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A payout from a connected account to the club's bank account
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectPayoutRecord {
+    pub id: String,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub status: String,
+    pub arrival_date: NaiveDate,
+    pub failure_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A movement on a connected account's Stripe balance
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectBalanceTransactionRecord {
+    pub id: String,
+    pub kind: String,
+    pub amount_cents: i64,
+    pub fee_cents: i64,
+    pub net_cents: i64,
+    pub currency: String,
+    pub source_id: Option<String>,
+    pub available_on: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Everything the provider reported for one connected account since a date
+#[derive(Debug, Default)]
+pub struct ConnectActivity {
+    pub transfers: Vec<ConnectTransferRecord>,
+    pub payouts: Vec<ConnectPayoutRecord>,
+    pub balance_transactions: Vec<ConnectBalanceTransactionRecord>,
+}
+
+/// A club with a connected account and where its next sync starts
+#[derive(Debug, FromRow)]
+pub struct ConnectSyncTarget {
+    pub club_id: Uuid,
+    pub connected_account_id: String,
+    pub synced_until: Option<DateTime<Utc>>,
+}
+
+/// Completed payment of a club, as the `fake` provider turns it into Stripe
+/// activity.
+#[derive(Debug, FromRow)]
+pub struct ClubPaymentRow {
+    pub stripe_payment_intent_id: String,
+    pub amount: Decimal,
+    pub refunded_amount: Decimal,
+    pub paid_at: DateTime<Utc>,
+    pub platform_commission_percent: Decimal,
+    pub platform_commission_fixed_fee: Decimal,
+}
+
+/// Period sums of one club: what Stripe moved and what the local ledger says.
+#[derive(Clone, Debug, Default, PartialEq, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectTotals {
+    /// Charged to guests (sum of the transferred charges)
+    pub gross: Decimal,
+    /// Application fees kept by the platform
+    pub platform_fees: Decimal,
+    /// Sent to the club's connected account
+    pub transferred: Decimal,
+    /// Taken back from the club by refunds
+    pub reversed: Decimal,
+    pub transfers: i64,
+    /// Payouts that reached the club's bank in the period
+    pub paid_out: Decimal,
+    pub payouts: i64,
+    pub failed_payouts: i64,
+    /// Completed local payments of the club's events in the period
+    pub local_gross: Decimal,
+    pub local_refunded: Decimal,
+}
+
+impl ConnectTotals {
+    pub fn add(&mut self, other: &ConnectTotals) {
+        self.gross += other.gross;
+        self.platform_fees += other.platform_fees;
+        self.transferred += other.transferred;
+        self.reversed += other.reversed;
+        self.transfers += other.transfers;
+        self.paid_out += other.paid_out;
+        self.payouts += other.payouts;
+        self.failed_payouts += other.failed_payouts;
+        self.local_gross += other.local_gross;
+        self.local_refunded += other.local_refunded;
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct ClubConnectTotalsRow {
+    pub club_id: Uuid,
+    pub club_name: String,
+    pub connected_account_id: Option<String>,
+    pub synced_until: Option<DateTime<Utc>>,
+    pub last_sync_error: Option<String>,
+    #[sqlx(flatten)]
+    pub totals: ConnectTotals,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectPayoutRow {
+    #[serde(skip)]
+    pub club_id: Uuid,
+    pub id: String,
+    pub amount: Decimal,
+    pub currency: String,
+    pub status: String,
+    pub arrival_date: NaiveDate,
+    pub failure_message: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationIssueKind {
+    /// Completed locally, no transfer to the club on Stripe
+    MissingTransfer,
+    /// Transfer for a payment intent no local payment of the club knows
+    UnknownTransfer,
+    /// Charged on Stripe, but the local payment is not completed
+    StatusMismatch,
+    /// Stripe charged a different amount than the local payment
+    AmountMismatch,
+    /// Refunded locally without reversing the transfer
+    RefundNotReversed,
+    PayoutFailed,
+}
+
+/// A local payment of a club and the transfer of its payment intent; either
+/// side can be missing.
+#[derive(Debug, Default, FromRow)]
+pub struct ReconciliationPairRow {
+    pub club_id: Uuid,
+    pub payment_id: Option<Uuid>,
+    pub payment_intent_id: Option<String>,
+    pub local_status: Option<String>,
+    pub local_amount: Option<Decimal>,
+    pub local_refunded: Option<Decimal>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub transfer_id: Option<String>,
+    pub charge_amount: Option<Decimal>,
+    pub amount_reversed: Option<Decimal>,
+    pub transferred_at: Option<DateTime<Utc>>,
+    /// Oldest synced transfer of the club; payments before it predate Connect
+    pub first_transfer_at: Option<DateTime<Utc>>,
+}
+
+impl ReconciliationPairRow {
+    pub fn classify(&self, now: DateTime<Utc>) -> Option<ReconciliationIssueKind> {
+        let completed = self.local_status.as_deref() == Some("completed");
+        match (self.payment_id, self.transfer_id.as_ref()) {
+            (None, Some(_)) => Some(ReconciliationIssueKind::UnknownTransfer),
+            (Some(_), Some(_)) if !completed => Some(ReconciliationIssueKind::StatusMismatch),
+            (Some(_), None) => {
+                let paid_at = self.paid_at?;
+                let on_connect = self.first_transfer_at.is_some_and(|first| paid_at >= first);
+                let overdue = paid_at < now - Duration::hours(MISSING_TRANSFER_GRACE_HOURS);
+                (completed && on_connect && overdue)
+                    .then_some(ReconciliationIssueKind::MissingTransfer)
+            }
+            (Some(_), Some(_)) => {
+                if self.charge_amount.is_some() && self.charge_amount != self.local_amount {
+                    Some(ReconciliationIssueKind::AmountMismatch)
+                } else if self.local_refunded.unwrap_or_default() > Decimal::ZERO
+                    && self.amount_reversed.unwrap_or_default().is_zero()
+                {
+                    Some(ReconciliationIssueKind::RefundNotReversed)
+                } else {
+                    None
+                }
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationIssue {
+    pub kind: ReconciliationIssueKind,
+    pub club_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_intent_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payout_id: Option<String>,
+    /// Local figure (payment amount, refunded amount)
+    pub expected_amount: Option<Decimal>,
+    /// Stripe figure (charge amount, reversed amount, payout amount)
+    pub actual_amount: Option<Decimal>,
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+impl ReconciliationIssue {
+    pub fn from_pair(kind: ReconciliationIssueKind, pair: ReconciliationPairRow) -> Self {
+        let (expected_amount, actual_amount) = match kind {
+            ReconciliationIssueKind::RefundNotReversed => {
+                (pair.local_refunded, pair.amount_reversed)
+            }
+            _ => (pair.local_amount, pair.charge_amount),
+        };
+        Self {
+            kind,
+            club_id: pair.club_id,
+            payment_id: pair.payment_id,
+            payment_intent_id: pair.payment_intent_id,
+            transfer_id: pair.transfer_id,
+            payout_id: None,
+            expected_amount,
+            actual_amount,
+            occurred_at: pair.paid_at.or(pair.transferred_at),
+        }
+    }
+
+    pub fn failed_payout(payout: &ConnectPayoutRow) -> Self {
+        Self {
+            kind: ReconciliationIssueKind::PayoutFailed,
+            club_id: payout.club_id,
+            payment_id: None,
+            payment_intent_id: None,
+            transfer_id: None,
+            payout_id: Some(payout.id.clone()),
+            expected_amount: None,
+            actual_amount: Some(payout.amount),
+            occurred_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectStatementResponse {
+    pub period: FinancePeriod,
+    pub connected_account_id: Option<String>,
+    /// Stripe activity is complete up to here
+    pub synced_until: Option<DateTime<Utc>>,
+    pub last_sync_error: Option<String>,
+    pub totals: ConnectTotals,
+    pub payouts: Vec<ConnectPayoutRow>,
+    pub issues: Vec<ReconciliationIssue>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClubFinanceSummary {
+    pub club_id: Uuid,
+    pub club_name: String,
+    pub connected_account_id: Option<String>,
+    pub synced_until: Option<DateTime<Utc>>,
+    pub last_sync_error: Option<String>,
+    #[serde(flatten)]
+    pub totals: ConnectTotals,
+    pub issues: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformFinanceReportResponse {
+    pub period: FinancePeriod,
+    pub totals: ConnectTotals,
+    pub clubs: Vec<ClubFinanceSummary>,
+    pub issues: Vec<ReconciliationIssue>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, day, 12, 0, 0).unwrap()
+    }
+
+    fn matched_pair() -> ReconciliationPairRow {
+        ReconciliationPairRow {
+            club_id: Uuid::nil(),
+            payment_id: Some(Uuid::nil()),
+            payment_intent_id: Some("pi_1".to_string()),
+            local_status: Some("completed".to_string()),
+            local_amount: Some(Decimal::new(5000, 2)),
+            local_refunded: Some(Decimal::ZERO),
+            paid_at: Some(at(10)),
+            transfer_id: Some("tr_1".to_string()),
+            charge_amount: Some(Decimal::new(5000, 2)),
+            amount_reversed: Some(Decimal::ZERO),
+            transferred_at: Some(at(10)),
+            first_transfer_at: Some(at(1)),
+        }
+    }
+
+    #[test]
+    fn period_defaults_to_the_current_month() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let period = FinancePeriodParams::default().into_period(today).unwrap();
+        assert_eq!(period.from, NaiveDate::from_ymd_opt(2026, 10, 1).unwrap());
+        assert_eq!(period.to, today);
+
+        let too_long = FinancePeriodParams {
+            from: Some(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+            to: Some(today),
+        };
+        assert!(too_long.into_period(today).is_err());
+        assert_eq!(cents_to_decimal(12345), Decimal::new(12345, 2));
+    }
+
+    #[test]
+    fn pairs_are_classified_by_what_differs() {
+        let now = at(18);
+        assert_eq!(matched_pair().classify(now), None);
+
+        let mismatch = ReconciliationPairRow {
+            charge_amount: Some(Decimal::new(4000, 2)),
+            ..matched_pair()
+        };
+        assert_eq!(
+            mismatch.classify(now),
+            Some(ReconciliationIssueKind::AmountMismatch)
+        );
+
+        let not_reversed = ReconciliationPairRow {
+            local_refunded: Some(Decimal::new(5000, 2)),
+            ..matched_pair()
+        };
+        assert_eq!(
+            not_reversed.classify(now),
+            Some(ReconciliationIssueKind::RefundNotReversed)
+        );
+
+        let unknown = ReconciliationPairRow {
+            payment_id: None,
+            local_status: None,
+            ..matched_pair()
+        };
+        assert_eq!(
+            unknown.classify(now),
+            Some(ReconciliationIssueKind::UnknownTransfer)
+        );
+
+        let missing = ReconciliationPairRow {
+            transfer_id: None,
+            ..matched_pair()
+        };
+        assert_eq!(
+            missing.classify(now),
+            Some(ReconciliationIssueKind::MissingTransfer)
+        );
+        // Still within the grace period, or paid before the club was on Connect
+        assert_eq!(missing.classify(at(10) + Duration::hours(2)), None);
+        let before_connect = ReconciliationPairRow {
+            first_transfer_at: Some(at(11)),
+            ..missing
+        };
+        assert_eq!(before_connect.classify(now), None);
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PaymentDispute {
+    pub id: Uuid,
+    pub stripe_dispute_id: String,
+    pub payment_id: Option<Uuid>,
+    pub payment_share_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    pub ticket_order_id: Option<Uuid>,
+    pub club_id: Option<Uuid>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub reason: Option<String>,
+    pub status: String,
+    pub evidence_due_by: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl PaymentDispute {
+    /// A disputed share is frozen while the dispute is open, and for good
+    /// once the cardholder has won it
+    pub fn keeps_share_frozen(&self) -> bool {
+        self.closed_at.is_none() || self.status == "lost"
+    }
+}
+
+/// A dispute as stored, with what it looked like before this webhook
+#[derive(Debug, FromRow)]
+pub struct RecordedDispute {
+    #[sqlx(flatten)]
+    pub dispute: PaymentDispute,
+    /// `None` when this webhook created the row
+    pub previous_status: Option<String>,
+    pub was_closed: bool,
+}
+
+impl RecordedDispute {
+    pub fn newly_opened(&self) -> bool {
+        self.previous_status.is_none()
+    }
+
+    pub fn newly_closed(&self) -> bool {
+        !self.was_closed && self.dispute.closed_at.is_some()
+    }
+}
+
+/// What a dispute was opened against, resolved from its PaymentIntent
+#[derive(Debug, Default, FromRow)]
+pub struct DisputeContext {
+    pub payment_id: Option<Uuid>,
+    pub payment_share_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    pub ticket_order_id: Option<Uuid>,
+    pub club_id: Option<Uuid>,
+}
+
+/// The fields of a Stripe `dispute` object we keep
+#[derive(Debug, PartialEq)]
+pub struct StripeDispute {
+    pub id: String,
+    pub charge_id: Option<String>,
+    pub payment_intent_id: Option<String>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub reason: Option<String>,
+    pub status: String,
+}
+
+impl StripeDispute {
+    pub fn from_object(object: &Value) -> Option<Self> {
+        let text = |name: &str| {
+            object[name]
+                .as_str()
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Some(StripeDispute {
+            id: text("id")?,
+            charge_id: text("charge"),
+            payment_intent_id: text("payment_intent"),
+            amount: Decimal::new(object["amount"].as_i64().unwrap_or(0), 2),
+            currency: text("currency").unwrap_or_else(|| "eur".to_string()),
+            reason: text("reason"),
+            status: text("status")?,
+        })
+    }
+
+    /// Final statuses, reported by `charge.dispute.closed`
+    pub fn is_closed(&self) -> bool {
+        matches!(self.status.as_str(), "won" | "lost" | "warning_closed")
+    }
+}
+
+/// `evidence_details.due_by` of a Stripe dispute object
+pub fn evidence_due_by(object: &Value) -> Option<DateTime<Utc>> {
+    object["evidence_details"]["due_by"]
+        .as_i64()
+        .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dispute_object_is_read() {
+        let object = json!({
+            "id": "dp_1",
+            "charge": "ch_1",
+            "payment_intent": "pi_1",
+            "amount": 4550,
+            "currency": "eur",
+            "reason": "fraudulent",
+            "status": "needs_response",
+            "evidence_details": { "due_by": 1760000000 }
+        });
+        let dispute = StripeDispute::from_object(&object).unwrap();
+        assert_eq!(dispute.amount, Decimal::new(4550, 2));
+        assert_eq!(dispute.payment_intent_id.as_deref(), Some("pi_1"));
+        assert!(!dispute.is_closed());
+        assert_eq!(
+            evidence_due_by(&object).map(|at| at.timestamp()),
+            Some(1760000000)
+        );
+        assert!(StripeDispute {
+            status: "warning_closed".into(),
+            ..dispute
+        }
+        .is_closed());
+        assert!(StripeDispute::from_object(&json!({ "status": "lost" })).is_none());
+    }
+}
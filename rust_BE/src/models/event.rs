@@ -5,6 +5,7 @@ use serde_json::Value as JsonValue;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::models::age_restriction::resolve_age_limit;
 use crate::models::genre::GenreResponse;
 
 #[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
@@ -17,6 +18,10 @@ pub struct Event {
     pub status: Option<String>,
     pub time: Option<String>,
     pub age_limit: Option<String>,
+    /// Minimum age derived from `age_limit`; NULL or 0 when unrestricted
+    pub min_age: Option<i32>,
+    /// Holders may hand their tickets and reservations over to someone else
+    pub transfers_enabled: bool,
     pub end_time: Option<String>,
     pub price: Option<String>,
     pub description: Option<String>,
@@ -38,6 +43,10 @@ pub struct CreateEventRequest {
     pub status: Option<String>,
     pub time: Option<String>,
     pub age_limit: Option<String>,
+    /// Structured minimum age; overrides `age_limit`, which becomes "N+"
+    pub min_age: Option<i32>,
+    /// Allow ticket and reservation transfers (default: true)
+    pub transfers_enabled: Option<bool>,
     pub end_time: Option<String>,
     pub price: Option<String>,
     pub description: Option<String>,
@@ -57,6 +66,10 @@ pub struct UpdateEventRequest {
     pub status: Option<String>,
     pub time: Option<String>,
     pub age_limit: Option<String>,
+    /// Structured minimum age; overrides `age_limit`, which becomes "N+"
+    pub min_age: Option<i32>,
+    /// Allow ticket and reservation transfers (default: true)
+    pub transfers_enabled: Option<bool>,
     pub end_time: Option<String>,
     pub price: Option<String>,
     pub description: Option<String>,
@@ -80,6 +93,11 @@ pub struct EventResponse {
     #[serde(rename = "ageLimit")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub age_limit: Option<String>,
+    #[serde(rename = "minAge")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_age: Option<i32>,
+    #[serde(rename = "transfersEnabled")]
+    pub transfers_enabled: bool,
     #[serde(rename = "endTime")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_time: Option<String>,
@@ -109,6 +127,22 @@ pub fn is_valid_event_image_url(url: &str) -> bool {
     }
 }
 
+impl CreateEventRequest {
+    /// Folds `min_age` into the stored `age_limit` text
+    pub fn normalize_age_limit(&mut self) -> Result<(), &'static str> {
+        self.age_limit = resolve_age_limit(self.min_age.take(), self.age_limit.take())?;
+        Ok(())
+    }
+}
+
+impl UpdateEventRequest {
+    /// Folds `min_age` into the stored `age_limit` text
+    pub fn normalize_age_limit(&mut self) -> Result<(), &'static str> {
+        self.age_limit = resolve_age_limit(self.min_age.take(), self.age_limit.take())?;
+        Ok(())
+    }
+}
+
 impl From<Event> for EventResponse {
     fn from(event: Event) -> Self {
         // Derive time from ISO date if the time column is empty/null
@@ -130,6 +164,8 @@ impl From<Event> for EventResponse {
             status,
             time,
             age_limit: event.age_limit.filter(|s| !s.is_empty()),
+            min_age: event.min_age.filter(|age| *age > 0),
+            transfers_enabled: event.transfers_enabled,
             end_time: event.end_time.filter(|s| !s.is_empty()),
             price: event.price,
             description: event.description,
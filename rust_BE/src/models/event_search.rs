@@ -0,0 +1,320 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::event::{Event, EventResponse};
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSearchSort {
+    /// Soonest first
+    Date,
+    /// Most reservations and tickets first, then soonest
+    Popularity,
+    /// Closest club to `lat`/`lng` first
+    Distance,
+}
+
+/// Query string of GET /events/search
+#[derive(Debug, Default, Deserialize)]
+pub struct EventSearchParams {
+    pub q: Option<String>,
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    /// Comma-separated genre ids; events with any of them match
+    pub genres: Option<String>,
+    pub club_id: Option<Uuid>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    /// Only events whose minimum age is at most this (or that have none)
+    pub max_age_limit: Option<i32>,
+    /// Only events with a free table for at least this many people
+    pub party_size: Option<i32>,
+    pub sort: Option<EventSearchSort>,
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+    /// Only events at clubs within this many km of `lat`/`lng`
+    pub radius_km: Option<f64>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+/// Position after the last event of a page. Carries the sort key so the next
+/// page continues right after it even when new events are inserted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventSearchCursor {
+    pub sort: EventSearchSort,
+    pub event_date: NaiveDate,
+    pub id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub popularity: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f64>,
+}
+
+impl EventSearchCursor {
+    pub fn encode(&self) -> String {
+        hex::encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let bytes = hex::decode(value.trim()).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+/// Validated search, ready for the repository.
+#[derive(Debug)]
+pub struct EventSearchFilters {
+    pub text: Option<String>,
+    pub date_from: NaiveDate,
+    pub date_to: Option<NaiveDate>,
+    pub genre_ids: Vec<Uuid>,
+    pub club_id: Option<Uuid>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub max_age_limit: Option<i32>,
+    pub party_size: Option<i32>,
+    pub sort: EventSearchSort,
+    /// (latitude, longitude) of the user, required to sort by distance
+    pub origin: Option<(f64, f64)>,
+    /// Maximum distance from `origin`; set only together with it
+    pub radius_km: Option<f64>,
+    pub limit: i64,
+    pub after: Option<EventSearchCursor>,
+}
+
+fn price(value: Option<f64>) -> Result<Option<Decimal>, &'static str> {
+    match value {
+        None => Ok(None),
+        Some(value) => Decimal::from_f64_retain(value)
+            .filter(|d| !d.is_sign_negative())
+            .map(Some)
+            .ok_or("Prezzo non valido"),
+    }
+}
+
+impl EventSearchParams {
+    /// Check the parameters; `today` is the default start of the date range.
+    pub fn into_filters(self, today: NaiveDate) -> Result<EventSearchFilters, &'static str> {
+        let date_from = self.date_from.unwrap_or(today);
+        if self.date_to.is_some_and(|to| to < date_from) {
+            return Err("Intervallo di date non valido");
+        }
+
+        let genre_ids = match self.genres.as_deref() {
+            Some(genres) => genres
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(Uuid::parse_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| "Genere non valido")?,
+            None => Vec::new(),
+        };
+
+        let min_price = price(self.min_price)?;
+        let max_price = price(self.max_price)?;
+        if let (Some(min), Some(max)) = (min_price, max_price) {
+            if max < min {
+                return Err("Intervallo di prezzo non valido");
+            }
+        }
+
+        if self.party_size.is_some_and(|size| size < 1) {
+            return Err("Numero di persone non valido");
+        }
+
+        let origin = match (self.lat, self.lng) {
+            (Some(lat), Some(lng))
+                if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng) =>
+            {
+                Some((lat, lng))
+            }
+            (None, None) => None,
+            _ => return Err("Posizione non valida"),
+        };
+
+        if let Some(radius_km) = self.radius_km {
+            if origin.is_none() {
+                return Err("La posizione è obbligatoria per filtrare per distanza");
+            }
+            if !(radius_km > 0.0 && radius_km.is_finite()) {
+                return Err("Raggio non valido");
+            }
+        }
+
+        let sort = self.sort.unwrap_or(EventSearchSort::Date);
+        if sort == EventSearchSort::Distance && origin.is_none() {
+            return Err("La posizione è obbligatoria per ordinare per distanza");
+        }
+
+        let after = match self.cursor.as_deref() {
+            Some(cursor) => {
+                let cursor = EventSearchCursor::decode(cursor).ok_or("Cursore non valido")?;
+                let keys_match = match sort {
+                    EventSearchSort::Date => true,
+                    EventSearchSort::Popularity => cursor.popularity.is_some(),
+                    EventSearchSort::Distance => cursor.distance_km.is_some(),
+                };
+                if cursor.sort != sort || !keys_match {
+                    return Err("Cursore non valido");
+                }
+                Some(cursor)
+            }
+            None => None,
+        };
+
+        Ok(EventSearchFilters {
+            text: self
+                .q
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty()),
+            date_from,
+            date_to: self.date_to,
+            genre_ids,
+            club_id: self.club_id,
+            min_price,
+            max_price,
+            max_age_limit: self.max_age_limit,
+            party_size: self.party_size,
+            sort,
+            origin,
+            radius_km: self.radius_km,
+            limit: self
+                .limit
+                .unwrap_or(DEFAULT_SEARCH_LIMIT)
+                .clamp(1, MAX_SEARCH_LIMIT),
+            after,
+        })
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct EventSearchRow {
+    #[sqlx(flatten)]
+    pub event: Event,
+    pub popularity: i64,
+    pub distance_km: Option<f64>,
+}
+
+impl EventSearchRow {
+    pub fn cursor(&self, sort: EventSearchSort) -> Option<EventSearchCursor> {
+        Some(EventSearchCursor {
+            sort,
+            event_date: self.event.event_date?,
+            id: self.event.id,
+            popularity: (sort == EventSearchSort::Popularity).then_some(self.popularity),
+            distance_km: if sort == EventSearchSort::Distance {
+                self.distance_km
+            } else {
+                None
+            },
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSearchResult {
+    #[serde(flatten)]
+    pub event: EventResponse,
+    pub popularity: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSearchResponse {
+    pub events: Vec<EventSearchResult>,
+    /// Pass back as `cursor` for the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    #[test]
+    fn defaults_start_today_sorted_by_date() {
+        let filters = EventSearchParams::default().into_filters(today()).unwrap();
+        assert_eq!(filters.date_from, today());
+        assert_eq!(filters.sort, EventSearchSort::Date);
+        assert_eq!(filters.limit, DEFAULT_SEARCH_LIMIT);
+        assert!(filters.genre_ids.is_empty());
+        assert!(filters.after.is_none());
+    }
+
+    #[test]
+    fn rejects_inconsistent_parameters() {
+        let distance_without_origin = EventSearchParams {
+            sort: Some(EventSearchSort::Distance),
+            ..Default::default()
+        };
+        assert!(distance_without_origin.into_filters(today()).is_err());
+
+        let inverted_prices = EventSearchParams {
+            min_price: Some(30.0),
+            max_price: Some(10.0),
+            ..Default::default()
+        };
+        assert!(inverted_prices.into_filters(today()).is_err());
+
+        let bad_genre = EventSearchParams {
+            genres: Some("techno".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_genre.into_filters(today()).is_err());
+
+        let half_position = EventSearchParams {
+            lat: Some(45.46),
+            ..Default::default()
+        };
+        assert!(half_position.into_filters(today()).is_err());
+
+        let radius_without_origin = EventSearchParams {
+            radius_km: Some(5.0),
+            ..Default::default()
+        };
+        assert!(radius_without_origin.into_filters(today()).is_err());
+    }
+
+    #[test]
+    fn cursor_round_trips_and_must_match_the_sort() {
+        let cursor = EventSearchCursor {
+            sort: EventSearchSort::Distance,
+            event_date: today(),
+            id: Uuid::new_v4(),
+            popularity: None,
+            distance_km: Some(1.234_567_891),
+        };
+        let encoded = cursor.encode();
+        assert_eq!(EventSearchCursor::decode(&encoded), Some(cursor));
+        assert_eq!(EventSearchCursor::decode("not-a-cursor"), None);
+
+        let by_distance = EventSearchParams {
+            sort: Some(EventSearchSort::Distance),
+            lat: Some(45.46),
+            lng: Some(9.19),
+            cursor: Some(encoded.clone()),
+            ..Default::default()
+        };
+        assert!(by_distance.into_filters(today()).unwrap().after.is_some());
+
+        let by_date = EventSearchParams {
+            cursor: Some(encoded),
+            ..Default::default()
+        };
+        assert!(by_date.into_filters(today()).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::age_restriction::{parse_age_limit, resolve_age_limit};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeriesFrequency {
+    Weekly,
+    Biweekly,
+    /// Same weekday in the same week of every month as the start date
+    /// ("second Saturday"); a start in the fifth week means "last".
+    Monthly,
+}
+
+impl SeriesFrequency {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "weekly" => Some(SeriesFrequency::Weekly),
+            "biweekly" => Some(SeriesFrequency::Biweekly),
+            "monthly" => Some(SeriesFrequency::Monthly),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct EventSeries {
+    pub id: Uuid,
+    pub club_id: Uuid,
+    pub title: String,
+    pub venue: String,
+    pub image: String,
+    pub time: Option<String>,
+    pub end_time: Option<String>,
+    pub age_limit: Option<String>,
+    pub price: Option<String>,
+    pub description: Option<String>,
+    pub genre_ids: Vec<Uuid>,
+    pub frequency: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub excluded_dates: Vec<NaiveDate>,
+    pub template_event_id: Option<Uuid>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// `weekday` of the `week`-th week (1-5) of a month; 5 means the last one.
+fn nth_weekday_of_month(
+    first_of_month: NaiveDate,
+    weekday: chrono::Weekday,
+    week: u32,
+) -> NaiveDate {
+    let offset =
+        (7 + weekday.num_days_from_monday() - first_of_month.weekday().num_days_from_monday()) % 7;
+    let first = first_of_month + Duration::days(i64::from(offset));
+    let candidate = first + Duration::weeks(i64::from(week - 1));
+    if candidate.month() == first_of_month.month() {
+        candidate
+    } else {
+        candidate - Duration::weeks(1)
+    }
+}
+
+impl EventSeries {
+    /// Dates the series runs on between `from` and `until` (inclusive),
+    /// honouring the end date and the excluded dates.
+    pub fn occurrences_between(&self, from: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+        let until = match self.end_date {
+            Some(end_date) => until.min(end_date),
+            None => until,
+        };
+        let Some(frequency) = SeriesFrequency::parse(&self.frequency) else {
+            return Vec::new();
+        };
+
+        let mut dates = Vec::new();
+        let mut push = |date: NaiveDate| {
+            if date >= from && !self.excluded_dates.contains(&date) {
+                dates.push(date);
+            }
+        };
+
+        match frequency {
+            SeriesFrequency::Weekly | SeriesFrequency::Biweekly => {
+                let step = if frequency == SeriesFrequency::Weekly {
+                    1
+                } else {
+                    2
+                };
+                let mut date = self.start_date;
+                if from > date {
+                    // Jump close to `from` instead of walking from the start
+                    let periods = (from - date).num_weeks() / step;
+                    date += Duration::weeks(periods * step);
+                }
+                while date <= until {
+                    push(date);
+                    date += Duration::weeks(step);
+                }
+            }
+            SeriesFrequency::Monthly => {
+                let week = (self.start_date.day() - 1) / 7 + 1;
+                let weekday = self.start_date.weekday();
+                let Some(mut month) = self.start_date.with_day(1) else {
+                    return dates;
+                };
+                loop {
+                    let date = nth_weekday_of_month(month, weekday, week);
+                    if date > until {
+                        break;
+                    }
+                    push(date);
+                    match month.checked_add_months(Months::new(1)) {
+                        Some(next) => month = next,
+                        None => break,
+                    }
+                }
+            }
+        }
+        dates
+    }
+
+    /// Whether the rule (and exceptions) schedule an occurrence on `date`.
+    pub fn runs_on(&self, date: NaiveDate) -> bool {
+        self.occurrences_between(date, date) == [date]
+    }
+
+    /// Human-readable date string stored on `events.date` for an occurrence.
+    pub fn occurrence_date_label(&self, date: NaiveDate) -> String {
+        match self.time.as_deref() {
+            Some(time) => format!("{}T{}:00", date.format("%Y-%m-%d"), time.trim()),
+            None => date.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// Body for POST /owner/event-series
+#[derive(Debug, Deserialize)]
+pub struct CreateEventSeriesRequest {
+    pub title: String,
+    pub venue: String,
+    pub image: String,
+    pub time: Option<String>,
+    pub end_time: Option<String>,
+    pub age_limit: Option<String>,
+    /// Structured minimum age; overrides `age_limit`, which becomes "N+"
+    pub min_age: Option<i32>,
+    pub price: Option<String>,
+    pub description: Option<String>,
+    pub genre_ids: Option<Vec<Uuid>>,
+    pub frequency: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub excluded_dates: Option<Vec<NaiveDate>>,
+    pub template_event_id: Option<Uuid>,
+}
+
+/// Body for PATCH /owner/event-series/:id. Detail fields are propagated to
+/// future occurrences without bookings; rule fields reshape the schedule.
+#[derive(Debug, Deserialize)]
+pub struct UpdateEventSeriesRequest {
+    pub title: Option<String>,
+    pub venue: Option<String>,
+    pub image: Option<String>,
+    pub time: Option<String>,
+    pub end_time: Option<String>,
+    pub age_limit: Option<String>,
+    /// Structured minimum age; overrides `age_limit`, which becomes "N+"
+    pub min_age: Option<i32>,
+    pub price: Option<String>,
+    pub description: Option<String>,
+    pub genre_ids: Option<Vec<Uuid>>,
+    pub frequency: Option<String>,
+    pub end_date: Option<NaiveDate>,
+    pub excluded_dates: Option<Vec<NaiveDate>>,
+    pub template_event_id: Option<Uuid>,
+    pub is_active: Option<bool>,
+}
+
+impl CreateEventSeriesRequest {
+    /// Folds `min_age` into the stored `age_limit` text
+    pub fn normalize_age_limit(&mut self) -> Result<(), &'static str> {
+        self.age_limit = resolve_age_limit(self.min_age.take(), self.age_limit.take())?;
+        Ok(())
+    }
+}
+
+impl UpdateEventSeriesRequest {
+    /// Folds `min_age` into the stored `age_limit` text
+    pub fn normalize_age_limit(&mut self) -> Result<(), &'static str> {
+        self.age_limit = resolve_age_limit(self.min_age.take(), self.age_limit.take())?;
+        Ok(())
+    }
+
+    pub fn changes_details(&self) -> bool {
+        self.title.is_some()
+            || self.venue.is_some()
+            || self.image.is_some()
+            || self.time.is_some()
+            || self.end_time.is_some()
+            || self.age_limit.is_some()
+            || self.min_age.is_some()
+            || self.price.is_some()
+            || self.description.is_some()
+            || self.genre_ids.is_some()
+    }
+
+    pub fn changes_schedule(&self) -> bool {
+        self.frequency.is_some()
+            || self.end_date.is_some()
+            || self.excluded_dates.is_some()
+            || self.is_active.is_some()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSeriesResponse {
+    pub id: String,
+    pub title: String,
+    pub venue: String,
+    pub image: String,
+    pub time: Option<String>,
+    pub end_time: Option<String>,
+    pub age_limit: Option<String>,
+    pub min_age: Option<i32>,
+    pub price: Option<String>,
+    pub description: Option<String>,
+    pub genre_ids: Vec<String>,
+    pub frequency: String,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub excluded_dates: Vec<String>,
+    pub template_event_id: Option<String>,
+    pub is_active: bool,
+}
+
+impl From<EventSeries> for EventSeriesResponse {
+    fn from(series: EventSeries) -> Self {
+        EventSeriesResponse {
+            id: series.id.to_string(),
+            title: series.title,
+            venue: series.venue,
+            image: series.image,
+            time: series.time,
+            end_time: series.end_time,
+            min_age: series
+                .age_limit
+                .as_deref()
+                .and_then(parse_age_limit)
+                .filter(|age| *age > 0),
+            age_limit: series.age_limit,
+            price: series.price,
+            description: series.description,
+            genre_ids: series.genre_ids.iter().map(Uuid::to_string).collect(),
+            frequency: series.frequency,
+            start_date: series.start_date.to_string(),
+            end_date: series.end_date.map(|date| date.to_string()),
+            excluded_dates: series
+                .excluded_dates
+                .iter()
+                .map(NaiveDate::to_string)
+                .collect(),
+            template_event_id: series.template_event_id.map(|id| id.to_string()),
+            is_active: series.is_active,
+        }
+    }
+}
+
+/// What a series edit did to its future occurrences.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesPropagation {
+    pub updated_occurrences: u64,
+    pub created_occurrences: u64,
+    pub removed_occurrences: u64,
+    /// Future occurrences left untouched because they already have bookings
+    pub skipped_occurrences: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateEventSeriesResponse {
+    pub series: EventSeriesResponse,
+    #[serde(flatten)]
+    pub propagation: SeriesPropagation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn series(frequency: &str, start_date: NaiveDate) -> EventSeries {
+        EventSeries {
+            id: Uuid::new_v4(),
+            club_id: Uuid::new_v4(),
+            title: "Venerdì Techno".to_string(),
+            venue: "Main room".to_string(),
+            image: "https://example.com/night.jpg".to_string(),
+            time: Some("23:00".to_string()),
+            end_time: Some("04:00".to_string()),
+            age_limit: Some("18+".to_string()),
+            price: None,
+            description: None,
+            genre_ids: Vec::new(),
+            frequency: frequency.to_string(),
+            start_date,
+            end_date: None,
+            excluded_dates: Vec::new(),
+            template_event_id: None,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn weekly_and_biweekly_skip_exceptions_and_stop_at_end_date() {
+        // Fridays from 2 Oct 2026
+        let mut weekly = series("weekly", date(2026, 10, 2));
+        weekly.excluded_dates = vec![date(2026, 10, 16)];
+        weekly.end_date = Some(date(2026, 10, 30));
+
+        assert_eq!(
+            weekly.occurrences_between(date(2026, 10, 10), date(2026, 12, 31)),
+            vec![date(2026, 10, 23), date(2026, 10, 30)]
+        );
+
+        let biweekly = series("biweekly", date(2026, 10, 2));
+        assert_eq!(
+            biweekly.occurrences_between(date(2026, 10, 10), date(2026, 11, 15)),
+            vec![date(2026, 10, 16), date(2026, 10, 30), date(2026, 11, 13)]
+        );
+        assert!(biweekly.runs_on(date(2026, 10, 30)));
+        assert!(!biweekly.runs_on(date(2026, 10, 23)));
+        assert!(!weekly.runs_on(date(2026, 10, 16)));
+    }
+
+    #[test]
+    fn monthly_keeps_the_weekday_and_week_of_the_month() {
+        // Second Saturday
+        let second_saturday = series("monthly", date(2026, 10, 10));
+        assert_eq!(
+            second_saturday.occurrences_between(date(2026, 10, 1), date(2027, 1, 31)),
+            vec![
+                date(2026, 10, 10),
+                date(2026, 11, 14),
+                date(2026, 12, 12),
+                date(2027, 1, 9)
+            ]
+        );
+
+        // Fifth Friday of October 2026 becomes the last Friday of each month
+        let last_friday = series("monthly", date(2026, 10, 30));
+        assert_eq!(
+            last_friday.occurrences_between(date(2026, 10, 1), date(2026, 12, 31)),
+            vec![date(2026, 10, 30), date(2026, 11, 27), date(2026, 12, 25)]
+        );
+    }
+
+    #[test]
+    fn occurrence_label_carries_the_start_time() {
+        let weekly = series("weekly", date(2026, 10, 2));
+        assert_eq!(
+            weekly.occurrence_date_label(date(2026, 10, 9)),
+            "2026-10-09T23:00:00"
+        );
+    }
+}
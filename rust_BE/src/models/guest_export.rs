@@ -0,0 +1,483 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sqlx::FromRow;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportSort {
+    /// Table name, then guest name
+    Table,
+    /// Area, then table name
+    Area,
+    /// Guest or holder name
+    Name,
+}
+
+/// Query string of the `/owner/events/:event_id/export/*` routes
+#[derive(Debug, Default, Deserialize)]
+pub struct GuestExportParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Comma-separated column keys, in output order; all columns by default
+    pub columns: Option<String>,
+    pub sort: Option<ExportSort>,
+    /// `true`: only fully checked-in entries, `false`: only those still expected
+    pub checked_in: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportKind {
+    Reservations,
+    Guests,
+    Tickets,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExportColumn {
+    pub key: &'static str,
+    pub header: &'static str,
+    /// Phone or email, masked for staff who may not see guest contacts
+    pub contact: bool,
+}
+
+const fn column(key: &'static str, header: &'static str) -> ExportColumn {
+    ExportColumn {
+        key,
+        header,
+        contact: false,
+    }
+}
+
+const fn contact_column(key: &'static str, header: &'static str) -> ExportColumn {
+    ExportColumn {
+        key,
+        header,
+        contact: true,
+    }
+}
+
+const RESERVATION_COLUMNS: &[ExportColumn] = &[
+    column("code", "Codice"),
+    column("name", "Nome"),
+    contact_column("phone", "Telefono"),
+    contact_column("email", "Email"),
+    column("table", "Tavolo"),
+    column("area", "Area"),
+    column("people", "Persone"),
+    column("checked_in", "Entrati"),
+    column("checkin_status", "Check-in"),
+    column("status", "Stato"),
+    column("total", "Totale"),
+    column("paid", "Pagato"),
+    column("notes", "Richieste"),
+];
+
+const GUEST_COLUMNS: &[ExportColumn] = &[
+    column("code", "Prenotazione"),
+    column("table", "Tavolo"),
+    column("area", "Area"),
+    column("type", "Tipo"),
+    column("name", "Nome"),
+    contact_column("phone", "Telefono"),
+    contact_column("email", "Email"),
+    column("payment_status", "Pagamento"),
+    column("amount", "Quota"),
+    column("checkin_status", "Check-in"),
+];
+
+const TICKET_COLUMNS: &[ExportColumn] = &[
+    column("code", "Codice"),
+    column("type", "Tipo"),
+    column("tier", "Categoria"),
+    column("name", "Nome"),
+    contact_column("phone", "Telefono"),
+    contact_column("email", "Email"),
+    column("price", "Prezzo"),
+    column("status", "Stato"),
+    column("checkin_status", "Check-in"),
+    column("purchased_at", "Acquistato il"),
+];
+
+impl ExportKind {
+    pub fn columns(self) -> &'static [ExportColumn] {
+        match self {
+            ExportKind::Reservations => RESERVATION_COLUMNS,
+            ExportKind::Guests => GUEST_COLUMNS,
+            ExportKind::Tickets => TICKET_COLUMNS,
+        }
+    }
+
+    /// Worksheet and file name
+    pub fn sheet_name(self) -> &'static str {
+        match self {
+            ExportKind::Reservations => "prenotazioni",
+            ExportKind::Guests => "ospiti",
+            ExportKind::Tickets => "biglietti",
+        }
+    }
+
+    /// The columns picked by `columns` (all when absent), in the requested order.
+    pub fn select_columns(
+        self,
+        columns: Option<&str>,
+    ) -> Result<Vec<&'static ExportColumn>, &'static str> {
+        let available = self.columns();
+        let Some(columns) = columns.filter(|columns| !columns.trim().is_empty()) else {
+            return Ok(available.iter().collect());
+        };
+        let mut selected: Vec<&'static ExportColumn> = Vec::new();
+        for key in columns
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+        {
+            let column = available
+                .iter()
+                .find(|column| column.key == key)
+                .ok_or("Colonna non valida")?;
+            if !selected.contains(&column) {
+                selected.push(column);
+            }
+        }
+        Ok(selected)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportValue {
+    Text(String),
+    Integer(i64),
+    Amount(Decimal),
+    Empty,
+}
+
+impl From<Option<String>> for ExportValue {
+    fn from(value: Option<String>) -> Self {
+        value.map(ExportValue::Text).unwrap_or(ExportValue::Empty)
+    }
+}
+
+/// A rendered export: one header row and the selected columns of each entry.
+#[derive(Debug)]
+pub struct ExportSheet {
+    pub name: &'static str,
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<ExportValue>>,
+}
+
+/// Any export row: the value of a column by key.
+pub trait ExportRecord {
+    fn value(&self, key: &str) -> ExportValue;
+}
+
+impl ExportSheet {
+    pub fn build<R: ExportRecord>(
+        kind: ExportKind,
+        columns: &[&'static ExportColumn],
+        records: &[R],
+        show_contacts: bool,
+    ) -> Self {
+        let rows = records
+            .iter()
+            .map(|record| {
+                columns
+                    .iter()
+                    .map(|column| match record.value(column.key) {
+                        ExportValue::Text(text) if column.contact && !show_contacts => {
+                            ExportValue::Text(mask_contact(&text))
+                        }
+                        value => value,
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            name: kind.sheet_name(),
+            headers: columns.iter().map(|column| column.header).collect(),
+            rows,
+        }
+    }
+
+    /// RFC 4180 CSV with a UTF-8 BOM so spreadsheet apps keep accented names.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("\u{feff}");
+        let mut push_line = |cells: Vec<String>| {
+            csv.push_str(&cells.join(","));
+            csv.push_str("\r\n");
+        };
+        push_line(self.headers.iter().map(|header| csv_cell(header)).collect());
+        for row in &self.rows {
+            push_line(
+                row.iter()
+                    .map(|value| match value {
+                        ExportValue::Text(text) => csv_cell(text),
+                        ExportValue::Integer(number) => number.to_string(),
+                        ExportValue::Amount(amount) => amount.round_dp(2).to_string(),
+                        ExportValue::Empty => String::new(),
+                    })
+                    .collect(),
+            );
+        }
+        csv
+    }
+}
+
+fn csv_cell(text: &str) -> String {
+    // Spreadsheet apps run cells starting with these as formulas; phone numbers
+    // ("+39 ...") are left as they are
+    let formula = text.starts_with(['=', '@', '\t', '\r'])
+        || (text.starts_with(['+', '-'])
+            && !text[1..]
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == ' ' || c == '.'));
+    let text = if formula {
+        format!("'{text}")
+    } else {
+        text.to_string()
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// "mario.rossi@example.com" -> "m***@example.com", "+39 333 1234567" -> "*********567"
+pub fn mask_contact(value: &str) -> String {
+    if let Some((local, domain)) = value.split_once('@') {
+        let first = local.chars().next().map(String::from).unwrap_or_default();
+        return format!("{first}***@{domain}");
+    }
+    let digits: Vec<char> = value.chars().filter(char::is_ascii_digit).collect();
+    let visible = digits.len().min(3);
+    let hidden = digits.len() - visible;
+    format!(
+        "{}{}",
+        "*".repeat(hidden),
+        digits[hidden..].iter().collect::<String>()
+    )
+}
+
+fn checkin_label(checked_in: i32, people: i32) -> String {
+    if people > 0 && checked_in >= people {
+        "Entrati".to_string()
+    } else if checked_in > 0 {
+        format!("Parziale ({checked_in}/{people})")
+    } else {
+        "Attesi".to_string()
+    }
+}
+
+fn status_label(status: &str) -> String {
+    match status {
+        "pending" => "In attesa",
+        "confirmed" => "Confermata",
+        "completed" => "Completata",
+        "paid" => "Pagato",
+        "expired" => "Scaduto",
+        "active" => "Valido",
+        "used" => "Usato",
+        other => other,
+    }
+    .to_string()
+}
+
+#[derive(Debug, FromRow)]
+pub struct ReservationExportRow {
+    pub reservation_code: String,
+    pub contact_name: String,
+    pub contact_phone: String,
+    pub contact_email: String,
+    pub table_name: String,
+    pub area_name: Option<String>,
+    pub num_people: i32,
+    pub checked_in_count: i32,
+    pub status: String,
+    pub total_amount: Decimal,
+    pub amount_paid: Decimal,
+    pub special_requests: Option<String>,
+}
+
+impl ExportRecord for ReservationExportRow {
+    fn value(&self, key: &str) -> ExportValue {
+        match key {
+            "code" => ExportValue::Text(self.reservation_code.clone()),
+            "name" => ExportValue::Text(self.contact_name.clone()),
+            "phone" => ExportValue::Text(self.contact_phone.clone()),
+            "email" => ExportValue::Text(self.contact_email.clone()),
+            "table" => ExportValue::Text(self.table_name.clone()),
+            "area" => self.area_name.clone().into(),
+            "people" => ExportValue::Integer(self.num_people.into()),
+            "checked_in" => ExportValue::Integer(self.checked_in_count.into()),
+            "checkin_status" => {
+                ExportValue::Text(checkin_label(self.checked_in_count, self.num_people))
+            }
+            "status" => ExportValue::Text(status_label(&self.status)),
+            "total" => ExportValue::Amount(self.total_amount),
+            "paid" => ExportValue::Amount(self.amount_paid),
+            "notes" => self.special_requests.clone().into(),
+            _ => ExportValue::Empty,
+        }
+    }
+}
+
+/// A paying participant (payment share) or a free guest of a reservation.
+#[derive(Debug, FromRow)]
+pub struct GuestExportRow {
+    pub reservation_code: String,
+    pub table_name: String,
+    pub area_name: Option<String>,
+    /// `payer` or `guest`
+    pub kind: String,
+    pub name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub payment_status: Option<String>,
+    pub amount: Option<Decimal>,
+    pub checked_in_count: i32,
+    pub num_people: i32,
+}
+
+impl ExportRecord for GuestExportRow {
+    fn value(&self, key: &str) -> ExportValue {
+        match key {
+            "code" => ExportValue::Text(self.reservation_code.clone()),
+            "table" => ExportValue::Text(self.table_name.clone()),
+            "area" => self.area_name.clone().into(),
+            "type" => ExportValue::Text(
+                if self.kind == "payer" {
+                    "Pagante"
+                } else {
+                    "Ospite"
+                }
+                .to_string(),
+            ),
+            "name" => self.name.clone().into(),
+            "phone" => self.phone.clone().into(),
+            "email" => self.email.clone().into(),
+            "payment_status" => self.payment_status.as_deref().map(status_label).into(),
+            "amount" => self
+                .amount
+                .map(ExportValue::Amount)
+                .unwrap_or(ExportValue::Empty),
+            // Check-in is tracked per reservation
+            "checkin_status" => {
+                ExportValue::Text(checkin_label(self.checked_in_count, self.num_people))
+            }
+            _ => ExportValue::Empty,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct TicketExportRow {
+    pub ticket_code: String,
+    pub ticket_type: String,
+    pub tier_name: Option<String>,
+    pub holder_name: Option<String>,
+    pub holder_phone: Option<String>,
+    pub holder_email: Option<String>,
+    pub price: Decimal,
+    pub status: String,
+    pub purchase_date: Option<DateTime<Utc>>,
+}
+
+impl ExportRecord for TicketExportRow {
+    fn value(&self, key: &str) -> ExportValue {
+        match key {
+            "code" => ExportValue::Text(self.ticket_code.clone()),
+            "type" => ExportValue::Text(self.ticket_type.clone()),
+            "tier" => self.tier_name.clone().into(),
+            "name" => self.holder_name.clone().into(),
+            "phone" => self.holder_phone.clone().into(),
+            "email" => self.holder_email.clone().into(),
+            "price" => ExportValue::Amount(self.price),
+            "status" => ExportValue::Text(status_label(&self.status)),
+            "checkin_status" => ExportValue::Text(
+                if self.status == "used" {
+                    "Entrato"
+                } else {
+                    "Atteso"
+                }
+                .to_string(),
+            ),
+            "purchased_at" => self
+                .purchase_date
+                .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+                .into(),
+            _ => ExportValue::Empty,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_are_selected_in_order_and_validated() {
+        let columns = ExportKind::Reservations
+            .select_columns(Some("table, name,table"))
+            .unwrap();
+        let keys: Vec<_> = columns.iter().map(|column| column.key).collect();
+        assert_eq!(keys, vec!["table", "name"]);
+        assert_eq!(
+            ExportKind::Tickets.select_columns(None).unwrap().len(),
+            TICKET_COLUMNS.len()
+        );
+        assert!(ExportKind::Guests.select_columns(Some("notes")).is_err());
+    }
+
+    #[test]
+    fn contacts_are_masked() {
+        assert_eq!(mask_contact("mario.rossi@example.com"), "m***@example.com");
+        assert_eq!(mask_contact("+39 333 1234567"), "*********567");
+        assert_eq!(mask_contact("12"), "12");
+    }
+
+    #[test]
+    fn csv_quotes_cells_and_neutralizes_formulas() {
+        let sheet = ExportSheet {
+            name: "ospiti",
+            headers: vec!["Nome", "Telefono", "Quota"],
+            rows: vec![vec![
+                ExportValue::Text("Rossi, \"Mario\"".to_string()),
+                ExportValue::Text("+39 333 1234567".to_string()),
+                ExportValue::Amount(Decimal::new(2550, 2)),
+            ]],
+        };
+        assert_eq!(
+            sheet.to_csv(),
+            "\u{feff}Nome,Telefono,Quota\r\n\"Rossi, \"\"Mario\"\"\",+39 333 1234567,25.50\r\n"
+        );
+        assert_eq!(csv_cell("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_cell("-1+2"), "'-1+2");
+    }
+}
@@ -4,10 +4,22 @@ pub use event::{
     is_valid_event_image_url, CreateEventRequest, Event, EventResponse, UpdateEventRequest,
 };
 
+pub mod age_restriction;
+
+pub mod event_search;
+pub use event_search::{EventSearchParams, EventSearchResponse, EventSearchResult};
+
+pub mod event_series;
+pub use event_series::{
+    CreateEventSeriesRequest, EventSeries, EventSeriesResponse, SeriesFrequency, SeriesPropagation,
+    UpdateEventSeriesRequest, UpdateEventSeriesResponse,
+};
+
 pub mod payment;
 pub use payment::{
     CancelPaymentRequest, CancelPaymentResponse, CapturePaymentRequest, CapturePaymentResponse,
-    PaymentCaptureMethod, PaymentEntity, PaymentFilter, PaymentRequest, PaymentStatus,
+    ClubStripeConnectConfig, PaymentCaptureMethod, PaymentEntity, PaymentFilter, PaymentRequest,
+    PaymentStatus,
 };
 
 pub mod user;
@@ -17,7 +29,10 @@ pub mod genre;
 pub use genre::{CreateGenreRequest, Genre, GenreResponse, UpdateGenreRequest};
 
 pub mod club;
-pub use club::{Club, ClubResponse, CreateClubRequest, UpdateClubRequest};
+pub use club::{
+    Club, ClubResponse, CreateClubRequest, NearbyClubResponse, NearbyParams, NearbyResponse,
+    UpdateClubRequest,
+};
 
 pub mod club_owner;
 
@@ -27,21 +42,108 @@ pub use ticket::{
     UpdateTicketRequest,
 };
 
+pub mod ticket_tier;
+pub use ticket_tier::{
+    CreateTicketCheckoutRequest, CreateTicketTierRequest, TicketCheckoutResponse, TicketOrder,
+    TicketPurchaseRejection, TicketTier, TicketTierResponse, UpdateTicketTierRequest,
+};
+
 pub mod table;
 pub use table::{
     AddPaymentToReservationRequest, CreateCheckoutRequest, CreateCheckoutResponse,
     CreatePaymentIntentResponse, CreateSplitPaymentIntentRequest, CreateSplitReservationRequest,
     CreateSplitReservationResponse, CreateTableRequest, CreateTableReservationRequest,
-    LinkTicketToReservationRequest, PaymentLinkPreviewResponse, PaymentShareResponse,
-    ReservationGuest, ReservationPaymentShare, ReservationPaymentStatusResponse, Table,
-    TableReservation, TableReservationResponse, TableReservationWithDetailsResponse,
-    TableReservationsResponse, TableReservationsWithDetailsResponse, TableResponse, TableSummary,
-    TablesResponse, UpdateTableRequest, UpdateTableReservationRequest,
+    LinkTicketToReservationRequest, PaymentLinkPreviewParams, PaymentLinkPreviewResponse,
+    PaymentShareResponse, ReservationGuest, ReservationPaymentShare,
+    ReservationPaymentStatusResponse, Table, TableReservation, TableReservationResponse,
+    TableReservationWithDetailsResponse, TableReservationsResponse,
+    TableReservationsWithDetailsResponse, TableResponse, TableSummary, TablesResponse,
+    UpdateTableRequest, UpdateTableReservationRequest,
+};
+
+pub mod split_payment;
+
+pub mod table_tab;
+pub use table_tab::{
+    AddTabLineRequest, SettleTabRequest, SettleTabResponse, TableTab, TableTabResponse,
+};
+
+pub mod connect_reconciliation;
+pub use connect_reconciliation::FinancePeriodParams;
+
+pub mod guest_export;
+pub use guest_export::GuestExportParams;
+
+pub mod owner_analytics;
+pub use owner_analytics::{OwnerAnalyticsParams, OwnerAnalyticsResponse};
+
+pub mod table_layout;
+pub use table_layout::{
+    plan_table_layout, ApplyTableLayoutRequest, CreateTableLayoutTemplateRequest,
+    TableLayoutTemplate, TableLayoutTemplateResponse, UpdateTableLayoutTemplateRequest,
 };
 
 pub mod area;
 pub use area::{Area, AreaResponse, AssignAreaRequest, CreateAreaRequest, UpdateAreaRequest};
 
+pub mod dispute;
+
+pub mod refund;
+pub use refund::{
+    CancelReservationResponse, CancellationActor, CancellationPolicy, CancellationPolicyResponse,
+    Refund, UpdateCancellationPolicyRequest,
+};
+
+pub mod promo_code;
+pub use promo_code::{
+    CreatePromoCodeRequest, PromoCode, PromoCodeRedemption, PromoCodeRejection, PromoCodeResponse,
+    UpdatePromoCodeRequest,
+};
+
+pub mod outbox;
+pub use outbox::{DeadLetterParams, OutboxEventResponse};
+
+pub mod stripe_webhook;
+pub use stripe_webhook::{StripeWebhookEventResponse, WebhookEventParams};
+
+pub mod password_reset;
+pub use password_reset::{
+    ForgotPasswordRequest, PasswordResetChannel, PasswordResetCode, PasswordResetResponse,
+    ResetCodeCheck, ResetPasswordRequest,
+};
+
+pub mod platform_admin;
+pub use platform_admin::{
+    AdminAuthResponse, AdminLoginRequest, AdminOwnerResponse, AdminOwnerRow, AdminRefundParams,
+    AdminRefundResponse, AdminRefundRow, CreatePlatformAdminRequest, PlatformAdmin,
+    PlatformAdminResponse, UpdateCommissionRequest, UpdateOwnerStatusRequest,
+};
+
+pub mod push;
+pub use push::{PushDeliveryMetrics, PushDeliveryMetricsResponse, PushMetricsParams};
+
+pub mod session;
+pub use session::{RefreshTokenRequest, SessionSubject, TokenPairResponse, UserSession};
+
+pub mod staff;
+pub use staff::{
+    AcceptStaffInviteRequest, ClubStaff, InviteStaffRequest, StaffAuthResponse,
+    StaffInviteResponse, StaffLoginRequest, StaffMemberResponse, StaffRole, UpdateStaffRequest,
+};
+
+pub mod transfer;
+pub use transfer::{
+    AcceptTransferRequest, AcceptTransferResponse, CreateTransferRequest, OwnershipTransfer,
+    TransferAuditEntryResponse, TransferAuditParams, TransferClaim, TransferResponse,
+    TransfersResponse,
+};
+
+pub mod waitlist;
+pub use waitlist::{
+    JoinWaitlistRequest, ReorderWaitlistRequest, WaitlistEntry, WaitlistEntryResponse,
+    WaitlistResponse,
+};
+
 use serde::Deserialize;
 
 #[allow(unused_imports)]
@@ -0,0 +1,49 @@
+use crate::infrastructure::outbox::OutboxEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Query for GET /admin/outbox/dead-letters
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterParams {
+    pub event_type: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEventResponse {
+    pub id: String,
+    pub event_type: String,
+    pub aggregate_type: Option<String>,
+    pub aggregate_id: Option<String>,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub replay_count: i32,
+    pub last_error: Option<String>,
+    pub available_at: String,
+    pub dead_lettered_at: Option<String>,
+    pub processed_at: Option<String>,
+    pub created_at: String,
+}
+
+impl From<OutboxEvent> for OutboxEventResponse {
+    fn from(event: OutboxEvent) -> Self {
+        OutboxEventResponse {
+            id: event.id.to_string(),
+            event_type: event.event_type,
+            aggregate_type: event.aggregate_type,
+            aggregate_id: event.aggregate_id.map(|id| id.to_string()),
+            payload: event.payload,
+            status: event.status,
+            attempts: event.attempts,
+            replay_count: event.replay_count,
+            last_error: event.last_error,
+            available_at: event.available_at.to_rfc3339(),
+            dead_lettered_at: event.dead_lettered_at.map(|at| at.to_rfc3339()),
+            processed_at: event.processed_at.map(|at| at.to_rfc3339()),
+            created_at: event.created_at.to_rfc3339(),
+        }
+    }
+}
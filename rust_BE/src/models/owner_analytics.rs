@@ -0,0 +1,342 @@
+use chrono::{Duration, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Longest range one request can cover
+pub const MAX_ANALYTICS_RANGE_DAYS: i64 = 731;
+/// Range used when `from` is not given, ending at `to`
+pub const DEFAULT_ANALYTICS_RANGE_DAYS: i64 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsGranularity {
+    Day,
+    Week,
+    Month,
+}
+
+impl AnalyticsGranularity {
+    /// `date_trunc` unit
+    pub fn sql_unit(self) -> &'static str {
+        match self {
+            AnalyticsGranularity::Day => "day",
+            AnalyticsGranularity::Week => "week",
+            AnalyticsGranularity::Month => "month",
+        }
+    }
+}
+
+/// Query string of GET /owner/analytics
+#[derive(Debug, Default, Deserialize)]
+pub struct OwnerAnalyticsParams {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub granularity: Option<AnalyticsGranularity>,
+}
+
+/// Validated range, both ends inclusive, and the equally long period right
+/// before it used for the comparison.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub granularity: AnalyticsGranularity,
+    pub previous_from: NaiveDate,
+    pub previous_to: NaiveDate,
+}
+
+impl OwnerAnalyticsParams {
+    pub fn into_range(self, today: NaiveDate) -> Result<AnalyticsRange, &'static str> {
+        let to = self.to.unwrap_or(today);
+        let from = self
+            .from
+            .unwrap_or(to - Duration::days(DEFAULT_ANALYTICS_RANGE_DAYS - 1));
+        if to < from {
+            return Err("Intervallo di date non valido");
+        }
+        let days = (to - from).num_days() + 1;
+        if days > MAX_ANALYTICS_RANGE_DAYS {
+            return Err("Intervallo di date troppo ampio (massimo 2 anni)");
+        }
+
+        Ok(AnalyticsRange {
+            from,
+            to,
+            granularity: self.granularity.unwrap_or(AnalyticsGranularity::Day),
+            previous_from: from - Duration::days(days),
+            previous_to: from - Duration::days(1),
+        })
+    }
+}
+
+/// Raw sums over a set of reservations. Cancelled reservations only count
+/// towards revenue (whatever was not refunded).
+#[derive(Clone, Debug, Default, PartialEq, FromRow)]
+pub struct AnalyticsTotalsRow {
+    pub revenue: Decimal,
+    pub reservations: i64,
+    pub guests: i64,
+    pub checked_in: i64,
+    pub tab_spend: Decimal,
+    pub tabs_closed: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsMetrics {
+    pub revenue: Decimal,
+    pub reservations: i64,
+    pub guests: i64,
+    pub checked_in: i64,
+    /// Checked-in guests over booked guests, 0-1
+    pub check_in_rate: Option<f64>,
+    /// Revenue per booked guest
+    pub average_spend: Option<Decimal>,
+    /// Final spend of the tables whose tab was closed
+    pub tab_spend: Decimal,
+    pub tabs_closed: i64,
+    /// Final spend per closed tab
+    pub average_tab_spend: Option<Decimal>,
+}
+
+impl From<AnalyticsTotalsRow> for AnalyticsMetrics {
+    fn from(row: AnalyticsTotalsRow) -> Self {
+        let check_in_rate = (row.guests > 0).then(|| row.checked_in as f64 / row.guests as f64);
+        let average_spend =
+            (row.guests > 0).then(|| (row.revenue / Decimal::from(row.guests)).round_dp(2));
+        let average_tab_spend = (row.tabs_closed > 0)
+            .then(|| (row.tab_spend / Decimal::from(row.tabs_closed)).round_dp(2));
+        Self {
+            revenue: row.revenue,
+            reservations: row.reservations,
+            guests: row.guests,
+            checked_in: row.checked_in,
+            check_in_rate,
+            average_spend,
+            tab_spend: row.tab_spend,
+            tabs_closed: row.tabs_closed,
+            average_tab_spend,
+        }
+    }
+}
+
+/// Relative change against the previous period (0.25 = +25%); `None` when the
+/// previous value is zero or missing.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsChange {
+    pub revenue: Option<f64>,
+    pub reservations: Option<f64>,
+    pub guests: Option<f64>,
+    pub check_in_rate: Option<f64>,
+    pub average_spend: Option<f64>,
+}
+
+fn relative_change(current: Option<f64>, previous: Option<f64>) -> Option<f64> {
+    match (current, previous) {
+        (Some(current), Some(previous)) if previous != 0.0 => {
+            Some((current - previous) / previous.abs())
+        }
+        _ => None,
+    }
+}
+
+impl AnalyticsChange {
+    pub fn between(current: &AnalyticsMetrics, previous: &AnalyticsMetrics) -> Self {
+        let decimal = |value: Decimal| value.to_f64();
+        Self {
+            revenue: relative_change(decimal(current.revenue), decimal(previous.revenue)),
+            reservations: relative_change(
+                Some(current.reservations as f64),
+                Some(previous.reservations as f64),
+            ),
+            guests: relative_change(Some(current.guests as f64), Some(previous.guests as f64)),
+            check_in_rate: relative_change(current.check_in_rate, previous.check_in_rate),
+            average_spend: relative_change(
+                current.average_spend.and_then(decimal),
+                previous.average_spend.and_then(decimal),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct AnalyticsBucketRow {
+    pub period_start: NaiveDate,
+    #[sqlx(flatten)]
+    pub totals: AnalyticsTotalsRow,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsBucket {
+    /// First day of the day/week (Monday)/month
+    pub period_start: NaiveDate,
+    #[serde(flatten)]
+    pub metrics: AnalyticsMetrics,
+}
+
+impl From<AnalyticsBucketRow> for AnalyticsBucket {
+    fn from(row: AnalyticsBucketRow) -> Self {
+        Self {
+            period_start: row.period_start,
+            metrics: row.totals.into(),
+        }
+    }
+}
+
+/// One line of the by-event, by-area or by-table breakdown. Tables are
+/// recreated for every event, so they are grouped by name and area.
+#[derive(Debug, FromRow)]
+pub struct AnalyticsBreakdownRow {
+    pub id: Option<Uuid>,
+    pub name: Option<String>,
+    pub event_date: Option<NaiveDate>,
+    pub area_name: Option<String>,
+    #[sqlx(flatten)]
+    pub totals: AnalyticsTotalsRow,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsBreakdown {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    /// `null` for reservations on tables without an area
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area_name: Option<String>,
+    #[serde(flatten)]
+    pub metrics: AnalyticsMetrics,
+}
+
+impl From<AnalyticsBreakdownRow> for AnalyticsBreakdown {
+    fn from(row: AnalyticsBreakdownRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            event_date: row.event_date,
+            area_name: row.area_name,
+            metrics: row.totals.into(),
+        }
+    }
+}
+
+/// Counts of the payment link analytics events of the range's reservations.
+#[derive(Debug, Default, FromRow)]
+pub struct PaymentFunnelRow {
+    pub link_views: i64,
+    pub checkouts_started: i64,
+    pub paid_shares: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentFunnel {
+    pub link_views: i64,
+    pub checkouts_started: i64,
+    pub paid_shares: i64,
+    pub view_to_checkout_rate: Option<f64>,
+    pub checkout_to_paid_rate: Option<f64>,
+}
+
+impl From<PaymentFunnelRow> for PaymentFunnel {
+    fn from(row: PaymentFunnelRow) -> Self {
+        let rate = |part: i64, whole: i64| (whole > 0).then(|| part as f64 / whole as f64);
+        Self {
+            view_to_checkout_rate: rate(row.checkouts_started, row.link_views),
+            checkout_to_paid_rate: rate(row.paid_shares, row.checkouts_started),
+            link_views: row.link_views,
+            checkouts_started: row.checkouts_started,
+            paid_shares: row.paid_shares,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerAnalyticsResponse {
+    pub range: AnalyticsRange,
+    pub totals: AnalyticsMetrics,
+    pub previous: AnalyticsMetrics,
+    pub change: AnalyticsChange,
+    pub series: Vec<AnalyticsBucket>,
+    pub by_event: Vec<AnalyticsBreakdown>,
+    pub by_area: Vec<AnalyticsBreakdown>,
+    pub by_table: Vec<AnalyticsBreakdown>,
+    pub funnel: PaymentFunnel,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn range_defaults_to_last_30_days_and_compares_with_the_period_before() {
+        let range = OwnerAnalyticsParams::default()
+            .into_range(date(30))
+            .unwrap();
+        assert_eq!(range.from, date(1));
+        assert_eq!(range.to, date(30));
+        assert_eq!(range.granularity, AnalyticsGranularity::Day);
+        assert_eq!(
+            range.previous_to,
+            NaiveDate::from_ymd_opt(2026, 9, 30).unwrap()
+        );
+        assert_eq!(
+            range.previous_from,
+            NaiveDate::from_ymd_opt(2026, 9, 1).unwrap()
+        );
+
+        let inverted = OwnerAnalyticsParams {
+            from: Some(date(10)),
+            to: Some(date(9)),
+            ..Default::default()
+        };
+        assert!(inverted.into_range(date(30)).is_err());
+    }
+
+    #[test]
+    fn metrics_derive_rates_and_changes_without_dividing_by_zero() {
+        let current = AnalyticsMetrics::from(AnalyticsTotalsRow {
+            revenue: Decimal::new(30000, 2),
+            reservations: 2,
+            guests: 8,
+            checked_in: 6,
+            tab_spend: Decimal::new(125000, 2),
+            tabs_closed: 2,
+        });
+        assert_eq!(current.check_in_rate, Some(0.75));
+        assert_eq!(current.average_spend, Some(Decimal::new(3750, 2)));
+        assert_eq!(current.average_tab_spend, Some(Decimal::new(62500, 2)));
+
+        let empty = AnalyticsMetrics::from(AnalyticsTotalsRow::default());
+        assert_eq!(empty.check_in_rate, None);
+        assert_eq!(empty.average_spend, None);
+        assert_eq!(empty.average_tab_spend, None);
+        assert_eq!(AnalyticsChange::between(&current, &empty).revenue, None);
+
+        let previous = AnalyticsMetrics::from(AnalyticsTotalsRow {
+            revenue: Decimal::new(20000, 2),
+            reservations: 4,
+            guests: 8,
+            checked_in: 8,
+            tab_spend: Decimal::ZERO,
+            tabs_closed: 0,
+        });
+        let change = AnalyticsChange::between(&current, &previous);
+        assert_eq!(change.revenue, Some(0.5));
+        assert_eq!(change.reservations, Some(-0.5));
+        assert_eq!(change.guests, Some(0.0));
+    }
+}
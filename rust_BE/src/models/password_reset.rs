@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Where a password reset code is delivered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasswordResetChannel {
+    Email,
+    Sms,
+}
+
+impl PasswordResetChannel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "email" => Some(PasswordResetChannel::Email),
+            "sms" => Some(PasswordResetChannel::Sms),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PasswordResetChannel::Email => "email",
+            PasswordResetChannel::Sms => "sms",
+        }
+    }
+}
+
+/// The latest code issued to an account.
+#[derive(Clone, Debug, FromRow)]
+pub struct PasswordResetCode {
+    pub id: Uuid,
+    pub code_hash: String,
+    pub failed_attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub invalidated_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of checking a submitted code against the latest issued one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetCodeCheck {
+    Accepted,
+    /// Wrong code; `locks` is set when this guess uses up the last attempt
+    WrongCode {
+        locks: bool,
+    },
+    Expired,
+    Used,
+    /// Replaced by a newer code or locked after too many wrong guesses
+    Invalidated,
+}
+
+impl PasswordResetCode {
+    pub fn check(
+        &self,
+        submitted_hash: &str,
+        now: DateTime<Utc>,
+        max_failed_attempts: i32,
+    ) -> ResetCodeCheck {
+        if self.used_at.is_some() {
+            return ResetCodeCheck::Used;
+        }
+        if self.invalidated_at.is_some() || self.failed_attempts >= max_failed_attempts {
+            return ResetCodeCheck::Invalidated;
+        }
+        if self.expires_at <= now {
+            return ResetCodeCheck::Expired;
+        }
+        if self.code_hash != submitted_hash {
+            return ResetCodeCheck::WrongCode {
+                locks: self.failed_attempts + 1 >= max_failed_attempts,
+            };
+        }
+        ResetCodeCheck::Accepted
+    }
+}
+
+/// Body for POST /auth/forgot-password and /auth/club-owner/forgot-password
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+    /// "email" (default) or "sms"; falls back to email when the account has no phone
+    pub channel: Option<String>,
+}
+
+/// Body for POST /auth/reset-password and /auth/club-owner/reset-password
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub email: String,
+    pub code: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PasswordResetResponse {
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    const MAX: i32 = 5;
+
+    fn code(expires_in_minutes: i64) -> PasswordResetCode {
+        PasswordResetCode {
+            id: Uuid::new_v4(),
+            code_hash: "right".to_string(),
+            failed_attempts: 0,
+            expires_at: Utc::now() + Duration::minutes(expires_in_minutes),
+            used_at: None,
+            invalidated_at: None,
+        }
+    }
+
+    #[test]
+    fn fresh_code_is_accepted() {
+        assert_eq!(
+            code(15).check("right", Utc::now(), MAX),
+            ResetCodeCheck::Accepted
+        );
+    }
+
+    #[test]
+    fn expired_code_is_rejected_even_when_right() {
+        let code = code(15);
+        assert_eq!(
+            code.check("right", code.expires_at, MAX),
+            ResetCodeCheck::Expired
+        );
+        assert_eq!(
+            code.check("right", code.expires_at + Duration::seconds(1), MAX),
+            ResetCodeCheck::Expired
+        );
+    }
+
+    #[test]
+    fn used_code_cannot_be_reused() {
+        let mut code = code(15);
+        code.used_at = Some(Utc::now());
+        assert_eq!(code.check("right", Utc::now(), MAX), ResetCodeCheck::Used);
+    }
+
+    #[test]
+    fn superseded_code_is_rejected() {
+        let mut code = code(15);
+        code.invalidated_at = Some(Utc::now());
+        assert_eq!(
+            code.check("right", Utc::now(), MAX),
+            ResetCodeCheck::Invalidated
+        );
+    }
+
+    #[test]
+    fn last_wrong_guess_locks_the_code() {
+        let mut code = code(15);
+        for attempt in 0..MAX {
+            code.failed_attempts = attempt;
+            assert_eq!(
+                code.check("wrong", Utc::now(), MAX),
+                ResetCodeCheck::WrongCode {
+                    locks: attempt == MAX - 1
+                }
+            );
+        }
+        code.failed_attempts = MAX;
+        assert_eq!(
+            code.check("right", Utc::now(), MAX),
+            ResetCodeCheck::Invalidated
+        );
+    }
+}
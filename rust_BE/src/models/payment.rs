@@ -105,3 +105,25 @@ pub struct CancelPaymentResponse {
 pub struct CancelPaymentRequest {
     pub idempotency_key: Option<Uuid>,
 }
+
+/// Stripe Connect state and commission of the club that runs an event.
+#[derive(Clone, Debug, FromRow)]
+pub struct ClubStripeConnectConfig {
+    pub stripe_connected_account_id: Option<String>,
+    pub stripe_onboarding_complete: Option<bool>,
+    pub stripe_charges_enabled: Option<bool>,
+    pub stripe_payouts_enabled: Option<bool>,
+    pub platform_commission_percent: Option<Decimal>,
+    pub platform_commission_fixed_fee: Option<Decimal>,
+}
+
+impl ClubStripeConnectConfig {
+    /// Whether charges can be sent to the club's connected account. Otherwise
+    /// the platform account collects the payment.
+    pub fn can_route_funds(&self) -> bool {
+        self.stripe_connected_account_id.is_some()
+            && self.stripe_onboarding_complete.unwrap_or(false)
+            && self.stripe_charges_enabled.unwrap_or(false)
+            && self.stripe_payouts_enabled.unwrap_or(false)
+    }
+}
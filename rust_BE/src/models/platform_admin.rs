@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct PlatformAdmin {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub is_active: bool,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for POST /auth/admin/login
+#[derive(Debug, Deserialize)]
+pub struct AdminLoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+/// Body for POST /admin/admins
+#[derive(Debug, Deserialize)]
+pub struct CreatePlatformAdminRequest {
+    pub email: String,
+    pub name: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformAdminResponse {
+    pub id: String,
+    pub email: String,
+    pub name: String,
+    pub is_active: bool,
+    pub last_login_at: Option<String>,
+    pub created_at: String,
+}
+
+impl From<PlatformAdmin> for PlatformAdminResponse {
+    fn from(admin: PlatformAdmin) -> Self {
+        PlatformAdminResponse {
+            id: admin.id.to_string(),
+            email: admin.email,
+            name: admin.name,
+            is_active: admin.is_active,
+            last_login_at: admin.last_login_at.map(|at| at.to_rfc3339()),
+            created_at: admin.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAuthResponse {
+    pub admin: PlatformAdminResponse,
+    pub token: String,
+    pub expires_in: i64,
+}
+
+/// Body for PUT /admin/clubs/:id/commission. Both values are required so the
+/// effective commission is always explicit.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCommissionRequest {
+    pub platform_commission_percent: Decimal,
+    pub platform_commission_fixed_fee: Decimal,
+}
+
+/// Club owner with the club they run, as listed in the back office
+#[derive(Clone, Debug, FromRow)]
+pub struct AdminOwnerRow {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub phone_number: Option<String>,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub club_id: Option<Uuid>,
+    pub club_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminOwnerResponse {
+    pub id: String,
+    pub email: String,
+    pub name: String,
+    pub phone_number: Option<String>,
+    pub suspended_at: Option<String>,
+    pub created_at: String,
+    pub club_id: Option<String>,
+    pub club_name: Option<String>,
+}
+
+impl From<AdminOwnerRow> for AdminOwnerResponse {
+    fn from(row: AdminOwnerRow) -> Self {
+        AdminOwnerResponse {
+            id: row.id.to_string(),
+            email: row.email,
+            name: row.name,
+            phone_number: row.phone_number,
+            suspended_at: row.suspended_at.map(|at| at.to_rfc3339()),
+            created_at: row.created_at.to_rfc3339(),
+            club_id: row.club_id.map(|id| id.to_string()),
+            club_name: row.club_name,
+        }
+    }
+}
+
+/// Body for PATCH /admin/owners/:id
+#[derive(Debug, Deserialize)]
+pub struct UpdateOwnerStatusRequest {
+    pub suspended: bool,
+}
+
+/// Query params for GET /admin/refunds: ?status=failed&limit=50&offset=0
+#[derive(Debug, Deserialize)]
+pub struct AdminRefundParams {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Refund with the reservation and club it belongs to
+#[derive(Clone, Debug, FromRow)]
+pub struct AdminRefundRow {
+    pub id: Uuid,
+    pub payment_id: Uuid,
+    pub reservation_id: Option<Uuid>,
+    pub stripe_refund_id: Option<String>,
+    pub amount: Decimal,
+    pub status: String,
+    pub reason: String,
+    pub refund_percent: Option<Decimal>,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub club_id: Option<Uuid>,
+    pub club_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRefundResponse {
+    pub id: String,
+    pub payment_id: String,
+    pub reservation_id: Option<String>,
+    pub stripe_refund_id: Option<String>,
+    pub amount: Decimal,
+    pub status: String,
+    pub reason: String,
+    pub refund_percent: Option<Decimal>,
+    pub failure_reason: Option<String>,
+    pub created_at: String,
+    pub club_id: Option<String>,
+    pub club_name: Option<String>,
+}
+
+impl From<AdminRefundRow> for AdminRefundResponse {
+    fn from(row: AdminRefundRow) -> Self {
+        AdminRefundResponse {
+            id: row.id.to_string(),
+            payment_id: row.payment_id.to_string(),
+            reservation_id: row.reservation_id.map(|id| id.to_string()),
+            stripe_refund_id: row.stripe_refund_id,
+            amount: row.amount,
+            status: row.status,
+            reason: row.reason,
+            refund_percent: row.refund_percent,
+            failure_reason: row.failure_reason,
+            created_at: row.created_at.to_rfc3339(),
+            club_id: row.club_id.map(|id| id.to_string()),
+            club_name: row.club_name,
+        }
+    }
+}
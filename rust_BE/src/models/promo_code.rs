@@ -0,0 +1,261 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Smallest amount Stripe will charge in EUR; discounts never go below it.
+pub const MIN_CHARGE_AMOUNT: Decimal = Decimal::from_parts(50, 0, 0, false, 2);
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct PromoCode {
+    pub id: Uuid,
+    pub club_id: Uuid,
+    pub code: String,
+    pub description: Option<String>,
+    pub discount_type: String, // "percentage" | "fixed"
+    pub discount_value: Decimal,
+    pub event_id: Option<Uuid>,
+    pub area_id: Option<Uuid>,
+    pub table_id: Option<Uuid>,
+    pub max_redemptions: Option<i32>,
+    pub redemption_count: i32,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub single_use_per_phone: bool,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Why a promo code cannot be used for a given share.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PromoCodeRejection {
+    NotFound,
+    Inactive,
+    NotYetValid,
+    Expired,
+    WrongScope,
+    Exhausted,
+    AlreadyUsed,
+}
+
+impl PromoCodeRejection {
+    pub fn message(&self) -> &'static str {
+        match self {
+            PromoCodeRejection::NotFound => "Codice promozionale non valido",
+            PromoCodeRejection::Inactive => "Codice promozionale non attivo",
+            PromoCodeRejection::NotYetValid => "Codice promozionale non ancora valido",
+            PromoCodeRejection::Expired => "Codice promozionale scaduto",
+            PromoCodeRejection::WrongScope => "Codice promozionale non valido per questo tavolo",
+            PromoCodeRejection::Exhausted => "Codice promozionale esaurito",
+            PromoCodeRejection::AlreadyUsed => "Hai già usato questo codice promozionale",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromoCodeRejection::NotFound => "not_found",
+            PromoCodeRejection::Inactive => "inactive",
+            PromoCodeRejection::NotYetValid => "not_yet_valid",
+            PromoCodeRejection::Expired => "expired",
+            PromoCodeRejection::WrongScope => "wrong_scope",
+            PromoCodeRejection::Exhausted => "exhausted",
+            PromoCodeRejection::AlreadyUsed => "already_used",
+        }
+    }
+}
+
+impl PromoCode {
+    /// Discount on `amount`, capped so at least `MIN_CHARGE_AMOUNT` is still charged.
+    pub fn discount_for(&self, amount: Decimal) -> Decimal {
+        let raw = if self.discount_type == "percentage" {
+            (amount * self.discount_value / Decimal::new(100, 0)).round_dp(2)
+        } else {
+            self.discount_value
+        };
+        let max_discount = (amount - MIN_CHARGE_AMOUNT).max(Decimal::ZERO);
+        raw.max(Decimal::ZERO).min(max_discount)
+    }
+
+    /// Static checks (status, validity window, scope). Usage caps and
+    /// per-phone limits are enforced when the code is redeemed.
+    pub fn check_applicable(
+        &self,
+        event_id: Uuid,
+        area_id: Option<Uuid>,
+        table_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<(), PromoCodeRejection> {
+        if !self.active {
+            return Err(PromoCodeRejection::Inactive);
+        }
+        if self.valid_from.is_some_and(|from| now < from) {
+            return Err(PromoCodeRejection::NotYetValid);
+        }
+        if self.valid_until.is_some_and(|until| now >= until) {
+            return Err(PromoCodeRejection::Expired);
+        }
+        if self.event_id.is_some_and(|id| id != event_id)
+            || self.table_id.is_some_and(|id| id != table_id)
+            || self.area_id.is_some_and(|id| Some(id) != area_id)
+        {
+            return Err(PromoCodeRejection::WrongScope);
+        }
+        if self
+            .max_redemptions
+            .is_some_and(|max| self.redemption_count >= max)
+        {
+            return Err(PromoCodeRejection::Exhausted);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct PromoCodeRedemption {
+    pub id: Uuid,
+    pub promo_code_id: Uuid,
+    pub reservation_id: Uuid,
+    pub payment_share_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub phone_number: Option<String>,
+    pub original_amount: Decimal,
+    pub discount_amount: Decimal,
+    pub final_amount: Decimal,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for POST /owner/promo-codes
+#[derive(Debug, Deserialize)]
+pub struct CreatePromoCodeRequest {
+    pub code: String,
+    pub description: Option<String>,
+    pub discount_type: String,
+    pub discount_value: Decimal,
+    pub event_id: Option<String>,
+    pub area_id: Option<String>,
+    pub table_id: Option<String>,
+    pub max_redemptions: Option<i32>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub single_use_per_phone: Option<bool>,
+}
+
+/// Body for PATCH /owner/promo-codes/:id
+#[derive(Debug, Deserialize)]
+pub struct UpdatePromoCodeRequest {
+    pub description: Option<String>,
+    pub max_redemptions: Option<i32>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromoCodeResponse {
+    pub id: String,
+    pub code: String,
+    pub description: Option<String>,
+    pub discount_type: String,
+    pub discount_value: Decimal,
+    pub event_id: Option<String>,
+    pub area_id: Option<String>,
+    pub table_id: Option<String>,
+    pub max_redemptions: Option<i32>,
+    pub redemption_count: i32,
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+    pub single_use_per_phone: bool,
+    pub active: bool,
+    pub created_at: String,
+}
+
+impl From<PromoCode> for PromoCodeResponse {
+    fn from(promo: PromoCode) -> Self {
+        PromoCodeResponse {
+            id: promo.id.to_string(),
+            code: promo.code,
+            description: promo.description,
+            discount_type: promo.discount_type,
+            discount_value: promo.discount_value,
+            event_id: promo.event_id.map(|id| id.to_string()),
+            area_id: promo.area_id.map(|id| id.to_string()),
+            table_id: promo.table_id.map(|id| id.to_string()),
+            max_redemptions: promo.max_redemptions,
+            redemption_count: promo.redemption_count,
+            valid_from: promo.valid_from.map(|at| at.to_rfc3339()),
+            valid_until: promo.valid_until.map(|at| at.to_rfc3339()),
+            single_use_per_phone: promo.single_use_per_phone,
+            active: promo.active,
+            created_at: promo.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn promo(discount_type: &str, value: i64) -> PromoCode {
+        PromoCode {
+            id: Uuid::new_v4(),
+            club_id: Uuid::new_v4(),
+            code: "SUMMER".to_string(),
+            description: None,
+            discount_type: discount_type.to_string(),
+            discount_value: Decimal::new(value, 0),
+            event_id: None,
+            area_id: None,
+            table_id: None,
+            max_redemptions: None,
+            redemption_count: 0,
+            valid_from: None,
+            valid_until: None,
+            single_use_per_phone: true,
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn discount_never_drops_below_minimum_charge() {
+        assert_eq!(
+            promo("percentage", 20).discount_for(Decimal::new(5000, 2)),
+            Decimal::new(1000, 2)
+        );
+        assert_eq!(
+            promo("fixed", 100).discount_for(Decimal::new(4000, 2)),
+            Decimal::new(3950, 2)
+        );
+    }
+
+    #[test]
+    fn scope_and_window_are_checked() {
+        let event_id = Uuid::new_v4();
+        let table_id = Uuid::new_v4();
+        let now = Utc::now();
+
+        let mut scoped = promo("fixed", 5);
+        scoped.event_id = Some(Uuid::new_v4());
+        assert_eq!(
+            scoped.check_applicable(event_id, None, table_id, now),
+            Err(PromoCodeRejection::WrongScope)
+        );
+
+        let mut expired = promo("fixed", 5);
+        expired.valid_until = Some(now - chrono::Duration::hours(1));
+        assert_eq!(
+            expired.check_applicable(event_id, None, table_id, now),
+            Err(PromoCodeRejection::Expired)
+        );
+
+        assert!(promo("fixed", 5)
+            .check_applicable(event_id, None, table_id, now)
+            .is_ok());
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Push ticket counts over a period, see `push_repository::get_push_delivery_metrics`
+#[derive(Debug, Clone, FromRow)]
+pub struct PushDeliveryMetrics {
+    pub sent: i64,
+    pub delivered: i64,
+    pub failed: i64,
+    pub pending_receipt: i64,
+    pub receipt_expired: i64,
+    pub device_not_registered: i64,
+    pub registered_devices: i64,
+}
+
+/// Query for GET /admin/push/metrics
+#[derive(Debug, Deserialize)]
+pub struct PushMetricsParams {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushDeliveryMetricsResponse {
+    pub days: i64,
+    pub sent: i64,
+    pub delivered: i64,
+    pub failed: i64,
+    pub pending_receipt: i64,
+    pub receipt_expired: i64,
+    pub device_not_registered: i64,
+    pub registered_devices: i64,
+    /// delivered / (delivered + failed); None until a receipt has come back
+    pub delivery_rate: Option<f64>,
+}
+
+impl PushDeliveryMetricsResponse {
+    pub fn new(days: i64, metrics: PushDeliveryMetrics) -> Self {
+        let resolved = metrics.delivered + metrics.failed;
+        PushDeliveryMetricsResponse {
+            days,
+            sent: metrics.sent,
+            delivered: metrics.delivered,
+            failed: metrics.failed,
+            pending_receipt: metrics.pending_receipt,
+            receipt_expired: metrics.receipt_expired,
+            device_not_registered: metrics.device_not_registered,
+            registered_devices: metrics.registered_devices,
+            delivery_rate: (resolved > 0).then(|| metrics.delivered as f64 / resolved as f64),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Per-club refund rules applied when a reservation is cancelled.
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct CancellationPolicy {
+    pub club_id: Uuid,
+    pub full_refund_hours: i32,
+    pub partial_refund_percent: Decimal,
+}
+
+impl CancellationPolicy {
+    /// Policy used for clubs that never configured one.
+    pub fn default_for_club(club_id: Uuid) -> Self {
+        CancellationPolicy {
+            club_id,
+            full_refund_hours: 48,
+            partial_refund_percent: Decimal::new(50, 0),
+        }
+    }
+}
+
+/// Who (or what) triggered the cancellation. Drives the refund percentage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CancellationActor {
+    User,
+    Owner,
+    /// Platform admin acting from the back office
+    Admin,
+}
+
+impl CancellationActor {
+    pub fn refund_reason(&self) -> &'static str {
+        match self {
+            CancellationActor::User => "user_cancelled",
+            CancellationActor::Owner => "owner_cancelled",
+            CancellationActor::Admin => "admin_cancelled",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct Refund {
+    pub id: Uuid,
+    pub payment_id: Uuid,
+    pub reservation_id: Option<Uuid>,
+    pub payment_share_id: Option<Uuid>,
+    pub stripe_refund_id: Option<String>,
+    pub amount: Decimal,
+    pub status: String,
+    pub reason: String,
+    pub refund_percent: Option<Decimal>,
+    pub application_fee_refunded: bool,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for PUT /owner/club/cancellation-policy
+#[derive(Debug, Deserialize)]
+pub struct UpdateCancellationPolicyRequest {
+    pub full_refund_hours: i32,
+    pub partial_refund_percent: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancellationPolicyResponse {
+    pub full_refund_hours: i32,
+    pub partial_refund_percent: Decimal,
+}
+
+impl From<CancellationPolicy> for CancellationPolicyResponse {
+    fn from(policy: CancellationPolicy) -> Self {
+        CancellationPolicyResponse {
+            full_refund_hours: policy.full_refund_hours,
+            partial_refund_percent: policy.partial_refund_percent,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundResponse {
+    pub id: String,
+    pub payment_id: String,
+    pub payment_share_id: Option<String>,
+    pub amount: String,
+    pub status: String,
+    pub reason: String,
+    pub failure_reason: Option<String>,
+    pub created_at: String,
+}
+
+impl From<Refund> for RefundResponse {
+    fn from(refund: Refund) -> Self {
+        RefundResponse {
+            id: refund.id.to_string(),
+            payment_id: refund.payment_id.to_string(),
+            payment_share_id: refund.payment_share_id.map(|id| id.to_string()),
+            amount: format!("{:.2} €", refund.amount),
+            status: refund.status,
+            reason: refund.reason,
+            failure_reason: refund.failure_reason,
+            created_at: refund.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelReservationResponse {
+    pub reservation_id: String,
+    pub status: String,
+    pub refund_percent: Decimal,
+    pub total_refunded: String,
+    pub refunds: Vec<RefundResponse>,
+}
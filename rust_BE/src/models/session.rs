@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Account a refresh-token session belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionSubject {
+    User(Uuid),
+    ClubOwner(Uuid),
+}
+
+impl SessionSubject {
+    pub fn id(&self) -> Uuid {
+        match self {
+            SessionSubject::User(id) | SessionSubject::ClubOwner(id) => *id,
+        }
+    }
+
+    /// `Claims.role` of the access tokens issued for this subject
+    pub fn role(&self) -> &'static str {
+        match self {
+            SessionSubject::User(_) => "user",
+            SessionSubject::ClubOwner(_) => "club_owner",
+        }
+    }
+}
+
+/// One issued refresh token. Rows of the same login share `family_id`.
+#[derive(Clone, Debug, FromRow)]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub club_owner_id: Option<Uuid>,
+    pub family_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl UserSession {
+    pub fn subject(&self) -> Option<SessionSubject> {
+        match (self.user_id, self.club_owner_id) {
+            (Some(user_id), None) => Some(SessionSubject::User(user_id)),
+            (None, Some(owner_id)) => Some(SessionSubject::ClubOwner(owner_id)),
+            _ => None,
+        }
+    }
+}
+
+/// Body for POST /auth/refresh and POST /auth/logout
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenPairResponse {
+    pub token: String,
+    pub refresh_token: String,
+    /// Access token lifetime in seconds
+    pub expires_in: i64,
+}
@@ -0,0 +1,401 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashSet;
+
+/// How the organizer of a shared reservation splits the table cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitMode {
+    /// Every slot costs the same; guests buy open slots on the shared link
+    #[default]
+    Equal,
+    /// The organizer sets a fixed amount per named guest
+    Custom,
+    /// The cost is shared in proportion to each person's weight
+    Weighted,
+}
+
+impl SplitMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SplitMode::Equal => "equal",
+            SplitMode::Custom => "custom",
+            SplitMode::Weighted => "weighted",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "custom" => SplitMode::Custom,
+            "weighted" => SplitMode::Weighted,
+            _ => SplitMode::Equal,
+        }
+    }
+
+    /// Guests pay the organizer's named allocations instead of open slots
+    pub fn uses_allocations(&self) -> bool {
+        *self != SplitMode::Equal
+    }
+}
+
+/// Split chosen by the organizer when booking. Without one the table is
+/// split equally and the organizer pays one slot.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SplitPlanRequest {
+    #[serde(default)]
+    pub mode: SplitMode,
+    /// Slots the organizer pays for, 1 by default
+    #[serde(default)]
+    pub owner_slots: Option<i32>,
+    /// Organizer's weight in a weighted split, 1 by default
+    #[serde(default)]
+    pub owner_weight: Option<Decimal>,
+    /// Named allocations, for custom and weighted splits
+    #[serde(default)]
+    pub guests: Vec<SplitGuestRequest>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SplitGuestRequest {
+    pub name: String,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    /// People the allocation pays for, 1 by default
+    #[serde(default)]
+    pub slots: Option<i32>,
+    /// Custom split only
+    #[serde(default)]
+    pub amount: Option<Decimal>,
+    /// Weighted split only
+    #[serde(default)]
+    pub weight: Option<Decimal>,
+}
+
+/// A named allocation guests can pay from the shared link
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedShare {
+    pub name: String,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub slots: i32,
+    pub amount: Decimal,
+    pub weight: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitPlan {
+    pub mode: SplitMode,
+    pub owner_slots: i32,
+    /// What the organizer pays upfront, before any promo code
+    pub owner_amount: Decimal,
+    /// Price of one slot in an equal split
+    pub slot_price: Decimal,
+    pub allocations: Vec<PlannedShare>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitPlanError {
+    InvalidOwnerSlots,
+    MissingGuests,
+    UnexpectedGuests,
+    MissingGuestName,
+    InvalidGuestSlots,
+    TooManySlots,
+    InvalidAmount,
+    InvalidWeight,
+    DuplicatePhone,
+    NothingLeftForOwner,
+}
+
+impl SplitPlanError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            SplitPlanError::InvalidOwnerSlots => "Numero di posti dell'organizzatore non valido",
+            SplitPlanError::MissingGuests => "Indica gli ospiti e le loro quote",
+            SplitPlanError::UnexpectedGuests => {
+                "Con la divisione in parti uguali gli ospiti pagano dal link condiviso"
+            }
+            SplitPlanError::MissingGuestName => "Ogni quota deve avere il nome dell'ospite",
+            SplitPlanError::InvalidGuestSlots => "Numero di posti della quota non valido",
+            SplitPlanError::TooManySlots => "Le quote superano i posti del tavolo",
+            SplitPlanError::InvalidAmount => "Importo della quota non valido",
+            SplitPlanError::InvalidWeight => "Peso della quota non valido",
+            SplitPlanError::DuplicatePhone => "Lo stesso telefono compare in più quote",
+            SplitPlanError::NothingLeftForOwner => {
+                "Le quote degli ospiti coprono tutto il tavolo: l'organizzatore deve pagare una parte"
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SplitPlanError::InvalidOwnerSlots => "invalid_owner_slots",
+            SplitPlanError::MissingGuests => "missing_guests",
+            SplitPlanError::UnexpectedGuests => "unexpected_guests",
+            SplitPlanError::MissingGuestName => "missing_guest_name",
+            SplitPlanError::InvalidGuestSlots => "invalid_guest_slots",
+            SplitPlanError::TooManySlots => "too_many_slots",
+            SplitPlanError::InvalidAmount => "invalid_amount",
+            SplitPlanError::InvalidWeight => "invalid_weight",
+            SplitPlanError::DuplicatePhone => "duplicate_phone",
+            SplitPlanError::NothingLeftForOwner => "nothing_left_for_owner",
+        }
+    }
+}
+
+/// Slots of a shared reservation, summed over its shares
+#[derive(Debug, Clone, Copy, Default, FromRow)]
+pub struct SplitSlotCounts {
+    /// Slots the organizer paid for
+    pub owner_slots: i32,
+    /// Guest slots paid or with a checkout under way
+    pub claimed_slots: i32,
+    /// Guest slots of the named allocations
+    pub allocated_slots: i32,
+}
+
+impl SplitSlotCounts {
+    /// Guest slots on offer: the open slots of an equal split, the named
+    /// allocations otherwise
+    pub fn guest_slots(&self, mode: SplitMode, capacity: i32) -> i32 {
+        if mode.uses_allocations() {
+            self.allocated_slots
+        } else {
+            (capacity - self.owner_slots).max(0)
+        }
+    }
+}
+
+/// One slot of an equal split, rounded to the cent
+pub fn equal_slot_price(total_cost: Decimal, capacity: i32) -> Decimal {
+    (total_cost / Decimal::from(capacity.max(1))).round_dp(2)
+}
+
+/// Works out what the organizer pays upfront and the allocations left to
+/// guests. Amounts are rounded to the cent and the organizer absorbs the
+/// remainder, so everything always adds up to `total_cost`.
+pub fn plan_split(
+    total_cost: Decimal,
+    capacity: i32,
+    request: Option<&SplitPlanRequest>,
+) -> Result<SplitPlan, SplitPlanError> {
+    let default_request = SplitPlanRequest::default();
+    let request = request.unwrap_or(&default_request);
+    let owner_slots = request.owner_slots.unwrap_or(1);
+    if !(1..=capacity).contains(&owner_slots) {
+        return Err(SplitPlanError::InvalidOwnerSlots);
+    }
+    let slot_price = equal_slot_price(total_cost, capacity);
+
+    if request.mode == SplitMode::Equal {
+        if !request.guests.is_empty() {
+            return Err(SplitPlanError::UnexpectedGuests);
+        }
+        return Ok(SplitPlan {
+            mode: SplitMode::Equal,
+            owner_slots,
+            owner_amount: total_cost - slot_price * Decimal::from(capacity - owner_slots),
+            slot_price,
+            allocations: Vec::new(),
+        });
+    }
+
+    if request.guests.is_empty() {
+        return Err(SplitPlanError::MissingGuests);
+    }
+    let mut allocated_slots = owner_slots;
+    let mut phones = HashSet::new();
+    for guest in &request.guests {
+        if guest.name.trim().is_empty() {
+            return Err(SplitPlanError::MissingGuestName);
+        }
+        let slots = guest.slots.unwrap_or(1);
+        if slots < 1 {
+            return Err(SplitPlanError::InvalidGuestSlots);
+        }
+        allocated_slots += slots;
+        if let Some(phone) = guest.phone.as_deref().filter(|p| !p.trim().is_empty()) {
+            if !phones.insert(phone.trim()) {
+                return Err(SplitPlanError::DuplicatePhone);
+            }
+        }
+    }
+    if allocated_slots > capacity {
+        return Err(SplitPlanError::TooManySlots);
+    }
+
+    let amounts: Vec<Decimal> = match request.mode {
+        SplitMode::Custom => request
+            .guests
+            .iter()
+            .map(|guest| match guest.amount {
+                Some(amount) if amount > Decimal::ZERO && amount == amount.round_dp(2) => {
+                    Ok(amount)
+                }
+                _ => Err(SplitPlanError::InvalidAmount),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => {
+            let owner_weight = request.owner_weight.unwrap_or(Decimal::ONE);
+            let weights: Vec<Decimal> = request
+                .guests
+                .iter()
+                .map(|guest| guest.weight.unwrap_or(Decimal::ONE))
+                .collect();
+            if owner_weight <= Decimal::ZERO || weights.iter().any(|w| *w <= Decimal::ZERO) {
+                return Err(SplitPlanError::InvalidWeight);
+            }
+            let total_weight = owner_weight + weights.iter().copied().sum::<Decimal>();
+            weights
+                .iter()
+                .map(|weight| (total_cost * *weight / total_weight).round_dp(2))
+                .collect()
+        }
+    };
+
+    let owner_amount = total_cost - amounts.iter().copied().sum::<Decimal>();
+    if owner_amount <= Decimal::ZERO {
+        return Err(SplitPlanError::NothingLeftForOwner);
+    }
+
+    let allocations = request
+        .guests
+        .iter()
+        .zip(amounts)
+        .map(|(guest, amount)| PlannedShare {
+            name: guest.name.trim().to_string(),
+            phone: guest
+                .phone
+                .as_deref()
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(str::to_string),
+            email: guest.email.clone().filter(|e| !e.trim().is_empty()),
+            slots: guest.slots.unwrap_or(1),
+            amount,
+            weight: (request.mode == SplitMode::Weighted)
+                .then(|| guest.weight.unwrap_or(Decimal::ONE)),
+        })
+        .collect();
+
+    Ok(SplitPlan {
+        mode: request.mode,
+        owner_slots,
+        owner_amount,
+        slot_price,
+        allocations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guest(name: &str) -> SplitGuestRequest {
+        SplitGuestRequest {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn equal_split_lets_the_organizer_cover_slots() {
+        let total = Decimal::new(10000, 2);
+        let plan = plan_split(total, 3, None).unwrap();
+        assert_eq!(plan.slot_price, Decimal::new(3333, 2));
+        assert_eq!(plan.owner_amount, Decimal::new(3334, 2));
+        assert!(plan.allocations.is_empty());
+
+        let request = SplitPlanRequest {
+            owner_slots: Some(2),
+            ..Default::default()
+        };
+        let plan = plan_split(total, 3, Some(&request)).unwrap();
+        assert_eq!(plan.owner_amount, Decimal::new(6667, 2));
+
+        let request = SplitPlanRequest {
+            owner_slots: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(
+            plan_split(total, 3, Some(&request)),
+            Err(SplitPlanError::InvalidOwnerSlots)
+        );
+    }
+
+    #[test]
+    fn custom_split_leaves_the_rest_to_the_organizer() {
+        let request = SplitPlanRequest {
+            mode: SplitMode::Custom,
+            guests: vec![
+                SplitGuestRequest {
+                    amount: Some(Decimal::new(6000, 2)),
+                    slots: Some(2),
+                    ..guest("Giulia")
+                },
+                SplitGuestRequest {
+                    amount: Some(Decimal::new(2550, 2)),
+                    phone: Some(" +39333 ".into()),
+                    ..guest("Marco")
+                },
+            ],
+            ..Default::default()
+        };
+        let plan = plan_split(Decimal::new(20000, 2), 5, Some(&request)).unwrap();
+        assert_eq!(plan.owner_amount, Decimal::new(11450, 2));
+        assert_eq!(plan.allocations[0].slots, 2);
+        assert_eq!(plan.allocations[1].phone.as_deref(), Some("+39333"));
+
+        assert_eq!(
+            plan_split(Decimal::new(20000, 2), 3, Some(&request)),
+            Err(SplitPlanError::TooManySlots)
+        );
+        assert_eq!(
+            plan_split(Decimal::new(8550, 2), 5, Some(&request)),
+            Err(SplitPlanError::NothingLeftForOwner)
+        );
+        let mut missing_amount = request.clone();
+        missing_amount.guests[1].amount = None;
+        assert_eq!(
+            plan_split(Decimal::new(20000, 2), 5, Some(&missing_amount)),
+            Err(SplitPlanError::InvalidAmount)
+        );
+    }
+
+    #[test]
+    fn weighted_split_adds_up_to_the_total() {
+        let request = SplitPlanRequest {
+            mode: SplitMode::Weighted,
+            owner_weight: Some(Decimal::ONE),
+            guests: vec![
+                SplitGuestRequest {
+                    weight: Some(Decimal::TWO),
+                    ..guest("Anna")
+                },
+                guest("Luca"),
+            ],
+            ..Default::default()
+        };
+        let total = Decimal::new(10000, 2);
+        let plan = plan_split(total, 4, Some(&request)).unwrap();
+        assert_eq!(plan.allocations[0].amount, Decimal::new(5000, 2));
+        assert_eq!(plan.allocations[1].amount, Decimal::new(2500, 2));
+        assert_eq!(plan.owner_amount, Decimal::new(2500, 2));
+
+        let mut thirds = request.clone();
+        thirds.guests[0].weight = None;
+        let plan = plan_split(total, 4, Some(&thirds)).unwrap();
+        let guests: Decimal = plan.allocations.iter().map(|a| a.amount).sum();
+        assert_eq!(plan.owner_amount + guests, total);
+        assert_eq!(plan.owner_amount, Decimal::new(3334, 2));
+
+        thirds.guests[1].weight = Some(Decimal::ZERO);
+        assert_eq!(
+            plan_split(total, 4, Some(&thirds)),
+            Err(SplitPlanError::InvalidWeight)
+        );
+    }
+}
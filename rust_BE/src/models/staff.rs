@@ -0,0 +1,154 @@
+use super::club::ClubResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What a staff member may do on the owner's club.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaffRole {
+    /// Scans tickets and reservations at the door
+    Door,
+    /// Door access plus reservations, tables and the waitlist
+    Host,
+    /// Everything except Stripe onboarding and staff management
+    Manager,
+    /// Revenue, stats and payout status
+    Finance,
+}
+
+impl StaffRole {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "door" => Some(StaffRole::Door),
+            "host" => Some(StaffRole::Host),
+            "manager" => Some(StaffRole::Manager),
+            "finance" => Some(StaffRole::Finance),
+            _ => None,
+        }
+    }
+
+    /// Door staff get guest lists with masked phone numbers and emails
+    pub fn sees_guest_contacts(&self) -> bool {
+        !matches!(self, StaffRole::Door)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StaffRole::Door => "door",
+            StaffRole::Host => "host",
+            StaffRole::Manager => "manager",
+            StaffRole::Finance => "finance",
+        }
+    }
+
+    /// Value of `Claims.role` in a staff token, e.g. "staff_door"
+    pub fn claim_role(&self) -> String {
+        format!("staff_{}", self.as_str())
+    }
+
+    pub fn from_claim_role(role: &str) -> Option<Self> {
+        role.strip_prefix("staff_").and_then(StaffRole::parse)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct ClubStaff {
+    pub id: Uuid,
+    pub club_id: Uuid,
+    pub invited_by: Uuid,
+    pub email: String,
+    pub name: String,
+    pub phone_number: Option<String>,
+    pub role: String,
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+    pub status: String, // "invited" | "active" | "revoked"
+    pub invite_expires_at: Option<DateTime<Utc>>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for POST /owner/staff
+#[derive(Debug, Deserialize)]
+pub struct InviteStaffRequest {
+    pub email: String,
+    pub name: String,
+    pub phone_number: Option<String>,
+    pub role: String,
+}
+
+/// Body for PATCH /owner/staff/:id
+#[derive(Debug, Deserialize)]
+pub struct UpdateStaffRequest {
+    pub name: Option<String>,
+    pub role: Option<String>,
+}
+
+/// Body for POST /auth/staff/accept-invite
+#[derive(Debug, Deserialize)]
+pub struct AcceptStaffInviteRequest {
+    pub token: String,
+    pub password: String,
+}
+
+/// Body for POST /auth/staff/login
+#[derive(Debug, Deserialize)]
+pub struct StaffLoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaffMemberResponse {
+    pub id: String,
+    pub club_id: String,
+    pub email: String,
+    pub name: String,
+    pub phone_number: Option<String>,
+    pub role: String,
+    pub status: String,
+    pub invite_expires_at: Option<String>,
+    pub accepted_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+}
+
+impl From<ClubStaff> for StaffMemberResponse {
+    fn from(staff: ClubStaff) -> Self {
+        StaffMemberResponse {
+            id: staff.id.to_string(),
+            club_id: staff.club_id.to_string(),
+            email: staff.email,
+            name: staff.name,
+            phone_number: staff.phone_number,
+            role: staff.role,
+            status: staff.status,
+            invite_expires_at: staff.invite_expires_at.map(|at| at.to_rfc3339()),
+            accepted_at: staff.accepted_at.map(|at| at.to_rfc3339()),
+            revoked_at: staff.revoked_at.map(|at| at.to_rfc3339()),
+            created_at: staff.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Response to an invitation: the link is returned so the owner can share it
+/// themselves when no phone number was given.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaffInviteResponse {
+    pub staff: StaffMemberResponse,
+    pub invite_url: String,
+    pub email_sent: bool,
+    pub sms_sent: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaffAuthResponse {
+    pub staff: StaffMemberResponse,
+    pub club: Option<ClubResponse>,
+    pub token: String,
+}
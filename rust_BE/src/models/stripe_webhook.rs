@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A Stripe webhook as stored in the inbox on receipt
+#[derive(Debug, Clone, FromRow)]
+pub struct StripeWebhookEvent {
+    pub id: Uuid,
+    pub stripe_event_id: String,
+    pub event_type: String,
+    /// Request body exactly as Stripe sent it
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub available_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub received_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+    pub replay_count: i32,
+}
+
+/// `id` and `type` of a Stripe event, read before it is stored
+#[derive(Debug, PartialEq, Eq)]
+pub struct StripeEventEnvelope {
+    pub id: String,
+    pub event_type: String,
+}
+
+impl StripeEventEnvelope {
+    pub fn parse(body: &[u8]) -> Result<Self, String> {
+        let event: Value = serde_json::from_slice(body).map_err(|e| e.to_string())?;
+        let field = |name: &str| {
+            event[name]
+                .as_str()
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| format!("Stripe event without {name}"))
+        };
+        Ok(StripeEventEnvelope {
+            id: field("id")?,
+            event_type: field("type")?,
+        })
+    }
+}
+
+/// Query for GET /admin/stripe/webhook-events
+#[derive(Debug, Deserialize)]
+pub struct WebhookEventParams {
+    pub status: Option<String>,
+    pub event_type: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StripeWebhookEventResponse {
+    pub id: String,
+    pub stripe_event_id: String,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: i32,
+    pub replay_count: i32,
+    pub last_error: Option<String>,
+    pub available_at: String,
+    pub received_at: String,
+    pub processed_at: Option<String>,
+    pub dead_lettered_at: Option<String>,
+    /// Only filled in for a single event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Value>,
+}
+
+impl StripeWebhookEventResponse {
+    pub fn with_payload(event: StripeWebhookEvent) -> Self {
+        let payload = serde_json::from_str(&event.payload)
+            .unwrap_or_else(|_| Value::String(event.payload.clone()));
+        StripeWebhookEventResponse {
+            payload: Some(payload),
+            ..Self::from(event)
+        }
+    }
+}
+
+impl From<StripeWebhookEvent> for StripeWebhookEventResponse {
+    fn from(event: StripeWebhookEvent) -> Self {
+        StripeWebhookEventResponse {
+            id: event.id.to_string(),
+            stripe_event_id: event.stripe_event_id,
+            event_type: event.event_type,
+            status: event.status,
+            attempts: event.attempts,
+            replay_count: event.replay_count,
+            last_error: event.last_error,
+            available_at: event.available_at.to_rfc3339(),
+            received_at: event.received_at.to_rfc3339(),
+            processed_at: event.processed_at.map(|at| at.to_rfc3339()),
+            dead_lettered_at: event.dead_lettered_at.map(|at| at.to_rfc3339()),
+            payload: None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
+use super::split_payment::SplitPlanRequest;
 use super::ticket::EventSummary;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -124,6 +125,8 @@ pub struct TableReservation {
     pub is_manual: bool,
     pub manual_notes: Option<String>,
     pub payment_link_token: Option<String>,
+    /// `equal`, `custom` or `weighted`, see `SplitMode`
+    pub split_mode: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -135,6 +138,9 @@ pub struct CreateTableReservationRequest {
     pub contact_email: String,
     pub contact_phone: String,
     pub special_requests: Option<String>,
+    /// Birth date to save when the profile has none, for age-restricted events
+    #[serde(default)]
+    pub date_of_birth: Option<NaiveDate>,
 }
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
@@ -311,6 +317,7 @@ pub struct CreatePaymentIntentResponse {
     pub total_cost: Option<String>,
     pub per_person_amount: Option<String>,
     pub owner_share: Option<String>,
+    pub discount: Option<String>, // Promo code discount on the owner's share
 }
 
 // ============================================================================
@@ -332,6 +339,16 @@ pub struct ReservationPaymentShare {
     pub guest_name: Option<String>,
     pub guest_email: Option<String>,
     pub stripe_checkout_session_id: Option<String>,
+    /// Set while the payment is disputed: refunds skip the share
+    pub frozen_at: Option<DateTime<Utc>>,
+    /// People the share pays for
+    pub slots: i32,
+    /// Amount the organizer set for a named allocation
+    pub planned_amount: Option<Decimal>,
+    pub weight: Option<Decimal>,
+    /// Who paid a named allocation on someone else's behalf
+    pub paid_by_phone: Option<String>,
+    pub checkout_started_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -363,6 +380,14 @@ pub struct CreateSplitPaymentIntentRequest {
     pub contact_phone: String,
     pub special_requests: Option<String>,
     pub idempotency_key: Option<Uuid>,
+    #[serde(default)]
+    pub promo_code: Option<String>,
+    /// Birth date to save when the profile has none, for age-restricted events
+    #[serde(default)]
+    pub date_of_birth: Option<NaiveDate>,
+    /// How the table is split; an equal split by default
+    #[serde(default)]
+    pub split: Option<SplitPlanRequest>,
 }
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
@@ -376,6 +401,11 @@ pub struct CreateSplitReservationRequest {
     pub contact_phone: String,
     pub special_requests: Option<String>,
     pub idempotency_key: Option<Uuid>,
+    #[serde(default)]
+    pub promo_code: Option<String>,
+    /// Must be the split the PaymentIntent was created with
+    #[serde(default)]
+    pub split: Option<SplitPlanRequest>,
 }
 
 #[derive(Debug, Serialize)]
@@ -389,6 +419,7 @@ pub struct PaymentShareResponse {
     pub is_owner: bool,
     pub guest_name: Option<String>,
     pub guest_email: Option<String>,
+    pub slots: i32,
 }
 
 impl From<ReservationPaymentShare> for PaymentShareResponse {
@@ -402,6 +433,7 @@ impl From<ReservationPaymentShare> for PaymentShareResponse {
             is_owner: share.is_owner,
             guest_name: share.guest_name,
             guest_email: share.guest_email,
+            slots: share.slots,
         }
     }
 }
@@ -417,12 +449,58 @@ pub struct CreateSplitReservationResponse {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentLinkPreviewResponse {
+    /// Price of the selected slots or allocation, after any promo code
     pub amount: String,
     pub event_name: String,
     pub table_name: String,
     pub status: String, // "open" | "full"
     pub slots_filled: i32,
     pub slots_total: i32,
+    pub discount: Option<String>,
+    pub promo_code_error: Option<String>,
+    /// Minimum age of the event; guests must then give their birth date
+    pub min_age: Option<i32>,
+    pub split_mode: String,
+    /// Named allocations of a custom or weighted split
+    pub allocations: Vec<PaymentLinkAllocation>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentLinkAllocation {
+    pub share_id: String,
+    pub guest_name: Option<String>,
+    pub amount: String,
+    pub slots: i32,
+    /// "open" | "in_progress" | "paid"
+    pub status: String,
+}
+
+impl From<&ReservationPaymentShare> for PaymentLinkAllocation {
+    fn from(share: &ReservationPaymentShare) -> Self {
+        PaymentLinkAllocation {
+            share_id: share.id.to_string(),
+            guest_name: share.guest_name.clone(),
+            amount: format!("{:.2} €", share.planned_amount.unwrap_or(share.amount)),
+            slots: share.slots,
+            status: match share.status.as_str() {
+                "reserved" => "open",
+                "checkout_pending" => "in_progress",
+                other => other,
+            }
+            .to_string(),
+        }
+    }
+}
+
+/// Query for GET /payment-links/:token — preview the share with a promo code
+#[derive(Debug, Default, Deserialize)]
+pub struct PaymentLinkPreviewParams {
+    pub promo_code: Option<String>,
+    /// Open slots to price, equal split only
+    pub slots: Option<i32>,
+    /// Allocation to price, custom and weighted splits
+    pub share_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -430,6 +508,17 @@ pub struct CreateCheckoutRequest {
     pub name: String,
     pub phone: String,
     pub email: Option<String>,
+    #[serde(default)]
+    pub promo_code: Option<String>,
+    /// Required when the event has a minimum age
+    #[serde(default)]
+    pub date_of_birth: Option<NaiveDate>,
+    /// Open slots to buy, for the guest and friends (equal split, 1 by default)
+    #[serde(default)]
+    pub slots: Option<i32>,
+    /// Named allocation to pay, one's own or a friend's (custom and weighted splits)
+    #[serde(default)]
+    pub share_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize)]
@@ -449,4 +538,5 @@ pub struct ReservationPaymentStatusResponse {
     pub share_link: Option<String>,
     pub slots_filled: i32,
     pub slots_total: i32,
+    pub split_mode: String,
 }
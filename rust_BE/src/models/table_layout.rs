@@ -0,0 +1,358 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// ============================================================================
+// Table layout templates (club-level snapshots of an event's tables)
+// ============================================================================
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct TableLayoutTemplate {
+    pub id: Uuid,
+    pub club_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub tour_provider: Option<String>,
+    pub marzipano_config: Option<JsonValue>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct TableLayoutTemplateTable {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    pub name: String,
+    pub zone: Option<String>,
+    pub capacity: i32,
+    pub min_spend: Decimal,
+    pub available: bool,
+    pub location_description: Option<String>,
+    pub features: Option<Vec<String>>,
+    pub marzipano_position: Option<JsonValue>,
+    pub area_id: Option<Uuid>,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct TableLayoutTemplateImage {
+    pub id: Uuid,
+    pub template_table_id: Uuid,
+    pub url: String,
+    pub display_order: i32,
+    pub alt_text: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A template with its tables and their images, in display order.
+#[derive(Clone, Debug)]
+pub struct TableLayoutTemplateDetails {
+    pub template: TableLayoutTemplate,
+    pub tables: Vec<TableLayoutTemplateTable>,
+    pub images: Vec<TableLayoutTemplateImage>,
+}
+
+/// Body for POST /owner/table-layouts: snapshot the tables of `event_id`.
+#[derive(Debug, Deserialize)]
+pub struct CreateTableLayoutTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub event_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TableLayoutTemplateImageInput {
+    pub url: String,
+    pub display_order: Option<i32>,
+    pub alt_text: Option<String>,
+}
+
+/// One table of an edited template. Entries with an `id` update that template
+/// table (keeping its images unless `images` is given); entries without one
+/// are added.
+#[derive(Debug, Deserialize)]
+pub struct TableLayoutTemplateTableInput {
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub zone: Option<String>,
+    pub capacity: i32,
+    pub min_spend: f64,
+    pub available: Option<bool>,
+    pub location_description: Option<String>,
+    pub features: Option<Vec<String>>,
+    pub marzipano_position: Option<JsonValue>,
+    pub area_id: Option<Uuid>,
+    pub images: Option<Vec<TableLayoutTemplateImageInput>>,
+}
+
+/// Body for PATCH /owner/table-layouts/:id. When `tables` is given it replaces
+/// the template's tables: listed ids are kept, missing ones are removed.
+#[derive(Debug, Deserialize)]
+pub struct UpdateTableLayoutTemplateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub marzipano_config: Option<JsonValue>,
+    pub tables: Option<Vec<TableLayoutTemplateTableInput>>,
+}
+
+/// Per-event change to one template table when applying a layout.
+#[derive(Debug, Deserialize)]
+pub struct TableLayoutOverride {
+    pub template_table_id: Uuid,
+    pub min_spend: Option<f64>,
+    pub available: Option<bool>,
+}
+
+/// Body for POST /owner/events/:event_id/table-layout
+#[derive(Debug, Deserialize)]
+pub struct ApplyTableLayoutRequest {
+    pub template_id: Uuid,
+    #[serde(default)]
+    pub overrides: Vec<TableLayoutOverride>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TableLayoutRejection {
+    /// An override points at a table that is not part of the template
+    UnknownTemplateTable(Uuid),
+    DuplicateOverride(Uuid),
+    InvalidMinSpend(Uuid),
+}
+
+impl TableLayoutRejection {
+    pub fn message(&self) -> String {
+        match self {
+            TableLayoutRejection::UnknownTemplateTable(id) => {
+                format!("Il tavolo {id} non fa parte del layout")
+            }
+            TableLayoutRejection::DuplicateOverride(id) => {
+                format!("Il tavolo {id} è modificato più volte")
+            }
+            TableLayoutRejection::InvalidMinSpend(id) => {
+                format!("Spesa minima non valida per il tavolo {id}")
+            }
+        }
+    }
+}
+
+/// A table about to be created on the event, after overrides.
+#[derive(Debug, PartialEq)]
+pub struct PlannedTable {
+    pub template_table_id: Uuid,
+    pub min_spend: Decimal,
+    pub total_cost: Decimal,
+    pub available: bool,
+}
+
+/// Resolve the price and availability of every template table for one event.
+pub fn plan_table_layout(
+    tables: &[TableLayoutTemplateTable],
+    overrides: &[TableLayoutOverride],
+) -> Result<Vec<PlannedTable>, TableLayoutRejection> {
+    let mut by_table: HashMap<Uuid, &TableLayoutOverride> = HashMap::new();
+    for entry in overrides {
+        if !tables.iter().any(|t| t.id == entry.template_table_id) {
+            return Err(TableLayoutRejection::UnknownTemplateTable(
+                entry.template_table_id,
+            ));
+        }
+        if by_table.insert(entry.template_table_id, entry).is_some() {
+            return Err(TableLayoutRejection::DuplicateOverride(
+                entry.template_table_id,
+            ));
+        }
+    }
+
+    tables
+        .iter()
+        .map(|table| {
+            let entry = by_table.get(&table.id);
+            let min_spend = match entry.and_then(|e| e.min_spend) {
+                Some(value) => Decimal::from_f64_retain(value)
+                    .filter(|d| !d.is_sign_negative())
+                    .map(|d| d.round_dp(2))
+                    .ok_or(TableLayoutRejection::InvalidMinSpend(table.id))?,
+                None => table.min_spend,
+            };
+            Ok(PlannedTable {
+                template_table_id: table.id,
+                min_spend,
+                total_cost: min_spend * Decimal::from(table.capacity),
+                available: entry.and_then(|e| e.available).unwrap_or(table.available),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableLayoutTemplateImageResponse {
+    pub url: String,
+    pub display_order: i32,
+    pub alt_text: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableLayoutTemplateTableResponse {
+    pub id: String,
+    pub name: String,
+    pub zone: Option<String>,
+    pub capacity: i32,
+    pub min_spend: String, // Formatted as "X.XX €"
+    pub available: bool,
+    pub location_description: Option<String>,
+    pub features: Option<Vec<String>>,
+    pub marzipano_position: Option<JsonValue>,
+    pub area_id: Option<String>,
+    pub images: Vec<TableLayoutTemplateImageResponse>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableLayoutTemplateResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub tour_provider: Option<String>,
+    pub marzipano_config: Option<JsonValue>,
+    pub tables: Vec<TableLayoutTemplateTableResponse>,
+    pub updated_at: String,
+}
+
+impl From<TableLayoutTemplateDetails> for TableLayoutTemplateResponse {
+    fn from(details: TableLayoutTemplateDetails) -> Self {
+        let mut images: HashMap<Uuid, Vec<TableLayoutTemplateImageResponse>> = HashMap::new();
+        for image in details.images {
+            images.entry(image.template_table_id).or_default().push(
+                TableLayoutTemplateImageResponse {
+                    url: image.url,
+                    display_order: image.display_order,
+                    alt_text: image.alt_text,
+                },
+            );
+        }
+
+        let tables = details
+            .tables
+            .into_iter()
+            .map(|table| TableLayoutTemplateTableResponse {
+                id: table.id.to_string(),
+                images: images.remove(&table.id).unwrap_or_default(),
+                name: table.name,
+                zone: table.zone,
+                capacity: table.capacity,
+                min_spend: format!("{:.2} €", table.min_spend),
+                available: table.available,
+                location_description: table.location_description,
+                features: table.features,
+                marzipano_position: table.marzipano_position,
+                area_id: table.area_id.map(|id| id.to_string()),
+            })
+            .collect();
+
+        TableLayoutTemplateResponse {
+            id: details.template.id.to_string(),
+            name: details.template.name,
+            description: details.template.description,
+            tour_provider: details.template.tour_provider,
+            marzipano_config: details.template.marzipano_config,
+            tables,
+            updated_at: details.template.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template_table(capacity: i32, min_spend: i64) -> TableLayoutTemplateTable {
+        TableLayoutTemplateTable {
+            id: Uuid::new_v4(),
+            template_id: Uuid::new_v4(),
+            name: "Tavolo VIP".to_string(),
+            zone: None,
+            capacity,
+            min_spend: Decimal::from(min_spend),
+            available: true,
+            location_description: None,
+            features: None,
+            marzipano_position: None,
+            area_id: None,
+            sort_order: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn overrides_change_price_and_availability_of_single_tables() {
+        let vip = template_table(8, 50);
+        let floor = template_table(6, 30);
+        let overrides = vec![TableLayoutOverride {
+            template_table_id: vip.id,
+            min_spend: Some(65.5),
+            available: Some(false),
+        }];
+
+        let planned = plan_table_layout(&[vip.clone(), floor.clone()], &overrides).unwrap();
+
+        assert_eq!(planned[0].min_spend, Decimal::new(6550, 2));
+        assert_eq!(planned[0].total_cost, Decimal::new(52400, 2));
+        assert!(!planned[0].available);
+        assert_eq!(planned[1].min_spend, Decimal::from(30));
+        assert_eq!(planned[1].total_cost, Decimal::from(180));
+        assert!(planned[1].available);
+    }
+
+    #[test]
+    fn overrides_must_target_template_tables_once_with_a_valid_price() {
+        let vip = template_table(8, 50);
+        let stranger = Uuid::new_v4();
+        let tables = [vip.clone()];
+
+        let unknown = [TableLayoutOverride {
+            template_table_id: stranger,
+            min_spend: None,
+            available: Some(false),
+        }];
+        assert_eq!(
+            plan_table_layout(&tables, &unknown),
+            Err(TableLayoutRejection::UnknownTemplateTable(stranger))
+        );
+
+        let twice = [
+            TableLayoutOverride {
+                template_table_id: vip.id,
+                min_spend: Some(40.0),
+                available: None,
+            },
+            TableLayoutOverride {
+                template_table_id: vip.id,
+                min_spend: None,
+                available: Some(false),
+            },
+        ];
+        assert_eq!(
+            plan_table_layout(&tables, &twice),
+            Err(TableLayoutRejection::DuplicateOverride(vip.id))
+        );
+
+        let negative = [TableLayoutOverride {
+            template_table_id: vip.id,
+            min_spend: Some(-1.0),
+            available: None,
+        }];
+        assert_eq!(
+            plan_table_layout(&tables, &negative),
+            Err(TableLayoutRejection::InvalidMinSpend(vip.id))
+        );
+    }
+}
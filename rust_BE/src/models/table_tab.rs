@@ -0,0 +1,291 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::ticket_tier::TICKET_CHECKOUT_HOLD_MINUTES;
+
+/// Categories a consumption line can be recorded under
+pub const TAB_LINE_CATEGORIES: [&str; 4] = ["bottle", "drink", "service", "other"];
+
+/// Lifetime of a tab's Stripe Checkout page, the same as a ticket checkout's:
+/// Stripe's 30-minute minimum plus a margin for clock skew
+pub const TAB_CHECKOUT_MINUTES: i64 = TICKET_CHECKOUT_HOLD_MINUTES;
+
+/// Minimum-spend tab of a checked-in reservation
+#[derive(Clone, Debug, FromRow)]
+pub struct TableTab {
+    pub id: Uuid,
+    pub reservation_id: Uuid,
+    pub table_id: Uuid,
+    pub event_id: Uuid,
+    pub club_id: Uuid,
+    /// open, settling, closed
+    pub status: String,
+    pub minimum_spend: Decimal,
+    pub opened_by: Uuid,
+    pub opened_at: DateTime<Utc>,
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub final_spend: Option<Decimal>,
+    #[sqlx(default)]
+    pub table_name: Option<String>,
+    #[sqlx(default)]
+    pub contact_name: Option<String>,
+    /// Non-voided lines
+    #[sqlx(default)]
+    pub consumed: Decimal,
+    /// Paid for the reservation before the night
+    #[sqlx(default)]
+    pub prepaid: Decimal,
+    /// Paid settlements of the tab
+    #[sqlx(default)]
+    pub settled: Decimal,
+}
+
+impl TableTab {
+    pub fn totals(&self) -> TabTotals {
+        TabTotals::new(
+            self.minimum_spend,
+            self.consumed,
+            self.prepaid,
+            self.settled,
+        )
+    }
+}
+
+/// Where a tab stands against its minimum spend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabTotals {
+    pub minimum_spend: Decimal,
+    pub consumed: Decimal,
+    /// Still to consume before the minimum is reached
+    pub remaining_to_minimum: Decimal,
+    /// What the table is charged for the night: the minimum or more
+    pub final_spend: Decimal,
+    pub prepaid: Decimal,
+    pub settled: Decimal,
+    /// Left to pay at close
+    pub balance_due: Decimal,
+}
+
+impl TabTotals {
+    pub fn new(
+        minimum_spend: Decimal,
+        consumed: Decimal,
+        prepaid: Decimal,
+        settled: Decimal,
+    ) -> Self {
+        let final_spend = consumed.max(minimum_spend);
+        Self {
+            minimum_spend,
+            consumed,
+            remaining_to_minimum: (minimum_spend - consumed).max(Decimal::ZERO),
+            final_spend,
+            prepaid,
+            settled,
+            balance_due: (final_spend - prepaid - settled).max(Decimal::ZERO),
+        }
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct TabLine {
+    pub id: Uuid,
+    pub category: String,
+    pub description: String,
+    pub quantity: i32,
+    pub unit_price: Decimal,
+    pub amount: Decimal,
+    pub added_by: Uuid,
+    pub voided_at: Option<DateTime<Utc>>,
+    pub voided_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct TabSettlement {
+    pub id: Uuid,
+    pub tab_id: Uuid,
+    /// cash, stripe
+    pub method: String,
+    pub amount: Decimal,
+    /// pending, paid, expired
+    pub status: String,
+    pub stripe_checkout_session_id: Option<String>,
+    pub stripe_payment_intent_id: Option<String>,
+    pub payment_id: Option<Uuid>,
+    pub recorded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+/// Body for POST /owner/tabs/:id/lines
+#[derive(Debug, Deserialize)]
+pub struct AddTabLineRequest {
+    pub category: String,
+    pub description: String,
+    pub quantity: Option<i32>,
+    pub unit_price: Decimal,
+}
+
+/// Body for POST /owner/tabs/:id/settle
+#[derive(Debug, Deserialize)]
+pub struct SettleTabRequest {
+    /// `cash` or `stripe`
+    pub method: String,
+    /// Defaults to the balance due; less leaves the rest open
+    pub amount: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabLineResponse {
+    pub id: String,
+    pub category: String,
+    pub description: String,
+    pub quantity: i32,
+    pub unit_price: Decimal,
+    pub amount: Decimal,
+    pub added_by: String,
+    pub voided: bool,
+    pub voided_by: Option<String>,
+    pub created_at: String,
+}
+
+impl From<TabLine> for TabLineResponse {
+    fn from(line: TabLine) -> Self {
+        Self {
+            id: line.id.to_string(),
+            category: line.category,
+            description: line.description,
+            quantity: line.quantity,
+            unit_price: line.unit_price,
+            amount: line.amount,
+            added_by: line.added_by.to_string(),
+            voided: line.voided_at.is_some(),
+            voided_by: line.voided_by.map(|id| id.to_string()),
+            created_at: line.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabSettlementResponse {
+    pub id: String,
+    pub method: String,
+    pub amount: Decimal,
+    pub status: String,
+    pub stripe_payment_intent_id: Option<String>,
+    pub recorded_by: String,
+    pub created_at: String,
+    pub paid_at: Option<String>,
+}
+
+impl From<TabSettlement> for TabSettlementResponse {
+    fn from(settlement: TabSettlement) -> Self {
+        Self {
+            id: settlement.id.to_string(),
+            method: settlement.method,
+            amount: settlement.amount,
+            status: settlement.status,
+            stripe_payment_intent_id: settlement.stripe_payment_intent_id,
+            recorded_by: settlement.recorded_by.to_string(),
+            created_at: settlement.created_at.to_rfc3339(),
+            paid_at: settlement.paid_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableTabResponse {
+    pub id: String,
+    pub reservation_id: String,
+    pub table_id: String,
+    pub table_name: Option<String>,
+    pub contact_name: Option<String>,
+    pub event_id: String,
+    pub status: String,
+    pub opened_by: String,
+    pub opened_at: String,
+    pub closed_by: Option<String>,
+    pub closed_at: Option<String>,
+    #[serde(flatten)]
+    pub totals: TabTotals,
+    /// Only on the single-tab view
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<Vec<TabLineResponse>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settlements: Option<Vec<TabSettlementResponse>>,
+}
+
+impl From<TableTab> for TableTabResponse {
+    fn from(tab: TableTab) -> Self {
+        let mut totals = tab.totals();
+        // A closed tab reports the spend it was closed with
+        if let Some(final_spend) = tab.final_spend {
+            totals.final_spend = final_spend;
+        }
+        Self {
+            id: tab.id.to_string(),
+            reservation_id: tab.reservation_id.to_string(),
+            table_id: tab.table_id.to_string(),
+            table_name: tab.table_name,
+            contact_name: tab.contact_name,
+            event_id: tab.event_id.to_string(),
+            status: tab.status,
+            opened_by: tab.opened_by.to_string(),
+            opened_at: tab.opened_at.to_rfc3339(),
+            closed_by: tab.closed_by.map(|id| id.to_string()),
+            closed_at: tab.closed_at.map(|at| at.to_rfc3339()),
+            totals,
+            lines: None,
+            settlements: None,
+        }
+    }
+}
+
+/// Response of POST /owner/tabs/:id/settle
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleTabResponse {
+    pub tab: TableTabResponse,
+    /// Stripe Checkout page for the guest to pay on, for `stripe` settlements
+    pub checkout_url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn euros(amount: i64) -> Decimal {
+        Decimal::from(amount)
+    }
+
+    #[test]
+    fn tab_below_minimum_pays_the_shortfall_after_prepayment() {
+        let totals = TabTotals::new(euros(600), euros(450), euros(300), Decimal::ZERO);
+        assert_eq!(totals.remaining_to_minimum, euros(150));
+        assert_eq!(totals.final_spend, euros(600));
+        assert_eq!(totals.balance_due, euros(300));
+
+        let settled = TabTotals::new(euros(600), euros(450), euros(300), euros(300));
+        assert_eq!(settled.balance_due, Decimal::ZERO);
+    }
+
+    #[test]
+    fn tab_above_minimum_pays_everything_consumed_beyond_the_prepayment() {
+        let totals = TabTotals::new(euros(600), euros(820), euros(600), euros(100));
+        assert_eq!(totals.remaining_to_minimum, Decimal::ZERO);
+        assert_eq!(totals.final_spend, euros(820));
+        assert_eq!(totals.balance_due, euros(120));
+
+        // Prepaid more than the night cost: nothing is owed, nothing is refunded here
+        let overpaid = TabTotals::new(euros(300), euros(100), euros(400), Decimal::ZERO);
+        assert_eq!(overpaid.balance_due, Decimal::ZERO);
+    }
+}
@@ -0,0 +1,324 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Most tickets a single checkout can buy, whatever the tier's per-user limit.
+pub const MAX_TICKETS_PER_ORDER: i32 = 10;
+
+/// How long a checkout holds its tickets. Stripe sessions must live at least
+/// 30 minutes; the margin absorbs clock skew with Stripe.
+pub const TICKET_CHECKOUT_HOLD_MINUTES: i64 = 32;
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct TicketTier {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub quantity_total: i32,
+    pub quantity_sold: i32,
+    pub quantity_held: i32,
+    pub sales_start_at: Option<DateTime<Utc>>,
+    pub sales_end_at: Option<DateTime<Utc>>,
+    pub max_per_user: Option<i32>,
+    pub sort_order: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Why a tier cannot be bought right now.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TicketPurchaseRejection {
+    InvalidQuantity,
+    Inactive,
+    NotYetOnSale,
+    SalesEnded,
+    SoldOut,
+    UserLimitReached,
+}
+
+impl TicketPurchaseRejection {
+    pub fn message(&self) -> &'static str {
+        match self {
+            TicketPurchaseRejection::InvalidQuantity => "Quantità di biglietti non valida",
+            TicketPurchaseRejection::Inactive => "Biglietti non disponibili",
+            TicketPurchaseRejection::NotYetOnSale => "La vendita non è ancora iniziata",
+            TicketPurchaseRejection::SalesEnded => "La vendita è terminata",
+            TicketPurchaseRejection::SoldOut => "Biglietti esauriti",
+            TicketPurchaseRejection::UserLimitReached => {
+                "Hai raggiunto il numero massimo di biglietti per questa tipologia"
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TicketPurchaseRejection::InvalidQuantity => "invalid_quantity",
+            TicketPurchaseRejection::Inactive => "inactive",
+            TicketPurchaseRejection::NotYetOnSale => "not_yet_on_sale",
+            TicketPurchaseRejection::SalesEnded => "sales_ended",
+            TicketPurchaseRejection::SoldOut => "sold_out",
+            TicketPurchaseRejection::UserLimitReached => "user_limit_reached",
+        }
+    }
+}
+
+impl TicketTier {
+    /// Tickets still available: not sold and not held by an open checkout.
+    pub fn available(&self) -> i32 {
+        (self.quantity_total - self.quantity_sold - self.quantity_held).max(0)
+    }
+
+    /// Static checks (status, sale window, quantity). Without a `sales_end_at`
+    /// sales close when the event starts. Inventory and the per-user limit are
+    /// enforced again when the tickets are held.
+    pub fn check_purchasable(
+        &self,
+        quantity: i32,
+        now: DateTime<Utc>,
+        event_start: Option<DateTime<Utc>>,
+    ) -> Result<(), TicketPurchaseRejection> {
+        if !(1..=MAX_TICKETS_PER_ORDER).contains(&quantity) {
+            return Err(TicketPurchaseRejection::InvalidQuantity);
+        }
+        if !self.is_active {
+            return Err(TicketPurchaseRejection::Inactive);
+        }
+        if self.sales_start_at.is_some_and(|start| now < start) {
+            return Err(TicketPurchaseRejection::NotYetOnSale);
+        }
+        if self
+            .sales_end_at
+            .or(event_start)
+            .is_some_and(|end| now >= end)
+        {
+            return Err(TicketPurchaseRejection::SalesEnded);
+        }
+        if self.available() < quantity {
+            return Err(TicketPurchaseRejection::SoldOut);
+        }
+        if self.max_per_user.is_some_and(|max| quantity > max) {
+            return Err(TicketPurchaseRejection::UserLimitReached);
+        }
+        Ok(())
+    }
+
+    /// `on_sale`, `scheduled`, `ended`, `sold_out` or `inactive`
+    pub fn sale_status(&self, now: DateTime<Utc>) -> &'static str {
+        if !self.is_active {
+            "inactive"
+        } else if self.sales_start_at.is_some_and(|start| now < start) {
+            "scheduled"
+        } else if self.sales_end_at.is_some_and(|end| now >= end) {
+            "ended"
+        } else if self.available() == 0 {
+            "sold_out"
+        } else {
+            "on_sale"
+        }
+    }
+}
+
+/// Body for POST /owner/events/:event_id/ticket-tiers
+#[derive(Debug, Deserialize)]
+pub struct CreateTicketTierRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub quantity_total: i32,
+    pub sales_start_at: Option<DateTime<Utc>>,
+    pub sales_end_at: Option<DateTime<Utc>>,
+    pub max_per_user: Option<i32>,
+    pub sort_order: Option<i32>,
+}
+
+/// Body for PATCH /owner/ticket-tiers/:tier_id
+#[derive(Debug, Deserialize)]
+pub struct UpdateTicketTierRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub price: Option<Decimal>,
+    pub quantity_total: Option<i32>,
+    pub sales_start_at: Option<DateTime<Utc>>,
+    pub sales_end_at: Option<DateTime<Utc>>,
+    pub max_per_user: Option<i32>,
+    pub sort_order: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketTierResponse {
+    pub id: String,
+    pub event_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub price: Decimal,
+    pub quantity_total: i32,
+    pub quantity_sold: i32,
+    pub available: i32,
+    pub sales_start_at: Option<String>,
+    pub sales_end_at: Option<String>,
+    pub max_per_user: Option<i32>,
+    pub sort_order: i32,
+    pub is_active: bool,
+    pub sale_status: String,
+}
+
+impl From<TicketTier> for TicketTierResponse {
+    fn from(tier: TicketTier) -> Self {
+        TicketTierResponse {
+            sale_status: tier.sale_status(Utc::now()).to_string(),
+            available: tier.available(),
+            id: tier.id.to_string(),
+            event_id: tier.event_id.to_string(),
+            name: tier.name,
+            description: tier.description,
+            price: tier.price,
+            quantity_total: tier.quantity_total,
+            quantity_sold: tier.quantity_sold,
+            sales_start_at: tier.sales_start_at.map(|at| at.to_rfc3339()),
+            sales_end_at: tier.sales_end_at.map(|at| at.to_rfc3339()),
+            max_per_user: tier.max_per_user,
+            sort_order: tier.sort_order,
+            is_active: tier.is_active,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct TicketOrder {
+    pub id: Uuid,
+    pub tier_id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i32,
+    pub unit_price: Decimal,
+    pub total_amount: Decimal,
+    pub application_fee_amount: Option<Decimal>,
+    pub status: String, // "pending" | "paid" | "expired"
+    pub stripe_checkout_session_id: Option<String>,
+    pub stripe_payment_intent_id: Option<String>,
+    pub payment_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for POST /ticket-tiers/:tier_id/checkout
+#[derive(Debug, Deserialize)]
+pub struct CreateTicketCheckoutRequest {
+    pub quantity: i32,
+    /// Birth date to save when the profile has none, for age-restricted events
+    #[serde(default)]
+    pub date_of_birth: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketCheckoutResponse {
+    pub order_id: String,
+    pub checkout_url: String,
+    pub expires_at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn tier(total: i32, sold: i32, held: i32) -> TicketTier {
+        TicketTier {
+            id: Uuid::new_v4(),
+            event_id: Uuid::new_v4(),
+            name: "Early bird".to_string(),
+            description: None,
+            price: Decimal::new(1500, 2),
+            quantity_total: total,
+            quantity_sold: sold,
+            quantity_held: held,
+            sales_start_at: None,
+            sales_end_at: None,
+            max_per_user: None,
+            sort_order: 0,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn held_tickets_count_against_inventory() {
+        let now = Utc::now();
+        let nearly_gone = tier(100, 95, 3);
+
+        assert_eq!(nearly_gone.available(), 2);
+        assert!(nearly_gone.check_purchasable(2, now, None).is_ok());
+        assert_eq!(
+            nearly_gone.check_purchasable(3, now, None),
+            Err(TicketPurchaseRejection::SoldOut)
+        );
+        assert_eq!(tier(10, 7, 3).sale_status(now), "sold_out");
+    }
+
+    #[test]
+    fn sale_window_quantity_and_limit_are_checked() {
+        let now = Utc::now();
+
+        let mut scheduled = tier(100, 0, 0);
+        scheduled.sales_start_at = Some(now + Duration::hours(1));
+        assert_eq!(
+            scheduled.check_purchasable(1, now, None),
+            Err(TicketPurchaseRejection::NotYetOnSale)
+        );
+        assert_eq!(scheduled.sale_status(now), "scheduled");
+
+        let mut ended = tier(100, 0, 0);
+        ended.sales_end_at = Some(now - Duration::minutes(1));
+        assert_eq!(
+            ended.check_purchasable(1, now, None),
+            Err(TicketPurchaseRejection::SalesEnded)
+        );
+
+        let mut limited = tier(100, 0, 0);
+        limited.max_per_user = Some(2);
+        assert_eq!(
+            limited.check_purchasable(3, now, None),
+            Err(TicketPurchaseRejection::UserLimitReached)
+        );
+        assert_eq!(
+            limited.check_purchasable(0, now, None),
+            Err(TicketPurchaseRejection::InvalidQuantity)
+        );
+        assert_eq!(
+            limited.check_purchasable(MAX_TICKETS_PER_ORDER + 1, now, None),
+            Err(TicketPurchaseRejection::InvalidQuantity)
+        );
+    }
+
+    #[test]
+    fn sales_close_at_event_start_without_an_end() {
+        let now = Utc::now();
+        let open_ended = tier(100, 0, 0);
+
+        assert!(open_ended
+            .check_purchasable(1, now, Some(now + Duration::hours(2)))
+            .is_ok());
+        assert_eq!(
+            open_ended.check_purchasable(1, now, Some(now - Duration::days(1))),
+            Err(TicketPurchaseRejection::SalesEnded)
+        );
+
+        // An explicit end wins over the event start
+        let mut late_sales = tier(100, 0, 0);
+        late_sales.sales_end_at = Some(now + Duration::hours(1));
+        assert!(late_sales
+            .check_purchasable(1, now, Some(now - Duration::hours(1)))
+            .is_ok());
+    }
+}
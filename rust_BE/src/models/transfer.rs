@@ -0,0 +1,426 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::User;
+
+/// Wrong claim codes after which an email transfer can no longer be accepted
+pub const MAX_FAILED_CLAIM_ATTEMPTS: i32 = 5;
+
+/// A ticket or reservation handed from one user to another, pending until the
+/// recipient accepts
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct OwnershipTransfer {
+    pub id: Uuid,
+    /// `ticket` or `reservation`
+    pub item_type: String,
+    pub ticket_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    pub event_id: Uuid,
+    pub from_user_id: Uuid,
+    pub to_user_id: Option<Uuid>,
+    pub recipient_phone: Option<String>,
+    pub recipient_email: Option<String>,
+    /// pending, accepted, declined, cancelled, expired
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    /// SHA-256 of the code emailed to `recipient_email`
+    #[serde(skip)]
+    pub claim_code_hash: Option<String>,
+    pub failed_claim_attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub event_title: Option<String>,
+    #[sqlx(default)]
+    pub from_user_name: Option<String>,
+}
+
+impl OwnershipTransfer {
+    pub fn item_id(&self) -> Option<Uuid> {
+        self.ticket_id.or(self.reservation_id)
+    }
+
+    fn matches_verified_phone(&self, user: &User) -> bool {
+        user.phone_verified
+            && user.phone_number.is_some()
+            && self.recipient_phone == user.phone_number
+    }
+
+    fn matches_email(&self, user: &User) -> bool {
+        self.recipient_email
+            .as_deref()
+            .is_some_and(|email| email.eq_ignore_ascii_case(&user.email))
+    }
+
+    /// The transfer went to the user's verified phone number or to their
+    /// account email. Enough to see or decline it; accepting an email transfer
+    /// also takes its claim code, see [`OwnershipTransfer::claim`].
+    pub fn is_addressed_to(&self, user: &User) -> bool {
+        self.matches_verified_phone(user) || self.matches_email(user)
+    }
+
+    /// Whether `user` may take the item, given the hash of the claim code they
+    /// entered. Account emails are unverified, so only the code sent to the
+    /// address proves the user reads it.
+    pub fn claim(&self, user: &User, claim_code_hash: Option<&str>) -> TransferClaim {
+        if self.matches_verified_phone(user) {
+            return TransferClaim::Allowed;
+        }
+        if !self.matches_email(user) {
+            return TransferClaim::NotAddressed;
+        }
+        let Some(expected) = self.claim_code_hash.as_deref() else {
+            return TransferClaim::Locked;
+        };
+        if self.failed_claim_attempts >= MAX_FAILED_CLAIM_ATTEMPTS {
+            return TransferClaim::Locked;
+        }
+        match claim_code_hash {
+            None => TransferClaim::CodeRequired,
+            Some(submitted) if submitted == expected => TransferClaim::Allowed,
+            Some(_) => TransferClaim::WrongCode,
+        }
+    }
+}
+
+/// Outcome of [`OwnershipTransfer::claim`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferClaim {
+    Allowed,
+    /// Sent by email; the code from that email is needed
+    CodeRequired,
+    WrongCode,
+    /// Too many wrong codes, or no code was ever sent
+    Locked,
+    NotAddressed,
+}
+
+/// A ticket or reservation together with what decides whether it can change hands.
+#[derive(Debug, FromRow)]
+pub struct TransferableItem {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub status: String,
+    pub code: String,
+    pub checked_in_count: i32,
+    pub transfers_enabled: bool,
+    pub event_date: Option<NaiveDate>,
+}
+
+impl TransferableItem {
+    /// Why the item cannot be handed over right now:
+    /// "transfers_disabled" | "not_transferable" | "checked_in" | "event_over"
+    pub fn blocking_reason(&self, today: NaiveDate) -> Option<&'static str> {
+        if !self.transfers_enabled {
+            Some("transfers_disabled")
+        } else if self.event_date.is_some_and(|date| date < today) {
+            Some("event_over")
+        } else if self.checked_in_count > 0 || matches!(self.status.as_str(), "used" | "completed")
+        {
+            Some("checked_in")
+        } else if !matches!(self.status.as_str(), "active" | "pending" | "confirmed") {
+            Some("not_transferable")
+        } else {
+            None
+        }
+    }
+}
+
+/// Body for POST /tickets/:id/transfer and /reservations/:id/transfer; exactly
+/// one of the two identifies the recipient
+#[derive(Debug, Deserialize)]
+pub struct CreateTransferRequest {
+    pub phone_number: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Body for POST /transfers/:id/accept
+#[derive(Debug, Default, Deserialize)]
+pub struct AcceptTransferRequest {
+    /// Birth date to save when the profile has none, for age-restricted events
+    pub date_of_birth: Option<NaiveDate>,
+    /// Code from the transfer email; required for transfers sent to an email
+    pub claim_code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferResponse {
+    pub id: String,
+    pub item_type: String,
+    pub item_id: Option<String>,
+    pub event_id: String,
+    pub event_title: Option<String>,
+    pub from_user_id: String,
+    pub from_user_name: Option<String>,
+    pub to_user_id: Option<String>,
+    pub recipient_phone: Option<String>,
+    pub recipient_email: Option<String>,
+    pub status: String,
+    pub expires_at: String,
+    pub responded_at: Option<String>,
+    pub created_at: String,
+}
+
+impl From<OwnershipTransfer> for TransferResponse {
+    fn from(transfer: OwnershipTransfer) -> Self {
+        TransferResponse {
+            id: transfer.id.to_string(),
+            item_id: transfer.item_id().map(|id| id.to_string()),
+            item_type: transfer.item_type,
+            event_id: transfer.event_id.to_string(),
+            event_title: transfer.event_title,
+            from_user_id: transfer.from_user_id.to_string(),
+            from_user_name: transfer.from_user_name,
+            to_user_id: transfer.to_user_id.map(|id| id.to_string()),
+            recipient_phone: transfer.recipient_phone,
+            recipient_email: transfer.recipient_email,
+            status: transfer.status,
+            expires_at: transfer.expires_at.to_rfc3339(),
+            responded_at: transfer.responded_at.map(|at| at.to_rfc3339()),
+            created_at: transfer.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// GET /transfers — pending transfers addressed to the caller and the ones they sent
+#[derive(Debug, Serialize)]
+pub struct TransfersResponse {
+    pub incoming: Vec<TransferResponse>,
+    pub outgoing: Vec<TransferResponse>,
+}
+
+/// Response of POST /transfers/:id/accept: the item now held by the caller and
+/// the code that replaced the old one
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptTransferResponse {
+    pub transfer: TransferResponse,
+    pub new_code: String,
+}
+
+// ── Transfer audit log ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, FromRow)]
+pub struct TransferAuditEntry {
+    pub id: Uuid,
+    pub transfer_id: Uuid,
+    pub event_id: Uuid,
+    pub item_type: String,
+    pub ticket_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    pub actor_user_id: Option<Uuid>,
+    pub action: String,
+    pub from_user_id: Uuid,
+    pub to_user_id: Option<Uuid>,
+    pub recipient_phone: Option<String>,
+    pub recipient_email: Option<String>,
+    pub old_code: Option<String>,
+    pub new_code: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub from_user_name: Option<String>,
+    #[sqlx(default)]
+    pub to_user_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferAuditParams {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferAuditEntryResponse {
+    pub id: String,
+    pub transfer_id: String,
+    pub event_id: String,
+    pub item_type: String,
+    pub ticket_id: Option<String>,
+    pub reservation_id: Option<String>,
+    pub actor_user_id: Option<String>,
+    pub action: String,
+    pub from_user_id: String,
+    pub from_user_name: Option<String>,
+    pub to_user_id: Option<String>,
+    pub to_user_name: Option<String>,
+    pub recipient_phone: Option<String>,
+    pub recipient_email: Option<String>,
+    pub old_code: Option<String>,
+    pub new_code: Option<String>,
+    pub created_at: String,
+}
+
+impl From<TransferAuditEntry> for TransferAuditEntryResponse {
+    fn from(entry: TransferAuditEntry) -> Self {
+        TransferAuditEntryResponse {
+            id: entry.id.to_string(),
+            transfer_id: entry.transfer_id.to_string(),
+            event_id: entry.event_id.to_string(),
+            item_type: entry.item_type,
+            ticket_id: entry.ticket_id.map(|id| id.to_string()),
+            reservation_id: entry.reservation_id.map(|id| id.to_string()),
+            actor_user_id: entry.actor_user_id.map(|id| id.to_string()),
+            action: entry.action,
+            from_user_id: entry.from_user_id.to_string(),
+            from_user_name: entry.from_user_name,
+            to_user_id: entry.to_user_id.map(|id| id.to_string()),
+            to_user_name: entry.to_user_name,
+            recipient_phone: entry.recipient_phone,
+            recipient_email: entry.recipient_email,
+            old_code: entry.old_code,
+            new_code: entry.new_code,
+            created_at: entry.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn user(phone_verified: bool) -> User {
+        User {
+            id: Uuid::new_v4(),
+            email: "giulia@example.com".to_string(),
+            password_hash: String::new(),
+            name: "Giulia".to_string(),
+            phone_number: Some("+393331234567".to_string()),
+            phone_verified,
+            avatar_url: None,
+            date_of_birth: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn transfer(phone: Option<&str>, email: Option<&str>) -> OwnershipTransfer {
+        OwnershipTransfer {
+            id: Uuid::new_v4(),
+            item_type: "ticket".to_string(),
+            ticket_id: Some(Uuid::new_v4()),
+            reservation_id: None,
+            event_id: Uuid::new_v4(),
+            from_user_id: Uuid::new_v4(),
+            to_user_id: None,
+            recipient_phone: phone.map(str::to_string),
+            recipient_email: email.map(str::to_string),
+            status: "pending".to_string(),
+            expires_at: Utc::now() + Duration::hours(48),
+            responded_at: None,
+            claim_code_hash: email.map(|_| "hash".to_string()),
+            failed_claim_attempts: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            event_title: None,
+            from_user_name: None,
+        }
+    }
+
+    fn item(status: &str, event_date: Option<NaiveDate>) -> TransferableItem {
+        TransferableItem {
+            id: Uuid::new_v4(),
+            event_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            status: status.to_string(),
+            code: "TKT-1".to_string(),
+            checked_in_count: 0,
+            transfers_enabled: true,
+            event_date,
+        }
+    }
+
+    #[test]
+    fn phone_transfers_need_a_verified_phone() {
+        let to_phone = transfer(Some("+393331234567"), None);
+
+        assert!(to_phone.is_addressed_to(&user(true)));
+        assert_eq!(to_phone.claim(&user(true), None), TransferClaim::Allowed);
+
+        assert!(!to_phone.is_addressed_to(&user(false)));
+        assert_eq!(
+            to_phone.claim(&user(false), None),
+            TransferClaim::NotAddressed
+        );
+    }
+
+    #[test]
+    fn email_transfers_need_the_emailed_code() {
+        let to_email = transfer(None, Some("Giulia@Example.com"));
+        let recipient = user(false);
+
+        assert!(to_email.is_addressed_to(&recipient));
+        assert_eq!(
+            to_email.claim(&recipient, None),
+            TransferClaim::CodeRequired
+        );
+        assert_eq!(
+            to_email.claim(&recipient, Some("other")),
+            TransferClaim::WrongCode
+        );
+        assert_eq!(
+            to_email.claim(&recipient, Some("hash")),
+            TransferClaim::Allowed
+        );
+
+        let mut someone_else = user(false);
+        someone_else.email = "marco@example.com".to_string();
+        assert!(!to_email.is_addressed_to(&someone_else));
+        assert_eq!(
+            to_email.claim(&someone_else, Some("hash")),
+            TransferClaim::NotAddressed
+        );
+    }
+
+    #[test]
+    fn email_transfers_lock_after_wrong_codes() {
+        let mut to_email = transfer(None, Some("giulia@example.com"));
+        to_email.failed_claim_attempts = MAX_FAILED_CLAIM_ATTEMPTS;
+        assert_eq!(
+            to_email.claim(&user(false), Some("hash")),
+            TransferClaim::Locked
+        );
+
+        let mut without_code = transfer(None, Some("giulia@example.com"));
+        without_code.claim_code_hash = None;
+        assert_eq!(
+            without_code.claim(&user(false), Some("hash")),
+            TransferClaim::Locked
+        );
+    }
+
+    #[test]
+    fn blocking_reasons() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+
+        assert_eq!(item("active", Some(today)).blocking_reason(today), None);
+        assert_eq!(item("confirmed", None).blocking_reason(today), None);
+
+        let mut disabled = item("active", Some(today));
+        disabled.transfers_enabled = false;
+        assert_eq!(disabled.blocking_reason(today), Some("transfers_disabled"));
+
+        assert_eq!(
+            item("active", today.pred_opt()).blocking_reason(today),
+            Some("event_over")
+        );
+
+        let mut admitted = item("confirmed", Some(today));
+        admitted.checked_in_count = 2;
+        assert_eq!(admitted.blocking_reason(today), Some("checked_in"));
+        assert_eq!(
+            item("used", Some(today)).blocking_reason(today),
+            Some("checked_in")
+        );
+
+        assert_eq!(
+            item("cancelled", Some(today)).blocking_reason(today),
+            Some("not_transferable")
+        );
+    }
+}
@@ -54,6 +54,9 @@ pub struct RegisterRequest {
     pub name: String,
     pub phone_number: String,
     pub date_of_birth: NaiveDate,
+    /// "it" or "en"; the Accept-Language header is used when omitted
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -66,13 +69,34 @@ pub struct LoginRequest {
 pub struct AuthResponse {
     pub user: UserResponse,
     pub token: String,
+    /// Present once the user is fully logged in (not on phone verification tokens)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
-    pub sub: String, // User ID or ClubOwner ID
+    pub sub: String, // User ID, ClubOwner ID or staff member ID
     pub email: String,
-    pub role: String, // "user" or "club_owner"
+    pub role: String, // "user", "club_owner" or "staff_<role>"
     pub exp: usize,   // Expiration time
     pub iat: usize,   // Issued at
+    /// Staff tokens only: the owner whose club the staff member works for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub club_owner_id: Option<String>,
+    /// Session family of a refreshable access token; revoking it ends the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+impl Claims {
+    /// Owner whose club this token acts on: the owner themselves, or a staff
+    /// member's employer.
+    pub fn acting_owner_id(&self) -> Option<Uuid> {
+        let owner_id = match self.role.as_str() {
+            "club_owner" => &self.sub,
+            _ => self.club_owner_id.as_ref()?,
+        };
+        Uuid::parse_str(owner_id).ok()
+    }
 }
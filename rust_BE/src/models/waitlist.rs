@@ -0,0 +1,248 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub area_id: Option<Uuid>,
+    pub min_capacity: Option<i32>,
+    pub position: i32,
+    pub status: String,
+    pub held_table_id: Option<Uuid>,
+    pub held_reservation_id: Option<Uuid>,
+    pub hold_expires_at: Option<DateTime<Utc>>,
+    pub offered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub user_name: Option<String>,
+    #[sqlx(default)]
+    pub user_phone: Option<String>,
+    #[sqlx(default)]
+    pub area_name: Option<String>,
+    #[sqlx(default)]
+    pub held_table_name: Option<String>,
+}
+
+/// What an offered entry is holding for its user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitlistHold {
+    /// A whole freed table
+    Table(Uuid),
+    /// One guest slot of a split reservation
+    Slot(Uuid),
+}
+
+impl WaitlistEntry {
+    pub fn hold(&self) -> Option<WaitlistHold> {
+        match (self.held_table_id, self.held_reservation_id) {
+            (Some(table_id), _) => Some(WaitlistHold::Table(table_id)),
+            (None, Some(reservation_id)) => Some(WaitlistHold::Slot(reservation_id)),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether the entry has been offered something and the hold has not run out.
+    pub fn hold_is_live(&self, now: DateTime<Utc>) -> bool {
+        self.status == "offered" && self.hold_expires_at.is_some_and(|at| at > now)
+    }
+
+    /// Whether the hold keeps `user_id` from taking what it reserves.
+    pub fn blocks(&self, user_id: Uuid, now: DateTime<Utc>) -> bool {
+        self.user_id != user_id && self.hold_is_live(now)
+    }
+}
+
+/// Title and body telling a user what was freed for them and for how long.
+pub fn hold_notification(
+    hold: WaitlistHold,
+    table_name: &str,
+    event_title: &str,
+    minutes: i64,
+) -> (String, String) {
+    match hold {
+        WaitlistHold::Table(_) => (
+            "Tavolo disponibile".to_string(),
+            format!(
+                "Si è liberato {} per {}. È riservato per te per {} minuti.",
+                table_name, event_title, minutes
+            ),
+        ),
+        WaitlistHold::Slot(_) => (
+            "Posto disponibile".to_string(),
+            format!(
+                "Si è liberato un posto al tavolo {} per {}. È riservato per te per {} minuti.",
+                table_name, event_title, minutes
+            ),
+        ),
+    }
+}
+
+/// Body for POST /events/:event_id/waitlist
+#[derive(Debug, Default, Deserialize)]
+pub struct JoinWaitlistRequest {
+    pub area_id: Option<String>,
+    pub min_capacity: Option<i32>,
+}
+
+/// Body for PUT /owner/events/:event_id/waitlist
+#[derive(Debug, Deserialize)]
+pub struct ReorderWaitlistRequest {
+    pub entry_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitlistEntryResponse {
+    pub id: String,
+    pub event_id: String,
+    pub user_id: String,
+    pub user_name: Option<String>,
+    pub user_phone: Option<String>,
+    pub area_id: Option<String>,
+    pub area_name: Option<String>,
+    pub min_capacity: Option<i32>,
+    pub position: i32,
+    pub status: String,
+    pub held_table_id: Option<String>,
+    pub held_table_name: Option<String>,
+    pub held_reservation_id: Option<String>,
+    pub hold_expires_at: Option<String>,
+    /// Active entries queued before this one (only on the user's own view)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub people_ahead: Option<i64>,
+    pub created_at: String,
+}
+
+impl From<WaitlistEntry> for WaitlistEntryResponse {
+    fn from(entry: WaitlistEntry) -> Self {
+        WaitlistEntryResponse {
+            id: entry.id.to_string(),
+            event_id: entry.event_id.to_string(),
+            user_id: entry.user_id.to_string(),
+            user_name: entry.user_name,
+            user_phone: entry.user_phone,
+            area_id: entry.area_id.map(|id| id.to_string()),
+            area_name: entry.area_name,
+            min_capacity: entry.min_capacity,
+            position: entry.position,
+            status: entry.status,
+            held_table_id: entry.held_table_id.map(|id| id.to_string()),
+            held_table_name: entry.held_table_name,
+            held_reservation_id: entry.held_reservation_id.map(|id| id.to_string()),
+            hold_expires_at: entry.hold_expires_at.map(|at| at.to_rfc3339()),
+            people_ahead: None,
+            created_at: entry.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitlistResponse {
+    pub entries: Vec<WaitlistEntryResponse>,
+    pub total: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(status: &str, hold: Option<WaitlistHold>, expires_in_minutes: i64) -> WaitlistEntry {
+        let now = Utc::now();
+        WaitlistEntry {
+            id: Uuid::new_v4(),
+            event_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            area_id: None,
+            min_capacity: None,
+            position: 1,
+            status: status.to_string(),
+            held_table_id: match hold {
+                Some(WaitlistHold::Table(id)) => Some(id),
+                _ => None,
+            },
+            held_reservation_id: match hold {
+                Some(WaitlistHold::Slot(id)) => Some(id),
+                _ => None,
+            },
+            hold_expires_at: hold.map(|_| now + Duration::minutes(expires_in_minutes)),
+            offered_at: hold.map(|_| now),
+            created_at: now,
+            updated_at: now,
+            user_name: None,
+            user_phone: None,
+            area_name: None,
+            held_table_name: None,
+        }
+    }
+
+    #[test]
+    fn live_hold_blocks_everyone_but_its_holder() {
+        let table_id = Uuid::new_v4();
+        let held = entry("offered", Some(WaitlistHold::Table(table_id)), 30);
+        let now = Utc::now();
+
+        assert_eq!(held.hold(), Some(WaitlistHold::Table(table_id)));
+        assert!(held.hold_is_live(now));
+        assert!(held.blocks(Uuid::new_v4(), now));
+        assert!(!held.blocks(held.user_id, now));
+    }
+
+    #[test]
+    fn hold_stops_blocking_once_expired_or_closed() {
+        let slot = Some(WaitlistHold::Slot(Uuid::new_v4()));
+        let now = Utc::now();
+
+        let expired = entry("offered", slot, -1);
+        assert!(!expired.hold_is_live(now));
+        assert!(!expired.blocks(Uuid::new_v4(), now));
+
+        // Still running on the clock, but already used or given up
+        for status in ["fulfilled", "cancelled", "expired"] {
+            assert!(!entry(status, slot, 30).blocks(Uuid::new_v4(), now));
+        }
+
+        let live = entry("offered", slot, 30);
+        let deadline = live.hold_expires_at.unwrap();
+        assert!(live.hold_is_live(deadline - Duration::seconds(1)));
+        assert!(!live.hold_is_live(deadline));
+    }
+
+    #[test]
+    fn waiting_entry_holds_nothing() {
+        let waiting = entry("waiting", None, 0);
+        assert_eq!(waiting.hold(), None);
+        assert!(!waiting.blocks(Uuid::new_v4(), Utc::now()));
+    }
+
+    #[test]
+    fn offer_message_names_what_was_freed() {
+        let (title, body) = hold_notification(
+            WaitlistHold::Table(Uuid::new_v4()),
+            "Tavolo 4",
+            "Neon Night",
+            30,
+        );
+        assert_eq!(title, "Tavolo disponibile");
+        assert_eq!(
+            body,
+            "Si è liberato Tavolo 4 per Neon Night. È riservato per te per 30 minuti."
+        );
+
+        let (title, body) = hold_notification(
+            WaitlistHold::Slot(Uuid::new_v4()),
+            "Tavolo 4",
+            "Neon Night",
+            15,
+        );
+        assert_eq!(title, "Posto disponibile");
+        assert!(body.starts_with("Si è liberato un posto al tavolo Tavolo 4"));
+        assert!(body.ends_with("per 15 minuti."));
+    }
+}
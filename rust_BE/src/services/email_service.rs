@@ -0,0 +1,226 @@
+use lettre::{
+    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use reqwest::Client;
+use tracing::{error, info, warn};
+
+use crate::bootstrap::config::EmailConfig;
+use crate::infrastructure::outbox::delivery::HttpStatusError;
+
+type EmailResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// One email as queued in the outbox.
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: Option<String>,
+}
+
+/// Sends a transactional email with the provider selected by `EMAIL_PROVIDER`:
+///   - `http`: JSON email API (Resend-compatible `POST {from, to, subject, text, html}`
+///     with a bearer key) at `EMAIL_API_URL`, authenticated with `EMAIL_API_KEY`
+///   - `smtp`: `SMTP_HOST` / `SMTP_PORT`, optional `SMTP_USERNAME` / `SMTP_PASSWORD`,
+///     `SMTP_TLS` = starttls | tls | none
+///   - `log`: logs the recipient, subject and body length and sends nothing (development)
+///
+/// `EMAIL_FROM` is the sender for every provider, e.g. "Pierre <no-reply@pierre.app>".
+/// An unconfigured provider skips the email instead of failing it.
+pub async fn send_email(config: &EmailConfig, email: &OutgoingEmail) -> EmailResult {
+    match config.provider.as_str() {
+        "http" => send_via_http(config, email).await,
+        "smtp" => send_via_smtp(config, email).await,
+        "log" => {
+            // The body can carry reset codes, so only its length is logged
+            info!(
+                to = %email.to,
+                subject = %email.subject,
+                text_len = email.text.len(),
+                "Email (log provider)"
+            );
+            Ok(())
+        }
+        other => Err(format!("Unsupported EMAIL_PROVIDER: {other}").into()),
+    }
+}
+
+async fn send_via_http(config: &EmailConfig, email: &OutgoingEmail) -> EmailResult {
+    let Some(url) = config.api_url.as_deref() else {
+        info!(to = %email.to, "Email skipped (EMAIL_API_URL not set)");
+        return Ok(());
+    };
+    let Some(api_key) = config.api_key.as_deref() else {
+        warn!("Email skipped: EMAIL_API_KEY not set");
+        return Ok(());
+    };
+    let Some(from) = config.from.as_deref() else {
+        warn!("Email skipped: EMAIL_FROM not set");
+        return Ok(());
+    };
+
+    let mut payload = serde_json::json!({
+        "from": from,
+        "to": [email.to],
+        "subject": email.subject,
+        "text": email.text,
+    });
+    if let Some(html) = email.html.as_deref() {
+        payload["html"] = serde_json::json!(html);
+    }
+
+    let client = Client::new();
+    match client
+        .post(url)
+        .bearer_auth(api_key)
+        .json(&payload)
+        .send()
+        .await
+    {
+        Ok(resp) if resp.status().is_success() => {
+            info!(to = %email.to, "Email sent successfully");
+            Ok(())
+        }
+        Ok(resp) => {
+            warn!(to = %email.to, status = %resp.status(), "Email returned non-success status");
+            Err(Box::new(HttpStatusError {
+                provider: "Email",
+                status: resp.status().as_u16(),
+            }))
+        }
+        Err(e) => {
+            error!(to = %email.to, error = %e, "Failed to send email");
+            Err(Box::new(e))
+        }
+    }
+}
+
+async fn send_via_smtp(config: &EmailConfig, email: &OutgoingEmail) -> EmailResult {
+    let Some(host) = config.smtp_host.as_deref() else {
+        info!(to = %email.to, "Email skipped (SMTP_HOST not set)");
+        return Ok(());
+    };
+    let Some(from) = config.from.as_deref() else {
+        warn!("Email skipped: EMAIL_FROM not set");
+        return Ok(());
+    };
+
+    let builder = Message::builder()
+        .from(from.parse::<Mailbox>()?)
+        .to(email.to.parse::<Mailbox>()?)
+        .subject(email.subject.as_str());
+    let message = match email.html.as_deref() {
+        Some(html) => builder.multipart(MultiPart::alternative_plain_html(
+            email.text.clone(),
+            html.to_string(),
+        ))?,
+        None => builder.singlepart(
+            SinglePart::builder()
+                .header(ContentType::TEXT_PLAIN)
+                .body(email.text.clone()),
+        )?,
+    };
+
+    let mut transport = match config.smtp_tls.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+        "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+    }
+    .port(config.smtp_port);
+    if let (Some(username), Some(password)) = (
+        config.smtp_username.as_deref(),
+        config.smtp_password.as_deref(),
+    ) {
+        transport =
+            transport.credentials(Credentials::new(username.to_string(), password.to_string()));
+    }
+
+    match transport.build().send(message).await {
+        Ok(_) => {
+            info!(to = %email.to, "Email sent successfully");
+            Ok(())
+        }
+        Err(e) => {
+            error!(to = %email.to, error = %e, "Failed to send email over SMTP");
+            Err(Box::new(e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Minimal SMTP sink: accepts one message and returns its DATA section.
+    async fn run_smtp_sink(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+
+        let mut data = String::new();
+        let mut in_data = false;
+        while let Some(line) = lines.next_line().await.unwrap() {
+            if in_data {
+                if line == "." {
+                    in_data = false;
+                    writer.write_all(b"250 queued\r\n").await.unwrap();
+                } else {
+                    data.push_str(&line);
+                    data.push('\n');
+                }
+                continue;
+            }
+
+            let command = line.to_ascii_uppercase();
+            let reply: &[u8] = if command.starts_with("DATA") {
+                in_data = true;
+                b"354 end with .\r\n"
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 bye\r\n").await.unwrap();
+                break;
+            } else {
+                b"250 ok\r\n"
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_smtp_provider_delivers_to_local_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink = tokio::spawn(run_smtp_sink(listener));
+
+        let config = EmailConfig {
+            provider: "smtp".to_string(),
+            from: Some("Pierre <no-reply@pierre.app>".to_string()),
+            api_url: None,
+            api_key: None,
+            smtp_host: Some("127.0.0.1".to_string()),
+            smtp_port: port,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_tls: "none".to_string(),
+        };
+        let email = OutgoingEmail {
+            to: "guest@example.com".to_string(),
+            subject: "Reservation confirmed".to_string(),
+            text: "See you tonight".to_string(),
+            html: Some("<p>See you tonight</p>".to_string()),
+        };
+
+        send_email(&config, &email).await.unwrap();
+        let data = sink.await.unwrap();
+
+        assert!(data.contains("Subject: Reservation confirmed"));
+        assert!(data.contains("To: guest@example.com"));
+        assert!(data.contains("See you tonight"));
+        assert!(data.contains("<p>See you tonight</p>"));
+    }
+}
@@ -0,0 +1,270 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+/// Language of a transactional email. Italian unless the recipient asked for English.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmailLocale {
+    It,
+    En,
+}
+
+impl EmailLocale {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "it" => Some(EmailLocale::It),
+            "en" => Some(EmailLocale::En),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailLocale::It => "it",
+            EmailLocale::En => "en",
+        }
+    }
+
+    /// Stored locale, falling back to Italian for missing or unknown values
+    pub fn from_stored(value: Option<&str>) -> Self {
+        value
+            .and_then(EmailLocale::parse)
+            .unwrap_or(EmailLocale::It)
+    }
+
+    /// Locale from an `Accept-Language` header: the first supported language wins
+    pub fn from_accept_language(header: Option<&str>) -> Self {
+        header
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .filter_map(|tag| tag.split(';').next())
+            .filter_map(|tag| tag.trim().split('-').next())
+            .find_map(|language| EmailLocale::parse(&language.to_ascii_lowercase()))
+            .unwrap_or(EmailLocale::It)
+    }
+}
+
+/// Subject and bodies ready for `outbox_service::enqueue_email_notification`
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// What every reservation email shows
+#[derive(Debug, Clone)]
+pub struct ReservationEmail {
+    pub recipient_name: String,
+    pub event_title: String,
+    pub event_date: Option<NaiveDate>,
+    pub table_name: String,
+    pub reservation_code: String,
+    pub num_people: i32,
+}
+
+/// Sent to the booking contact when every share is paid or the club confirms
+pub fn reservation_confirmed(locale: EmailLocale, reservation: &ReservationEmail) -> RenderedEmail {
+    let (subject, intro) = match locale {
+        EmailLocale::It => (
+            format!("Prenotazione confermata - {}", reservation.event_title),
+            "la tua prenotazione è confermata. Mostra il codice all'ingresso.",
+        ),
+        EmailLocale::En => (
+            format!("Reservation confirmed - {}", reservation.event_title),
+            "your reservation is confirmed. Show the code at the door.",
+        ),
+    };
+    render(locale, subject, reservation, &[intro.to_string()])
+}
+
+/// Sent to a guest after paying their share through a payment link
+pub fn share_payment_receipt(
+    locale: EmailLocale,
+    reservation: &ReservationEmail,
+    amount: Decimal,
+) -> RenderedEmail {
+    let (subject, intro) = match locale {
+        EmailLocale::It => (
+            format!("Ricevuta di pagamento - {}", reservation.event_title),
+            format!(
+                "abbiamo ricevuto il tuo pagamento di €{amount:.2} per la tua quota del tavolo."
+            ),
+        ),
+        EmailLocale::En => (
+            format!("Payment receipt - {}", reservation.event_title),
+            format!("we received your payment of €{amount:.2} for your share of the table."),
+        ),
+    };
+    render(locale, subject, reservation, &[intro])
+}
+
+/// Sent to the booking contact and to every guest who paid when a reservation
+/// is cancelled. `refunded` is what goes back to this recipient, if anything.
+pub fn reservation_cancelled(
+    locale: EmailLocale,
+    reservation: &ReservationEmail,
+    refunded: Option<Decimal>,
+) -> RenderedEmail {
+    let refunded = refunded.filter(|amount| !amount.is_zero());
+    let (subject, intro, refund_line) = match locale {
+        EmailLocale::It => (
+            format!("Prenotazione annullata - {}", reservation.event_title),
+            "la prenotazione è stata annullata.".to_string(),
+            refunded.map(|amount| {
+                format!("Ti rimborseremo €{amount:.2}: l'importo arriva sul tuo metodo di pagamento entro 5-10 giorni lavorativi.")
+            }),
+        ),
+        EmailLocale::En => (
+            format!("Reservation cancelled - {}", reservation.event_title),
+            "the reservation has been cancelled.".to_string(),
+            refunded.map(|amount| {
+                format!("We are refunding €{amount:.2}; it reaches your payment method within 5-10 business days.")
+            }),
+        ),
+    };
+    let mut paragraphs = vec![intro];
+    paragraphs.extend(refund_line);
+    render(locale, subject, reservation, &paragraphs)
+}
+
+fn render(
+    locale: EmailLocale,
+    subject: String,
+    reservation: &ReservationEmail,
+    paragraphs: &[String],
+) -> RenderedEmail {
+    let (greeting, date_label, table_label, people_label, code_label, signature) = match locale {
+        EmailLocale::It => (
+            "Ciao",
+            "Data",
+            "Tavolo",
+            "Persone",
+            "Codice",
+            "A presto,\nPierre",
+        ),
+        EmailLocale::En => (
+            "Hi",
+            "Date",
+            "Table",
+            "People",
+            "Code",
+            "See you soon,\nPierre",
+        ),
+    };
+    let date = reservation
+        .event_date
+        .map(|date| match locale {
+            EmailLocale::It => date.format("%d/%m/%Y").to_string(),
+            EmailLocale::En => date.format("%-d %B %Y").to_string(),
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let details = [
+        (date_label, date),
+        (table_label, reservation.table_name.clone()),
+        (people_label, reservation.num_people.to_string()),
+        (code_label, reservation.reservation_code.clone()),
+    ];
+
+    let mut text = format!("{greeting} {},\n\n", reservation.recipient_name);
+    for paragraph in paragraphs {
+        text.push_str(paragraph);
+        text.push_str("\n\n");
+    }
+    text.push_str(&reservation.event_title);
+    text.push('\n');
+    for (label, value) in &details {
+        text.push_str(&format!("{label}: {value}\n"));
+    }
+    text.push('\n');
+    text.push_str(signature);
+    text.push('\n');
+
+    let mut html = format!(
+        "<!DOCTYPE html><html lang=\"{}\"><body style=\"font-family:Helvetica,Arial,sans-serif;color:#111;\">",
+        locale.as_str()
+    );
+    html.push_str(&format!(
+        "<p>{greeting} {},</p>",
+        escape_html(&reservation.recipient_name)
+    ));
+    for paragraph in paragraphs {
+        html.push_str(&format!("<p>{}</p>", escape_html(paragraph)));
+    }
+    html.push_str(&format!(
+        "<h2 style=\"margin-bottom:4px;\">{}</h2><table cellpadding=\"4\">",
+        escape_html(&reservation.event_title)
+    ));
+    for (label, value) in &details {
+        html.push_str(&format!(
+            "<tr><td style=\"color:#666;\">{label}</td><td><strong>{}</strong></td></tr>",
+            escape_html(value)
+        ));
+    }
+    html.push_str(&format!(
+        "</table><p>{}</p></body></html>",
+        escape_html(signature).replace('\n', "<br>")
+    ));
+
+    RenderedEmail {
+        subject,
+        text,
+        html,
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reservation() -> ReservationEmail {
+        ReservationEmail {
+            recipient_name: "Giulia <script>".to_string(),
+            event_title: "Neon Nights".to_string(),
+            event_date: NaiveDate::from_ymd_opt(2026, 3, 14),
+            table_name: "VIP 1".to_string(),
+            reservation_code: "RES-ABC123".to_string(),
+            num_people: 4,
+        }
+    }
+
+    #[test]
+    fn test_locale_from_accept_language() {
+        assert_eq!(
+            EmailLocale::from_accept_language(Some("en-GB,en;q=0.9,it;q=0.8")),
+            EmailLocale::En
+        );
+        assert_eq!(
+            EmailLocale::from_accept_language(Some("de-DE,it;q=0.5")),
+            EmailLocale::It
+        );
+        assert_eq!(EmailLocale::from_accept_language(None), EmailLocale::It);
+    }
+
+    #[test]
+    fn test_templates_render_both_locales_and_escape_html() {
+        let it = reservation_confirmed(EmailLocale::It, &reservation());
+        assert_eq!(it.subject, "Prenotazione confermata - Neon Nights");
+        assert!(it.text.contains("Data: 14/03/2026"));
+        assert!(it.text.contains("Giulia <script>"));
+        assert!(it.html.contains("Giulia &lt;script&gt;"));
+        assert!(!it.html.contains("<script>"));
+
+        let en =
+            reservation_cancelled(EmailLocale::En, &reservation(), Some(Decimal::new(4500, 2)));
+        assert_eq!(en.subject, "Reservation cancelled - Neon Nights");
+        assert!(en.text.contains("Date: 14 March 2026"));
+        assert!(en.text.contains("€45.00"));
+
+        let no_refund = reservation_cancelled(EmailLocale::It, &reservation(), Some(Decimal::ZERO));
+        assert!(!no_refund.text.contains("rimborseremo"));
+    }
+}
@@ -0,0 +1,104 @@
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::bootstrap::config::GeocodingConfig;
+use crate::infrastructure::outbox::delivery::HttpStatusError;
+
+type GeocodingResult = Result<Option<(f64, f64)>, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Centre of the fake coordinates returned by the `stub` provider (Milan).
+const STUB_ORIGIN: (f64, f64) = (45.4642, 9.19);
+
+/// Looks up the (latitude, longitude) of an address with the provider selected
+/// by `GEOCODER_PROVIDER`:
+///   - `nominatim`: Nominatim-compatible `/search` API at `GEOCODER_API_URL`
+///     (OpenStreetMap by default; hosted clones take `GEOCODER_API_KEY` as `key`)
+///   - `stub`: stable fake coordinates within a few km of Milan, no network
+///   - `none`: geocoding disabled
+///
+/// `Ok(None)` means the provider has no result for the address.
+pub async fn geocode_address(
+    config: &GeocodingConfig,
+    user_agent: &str,
+    address: &str,
+) -> GeocodingResult {
+    match config.provider.as_str() {
+        "nominatim" => geocode_via_nominatim(config, user_agent, address).await,
+        "stub" => Ok(Some(stub_coordinates(address))),
+        "none" => Ok(None),
+        other => Err(format!("Unsupported GEOCODER_PROVIDER: {other}").into()),
+    }
+}
+
+pub fn geocoding_enabled(config: &GeocodingConfig) -> bool {
+    config.provider != "none"
+}
+
+#[derive(Deserialize)]
+struct NominatimPlace {
+    lat: String,
+    lon: String,
+}
+
+async fn geocode_via_nominatim(
+    config: &GeocodingConfig,
+    user_agent: &str,
+    address: &str,
+) -> GeocodingResult {
+    let url = format!("{}/search", config.api_url.trim_end_matches('/'));
+    let mut query = vec![("q", address), ("format", "jsonv2"), ("limit", "1")];
+    if let Some(api_key) = config.api_key.as_deref() {
+        query.push(("key", api_key));
+    }
+
+    let response = Client::new()
+        .get(&url)
+        .query(&query)
+        // Nominatim's usage policy requires an identifying User-Agent
+        .header(reqwest::header::USER_AGENT, user_agent)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        warn!(status = %response.status(), "Geocoder returned non-success status");
+        return Err(Box::new(HttpStatusError {
+            provider: "Geocoder",
+            status: response.status().as_u16(),
+        }));
+    }
+
+    let places: Vec<NominatimPlace> = response.json().await?;
+    Ok(places.first().and_then(|place| {
+        let latitude = place.lat.parse::<f64>().ok()?;
+        let longitude = place.lon.parse::<f64>().ok()?;
+        Some((latitude, longitude))
+    }))
+}
+
+/// Deterministic point within about 5 km of `STUB_ORIGIN`, so local maps and
+/// nearby searches show clubs spread around one city.
+fn stub_coordinates(address: &str) -> (f64, f64) {
+    let digest = Sha256::digest(address.trim().to_lowercase().as_bytes());
+    let offset = |byte: u8| (f64::from(byte) / 255.0 - 0.5) * 0.09;
+    (
+        STUB_ORIGIN.0 + offset(digest[0]),
+        STUB_ORIGIN.1 + offset(digest[1]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stub_coordinates_are_stable_and_stay_near_the_origin() {
+        let first = stub_coordinates("Via Valtellina 21, Milano");
+        assert_eq!(first, stub_coordinates("  via valtellina 21, milano "));
+        assert_ne!(first, stub_coordinates("Corso Como 15, Milano"));
+        assert!((first.0 - STUB_ORIGIN.0).abs() <= 0.045);
+        assert!((first.1 - STUB_ORIGIN.1).abs() <= 0.045);
+    }
+}
@@ -1,3 +1,7 @@
+pub mod email_service;
+pub mod email_templates;
+pub mod geocoding_service;
 pub mod notification_service;
 pub mod sms_service;
 pub mod storage_service;
+pub mod stripe_finance_service;
@@ -1,7 +1,12 @@
 use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
 use tracing::{error, info, warn};
 
 use crate::bootstrap::config::AppConfig;
+use crate::infrastructure::outbox::delivery::HttpStatusError;
+
+const EXPO_PUSH_API: &str = "https://exp.host/--/api/v2/push";
 
 /// Sends a plain SMS via the Twilio Messages API.
 ///
@@ -48,7 +53,10 @@ pub async fn send_sms(
         }
         Ok(resp) => {
             warn!(to = %to, status = %resp.status(), "SMS returned non-success status");
-            Err(format!("SMS returned non-success status {}", resp.status()).into())
+            Err(Box::new(HttpStatusError {
+                provider: "SMS",
+                status: resp.status().as_u16(),
+            }))
         }
         Err(e) => {
             error!(to = %to, error = %e, "Failed to send SMS");
@@ -57,20 +65,94 @@ pub async fn send_sms(
     }
 }
 
+/// Outcome Expo reports for one message, first as a push ticket and later as
+/// a push receipt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushStatus {
+    Ok,
+    /// `code` is Expo's `details.error`, e.g. "DeviceNotRegistered"
+    Error {
+        message: String,
+        code: Option<String>,
+    },
+}
+
+impl PushStatus {
+    /// The device uninstalled the app or revoked permission; the token is dead
+    pub fn is_device_not_registered(&self) -> bool {
+        matches!(self, PushStatus::Error { code: Some(code), .. } if code == "DeviceNotRegistered")
+    }
+
+    fn from_json(value: &Value) -> Self {
+        if value.get("status").and_then(Value::as_str) == Some("ok") {
+            return PushStatus::Ok;
+        }
+        PushStatus::Error {
+            message: value
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("Unknown Expo push error")
+                .to_string(),
+            code: value
+                .pointer("/details/error")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Push ticket for one message; `id` is what the receipt is fetched with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushTicket {
+    pub id: Option<String>,
+    pub status: PushStatus,
+}
+
+/// Parses the body of POST /push/send for a single message.
+pub fn parse_push_ticket(body: &Value) -> Option<PushTicket> {
+    let data = body.get("data")?;
+    // A single message gets an object, a batch gets an array
+    let ticket = data
+        .as_array()
+        .and_then(|tickets| tickets.first())
+        .unwrap_or(data);
+
+    Some(PushTicket {
+        id: ticket.get("id").and_then(Value::as_str).map(str::to_string),
+        status: PushStatus::from_json(ticket),
+    })
+}
+
+/// Parses the body of POST /push/getReceipts. Receipts that are not ready yet
+/// are simply missing from the map.
+pub fn parse_push_receipts(body: &Value) -> HashMap<String, PushStatus> {
+    body.get("data")
+        .and_then(Value::as_object)
+        .map(|receipts| {
+            receipts
+                .iter()
+                .map(|(id, receipt)| (id.clone(), PushStatus::from_json(receipt)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Sends a push notification via the Expo Push API.
 ///
 /// `token` must be a valid Expo push token, e.g. "ExponentPushToken[...]".
 /// No API key required for Expo's free push service.
 ///
-/// Fire-and-forget — logs errors but never panics or blocks the caller.
+/// A 2xx response only means Expo accepted the request: the returned ticket
+/// says whether the message was queued, and its receipt (see
+/// `fetch_push_receipts`) whether it reached the device.
 pub async fn send_push_notification(
     _config: &AppConfig,
     token: &str,
     title: &str,
     body: &str,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<Option<PushTicket>, Box<dyn std::error::Error + Send + Sync>> {
     if token.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
 
     let payload = serde_json::json!({
@@ -82,22 +164,26 @@ pub async fn send_push_notification(
 
     let client = Client::new();
     match client
-        .post("https://exp.host/--/api/v2/push/send")
+        .post(format!("{EXPO_PUSH_API}/send"))
         .json(&payload)
         .send()
         .await
     {
         Ok(resp) if resp.status().is_success() => {
-            info!("Push notification sent");
-            Ok(())
+            let body: Value = resp.json().await?;
+            let ticket = parse_push_ticket(&body).ok_or("Expo push response has no ticket")?;
+            info!(
+                ok = ticket.status == PushStatus::Ok,
+                "Push notification sent"
+            );
+            Ok(Some(ticket))
         }
         Ok(resp) => {
             warn!(status = %resp.status(), "Push notification returned non-success status");
-            Err(format!(
-                "Push notification returned non-success status {}",
-                resp.status()
-            )
-            .into())
+            Err(Box::new(HttpStatusError {
+                provider: "Push notification",
+                status: resp.status().as_u16(),
+            }))
         }
         Err(e) => {
             error!(error = %e, "Failed to send push notification");
@@ -105,3 +191,68 @@ pub async fn send_push_notification(
         }
     }
 }
+
+/// Fetches push receipts for up to 1000 ticket ids.
+pub async fn fetch_push_receipts(
+    ticket_ids: &[String],
+) -> Result<HashMap<String, PushStatus>, Box<dyn std::error::Error + Send + Sync>> {
+    let response = Client::new()
+        .post(format!("{EXPO_PUSH_API}/getReceipts"))
+        .json(&serde_json::json!({ "ids": ticket_ids }))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(Box::new(HttpStatusError {
+            provider: "Push receipts",
+            status: response.status().as_u16(),
+        }));
+    }
+
+    let body: Value = response.json().await?;
+    Ok(parse_push_receipts(&body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_push_ticket() {
+        let ok = json!({ "data": { "status": "ok", "id": "XXXX-1" } });
+        assert_eq!(
+            parse_push_ticket(&ok),
+            Some(PushTicket {
+                id: Some("XXXX-1".to_string()),
+                status: PushStatus::Ok,
+            })
+        );
+
+        let unregistered = json!({ "data": [{
+            "status": "error",
+            "message": "\"ExponentPushToken[x]\" is not a registered push notification recipient",
+            "details": { "error": "DeviceNotRegistered" },
+        }] });
+        let ticket = parse_push_ticket(&unregistered).unwrap();
+        assert_eq!(ticket.id, None);
+        assert!(ticket.status.is_device_not_registered());
+
+        assert_eq!(parse_push_ticket(&json!({ "errors": [] })), None);
+    }
+
+    #[test]
+    fn test_parse_push_receipts() {
+        let receipts = parse_push_receipts(&json!({ "data": {
+            "a": { "status": "ok" },
+            "b": { "status": "error", "message": "Rate", "details": { "error": "MessageRateExceeded" } },
+        } }));
+
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts["a"], PushStatus::Ok);
+        assert!(!receipts["b"].is_device_not_registered());
+        assert!(
+            matches!(&receipts["b"], PushStatus::Error { code: Some(code), .. } if code == "MessageRateExceeded")
+        );
+    }
+}
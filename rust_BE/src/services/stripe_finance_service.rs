@@ -0,0 +1,317 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use stripe::{
+    AccountId, BalanceTransaction, ListBalanceTransactions, ListPayouts, ListTransfers, Payout,
+    RangeQuery, Transfer,
+};
+use uuid::Uuid;
+
+use crate::application::{connect_reconciliation_service, payment_service};
+use crate::bootstrap::config::StripeConfig;
+use crate::bootstrap::state::AppState;
+use crate::models::connect_reconciliation::{
+    ClubPaymentRow, ConnectActivity, ConnectBalanceTransactionRecord, ConnectPayoutRecord,
+    ConnectTransferRecord,
+};
+
+type ConnectResult = Result<ConnectActivity, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Largest page Stripe's list endpoints return
+const PAGE_SIZE: u64 = 100;
+
+/// Days between a fake payout's creation and its arrival at the bank
+const FAKE_PAYOUT_DELAY_DAYS: i64 = 2;
+
+/// Reads the Connect activity of a club's connected account created since
+/// `since`, with the provider selected by `STRIPE_RECONCILIATION_PROVIDER`:
+///   - `stripe`: transfers from the platform (with their source charge),
+///     payouts and balance transactions of the connected account
+///   - `fake`: activity derived from the club's local payments, as destination
+///     charges with daily payouts would produce it; no network
+///   - `none`: reconciliation disabled
+pub async fn fetch_connect_activity(
+    state: &AppState,
+    club_id: Uuid,
+    connected_account_id: &str,
+    since: DateTime<Utc>,
+) -> ConnectResult {
+    match state.config.stripe.reconciliation_provider.as_str() {
+        "stripe" => fetch_from_stripe(&state.stripe_client, connected_account_id, since).await,
+        "fake" => {
+            let payments = connect_reconciliation_service::get_club_payments_since(
+                &state.db_pool,
+                club_id,
+                since,
+            )
+            .await?;
+            Ok(fake_activity(connected_account_id, &payments, Utc::now()))
+        }
+        "none" => Ok(ConnectActivity::default()),
+        other => Err(format!("Unsupported STRIPE_RECONCILIATION_PROVIDER: {other}").into()),
+    }
+}
+
+pub fn reconciliation_enabled(config: &StripeConfig) -> bool {
+    config.reconciliation_provider != "none"
+}
+
+fn timestamp(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(seconds, 0).unwrap_or_default()
+}
+
+async fn fetch_from_stripe(
+    client: &stripe::Client,
+    connected_account_id: &str,
+    since: DateTime<Utc>,
+) -> ConnectResult {
+    let account = connected_account_id.parse::<AccountId>()?;
+    let created = RangeQuery::gte(since.timestamp());
+    let mut activity = ConnectActivity::default();
+
+    // Transfers live on the platform account
+    let mut params = ListTransfers::new();
+    params.destination = Some(connected_account_id.to_string());
+    params.created = Some(created.clone());
+    params.expand = &["data.source_transaction"];
+    params.limit = Some(PAGE_SIZE);
+    loop {
+        let page = Transfer::list(client, &params).await?;
+        params.starting_after = page.data.last().map(|transfer| transfer.id.clone());
+        activity
+            .transfers
+            .extend(page.data.iter().map(transfer_record));
+        if !page.has_more || params.starting_after.is_none() {
+            break;
+        }
+    }
+
+    // Payouts and balance transactions live on the connected account
+    let connected = client.clone().with_stripe_account(account);
+
+    let mut params = ListPayouts::new();
+    params.created = Some(created.clone());
+    params.limit = Some(PAGE_SIZE);
+    loop {
+        let page = Payout::list(&connected, &params).await?;
+        params.starting_after = page.data.last().map(|payout| payout.id.clone());
+        activity
+            .payouts
+            .extend(page.data.iter().map(|payout| ConnectPayoutRecord {
+                id: payout.id.to_string(),
+                amount_cents: payout.amount,
+                currency: payout.currency.to_string(),
+                status: payout.status.clone(),
+                arrival_date: timestamp(payout.arrival_date).date_naive(),
+                failure_message: payout.failure_message.clone(),
+                created_at: timestamp(payout.created),
+            }));
+        if !page.has_more || params.starting_after.is_none() {
+            break;
+        }
+    }
+
+    let mut params = ListBalanceTransactions::new();
+    params.created = Some(created);
+    params.limit = Some(PAGE_SIZE);
+    loop {
+        let page = BalanceTransaction::list(&connected, &params).await?;
+        params.starting_after = page.data.last().map(|transaction| transaction.id.clone());
+        activity
+            .balance_transactions
+            .extend(page.data.iter().map(|transaction| {
+                ConnectBalanceTransactionRecord {
+                    id: transaction.id.to_string(),
+                    kind: transaction.type_.to_string(),
+                    amount_cents: transaction.amount,
+                    fee_cents: transaction.fee,
+                    net_cents: transaction.net,
+                    currency: transaction.currency.to_string(),
+                    source_id: transaction
+                        .source
+                        .as_ref()
+                        .map(|source| source.id().to_string())
+                        .filter(|id| !id.is_empty()),
+                    available_on: Some(timestamp(transaction.available_on).date_naive()),
+                    created_at: timestamp(transaction.created),
+                }
+            }));
+        if !page.has_more || params.starting_after.is_none() {
+            break;
+        }
+    }
+
+    Ok(activity)
+}
+
+fn transfer_record(transfer: &Transfer) -> ConnectTransferRecord {
+    let source = transfer.source_transaction.as_ref();
+    let charge = source.and_then(|source| source.as_object());
+    ConnectTransferRecord {
+        id: transfer.id.to_string(),
+        amount_cents: transfer.amount,
+        amount_reversed_cents: transfer.amount_reversed,
+        currency: transfer.currency.to_string(),
+        charge_id: source.map(|source| source.id().to_string()),
+        payment_intent_id: charge
+            .and_then(|charge| charge.payment_intent.as_ref())
+            .map(|intent| intent.id().to_string()),
+        charge_amount_cents: charge.map(|charge| charge.amount),
+        application_fee_cents: charge.and_then(|charge| charge.application_fee_amount),
+        created_at: timestamp(transfer.created),
+    }
+}
+
+fn fake_id(prefix: &str, seed: &str) -> String {
+    let digest = hex::encode(Sha256::digest(seed.as_bytes()));
+    format!("{prefix}_fake_{}", &digest[..24])
+}
+
+fn decimal_cents(amount: Decimal) -> i64 {
+    (amount * Decimal::ONE_HUNDRED)
+        .round()
+        .to_i64()
+        .unwrap_or(0)
+}
+
+/// One transfer per payment (charge minus the club's commission, reversed in
+/// proportion to what was refunded) and one payout per past day of
+/// transfers, arriving `FAKE_PAYOUT_DELAY_DAYS` later.
+fn fake_activity(
+    connected_account_id: &str,
+    payments: &[ClubPaymentRow],
+    now: DateTime<Utc>,
+) -> ConnectActivity {
+    let mut activity = ConnectActivity::default();
+    let mut daily_net: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+
+    for payment in payments {
+        let charge_cents = decimal_cents(payment.amount);
+        let fee_cents = payment_service::compute_application_fee_cents(
+            payment.amount,
+            Some(payment.platform_commission_percent),
+            Some(payment.platform_commission_fixed_fee),
+        );
+        let amount_cents = charge_cents - fee_cents;
+        let reversed_cents = if charge_cents > 0 {
+            (decimal_cents(payment.refunded_amount) * amount_cents) / charge_cents
+        } else {
+            0
+        };
+        let transfer_id = fake_id("tr", &payment.stripe_payment_intent_id);
+
+        activity
+            .balance_transactions
+            .push(ConnectBalanceTransactionRecord {
+                id: fake_id("txn", &transfer_id),
+                kind: "payment".to_string(),
+                amount_cents,
+                fee_cents: 0,
+                net_cents: amount_cents,
+                currency: "eur".to_string(),
+                source_id: Some(fake_id("py", &transfer_id)),
+                available_on: Some(
+                    payment.paid_at.date_naive() + Duration::days(FAKE_PAYOUT_DELAY_DAYS),
+                ),
+                created_at: payment.paid_at,
+            });
+        activity.transfers.push(ConnectTransferRecord {
+            id: transfer_id,
+            amount_cents,
+            amount_reversed_cents: reversed_cents,
+            currency: "eur".to_string(),
+            charge_id: Some(fake_id("ch", &payment.stripe_payment_intent_id)),
+            payment_intent_id: Some(payment.stripe_payment_intent_id.clone()),
+            charge_amount_cents: Some(charge_cents),
+            application_fee_cents: Some(fee_cents),
+            created_at: payment.paid_at,
+        });
+        *daily_net.entry(payment.paid_at.date_naive()).or_default() +=
+            amount_cents - reversed_cents;
+    }
+
+    let today = now.date_naive();
+    for (day, net_cents) in daily_net {
+        if day >= today || net_cents <= 0 {
+            continue;
+        }
+        let payout_id = fake_id("po", &format!("{connected_account_id}:{day}"));
+        let created_at = day
+            .succ_opt()
+            .and_then(|next| next.and_hms_opt(0, 0, 0))
+            .map(|start| start.and_utc())
+            .unwrap_or(now);
+        let arrival_date = day + Duration::days(FAKE_PAYOUT_DELAY_DAYS);
+        activity
+            .balance_transactions
+            .push(ConnectBalanceTransactionRecord {
+                id: fake_id("txn", &payout_id),
+                kind: "payout".to_string(),
+                amount_cents: -net_cents,
+                fee_cents: 0,
+                net_cents: -net_cents,
+                currency: "eur".to_string(),
+                source_id: Some(payout_id.clone()),
+                available_on: Some(day + Duration::days(1)),
+                created_at,
+            });
+        activity.payouts.push(ConnectPayoutRecord {
+            id: payout_id,
+            amount_cents: net_cents,
+            currency: "eur".to_string(),
+            status: if arrival_date <= today {
+                "paid"
+            } else {
+                "in_transit"
+            }
+            .to_string(),
+            arrival_date,
+            failure_message: None,
+            created_at,
+        });
+    }
+
+    activity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fake_activity_keeps_the_commission_and_pays_out_past_days() {
+        let payment = |intent: &str, day: u32, refunded: i64| ClubPaymentRow {
+            stripe_payment_intent_id: intent.to_string(),
+            amount: Decimal::new(10000, 2),
+            refunded_amount: Decimal::new(refunded, 2),
+            paid_at: Utc.with_ymd_and_hms(2026, 10, day, 22, 0, 0).unwrap(),
+            platform_commission_percent: Decimal::new(5, 0),
+            platform_commission_fixed_fee: Decimal::new(50, 2),
+        };
+        let payments = [
+            payment("pi_a", 10, 0),
+            payment("pi_b", 10, 5000),
+            payment("pi_c", 11, 0),
+        ];
+        let now = Utc.with_ymd_and_hms(2026, 10, 11, 23, 0, 0).unwrap();
+        let activity = fake_activity("acct_1", &payments, now);
+
+        let first = &activity.transfers[0];
+        assert_eq!(first.charge_amount_cents, Some(10000));
+        assert_eq!(first.application_fee_cents, Some(550));
+        assert_eq!(first.amount_cents, 9450);
+        assert_eq!(activity.transfers[1].amount_reversed_cents, 4725);
+        assert_eq!(
+            first.id,
+            fake_activity("acct_1", &payments, now).transfers[0].id
+        );
+
+        // Only the 10th is over; its payout is still on the way
+        assert_eq!(activity.payouts.len(), 1);
+        assert_eq!(activity.payouts[0].amount_cents, 9450 * 2 - 4725);
+        assert_eq!(activity.payouts[0].status, "in_transit");
+    }
+}
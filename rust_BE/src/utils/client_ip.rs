@@ -0,0 +1,39 @@
+use axum::http::HeaderMap;
+use std::net::SocketAddr;
+
+/// Best-effort client IP: the first `X-Forwarded-For` hop, then `X-Real-IP`,
+/// then the peer address. Matches the key the auth rate limiter uses behind
+/// the load balancer.
+pub fn client_ip(headers: &HeaderMap, peer: SocketAddr) -> String {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .or_else(|| {
+            headers
+                .get("x-real-ip")
+                .and_then(|value| value.to_str().ok())
+        })
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| peer.ip().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_ip_prefers_forwarded_header() {
+        let peer: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers, peer), "10.0.0.1");
+
+        headers.insert("x-real-ip", "203.0.113.9".parse().unwrap());
+        assert_eq!(client_ip(&headers, peer), "203.0.113.9");
+
+        headers.insert("x-forwarded-for", "198.51.100.7, 10.0.0.2".parse().unwrap());
+        assert_eq!(client_ip(&headers, peer), "198.51.100.7");
+    }
+}
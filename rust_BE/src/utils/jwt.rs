@@ -4,6 +4,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 const TOKEN_EXPIRATION_HOURS: u64 = 24; // 24 hours
+/// Back-office tokens are short-lived: admins log in again after a shift
+pub const ADMIN_TOKEN_EXPIRATION_HOURS: u64 = 8;
 
 /// Generate a JWT token for a user or club owner
 pub fn generate_token(
@@ -25,6 +27,103 @@ pub fn generate_token(
         role,
         exp: expiration,
         iat: now,
+        club_owner_id: None,
+        sid: None,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+}
+
+/// Generate a short-lived access token bound to a refresh-token session family
+pub fn generate_access_token(
+    subject_id: Uuid,
+    email: String,
+    role: String,
+    session_id: Uuid,
+    ttl_minutes: i64,
+    secret: &str,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as usize;
+
+    let expiration = now + (ttl_minutes.max(1) * 60) as usize;
+
+    let claims = Claims {
+        sub: subject_id.to_string(),
+        email,
+        role,
+        exp: expiration,
+        iat: now,
+        club_owner_id: None,
+        sid: Some(session_id.to_string()),
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+}
+
+/// Generate a JWT token for a club staff member acting on `club_owner_id`'s club
+pub fn generate_staff_token(
+    staff_id: Uuid,
+    email: String,
+    role: String,
+    club_owner_id: Uuid,
+    secret: &str,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as usize;
+
+    let expiration = now + (TOKEN_EXPIRATION_HOURS * 3600) as usize;
+
+    let claims = Claims {
+        sub: staff_id.to_string(),
+        email,
+        role,
+        exp: expiration,
+        iat: now,
+        club_owner_id: Some(club_owner_id.to_string()),
+        sid: None,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+}
+
+/// Generate a JWT token for a platform admin
+pub fn generate_admin_token(
+    admin_id: Uuid,
+    email: String,
+    secret: &str,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as usize;
+
+    let expiration = now + (ADMIN_TOKEN_EXPIRATION_HOURS * 3600) as usize;
+
+    let claims = Claims {
+        sub: admin_id.to_string(),
+        email,
+        role: "platform_admin".to_string(),
+        exp: expiration,
+        iat: now,
+        club_owner_id: None,
+        sid: None,
     };
 
     encode(
@@ -76,4 +175,64 @@ mod tests {
         assert_eq!(claims.sub, owner_id.to_string());
         assert_eq!(claims.role, "club_owner");
     }
+
+    #[test]
+    fn test_staff_token_acts_on_owner_club() {
+        let secret = "test_secret";
+        let staff_id = Uuid::new_v4();
+        let owner_id = Uuid::new_v4();
+
+        let token = generate_staff_token(
+            staff_id,
+            "door@club.com".to_string(),
+            "staff_door".to_string(),
+            owner_id,
+            secret,
+        )
+        .unwrap();
+        let claims = validate_token(&token, secret).unwrap();
+
+        assert_eq!(claims.sub, staff_id.to_string());
+        assert_eq!(claims.role, "staff_door");
+        assert_eq!(claims.acting_owner_id(), Some(owner_id));
+    }
+
+    #[test]
+    fn test_access_token_carries_session_family() {
+        let secret = "test_secret";
+        let user_id = Uuid::new_v4();
+        let family_id = Uuid::new_v4();
+
+        let token = generate_access_token(
+            user_id,
+            "test@example.com".to_string(),
+            "user".to_string(),
+            family_id,
+            15,
+            secret,
+        )
+        .unwrap();
+        let claims = validate_token(&token, secret).unwrap();
+
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.sid, Some(family_id.to_string()));
+        assert_eq!(claims.exp - claims.iat, 15 * 60);
+    }
+
+    #[test]
+    fn test_admin_token_is_short_lived() {
+        let secret = "test_secret";
+        let admin_id = Uuid::new_v4();
+
+        let token = generate_admin_token(admin_id, "ops@pierre.app".to_string(), secret).unwrap();
+        let claims = validate_token(&token, secret).unwrap();
+
+        assert_eq!(claims.sub, admin_id.to_string());
+        assert_eq!(claims.role, "platform_admin");
+        assert_eq!(claims.acting_owner_id(), None);
+        assert_eq!(
+            claims.exp - claims.iat,
+            (ADMIN_TOKEN_EXPIRATION_HOURS * 3600) as usize
+        );
+    }
 }
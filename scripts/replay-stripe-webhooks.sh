@@ -0,0 +1,70 @@
+#!/usr/bin/env bash
+
+# Sends recorded Stripe events to a local backend, signed the way Stripe
+# signs them, so the webhook inbox and its worker can be exercised without
+# the Stripe CLI.
+#
+#   STRIPE_WEBHOOK_SECRET=whsec_... scripts/replay-stripe-webhooks.sh [payload.json ...]
+#
+# Without arguments every file in scripts/stripe-fixtures is sent. Set
+# FRESH_IDS=1 to suffix each event id so the same payloads are processed
+# again instead of being acknowledged as duplicates.
+
+set -euo pipefail
+
+ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+API_URL="${API_URL:-http://127.0.0.1:3000}"
+FIXTURES_DIR="${ROOT_DIR}/scripts/stripe-fixtures"
+
+require_cmd() {
+  if ! command -v "$1" >/dev/null 2>&1; then
+    echo "Missing required command: $1" >&2
+    exit 1
+  fi
+}
+
+require_cmd curl
+require_cmd openssl
+
+if [[ -z "${STRIPE_WEBHOOK_SECRET:-}" && -f "${ROOT_DIR}/rust_BE/.env" ]]; then
+  STRIPE_WEBHOOK_SECRET="$(grep -E '^STRIPE_WEBHOOK_SECRET=' "${ROOT_DIR}/rust_BE/.env" | tail -n 1 | cut -d= -f2-)"
+fi
+if [[ -z "${STRIPE_WEBHOOK_SECRET:-}" ]]; then
+  echo "STRIPE_WEBHOOK_SECRET must be set (or present in rust_BE/.env)" >&2
+  exit 1
+fi
+
+if [[ $# -gt 0 ]]; then
+  payloads=("$@")
+else
+  payloads=("${FIXTURES_DIR}"/*.json)
+fi
+
+failures=0
+for payload_file in "${payloads[@]}"; do
+  body="$(cat "${payload_file}")"
+  if [[ "${FRESH_IDS:-0}" == "1" ]]; then
+    body="$(printf '%s' "${body}" | sed -E "0,/\"id\": *\"(evt_[^\"]*)\"/s//\"id\": \"\\1_$(date +%s%N)\"/")"
+  fi
+
+  timestamp="$(date +%s)"
+  signature="$(printf '%s.%s' "${timestamp}" "${body}" \
+    | openssl dgst -sha256 -hmac "${STRIPE_WEBHOOK_SECRET}" -hex \
+    | sed 's/^.*= //')"
+
+  status="$(curl -sS -o /dev/null -w '%{http_code}' \
+    -X POST "${API_URL}/stripe/webhooks" \
+    -H 'Content-Type: application/json' \
+    -H "Stripe-Signature: t=${timestamp},v1=${signature}" \
+    --data-binary "${body}")"
+
+  echo "${status} $(basename "${payload_file}")"
+  if [[ "${status}" != 2* ]]; then
+    failures=$((failures + 1))
+  fi
+done
+
+if [[ ${failures} -gt 0 ]]; then
+  echo "${failures} webhook(s) were not accepted" >&2
+  exit 1
+fi